@@ -0,0 +1,86 @@
+use std::{collections::HashMap, sync::Mutex};
+
+#[derive(Default)]
+struct PendingAck {
+    acked: bool,
+    processed: bool,
+}
+
+/// Holds the completed uploads awaiting an acknowledgment from the application
+/// before being auto-terminated.
+#[derive(Default)]
+pub(crate) struct PendingAcks {
+    entries: Mutex<HashMap<String, PendingAck>>,
+}
+
+impl PendingAcks {
+    /// Starts awaiting the acknowledgment of an upload, along with its processing
+    /// when still running.
+    pub(crate) fn expect(&self, file_id: &str, processed: bool) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(
+                file_id.to_string(),
+                PendingAck {
+                    acked: false,
+                    processed,
+                },
+            );
+    }
+
+    /// Records the acknowledgment of an upload, returning `Some(true)` once it can be
+    /// terminated, or `None` if the upload isn't awaiting one.
+    pub(crate) fn ack(&self, file_id: &str) -> Option<bool> {
+        let mut entries =
+            self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+        let pending = entries.get_mut(file_id)?;
+        pending.acked = true;
+
+        Some(Self::settle(&mut entries, file_id))
+    }
+
+    /// Records the end of the processing of an upload, returning `true` once it can
+    /// be terminated.
+    pub(crate) fn processed(&self, file_id: &str) -> bool {
+        let mut entries =
+            self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+        let Some(pending) = entries.get_mut(file_id) else {
+            return false;
+        };
+        pending.processed = true;
+
+        Self::settle(&mut entries, file_id)
+    }
+
+    pub(crate) fn contains(&self, file_id: &str) -> bool {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(file_id)
+    }
+
+    pub(crate) fn remove(&self, file_id: &str) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(file_id);
+    }
+
+    fn settle(
+        entries: &mut HashMap<String, PendingAck>,
+        file_id: &str,
+    ) -> bool {
+        let settled = entries
+            .get(file_id)
+            .is_some_and(|pending| pending.acked && pending.processed);
+
+        if settled {
+            entries.remove(file_id);
+        }
+
+        settled
+    }
+}
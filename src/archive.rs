@@ -0,0 +1,549 @@
+use std::{
+    collections::HashSet,
+    io::{self, Cursor, Read},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use rocket::{
+    http::ContentType,
+    response::{self, Responder},
+    tokio::{self, io::AsyncWriteExt, runtime::Handle},
+    Request, Response,
+};
+
+use crate::{fs::sanitize_filename, CompletedGroup, Vault, VaultError};
+
+/// The size of the tar headers and blocks.
+const TAR_BLOCK: u64 = 512;
+/// The size of the zip local file headers, without the entry name.
+const ZIP_LOCAL_HEADER: u64 = 30;
+/// The size of the zip data descriptors following each entry.
+const ZIP_DESCRIPTOR: u64 = 16;
+/// The size of the zip central directory headers, without the entry name.
+const ZIP_CENTRAL_HEADER: u64 = 46;
+/// The size of the zip end of central directory record.
+const ZIP_END_RECORD: u64 = 22;
+
+/// The format of a [`GroupArchive`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// A POSIX `ustar` archive, holding entries of any size.
+    Tar,
+    /// A zip archive of uncompressed entries, limited to 4 GiB and 65535 entries.
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Returns the file extension of the format, without the leading dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Tar => "tar",
+            Self::Zip => "zip",
+        }
+    }
+
+    /// Returns the content type of the format.
+    pub fn content_type(self) -> ContentType {
+        match self {
+            Self::Tar => ContentType::new("application", "x-tar"),
+            Self::Zip => ContentType::ZIP,
+        }
+    }
+}
+
+/// An upload stored in a [`GroupArchive`].
+struct ArchiveEntry {
+    file_id: String,
+    name: String,
+    size: u64,
+    /// The archive position of the entry header.
+    position: u64,
+    crc: u32,
+}
+
+/// A tar or zip archive of completed uploads, like the members of a
+/// [`CompletedGroup`], streamed from the [`Vault`] without copying their content
+/// out first.
+///
+/// Entries are named by the sanitized `filename` metadata of the uploads, falling
+/// back to their ids, and prefixed by their ids when names collide. The archive
+/// implements [`Read`], and [`Responder`] to be downloaded as an attachment.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use std::sync::Arc;
+///
+/// use meteoritus::{ArchiveFormat, GroupArchive, Vault};
+/// use rocket::State;
+///
+/// #[get("/submissions/<id>/archive")]
+/// fn archive(id: &str, vault: &State<Arc<dyn Vault>>) -> Option<GroupArchive> {
+///     // The members recorded by the `on_group_completed` callback
+///     let members = vec![format!("{id}-cover"), format!("{id}-manuscript")];
+///
+///     GroupArchive::new(id, vault.inner().clone(), &members, ArchiveFormat::Zip).ok()
+/// }
+/// ```
+pub struct GroupArchive {
+    name: String,
+    format: ArchiveFormat,
+    vault: Arc<dyn Vault>,
+    entries: Vec<ArchiveEntry>,
+    modified: SystemTime,
+    len: u64,
+    next_entry: usize,
+    content: Option<Box<dyn Read + Send>>,
+    remaining: u64,
+    hasher: crc32fast::Hasher,
+    pending: Cursor<Vec<u8>>,
+    finished: bool,
+}
+
+impl GroupArchive {
+    /// Prepares an archive named `name` of the given completed uploads, in order.
+    ///
+    /// Fails with a [`VaultError::Conflict`] when an upload isn't completed, and
+    /// with a [`VaultError::Invalid`] when the uploads exceed the zip limits.
+    pub fn new(
+        name: &str,
+        vault: Arc<dyn Vault>,
+        members: &[String],
+        format: ArchiveFormat,
+    ) -> Result<Self, VaultError> {
+        let mut names = HashSet::new();
+        let mut entries = Vec::with_capacity(members.len());
+        let mut position = 0;
+
+        for file_id in members {
+            let file = vault.get_file(file_id)?;
+
+            if file.is_length_deferred() || file.offset() != file.length() {
+                return Err(VaultError::Conflict(format!(
+                    "upload {file_id} is not completed"
+                )));
+            }
+
+            let name = file
+                .metadata()
+                .as_ref()
+                .and_then(|metadata| metadata.get_raw("filename").ok())
+                .map(|value| String::from_utf8_lossy(&value).into_owned())
+                .filter(|name| !name.is_empty())
+                .map(|name| sanitize_filename(&name))
+                .unwrap_or_else(|| file_id.to_owned());
+
+            let name = match names.contains(&name) {
+                true => format!("{file_id}-{name}"),
+                false => name,
+            };
+
+            names.insert(name.to_owned());
+
+            let entry = ArchiveEntry {
+                file_id: file_id.to_owned(),
+                size: *file.offset(),
+                position,
+                crc: 0,
+                name: match format {
+                    ArchiveFormat::Tar => truncate(name, 100),
+                    ArchiveFormat::Zip => truncate(name, u16::MAX as usize),
+                },
+            };
+
+            position += match format {
+                ArchiveFormat::Tar => {
+                    TAR_BLOCK + entry.size.div_ceil(TAR_BLOCK) * TAR_BLOCK
+                }
+                ArchiveFormat::Zip => {
+                    ZIP_LOCAL_HEADER
+                        + entry.name.len() as u64
+                        + entry.size
+                        + ZIP_DESCRIPTOR
+                }
+            };
+
+            entries.push(entry);
+        }
+
+        let len = match format {
+            ArchiveFormat::Tar => position + 2 * TAR_BLOCK,
+            ArchiveFormat::Zip => {
+                let central_directory: u64 = entries
+                    .iter()
+                    .map(|entry| ZIP_CENTRAL_HEADER + entry.name.len() as u64)
+                    .sum();
+
+                if position > u32::MAX as u64
+                    || central_directory > u32::MAX as u64
+                    || entries.len() > u16::MAX as usize
+                {
+                    return Err(VaultError::Invalid(
+                        "uploads exceed the zip archive limits".into(),
+                    ));
+                }
+
+                position + central_directory + ZIP_END_RECORD
+            }
+        };
+
+        Ok(Self {
+            name: name.to_owned(),
+            format,
+            vault,
+            entries,
+            modified: SystemTime::now(),
+            len,
+            next_entry: 0,
+            content: None,
+            remaining: 0,
+            hasher: crc32fast::Hasher::new(),
+            pending: Cursor::default(),
+            finished: false,
+        })
+    }
+
+    /// Returns the file name of the archive, its sanitized name followed by the
+    /// format extension.
+    pub fn file_name(&self) -> String {
+        let name = sanitize_filename(&self.name);
+
+        format!("{}.{}", name, self.format.extension())
+    }
+
+    /// Returns the total size of the archive, in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Indicates if the archive has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Opens the content of the next entry, queuing its header.
+    fn start_entry(&mut self) -> io::Result<()> {
+        let index = self.next_entry;
+        self.next_entry += 1;
+
+        let entry = &self.entries[index];
+
+        let content = self
+            .vault
+            .open_content(&entry.file_id)
+            .map_err(io::Error::other)?;
+
+        let header = match self.format {
+            ArchiveFormat::Tar => tar_header(entry, self.modified),
+            ArchiveFormat::Zip => zip_local_header(entry, self.modified),
+        };
+
+        self.content = Some(Box::new(content.take(entry.size)));
+        self.remaining = entry.size;
+        self.hasher = crc32fast::Hasher::new();
+        self.pending = Cursor::new(header);
+
+        Ok(())
+    }
+
+    /// Queues the padding or data descriptor following the content of the
+    /// current entry.
+    fn finish_entry(&mut self) {
+        let entry = &mut self.entries[self.next_entry - 1];
+
+        let trailer = match self.format {
+            ArchiveFormat::Tar => {
+                let padding =
+                    entry.size.div_ceil(TAR_BLOCK) * TAR_BLOCK - entry.size;
+
+                vec![0; padding as usize]
+            }
+            ArchiveFormat::Zip => {
+                entry.crc = self.hasher.clone().finalize();
+
+                let mut descriptor = Vec::with_capacity(16);
+                descriptor.extend_from_slice(&0x08074b50u32.to_le_bytes());
+                descriptor.extend_from_slice(&entry.crc.to_le_bytes());
+                descriptor
+                    .extend_from_slice(&(entry.size as u32).to_le_bytes());
+                descriptor
+                    .extend_from_slice(&(entry.size as u32).to_le_bytes());
+                descriptor
+            }
+        };
+
+        self.content = None;
+        self.pending = Cursor::new(trailer);
+    }
+
+    /// Queues the end of the archive.
+    fn finish_archive(&mut self) {
+        let trailer = match self.format {
+            ArchiveFormat::Tar => vec![0; 2 * TAR_BLOCK as usize],
+            ArchiveFormat::Zip => {
+                zip_central_directory(&self.entries, self.modified)
+            }
+        };
+
+        self.finished = true;
+        self.pending = Cursor::new(trailer);
+    }
+}
+
+impl Read for GroupArchive {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            let read = self.pending.read(buf)?;
+
+            if read > 0 {
+                return Ok(read);
+            }
+
+            if let Some(content) = &mut self.content {
+                let read = content.read(buf)?;
+
+                if read > 0 {
+                    self.hasher.update(&buf[..read]);
+                    self.remaining -= read as u64;
+                    return Ok(read);
+                }
+
+                /* Headers declared the stored size, so it can't be shorter */
+                if self.remaining > 0 {
+                    return Err(io::ErrorKind::UnexpectedEof.into());
+                }
+
+                self.finish_entry();
+            } else if self.next_entry < self.entries.len() {
+                self.start_entry()?;
+            } else if !self.finished {
+                self.finish_archive();
+            } else {
+                return Ok(0);
+            }
+        }
+    }
+}
+
+impl<'r> Responder<'r, 'static> for GroupArchive {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        let (reader, mut writer) = tokio::io::duplex(64 * 1024);
+
+        let content_type = self.format.content_type();
+        let disposition =
+            format!("attachment; filename=\"{}\"", self.file_name());
+
+        let runtime = Handle::current();
+        let mut archive = self;
+
+        /* Vaults are blocking, so the archive is read out of the async runtime */
+        tokio::task::spawn_blocking(move || {
+            let mut buf = vec![0; 64 * 1024];
+
+            loop {
+                match archive.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(read) => {
+                        if runtime
+                            .block_on(writer.write_all(&buf[..read]))
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        warn_!(
+                            "Unable to stream archive {}: {}",
+                            archive.name,
+                            e
+                        );
+                        break;
+                    }
+                }
+            }
+        });
+
+        Response::build()
+            .header(content_type)
+            .raw_header("Content-Disposition", disposition)
+            .streamed_body(reader)
+            .ok()
+    }
+}
+
+impl CompletedGroup {
+    /// Prepares an archive of the group uploads, named by the group id, see
+    /// [`GroupArchive::new()`].
+    pub fn archive(
+        &self,
+        vault: Arc<dyn Vault>,
+        format: ArchiveFormat,
+    ) -> Result<GroupArchive, VaultError> {
+        GroupArchive::new(self.id(), vault, self.members(), format)
+    }
+}
+
+/// Truncates a name to the given number of bytes, at a character boundary.
+fn truncate(mut name: String, max_len: usize) -> String {
+    if name.len() > max_len {
+        let mut len = max_len;
+
+        while !name.is_char_boundary(len) {
+            len -= 1;
+        }
+
+        name.truncate(len);
+    }
+
+    name
+}
+
+fn tar_header(entry: &ArchiveEntry, modified: SystemTime) -> Vec<u8> {
+    let mut header = vec![0; TAR_BLOCK as usize];
+
+    let octal = |field: &mut [u8], value: u64| {
+        let digits = format!("{:0width$o}", value, width = field.len() - 1);
+        field[..digits.len()].copy_from_slice(digits.as_bytes());
+    };
+
+    let mtime = modified
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+
+    header[..entry.name.len()].copy_from_slice(entry.name.as_bytes());
+    octal(&mut header[100..108], 0o644);
+    octal(&mut header[108..116], 0);
+    octal(&mut header[116..124], 0);
+
+    /* Sizes over the 11 octal digits use the GNU base-256 encoding */
+    match entry.size < 0o100000000000 {
+        true => octal(&mut header[124..136], entry.size),
+        false => {
+            header[124] = 0x80;
+            header[128..136].copy_from_slice(&entry.size.to_be_bytes());
+        }
+    }
+
+    octal(&mut header[136..148], mtime);
+    header[148..156].fill(b' ');
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+
+    let checksum: u64 = header.iter().map(|byte| *byte as u64).sum();
+    let checksum = format!("{checksum:06o}\0 ");
+    header[148..156].copy_from_slice(checksum.as_bytes());
+
+    header
+}
+
+/// The general purpose flags of the zip entries, declaring the data descriptors
+/// and UTF-8 names.
+const ZIP_FLAGS: u16 = 0x0808;
+
+fn zip_local_header(entry: &ArchiveEntry, modified: SystemTime) -> Vec<u8> {
+    let (time, date) = dos_datetime(modified);
+
+    let mut header = Vec::with_capacity(30 + entry.name.len());
+    header.extend_from_slice(&0x04034b50u32.to_le_bytes());
+    header.extend_from_slice(&20u16.to_le_bytes());
+    header.extend_from_slice(&ZIP_FLAGS.to_le_bytes());
+    header.extend_from_slice(&0u16.to_le_bytes());
+    header.extend_from_slice(&time.to_le_bytes());
+    header.extend_from_slice(&date.to_le_bytes());
+    /* The checksum follows the content, in the data descriptor */
+    header.extend_from_slice(&0u32.to_le_bytes());
+    header.extend_from_slice(&(entry.size as u32).to_le_bytes());
+    header.extend_from_slice(&(entry.size as u32).to_le_bytes());
+    header.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+    header.extend_from_slice(&0u16.to_le_bytes());
+    header.extend_from_slice(entry.name.as_bytes());
+    header
+}
+
+fn zip_central_directory(
+    entries: &[ArchiveEntry],
+    modified: SystemTime,
+) -> Vec<u8> {
+    let (time, date) = dos_datetime(modified);
+
+    let mut directory = Vec::new();
+
+    for entry in entries {
+        directory.extend_from_slice(&0x02014b50u32.to_le_bytes());
+        directory.extend_from_slice(&20u16.to_le_bytes());
+        directory.extend_from_slice(&20u16.to_le_bytes());
+        directory.extend_from_slice(&ZIP_FLAGS.to_le_bytes());
+        directory.extend_from_slice(&0u16.to_le_bytes());
+        directory.extend_from_slice(&time.to_le_bytes());
+        directory.extend_from_slice(&date.to_le_bytes());
+        directory.extend_from_slice(&entry.crc.to_le_bytes());
+        directory.extend_from_slice(&(entry.size as u32).to_le_bytes());
+        directory.extend_from_slice(&(entry.size as u32).to_le_bytes());
+        directory.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+        /* Extra field, comment, disk number and attributes */
+        directory.extend_from_slice(&[0; 12]);
+        directory.extend_from_slice(&(entry.position as u32).to_le_bytes());
+        directory.extend_from_slice(entry.name.as_bytes());
+    }
+
+    let directory_start = entries.last().map_or(0, |entry| {
+        entry.position
+            + ZIP_LOCAL_HEADER
+            + entry.name.len() as u64
+            + entry.size
+            + ZIP_DESCRIPTOR
+    });
+
+    let directory_len = directory.len() as u32;
+    let count = entries.len() as u16;
+
+    directory.extend_from_slice(&0x06054b50u32.to_le_bytes());
+    directory.extend_from_slice(&[0; 4]);
+    directory.extend_from_slice(&count.to_le_bytes());
+    directory.extend_from_slice(&count.to_le_bytes());
+    directory.extend_from_slice(&directory_len.to_le_bytes());
+    directory.extend_from_slice(&(directory_start as u32).to_le_bytes());
+    directory.extend_from_slice(&0u16.to_le_bytes());
+    directory
+}
+
+/// Converts a time into the MS-DOS `(time, date)` of zip entries, in UTC and
+/// clamped to their 1980 epoch.
+fn dos_datetime(time: SystemTime) -> (u16, u16) {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+
+    let days = (secs / 86400) as i64;
+    let secs = secs % 86400;
+
+    /* Civil date from the days since the UNIX epoch */
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    if year < 1980 {
+        return (0, (1 << 5) | 1);
+    }
+
+    let time =
+        (secs / 3600) << 11 | (secs % 3600 / 60) << 5 | ((secs % 60) / 2);
+    let date = ((year - 1980) as u16) << 9 | (month as u16) << 5 | day as u16;
+
+    (time as u16, date)
+}
@@ -0,0 +1,227 @@
+//! Command line tool managing the uploads of a [`LocalVault`], like from cron jobs.
+//!
+//! ```text
+//! meteoritus [--path <dir>] ls
+//! meteoritus [--path <dir>] rm <id>
+//! meteoritus [--path <dir>] gc [--max-age <seconds>] [--completed] [--dry-run]
+//! ```
+
+use std::{
+    env, fs,
+    process::ExitCode,
+    time::{Duration, SystemTime},
+};
+
+use meteoritus::{Created, FileInfo, LocalVault, Vault};
+
+const USAGE: &str = "\
+Usage: meteoritus [--path <dir>] <command>
+
+Commands:
+  ls        Lists the stored uploads, along with their state and progress
+  rm <id>   Terminates the given upload
+  gc        Terminates the uploads not modified for a while
+
+Options:
+  --path <dir>           Directory of the vault, defaults to $METEORITUS_PATH or ./tmp/files
+  --max-age <seconds>    [gc] Age of the uploads to terminate, defaults to 86400
+  --completed            [gc] Also terminates completed uploads
+  --dry-run              [gc] Only lists the uploads that would be terminated";
+
+enum Command {
+    List,
+    Remove(String),
+    Collect {
+        max_age: Duration,
+        completed: bool,
+        dry_run: bool,
+    },
+}
+
+struct Args {
+    path: String,
+    command: Command,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut path = env::var("METEORITUS_PATH")
+            .unwrap_or_else(|_| "./tmp/files".to_string());
+        let mut command = None;
+        let mut target = None;
+        let mut max_age = Duration::from_secs(24 * 60 * 60);
+        let mut completed = false;
+        let mut dry_run = false;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--path" => {
+                    path = args.next().ok_or("missing --path value")?;
+                }
+                "--max-age" => {
+                    let seconds = args
+                        .next()
+                        .and_then(|value| value.parse().ok())
+                        .ok_or("invalid --max-age value")?;
+
+                    max_age = Duration::from_secs(seconds);
+                }
+                "--completed" => completed = true,
+                "--dry-run" => dry_run = true,
+                flag if flag.starts_with("--") => {
+                    return Err(format!("unknown option {flag}"));
+                }
+                _ if command.is_none() => command = Some(arg),
+                _ if target.is_none() => target = Some(arg),
+                _ => return Err(format!("unexpected argument {arg}")),
+            }
+        }
+
+        let command = match (command.as_deref(), target) {
+            (Some("ls"), None) => Command::List,
+            (Some("rm"), Some(id)) => Command::Remove(id),
+            (Some("rm"), None) => return Err("missing upload id".to_string()),
+            (Some("gc"), None) => Command::Collect {
+                max_age,
+                completed,
+                dry_run,
+            },
+            (Some(command), _) => {
+                return Err(format!("unexpected command {command}"))
+            }
+            (None, _) => return Err("missing command".to_string()),
+        };
+
+        Ok(Self { path, command })
+    }
+}
+
+fn is_completed(file: &FileInfo<Created>) -> bool {
+    !file.is_length_deferred() && file.offset() == file.length()
+}
+
+/// Returns how long ago the content of the upload was modified, if known.
+fn age_of(file: &FileInfo<Created>, now: SystemTime) -> Option<Duration> {
+    let path = file.storage_ref().as_path()?.to_path_buf();
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+
+    now.duration_since(modified).ok()
+}
+
+fn list(vault: &dyn Vault) -> Result<(), String> {
+    let now = SystemTime::now();
+
+    for file_id in vault.list_files().map_err(|e| e.to_string())? {
+        let file = match vault.get_file(&file_id) {
+            Ok(file) => file,
+            Err(e) => {
+                eprintln!("{file_id}: {e}");
+                continue;
+            }
+        };
+
+        let state = match () {
+            _ if file.is_quarantined() => "quarantined",
+            _ if is_completed(&file) => "completed",
+            _ => "in-progress",
+        };
+
+        let length = match file.is_length_deferred() {
+            true => "?".to_string(),
+            false => file.length().to_string(),
+        };
+
+        let age = age_of(&file, now)
+            .map(|age| format!("{}s", age.as_secs()))
+            .unwrap_or_else(|| "-".to_string());
+
+        println!(
+            "{}\t{}\t{}/{}\t{}",
+            file_id,
+            state,
+            file.offset(),
+            length,
+            age
+        );
+    }
+
+    Ok(())
+}
+
+fn remove(vault: &dyn Vault, file_id: &str) -> Result<(), String> {
+    vault
+        .terminate_file(file_id)
+        .map(|_| println!("{file_id}"))
+        .map_err(|e| format!("{file_id}: {e}"))
+}
+
+/// Terminates the uploads whose content was not modified for `max_age`, skipping
+/// quarantined uploads, and completed ones unless `completed` is set.
+fn collect(
+    vault: &dyn Vault,
+    max_age: Duration,
+    completed: bool,
+    dry_run: bool,
+) -> Result<(), String> {
+    let now = SystemTime::now();
+    let mut failed = false;
+
+    for file_id in vault.list_files().map_err(|e| e.to_string())? {
+        let Ok(file) = vault.get_file(&file_id) else {
+            continue;
+        };
+
+        if file.is_quarantined() || (is_completed(&file) && !completed) {
+            continue;
+        }
+
+        /* Keeping the uploads of unknown age */
+        match age_of(&file, now) {
+            Some(age) if age >= max_age => {}
+            _ => continue,
+        }
+
+        if dry_run {
+            println!("{file_id}");
+        } else if let Err(e) = remove(vault, &file_id) {
+            eprintln!("{e}");
+            failed = true;
+        }
+    }
+
+    match failed {
+        true => Err("some uploads could not be terminated".to_string()),
+        false => Ok(()),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    /* The vault lives until the process exits */
+    let vault = LocalVault::new(Box::leak(args.path.into_boxed_str()));
+
+    let result = match args.command {
+        Command::List => list(&vault),
+        Command::Remove(file_id) => remove(&vault, &file_id),
+        Command::Collect {
+            max_age,
+            completed,
+            dry_run,
+        } => collect(&vault, max_age, completed, dry_run),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
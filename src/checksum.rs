@@ -0,0 +1,178 @@
+use std::{fmt, sync::Arc};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+/// A hash algorithm of the `checksum` extension, verifying the chunks declaring
+/// an `Upload-Checksum` of its [`name`](ChecksumAlgorithm::name).
+///
+/// The `sha1`, `sha256` and `md5` algorithms are built-in, while others, like
+/// `crc32c` or `blake3`, can be registered through
+/// [`Meteoritus::with_checksum_algorithm()`](crate::Meteoritus::with_checksum_algorithm).
+/// Registered algorithms are advertised by the `Tus-Checksum-Algorithm` header.
+///
+/// # Example
+///
+/// ```rust
+/// use meteoritus::ChecksumAlgorithm;
+///
+/// struct Crc32;
+///
+/// impl ChecksumAlgorithm for Crc32 {
+///     fn name(&self) -> &str {
+///         "crc32"
+///     }
+///
+///     fn digest(&self, chunk: &[u8]) -> Vec<u8> {
+///         crc32fast::hash(chunk).to_be_bytes().to_vec()
+///     }
+/// }
+/// ```
+pub trait ChecksumAlgorithm: Send + Sync {
+    /// Returns the name of the algorithm, as declared by clients. Names are matched
+    /// ignoring the ASCII case.
+    fn name(&self) -> &str;
+
+    /// Returns the digest of the given chunk, compared against the base64 decoded
+    /// digest declared by the client.
+    fn digest(&self, chunk: &[u8]) -> Vec<u8>;
+}
+
+struct Sha1Algorithm;
+
+impl ChecksumAlgorithm for Sha1Algorithm {
+    fn name(&self) -> &str {
+        "sha1"
+    }
+
+    fn digest(&self, chunk: &[u8]) -> Vec<u8> {
+        Sha1::digest(chunk).to_vec()
+    }
+}
+
+struct Sha256Algorithm;
+
+impl ChecksumAlgorithm for Sha256Algorithm {
+    fn name(&self) -> &str {
+        "sha256"
+    }
+
+    fn digest(&self, chunk: &[u8]) -> Vec<u8> {
+        Sha256::digest(chunk).to_vec()
+    }
+}
+
+struct Md5Algorithm;
+
+impl ChecksumAlgorithm for Md5Algorithm {
+    fn name(&self) -> &str {
+        "md5"
+    }
+
+    fn digest(&self, chunk: &[u8]) -> Vec<u8> {
+        Md5::digest(chunk).to_vec()
+    }
+}
+
+/// The algorithms supported by the `checksum` extension, holding the built-ins
+/// along with the registered ones.
+#[derive(Clone)]
+pub(crate) struct ChecksumRegistry {
+    algorithms: Vec<Arc<dyn ChecksumAlgorithm>>,
+}
+
+impl Default for ChecksumRegistry {
+    fn default() -> Self {
+        Self {
+            algorithms: vec![
+                Arc::new(Sha1Algorithm),
+                Arc::new(Sha256Algorithm),
+                Arc::new(Md5Algorithm),
+            ],
+        }
+    }
+}
+
+impl ChecksumRegistry {
+    /// Registers an algorithm, replacing any algorithm of the same name.
+    pub(crate) fn register(&mut self, algorithm: Arc<dyn ChecksumAlgorithm>) {
+        self.algorithms.retain(|registered| {
+            !registered.name().eq_ignore_ascii_case(algorithm.name())
+        });
+
+        self.algorithms.push(algorithm);
+    }
+
+    /// Returns the algorithm of the given name, ignoring the ASCII case.
+    pub(crate) fn find(
+        &self,
+        name: &str,
+    ) -> Option<&Arc<dyn ChecksumAlgorithm>> {
+        self.algorithms
+            .iter()
+            .find(|algorithm| algorithm.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the names of the algorithms, in registration order.
+    pub(crate) fn names(&self) -> Vec<String> {
+        self.algorithms
+            .iter()
+            .map(|algorithm| algorithm.name().to_string())
+            .collect()
+    }
+}
+
+/// A parsing failure of the `Upload-Checksum` header.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum ChecksumError {
+    Malformed,
+    UnsupportedAlgorithm,
+}
+
+/// The checksum of a chunk, declared through the `Upload-Checksum` header.
+pub(crate) struct UploadChecksum {
+    algorithm: Arc<dyn ChecksumAlgorithm>,
+    digest: Vec<u8>,
+}
+
+impl UploadChecksum {
+    /// Parses a `<algorithm> <base64 digest>` header value, of an algorithm of the
+    /// given registry.
+    pub(crate) fn parse(
+        value: &str,
+        registry: &ChecksumRegistry,
+    ) -> Result<Self, ChecksumError> {
+        let Some((algorithm, digest)) = value.trim().split_once(' ') else {
+            return Err(ChecksumError::Malformed);
+        };
+
+        let Some(algorithm) = registry.find(algorithm) else {
+            return Err(ChecksumError::UnsupportedAlgorithm);
+        };
+
+        let digest = STANDARD
+            .decode(digest.trim())
+            .map_err(|_| ChecksumError::Malformed)?;
+
+        Ok(Self {
+            algorithm: algorithm.to_owned(),
+            digest,
+        })
+    }
+
+    /// Returns `true` when the given chunk matches the declared digest.
+    pub(crate) fn verify(&self, chunk: &[u8]) -> bool {
+        self.algorithm.digest(chunk) == self.digest
+    }
+}
+
+impl fmt::Debug for UploadChecksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UploadChecksum")
+            .field("algorithm", &self.algorithm.name())
+            .field("digest", &self.digest)
+            .finish()
+    }
+}
@@ -0,0 +1,38 @@
+use std::time::SystemTime;
+
+/// A source of the current time, used by Meteoritus instead of calling the system
+/// time directly.
+///
+/// It is consulted for time based behaviors, like the stall timeout and the dates of
+/// [`LocalVault`](crate::LocalVault) path templates, so tests can control time
+/// deterministically, see [`testing::ManualClock`](crate::testing::ManualClock).
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::{Duration, SystemTime};
+/// use meteoritus::Clock;
+///
+/// /// A clock running one hour ahead.
+/// struct SkewedClock;
+///
+/// impl Clock for SkewedClock {
+///     fn now(&self) -> SystemTime {
+///         SystemTime::now() + Duration::from_secs(3600)
+///     }
+/// }
+/// ```
+pub trait Clock: Send + Sync {
+    /// Returns the current time.
+    fn now(&self) -> SystemTime;
+}
+
+/// The default [`Clock`], reading the system time.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
@@ -0,0 +1,301 @@
+use std::{collections::HashSet, io::Read, sync::Mutex};
+
+use rocket::http::Status;
+
+use crate::{
+    fs::{Created, FileInfo, Metadata, PatchOption},
+    Completed, ErrorCode, TusError, Vault, VaultError,
+};
+
+/// Metadata key holding the id of the group a partial upload belongs to.
+pub(crate) const GROUP_KEY: &str = "concat-group";
+/// Metadata key holding the number of parts of the group.
+pub(crate) const PARTS_KEY: &str = "concat-parts";
+/// Metadata key holding the position of the part in the concatenated result.
+pub(crate) const INDEX_KEY: &str = "concat-index";
+
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// The group membership of a partial upload, read from its metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ConcatPart {
+    pub(crate) group: String,
+    pub(crate) parts: usize,
+    pub(crate) index: usize,
+}
+
+impl ConcatPart {
+    /// Reads the group membership of an upload, if any.
+    ///
+    /// Returns an error when the membership is incomplete or malformed.
+    pub(crate) fn of(metadata: &Option<Metadata>) -> Result<Option<Self>, ()> {
+        let Some(metadata) = metadata else {
+            return Ok(None);
+        };
+
+        let read = |key| {
+            metadata
+                .get_raw(key)
+                .ok()
+                .and_then(|value| String::from_utf8(value).ok())
+        };
+
+        let group = match read(GROUP_KEY) {
+            Some(group) if !group.is_empty() => group,
+            Some(_) => return Err(()),
+            None => return Ok(None),
+        };
+
+        let parse = |key| read(key).and_then(|value| value.parse().ok());
+
+        match (parse(PARTS_KEY), parse(INDEX_KEY)) {
+            (Some(parts), Some(index)) if index < parts => Ok(Some(Self {
+                group,
+                parts,
+                index,
+            })),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The outcome of completing an upload, when concatenation groups are enabled.
+pub(crate) enum PartCompletion {
+    /// The upload isn't part of a group.
+    Ungrouped(FileInfo<Completed>),
+    /// The upload is a part of a group still waiting for other parts.
+    Pending,
+    /// The upload was the last part of its group, which got concatenated.
+    Concatenated(FileInfo<Completed>),
+}
+
+/// Tracks the partial uploads grouped for concatenation, see
+/// [`Meteoritus::with_concat_groups()`](crate::Meteoritus::with_concat_groups).
+pub(crate) struct ConcatGroups {
+    max_parts: usize,
+    concatenating: Mutex<HashSet<String>>,
+}
+
+impl ConcatGroups {
+    pub(crate) fn new(max_parts: usize) -> Self {
+        Self {
+            max_parts,
+            concatenating: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Checks the group membership of an upload being created.
+    pub(crate) fn validate<S>(
+        &self,
+        file: &FileInfo<S>,
+    ) -> Result<(), TusError> {
+        match ConcatPart::of(file.metadata()) {
+            Ok(Some(part)) if part.parts > self.max_parts => {
+                Err(TusError::new(
+                    Status::BadRequest,
+                    ErrorCode::InvalidConcatGroup,
+                    format!(
+                        "Concatenation groups are limited to {} parts",
+                        self.max_parts
+                    ),
+                ))
+            }
+            Ok(_) => Ok(()),
+            Err(_) => Err(TusError::new(
+                Status::BadRequest,
+                ErrorCode::InvalidConcatGroup,
+                format!(
+                    "Invalid {}, {} or {} metadata",
+                    GROUP_KEY, PARTS_KEY, INDEX_KEY
+                ),
+            )),
+        }
+    }
+
+    /// Returns the stored parts of the given group, along with their membership.
+    pub(crate) fn parts_of(
+        &self,
+        vault: &dyn Vault,
+        group: &str,
+    ) -> Result<Vec<(ConcatPart, FileInfo<Created>)>, VaultError> {
+        let mut parts = Vec::new();
+
+        for file_id in vault.list_files()? {
+            let Ok(file) = vault.get_file(&file_id) else {
+                continue;
+            };
+
+            if let Ok(Some(part)) = ConcatPart::of(file.metadata()) {
+                if part.group == group {
+                    parts.push((part, file));
+                }
+            }
+        }
+
+        parts.sort_by_key(|(part, _)| part.index);
+
+        Ok(parts)
+    }
+
+    /// Returns the concatenated result of the given group, once created.
+    pub(crate) fn result_of(
+        &self,
+        vault: &dyn Vault,
+        group: &str,
+    ) -> Result<Option<FileInfo<Created>>, VaultError> {
+        for file_id in vault.list_files()? {
+            let Ok(file) = vault.get_file(&file_id) else {
+                continue;
+            };
+
+            let is_result = file.metadata().as_ref().is_some_and(|metadata| {
+                metadata.get_raw(GROUP_KEY).ok().as_deref()
+                    == Some(group.as_bytes())
+                    && metadata.get_raw(INDEX_KEY).is_err()
+            });
+
+            if is_result {
+                return Ok(Some(file));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Handles a completed upload, concatenating its group once every part is
+    /// completed.
+    ///
+    /// The concatenated result is a new upload holding the parts content in index
+    /// order, along with the metadata of the first part. The parts are terminated
+    /// once concatenated.
+    pub(crate) fn complete(
+        &self,
+        vault: &dyn Vault,
+        completed: FileInfo<Completed>,
+    ) -> Result<PartCompletion, VaultError> {
+        let part = match ConcatPart::of(completed.metadata()) {
+            Ok(Some(part)) => part,
+            _ => return Ok(PartCompletion::Ungrouped(completed)),
+        };
+
+        let members = self.parts_of(vault, &part.group)?;
+
+        /* Picking a single completed upload for each part of the group */
+        let mut parts: Vec<&FileInfo<Created>> = Vec::with_capacity(part.parts);
+
+        for index in 0..part.parts {
+            let found = members.iter().find(|(member, file)| {
+                member.index == index
+                    && member.parts == part.parts
+                    && !file.is_length_deferred()
+                    && file.offset() == file.length()
+            });
+
+            match found {
+                Some((_, file)) => parts.push(file),
+                None => return Ok(PartCompletion::Pending),
+            }
+        }
+
+        if !self.lock(&part.group) {
+            return Ok(PartCompletion::Pending);
+        }
+
+        /* Skipping groups concatenated meanwhile by another completed part */
+        if !parts.iter().all(|file| vault.exists(file.id())) {
+            self.unlock(&part.group);
+            return Ok(PartCompletion::Pending);
+        }
+
+        let result = concatenate(vault, &parts, &part.group);
+
+        if result.is_ok() {
+            for (_, member) in &members {
+                if vault.terminate_file(member.id()).is_err() {
+                    warn_!(
+                        "Unable to terminate concatenated part: {}",
+                        member.id()
+                    );
+                }
+            }
+        }
+
+        self.unlock(&part.group);
+
+        result.map(PartCompletion::Concatenated)
+    }
+
+    fn lock(&self, group: &str) -> bool {
+        self.concatenating
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(group.to_string())
+    }
+
+    fn unlock(&self, group: &str) {
+        self.concatenating
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(group);
+    }
+}
+
+/// Creates a new upload holding the content of the given parts, in order.
+fn concatenate(
+    vault: &dyn Vault,
+    parts: &[&FileInfo<Created>],
+    group: &str,
+) -> Result<FileInfo<Completed>, VaultError> {
+    let length = parts.iter().map(|part| *part.length()).sum();
+
+    let mut metadata = parts[0].metadata().to_owned().unwrap_or_default();
+    metadata.remove(PARTS_KEY);
+    metadata.remove(INDEX_KEY);
+
+    let mut result = vault.build_file(length, None)?;
+    result.set_metadata(metadata);
+
+    let result = vault.create_file(result)?;
+    let result_id = result.id().to_string();
+
+    let copy_parts = || -> Result<FileInfo<Completed>, VaultError> {
+        let mut buf = vec![0; CHUNK_SIZE];
+        let mut offset = 0;
+
+        for part in parts {
+            let mut content =
+                vault.open_content(part.id())?.take(*part.length());
+
+            loop {
+                let read = content.read(&mut buf)?;
+
+                if read == 0 {
+                    break;
+                }
+
+                if let PatchOption::Completed(completed) =
+                    vault.patch_file(&result_id, &mut buf[..read], offset)?
+                {
+                    return Ok(completed);
+                }
+
+                offset += read as u64;
+            }
+        }
+
+        /* Completing empty results, which got no content to patch */
+        match vault.patch_file(&result_id, &mut [], offset)? {
+            PatchOption::Completed(completed) => Ok(completed),
+            PatchOption::Patched(_) => Err(VaultError::Conflict(format!(
+                "concatenation of group {} is incomplete",
+                group
+            ))),
+        }
+    };
+
+    copy_parts().inspect_err(|_| {
+        if vault.terminate_file(&result_id).is_err() {
+            warn_!("Unable to terminate failed concatenation: {}", result_id);
+        }
+    })
+}
@@ -0,0 +1,466 @@
+//! A reusable suite verifying that a server built on top of [`Meteoritus`] complies
+//! with the [tus protocol](https://tus.io/protocols/resumable-upload).
+//!
+//! This module is only available when the `conformance` feature is enabled:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! meteoritus = { version = "0.2.1", features = ["conformance"] }
+//! ```
+//!
+//! Each scenario drives a [`rocket::local::asynchronous::Client`] through the
+//! requests of a protocol section, panicking with a description of the first
+//! violation found, so they can be called from plain unit tests. This is mostly
+//! useful to verify custom [`Vault`] implementations end to end:
+//!
+//! * [`discovery()`] - the `OPTIONS` capabilities of the server.
+//! * [`creation()`] - the creation of uploads, including deferred lengths.
+//! * [`head()`] - the `HEAD` semantics of created and unknown uploads.
+//! * [`offsets()`] - the `PATCH` of chunks at the expected offsets.
+//! * [`termination()`] - the termination of uploads.
+//! * [`expiration()`] - the rejection of uploads past their deadline.
+//!
+//! [`run()`] runs every scenario but [`expiration()`], which requires the server
+//! to set deadlines.
+//!
+//! # Example
+//!
+//! ```rust
+//! use meteoritus::{conformance, testing::MockVault, Meteoritus};
+//! use rocket::local::asynchronous::Client;
+//!
+//! # rocket::execute(async {
+//! let meteoritus = Meteoritus::new()
+//!     .mount_to("/files")
+//!     .with_vault(MockVault::new())
+//!     .keep_on_disk()
+//!     .build();
+//!
+//! let client = Client::tracked(rocket::build().attach(meteoritus))
+//!     .await
+//!     .unwrap();
+//!
+//! conformance::run(&client, "/files").await;
+//! # });
+//! ```
+//!
+//! [`Meteoritus`]: crate::Meteoritus
+//! [`Vault`]: crate::Vault
+
+use rocket::{
+    http::{ContentType, Header, Status},
+    local::asynchronous::{Client, LocalResponse},
+};
+
+use crate::testing::{
+    creation_request, info_request, termination_request, upload_info_request,
+    upload_request,
+};
+
+/// Runs every scenario of the suite against the server mounted at `base_route`,
+/// except [`expiration()`].
+pub async fn run(client: &Client, base_route: &str) {
+    discovery(client, base_route).await;
+    creation(client, base_route).await;
+    head(client, base_route).await;
+    offsets(client, base_route).await;
+    termination(client, base_route).await;
+}
+
+/// Verifies that `OPTIONS` requests advertise the supported protocol versions
+/// and the `creation` and `termination` extensions.
+pub async fn discovery(client: &Client, base_route: &str) {
+    let res = info_request(client, base_route).dispatch().await;
+
+    assert!(
+        matches!(res.status().code, 200 | 204),
+        "OPTIONS must respond 200 or 204, got {}",
+        res.status()
+    );
+
+    let versions = header(&res, "Tus-Version")
+        .expect("OPTIONS must respond the Tus-Version header");
+    assert!(
+        list_contains(&versions, "1.0.0"),
+        "Tus-Version must include 1.0.0, got {versions:?}"
+    );
+
+    let extensions = header(&res, "Tus-Extension")
+        .expect("OPTIONS must respond the Tus-Extension header");
+    for extension in ["creation", "termination"] {
+        assert!(
+            list_contains(&extensions, extension),
+            "Tus-Extension must include {extension}, got {extensions:?}"
+        );
+    }
+
+    if let Some(max_size) = header(&res, "Tus-Max-Size") {
+        assert!(
+            max_size.parse::<u64>().is_ok(),
+            "Tus-Max-Size must be a non-negative integer, got {max_size:?}"
+        );
+    }
+}
+
+/// Verifies the creation of uploads, of known and deferred lengths, along with
+/// the rejection of invalid creation requests.
+pub async fn creation(client: &Client, base_route: &str) {
+    let res = creation_request(client, base_route, 11).dispatch().await;
+
+    assert_eq!(
+        res.status(),
+        Status::Created,
+        "POST with a valid Upload-Length must respond 201"
+    );
+    assert_resumable(&res);
+
+    let location = header(&res, "Location")
+        .expect("POST must respond the Location of the created upload");
+
+    let res = upload_info_request(client, location).dispatch().await;
+    assert_eq!(
+        header(&res, "Upload-Offset").as_deref(),
+        Some("0"),
+        "created uploads must start at offset 0"
+    );
+
+    let res = client
+        .post(base_route)
+        .header(Header::new("Upload-Length", "11"))
+        .dispatch()
+        .await;
+    assert!(
+        res.status().class().is_client_error(),
+        "POST without Tus-Resumable must be rejected, got {}",
+        res.status()
+    );
+
+    let res = client
+        .post(base_route)
+        .header(Header::new("Tus-Resumable", "1.0.0"))
+        .dispatch()
+        .await;
+    assert_eq!(
+        res.status(),
+        Status::BadRequest,
+        "POST without Upload-Length nor Upload-Defer-Length must respond 400"
+    );
+
+    let res = client
+        .post(base_route)
+        .header(Header::new("Tus-Resumable", "1.0.0"))
+        .header(Header::new("Upload-Length", "eleven"))
+        .dispatch()
+        .await;
+    assert_eq!(
+        res.status(),
+        Status::BadRequest,
+        "POST with a non-numeric Upload-Length must respond 400"
+    );
+
+    let res = info_request(client, base_route).dispatch().await;
+    if let Some(max_size) = header(&res, "Tus-Max-Size")
+        .and_then(|max_size| max_size.parse::<u64>().ok())
+    {
+        let res = creation_request(client, base_route, max_size + 1)
+            .dispatch()
+            .await;
+        assert_eq!(
+            res.status(),
+            Status::PayloadTooLarge,
+            "POST exceeding Tus-Max-Size must respond 413"
+        );
+    }
+
+    let extensions = header(&res, "Tus-Extension").unwrap_or_default();
+    if list_contains(&extensions, "creation-defer-length") {
+        let res = client
+            .post(base_route)
+            .header(Header::new("Tus-Resumable", "1.0.0"))
+            .header(Header::new("Upload-Defer-Length", "1"))
+            .dispatch()
+            .await;
+        assert_eq!(
+            res.status(),
+            Status::Created,
+            "POST with Upload-Defer-Length: 1 must respond 201"
+        );
+
+        let location = header(&res, "Location")
+            .expect("POST must respond the Location of the created upload");
+
+        let res = upload_info_request(client, location).dispatch().await;
+        assert_eq!(
+            header(&res, "Upload-Defer-Length").as_deref(),
+            Some("1"),
+            "HEAD of deferred uploads must respond Upload-Defer-Length: 1"
+        );
+        assert!(
+            header(&res, "Upload-Length").is_none(),
+            "HEAD of deferred uploads must not respond Upload-Length"
+        );
+    }
+}
+
+/// Verifies that `HEAD` requests respond the offset and length of uploads, are
+/// not cached, and respond `404` or `410` for unknown uploads.
+pub async fn head(client: &Client, base_route: &str) {
+    let location = create(client, base_route, 11).await;
+
+    let res = upload_info_request(client, location.as_str())
+        .dispatch()
+        .await;
+
+    assert!(
+        matches!(res.status().code, 200 | 204),
+        "HEAD must respond 200 or 204, got {}",
+        res.status()
+    );
+    assert_resumable(&res);
+    assert_eq!(
+        header(&res, "Upload-Offset").as_deref(),
+        Some("0"),
+        "HEAD must respond the Upload-Offset"
+    );
+    assert_eq!(
+        header(&res, "Upload-Length").as_deref(),
+        Some("11"),
+        "HEAD must respond the Upload-Length"
+    );
+    assert_eq!(
+        header(&res, "Cache-Control").as_deref(),
+        Some("no-store"),
+        "HEAD must respond Cache-Control: no-store"
+    );
+
+    let unknown = format!("{}/unknown-conformance-upload", base_route);
+    let res = upload_info_request(client, unknown).dispatch().await;
+    assert!(
+        matches!(res.status().code, 404 | 410),
+        "HEAD of unknown uploads must respond 404 or 410, got {}",
+        res.status()
+    );
+}
+
+/// Verifies that chunks are only accepted at the current offset, with the
+/// `application/offset+octet-stream` content type, advancing the offset.
+pub async fn offsets(client: &Client, base_route: &str) {
+    let location = create(client, base_route, 11).await;
+
+    let res = upload_request(client, location.as_str(), 0, "hello")
+        .dispatch()
+        .await;
+    assert_eq!(
+        res.status(),
+        Status::NoContent,
+        "PATCH at the current offset must respond 204"
+    );
+    assert_resumable(&res);
+    assert_eq!(
+        header(&res, "Upload-Offset").as_deref(),
+        Some("5"),
+        "PATCH must respond the new Upload-Offset"
+    );
+
+    let res = upload_request(client, location.as_str(), 3, "lo wor")
+        .dispatch()
+        .await;
+    assert_eq!(
+        res.status(),
+        Status::Conflict,
+        "PATCH at another offset must respond 409"
+    );
+
+    let res = client
+        .patch(location.as_str())
+        .header(Header::new("Tus-Resumable", "1.0.0"))
+        .header(ContentType::Binary)
+        .header(Header::new("Upload-Offset", "5"))
+        .body(" world")
+        .dispatch()
+        .await;
+    assert_eq!(
+        res.status(),
+        Status::UnsupportedMediaType,
+        "PATCH of another content type must respond 415"
+    );
+
+    let res = client
+        .patch(location.as_str())
+        .header(ContentType::new("application", "offset+octet-stream"))
+        .header(Header::new("Upload-Offset", "5"))
+        .body(" world")
+        .dispatch()
+        .await;
+    assert!(
+        res.status().class().is_client_error(),
+        "PATCH without Tus-Resumable must be rejected, got {}",
+        res.status()
+    );
+
+    let res = upload_info_request(client, location.as_str())
+        .dispatch()
+        .await;
+    assert_eq!(
+        header(&res, "Upload-Offset").as_deref(),
+        Some("5"),
+        "rejected chunks must not advance the offset"
+    );
+
+    let res = upload_request(client, location.as_str(), 5, " world")
+        .dispatch()
+        .await;
+    assert_eq!(
+        res.status(),
+        Status::NoContent,
+        "PATCH completing the upload must respond 204"
+    );
+    assert_eq!(
+        header(&res, "Upload-Offset").as_deref(),
+        Some("11"),
+        "PATCH completing the upload must respond its length as offset"
+    );
+
+    let unknown = format!("{}/unknown-conformance-upload", base_route);
+    let res = upload_request(client, unknown, 0, "hello").dispatch().await;
+    assert!(
+        matches!(res.status().code, 404 | 410),
+        "PATCH of unknown uploads must respond 404 or 410, got {}",
+        res.status()
+    );
+}
+
+/// Verifies that `DELETE` requests terminate uploads, which are no longer
+/// available afterwards.
+pub async fn termination(client: &Client, base_route: &str) {
+    let location = create(client, base_route, 11).await;
+
+    let res = upload_request(client, location.as_str(), 0, "hello")
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::NoContent, "PATCH must respond 204");
+
+    let res = termination_request(client, location.as_str())
+        .dispatch()
+        .await;
+    assert_eq!(
+        res.status(),
+        Status::NoContent,
+        "DELETE of uploads in progress must respond 204"
+    );
+    assert_resumable(&res);
+
+    let res = upload_info_request(client, location.as_str())
+        .dispatch()
+        .await;
+    assert!(
+        matches!(res.status().code, 404 | 410),
+        "HEAD of terminated uploads must respond 404 or 410, got {}",
+        res.status()
+    );
+
+    let res = upload_request(client, location.as_str(), 5, " world")
+        .dispatch()
+        .await;
+    assert!(
+        matches!(res.status().code, 404 | 410),
+        "PATCH of terminated uploads must respond 404 or 410, got {}",
+        res.status()
+    );
+}
+
+/// Verifies that uploads past their deadline reject further chunks with `404`
+/// or `410`.
+///
+/// The server must set a deadline on created uploads, like through
+/// [`HandlerContext::set_deadline()`](crate::HandlerContext::set_deadline), and
+/// `expire` must move past it, like by advancing a
+/// [`ManualClock`](crate::testing::ManualClock).
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::{Duration, SystemTime};
+/// use meteoritus::{
+///     conformance, testing::{ManualClock, MockVault}, Clock, Meteoritus,
+/// };
+/// use rocket::local::asynchronous::Client;
+///
+/// # rocket::execute(async {
+/// let clock = ManualClock::new(SystemTime::now());
+/// let deadlines = clock.clone();
+///
+/// let meteoritus = Meteoritus::new()
+///     .mount_to("/files")
+///     .with_vault(MockVault::new())
+///     .with_clock(clock.clone())
+///     .on_creation(move |ctx| {
+///         ctx.set_deadline(deadlines.now() + Duration::from_secs(60));
+///         Ok(())
+///     })
+///     .build();
+///
+/// let client = Client::tracked(rocket::build().attach(meteoritus))
+///     .await
+///     .unwrap();
+///
+/// conformance::expiration(&client, "/files", || {
+///     clock.advance(Duration::from_secs(120))
+/// })
+/// .await;
+/// # });
+/// ```
+pub async fn expiration<F>(client: &Client, base_route: &str, expire: F)
+where
+    F: FnOnce(),
+{
+    let location = create(client, base_route, 11).await;
+
+    let res = upload_request(client, location.as_str(), 0, "hello")
+        .dispatch()
+        .await;
+    assert_eq!(
+        res.status(),
+        Status::NoContent,
+        "PATCH before the deadline must respond 204"
+    );
+
+    expire();
+
+    let res = upload_request(client, location.as_str(), 5, " world")
+        .dispatch()
+        .await;
+    assert!(
+        matches!(res.status().code, 404 | 410),
+        "PATCH of expired uploads must respond 404 or 410, got {}",
+        res.status()
+    );
+}
+
+/// Creates an upload of the given `length`, returning its location.
+async fn create(client: &Client, base_route: &str, length: u64) -> String {
+    let res = creation_request(client, base_route, length)
+        .dispatch()
+        .await;
+
+    assert_eq!(res.status(), Status::Created, "POST must respond 201");
+
+    header(&res, "Location")
+        .expect("POST must respond the Location of the created upload")
+}
+
+fn header(res: &LocalResponse<'_>, name: &str) -> Option<String> {
+    res.headers().get_one(name).map(str::to_string)
+}
+
+fn list_contains(list: &str, value: &str) -> bool {
+    list.split(',').any(|item| item.trim() == value)
+}
+
+fn assert_resumable(res: &LocalResponse<'_>) {
+    assert_eq!(
+        header(res, "Tus-Resumable").as_deref(),
+        Some("1.0.0"),
+        "responses must include Tus-Resumable: 1.0.0"
+    );
+}
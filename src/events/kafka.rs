@@ -0,0 +1,123 @@
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use rocket::tokio::{self, sync::OnceCell};
+use rskafka::{
+    chrono::{DateTime, Utc},
+    client::{
+        partition::{Compression, PartitionClient, UnknownTopicHandling},
+        ClientBuilder,
+    },
+    record::Record,
+};
+
+use super::{EventPublisher, UploadEvent};
+
+/// An [`EventPublisher`] producing [CloudEvents](https://cloudevents.io) into a
+/// [Kafka](https://kafka.apache.org) topic.
+///
+/// Events are keyed by the upload id and produced into the first partition of the topic.
+/// The connection is established on the first published event.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Ignite;
+/// use meteoritus::{KafkaPublisher, Meteoritus};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+///         .with_event_publisher(KafkaPublisher::new(vec!["localhost:9092"], "uploads"))
+///         .build();
+///
+///     rocket::build().attach(meteoritus)
+/// }
+/// ```
+pub struct KafkaPublisher {
+    brokers: Vec<String>,
+    topic: String,
+    source: String,
+    client: Arc<OnceCell<PartitionClient>>,
+}
+
+impl KafkaPublisher {
+    /// Creates a new [`KafkaPublisher`] for the given bootstrap `brokers`,
+    /// producing into the given `topic`.
+    pub fn new<B>(brokers: Vec<B>, topic: impl Into<String>) -> Self
+    where
+        B: Into<String>,
+    {
+        Self {
+            brokers: brokers.into_iter().map(Into::into).collect(),
+            topic: topic.into(),
+            source: "meteoritus".to_string(),
+            client: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// Specifies the CloudEvents `source` attribute, by default `meteoritus`.
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = source.into();
+        self
+    }
+}
+
+impl EventPublisher for KafkaPublisher {
+    fn publish(&self, event: &UploadEvent) {
+        let brokers = self.brokers.to_owned();
+        let topic = self.topic.to_owned();
+        let client = self.client.to_owned();
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .and_then(|d| {
+                DateTime::<Utc>::from_timestamp_millis(d.as_millis() as i64)
+            })
+            .unwrap_or_default();
+
+        let record = Record {
+            key: Some(event.file_id().as_bytes().to_vec()),
+            value: Some(
+                event.to_cloud_event(&self.source).to_string().into_bytes(),
+            ),
+            headers: BTreeMap::from([(
+                "content-type".to_string(),
+                b"application/cloudevents+json".to_vec(),
+            )]),
+            timestamp,
+        };
+
+        tokio::spawn(async move {
+            let client = client
+                .get_or_try_init(|| async {
+                    ClientBuilder::new(brokers)
+                        .build()
+                        .await?
+                        .partition_client(topic, 0, UnknownTopicHandling::Retry)
+                        .await
+                })
+                .await;
+
+            let client = match client {
+                Ok(client) => client,
+                Err(e) => {
+                    warn_!("Unable to connect to Kafka brokers: {}", e);
+                    return;
+                }
+            };
+
+            if let Err(e) = client
+                .produce(vec![record], Compression::NoCompression)
+                .await
+            {
+                warn_!("Unable to produce Kafka event: {}", e);
+            }
+        });
+    }
+}
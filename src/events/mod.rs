@@ -0,0 +1,208 @@
+#[cfg(feature = "kafka")]
+mod kafka;
+#[cfg(feature = "nats")]
+mod nats;
+#[cfg(feature = "redis")]
+mod progress;
+#[cfg(feature = "webhooks")]
+mod webhook;
+
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaPublisher;
+#[cfg(feature = "nats")]
+pub use nats::NatsPublisher;
+#[cfg(feature = "redis")]
+pub use progress::{
+    ProgressSubscriber, RedisProgressPublisher, UploadProgress,
+};
+#[cfg(feature = "webhooks")]
+pub use webhook::WebhookPublisher;
+
+use std::time::Duration;
+
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    timeline::{rfc3339, Timestamp, UploadTimeline},
+    FileInfo, Metadata,
+};
+
+/// Identifies the kind of an [`UploadEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UploadEventKind {
+    Created,
+    /// Sent after each chunk received, except for the last one.
+    Progress,
+    Completed,
+    Terminated,
+}
+
+impl UploadEventKind {
+    /// Returns the short name of the event kind, like `created`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Progress => "progress",
+            Self::Completed => "completed",
+            Self::Terminated => "terminated",
+        }
+    }
+
+    /// Returns the [CloudEvents](https://cloudevents.io) `type` attribute of the event kind,
+    /// like `io.tus.upload.created`.
+    pub fn cloud_event_type(&self) -> &'static str {
+        match self {
+            Self::Created => "io.tus.upload.created",
+            Self::Progress => "io.tus.upload.progress",
+            Self::Completed => "io.tus.upload.completed",
+            Self::Terminated => "io.tus.upload.terminated",
+        }
+    }
+}
+
+/// An upload lifecycle event, sent to every registered [`EventPublisher`].
+#[derive(Debug, Clone)]
+pub struct UploadEvent {
+    id: String,
+    kind: UploadEventKind,
+    file_id: String,
+    length: u64,
+    offset: u64,
+    metadata: Option<Metadata>,
+    timestamp: Timestamp,
+    elapsed: Option<Duration>,
+}
+
+impl UploadEvent {
+    pub(crate) fn new<S>(
+        kind: UploadEventKind,
+        file: &FileInfo<S>,
+        offset: u64,
+        timestamp: Timestamp,
+        timeline: &UploadTimeline,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().simple().to_string(),
+            kind,
+            file_id: file.id().to_string(),
+            length: *file.length(),
+            offset,
+            metadata: file.metadata().clone(),
+            timestamp,
+            elapsed: timeline
+                .created()
+                .map(|created| timestamp.duration_since(&created)),
+        }
+    }
+
+    /// Returns the unique id of the event.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the kind of the event.
+    pub fn kind(&self) -> UploadEventKind {
+        self.kind
+    }
+
+    /// Returns the id of the upload.
+    pub fn file_id(&self) -> &str {
+        &self.file_id
+    }
+
+    /// Returns the `Upload-Length` of the upload.
+    pub fn length(&self) -> u64 {
+        self.length
+    }
+
+    /// Returns the offset of the upload when the event happened.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the metadata of the upload.
+    pub fn metadata(&self) -> &Option<Metadata> {
+        &self.metadata
+    }
+
+    /// Returns when the event happened.
+    pub fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
+    /// Returns the time elapsed since the upload creation, measured by a monotonic
+    /// clock, when the creation was observed by this server.
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.elapsed
+    }
+
+    /// Returns the event formatted as a structured [CloudEvents](https://cloudevents.io)
+    /// 1.0 JSON message, using the given `source` attribute.
+    ///
+    /// # Example
+    ///
+    /// ```json
+    /// {
+    ///   "specversion": "1.0",
+    ///   "id": "4b2d1ab4c5e04bd3b7d27e0a6b3b4a4f",
+    ///   "source": "meteoritus",
+    ///   "type": "io.tus.upload.completed",
+    ///   "subject": "0b6b4d8e",
+    ///   "time": "2024-09-09T12:00:00Z",
+    ///   "datacontenttype": "application/json",
+    ///   "data": {
+    ///     "id": "0b6b4d8e",
+    ///     "length": 1024,
+    ///     "offset": 1024,
+    ///     "metadata": { "filename": "bXlfdmlkZW8ubXA0" },
+    ///     "elapsed_ms": 5230
+    ///   }
+    /// }
+    /// ```
+    pub fn to_cloud_event(&self, source: &str) -> serde_json::Value {
+        json!({
+            "specversion": "1.0",
+            "id": self.id,
+            "source": source,
+            "type": self.kind.cloud_event_type(),
+            "subject": self.file_id,
+            "time": rfc3339(self.timestamp.wall()),
+            "datacontenttype": "application/json",
+            "data": {
+                "id": self.file_id,
+                "length": self.length,
+                "offset": self.offset,
+                "metadata": self.metadata,
+                "elapsed_ms": self.elapsed.map(|elapsed| elapsed.as_millis() as u64),
+            },
+        })
+    }
+}
+
+/// Publishes upload lifecycle events to external systems, like message brokers.
+///
+/// Publishers are registered through [`Meteoritus::with_event_publisher()`](crate::Meteoritus::with_event_publisher)
+/// and receive every [`UploadEvent`], so downstream processing pipelines can react
+/// to uploads without modifying the web application.
+///
+/// Since it is called from the request handlers, implementations should not block,
+/// consider spawning a task to deliver the event.
+///
+/// # Example
+///
+/// ```rust
+/// use meteoritus::{EventPublisher, UploadEvent};
+///
+/// struct StdoutPublisher;
+///
+/// impl EventPublisher for StdoutPublisher {
+///     fn publish(&self, event: &UploadEvent) {
+///         println!("{}", event.to_cloud_event("my-app"));
+///     }
+/// }
+/// ```
+pub trait EventPublisher: Send + Sync {
+    /// Publishes the given `event`.
+    fn publish(&self, event: &UploadEvent);
+}
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use async_nats::{Client, HeaderMap};
+use rocket::tokio::{self, sync::OnceCell};
+
+use super::{EventPublisher, UploadEvent};
+
+/// An [`EventPublisher`] sending [CloudEvents](https://cloudevents.io) to a
+/// [NATS](https://nats.io) server.
+///
+/// Each event is published to the `<subject>.<kind>` subject, like `uploads.completed`.
+/// The connection is established on the first published event.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Ignite;
+/// use meteoritus::{Meteoritus, NatsPublisher};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+///         .with_event_publisher(NatsPublisher::new("nats://localhost:4222", "uploads"))
+///         .build();
+///
+///     rocket::build().attach(meteoritus)
+/// }
+/// ```
+pub struct NatsPublisher {
+    url: String,
+    subject: String,
+    source: String,
+    client: Arc<OnceCell<Client>>,
+}
+
+impl NatsPublisher {
+    /// Creates a new [`NatsPublisher`] for the server at `url`, publishing under
+    /// the given `subject` prefix.
+    pub fn new(url: impl Into<String>, subject: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            subject: subject.into(),
+            source: "meteoritus".to_string(),
+            client: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// Specifies the CloudEvents `source` attribute, by default `meteoritus`.
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = source.into();
+        self
+    }
+}
+
+impl EventPublisher for NatsPublisher {
+    fn publish(&self, event: &UploadEvent) {
+        let url = self.url.to_owned();
+        let client = self.client.to_owned();
+        let subject = format!("{}.{}", self.subject, event.kind().as_str());
+        let payload = event.to_cloud_event(&self.source).to_string();
+
+        tokio::spawn(async move {
+            let client =
+                match client.get_or_try_init(|| async_nats::connect(url)).await
+                {
+                    Ok(client) => client,
+                    Err(e) => {
+                        warn_!("Unable to connect to NATS server: {}", e);
+                        return;
+                    }
+                };
+
+            let mut headers = HeaderMap::new();
+            headers.insert("Content-Type", "application/cloudevents+json");
+
+            if let Err(e) = client
+                .publish_with_headers(subject, headers, payload.into())
+                .await
+            {
+                warn_!("Unable to publish NATS event: {}", e);
+            }
+        });
+    }
+}
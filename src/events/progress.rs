@@ -0,0 +1,198 @@
+use redis::{aio::MultiplexedConnection, AsyncCommands, Client, RedisError};
+use rocket::{
+    futures::{future, Stream, StreamExt},
+    serde::{Deserialize, Serialize},
+    tokio::{self, sync::OnceCell},
+};
+use std::sync::Arc;
+
+use super::{EventPublisher, UploadEvent, UploadEventKind};
+
+/// A progress update of an upload, broadcasted by [`RedisProgressPublisher`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct UploadProgress {
+    id: String,
+    offset: u64,
+    length: u64,
+}
+
+impl UploadProgress {
+    /// Returns the id of the upload.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the current offset of the upload.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the `Upload-Length` of the upload.
+    pub fn length(&self) -> u64 {
+        self.length
+    }
+
+    /// Indicates if the upload is completed.
+    pub fn is_completed(&self) -> bool {
+        self.offset == self.length
+    }
+}
+
+/// An [`EventPublisher`] broadcasting the upload progress to [Redis](https://redis.io) channels.
+///
+/// For horizontally scaled deployments, each received chunk publishes an [`UploadProgress`]
+/// JSON message into the `<prefix>:<upload id>` channel, so a progress API or websocket
+/// gateway running on another node can surface it through a [`ProgressSubscriber`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Ignite;
+/// use meteoritus::{Meteoritus, RedisProgressPublisher};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let publisher =
+///         RedisProgressPublisher::new("redis://localhost:6379", "uploads").unwrap();
+///
+///     let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+///         .with_event_publisher(publisher)
+///         .build();
+///
+///     rocket::build().attach(meteoritus)
+/// }
+/// ```
+pub struct RedisProgressPublisher {
+    client: Client,
+    prefix: String,
+    connection: Arc<OnceCell<MultiplexedConnection>>,
+}
+
+impl RedisProgressPublisher {
+    /// Creates a new [`RedisProgressPublisher`] for the server at `url`, publishing
+    /// into channels with the given `prefix`.
+    ///
+    /// The connection is established on the first published progress.
+    pub fn new(
+        url: &str,
+        prefix: impl Into<String>,
+    ) -> Result<Self, RedisError> {
+        Ok(Self {
+            client: Client::open(url)?,
+            prefix: prefix.into(),
+            connection: Arc::new(OnceCell::new()),
+        })
+    }
+}
+
+impl EventPublisher for RedisProgressPublisher {
+    fn publish(&self, event: &UploadEvent) {
+        if !matches!(
+            event.kind(),
+            UploadEventKind::Progress | UploadEventKind::Completed
+        ) {
+            return;
+        }
+
+        let progress = UploadProgress {
+            id: event.file_id().to_string(),
+            offset: event.offset(),
+            length: event.length(),
+        };
+
+        let Ok(payload) = serde_json::to_string(&progress) else {
+            return;
+        };
+
+        let channel = format!("{}:{}", self.prefix, progress.id);
+        let client = self.client.to_owned();
+        let connection = self.connection.to_owned();
+
+        tokio::spawn(async move {
+            let connection = connection
+                .get_or_try_init(|| client.get_multiplexed_async_connection())
+                .await;
+
+            let mut connection = match connection {
+                Ok(connection) => connection.to_owned(),
+                Err(e) => {
+                    warn_!("Unable to connect to Redis server: {}", e);
+                    return;
+                }
+            };
+
+            if let Err(e) =
+                connection.publish::<_, _, ()>(channel, payload).await
+            {
+                warn_!("Unable to publish upload progress: {}", e);
+            }
+        });
+    }
+}
+
+/// Subscribes to the upload progress broadcasted by [`RedisProgressPublisher`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rocket::futures::StreamExt;
+/// use meteoritus::ProgressSubscriber;
+///
+/// # rocket::execute(async {
+/// let subscriber =
+///     ProgressSubscriber::new("redis://localhost:6379", "uploads").unwrap();
+///
+/// let mut progress = subscriber.subscribe("0b6b4d8e").await.unwrap();
+///
+/// while let Some(progress) = progress.next().await {
+///     println!("{}/{}", progress.offset(), progress.length());
+///
+///     if progress.is_completed() {
+///         break;
+///     }
+/// }
+/// # });
+/// ```
+pub struct ProgressSubscriber {
+    client: Client,
+    prefix: String,
+}
+
+impl ProgressSubscriber {
+    /// Creates a new [`ProgressSubscriber`] for the server at `url`, listening to
+    /// channels with the given `prefix`.
+    pub fn new(
+        url: &str,
+        prefix: impl Into<String>,
+    ) -> Result<Self, RedisError> {
+        Ok(Self {
+            client: Client::open(url)?,
+            prefix: prefix.into(),
+        })
+    }
+
+    /// Returns a stream with the progress updates of the given upload.
+    ///
+    /// Each subscription opens its own connection, closed once the stream is dropped.
+    pub async fn subscribe(
+        &self,
+        file_id: &str,
+    ) -> Result<impl Stream<Item = UploadProgress>, RedisError> {
+        let mut pubsub = self.client.get_async_pubsub().await?;
+
+        pubsub
+            .subscribe(format!("{}:{}", self.prefix, file_id))
+            .await?;
+
+        Ok(pubsub.into_on_message().filter_map(|msg| {
+            let progress = msg
+                .get_payload::<String>()
+                .ok()
+                .and_then(|payload| serde_json::from_str(&payload).ok());
+
+            future::ready(progress)
+        }))
+    }
+}
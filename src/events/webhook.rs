@@ -0,0 +1,92 @@
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE},
+    Client,
+};
+use rocket::tokio;
+
+use super::{EventPublisher, UploadEvent};
+
+/// An [`EventPublisher`] posting [CloudEvents](https://cloudevents.io) to an HTTP
+/// endpoint, like a tusd `http` hook receiver.
+///
+/// Each event is sent as the body of a `POST` request, in the structured content
+/// mode. Failed deliveries are logged and not retried.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Ignite;
+/// use meteoritus::{Meteoritus, WebhookPublisher};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let webhook = WebhookPublisher::new("https://hooks.example.com/uploads")
+///         .with_header("Authorization", "Bearer secret");
+///
+///     let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+///         .with_event_publisher(webhook)
+///         .build();
+///
+///     rocket::build().attach(meteoritus)
+/// }
+/// ```
+pub struct WebhookPublisher {
+    url: String,
+    source: String,
+    headers: HeaderMap,
+    client: Client,
+}
+
+impl WebhookPublisher {
+    /// Creates a new [`WebhookPublisher`] posting the events to `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            source: "meteoritus".to_string(),
+            headers: HeaderMap::new(),
+            client: Client::new(),
+        }
+    }
+
+    /// Specifies the CloudEvents `source` attribute, by default `meteoritus`.
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = source.into();
+        self
+    }
+
+    /// Adds a header to every request, like an `Authorization` shared with the
+    /// receiver.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` or `value` aren't valid header characters.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        let name = HeaderName::try_from(name).expect("invalid header name");
+        let value = HeaderValue::try_from(value).expect("invalid header value");
+
+        self.headers.insert(name, value);
+        self
+    }
+}
+
+impl EventPublisher for WebhookPublisher {
+    fn publish(&self, event: &UploadEvent) {
+        let request = self
+            .client
+            .post(&self.url)
+            .headers(self.headers.to_owned())
+            .header(CONTENT_TYPE, "application/cloudevents+json")
+            .body(event.to_cloud_event(&self.source).to_string());
+
+        tokio::spawn(async move {
+            match request.send().await {
+                Ok(res) if !res.status().is_success() => {
+                    warn_!("Webhook rejected the event: {}", res.status());
+                }
+                Ok(_) => (),
+                Err(e) => warn_!("Unable to deliver webhook event: {}", e),
+            }
+        });
+    }
+}
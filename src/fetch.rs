@@ -0,0 +1,226 @@
+use std::{collections::HashSet, error::Error, sync::Arc, sync::Mutex};
+
+use reqwest::{redirect, Client, Url};
+use rocket::{http::Status, tokio, Orbit};
+
+use crate::{
+    fs::PatchOption,
+    handlers::{ErrorCode, TusError},
+    FileInfo, Meteoritus, UploadEventKind, Vault,
+};
+
+/// The metadata key holding the remote URL of uploads fetched by the server.
+pub(crate) const SOURCE_URL_KEY: &str = "sourceUrl";
+
+/// Downloads remote content into the vault on behalf of clients, restricted to an
+/// allow-list of hosts.
+pub(crate) struct RemoteFetch {
+    hosts: Arc<Vec<String>>,
+    client: Client,
+    in_progress: Mutex<HashSet<String>>,
+}
+
+impl RemoteFetch {
+    pub(crate) fn new(hosts: Vec<String>) -> Self {
+        let hosts = Arc::new(
+            hosts
+                .into_iter()
+                .map(|host| host.to_ascii_lowercase())
+                .collect::<Vec<_>>(),
+        );
+
+        /* Redirects must not lead outside of the allowed hosts */
+        let redirect_hosts = hosts.to_owned();
+        let policy = redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= 10 {
+                attempt.error("too many redirects")
+            } else if is_allowed(&redirect_hosts, attempt.url()) {
+                attempt.follow()
+            } else {
+                attempt.stop()
+            }
+        });
+
+        let client = Client::builder()
+            .redirect(policy)
+            .build()
+            .expect("Unable to build the remote fetch client");
+
+        Self {
+            hosts,
+            client,
+            in_progress: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Returns the remote URL declared by the upload metadata, if any.
+    pub(crate) fn source_of<S>(
+        &self,
+        file: &FileInfo<S>,
+    ) -> Result<Option<Url>, TusError> {
+        let Some(value) = file
+            .metadata()
+            .as_ref()
+            .and_then(|metadata| metadata.get_raw(SOURCE_URL_KEY).ok())
+        else {
+            return Ok(None);
+        };
+
+        let url = String::from_utf8(value)
+            .ok()
+            .and_then(|value| Url::parse(&value).ok())
+            .filter(|url| matches!(url.scheme(), "http" | "https"));
+
+        let Some(url) = url else {
+            return Err(TusError::new(
+                Status::BadRequest,
+                ErrorCode::InvalidSourceUrl,
+                "Invalid sourceUrl metadata",
+            ));
+        };
+
+        if !is_allowed(&self.hosts, &url) {
+            return Err(TusError::new(
+                Status::Forbidden,
+                ErrorCode::SourceHostNotAllowed,
+                "Host of sourceUrl is not allowed",
+            ));
+        }
+
+        Ok(Some(url))
+    }
+
+    /// Indicates if the content of the given upload is being fetched.
+    pub(crate) fn is_fetching(&self, file_id: &str) -> bool {
+        self.in_progress.lock().unwrap().contains(file_id)
+    }
+
+    /// Downloads `source` into the given upload in background, terminating the upload
+    /// when the download fails.
+    pub(crate) fn spawn(
+        self: Arc<Self>,
+        meteoritus: Meteoritus<Orbit>,
+        vault: Arc<dyn Vault>,
+        file_id: &str,
+        source: Url,
+    ) {
+        let file_id = file_id.to_string();
+
+        self.in_progress.lock().unwrap().insert(file_id.to_owned());
+
+        tokio::spawn(async move {
+            let result = self
+                .download(&meteoritus, vault.as_ref(), &file_id, source)
+                .await;
+
+            self.in_progress.lock().unwrap().remove(&file_id);
+
+            if let Err(e) = result {
+                warn_!("Unable to fetch upload {}: {}", file_id, e);
+
+                meteoritus.forget_upload(&file_id);
+
+                if vault.terminate_file(&file_id).is_err() {
+                    warn_!("Unable to terminate failed upload: {}", file_id);
+                }
+            }
+        });
+    }
+
+    async fn download(
+        &self,
+        meteoritus: &Meteoritus<Orbit>,
+        vault: &dyn Vault,
+        file_id: &str,
+        source: Url,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut response = self.client.get(source).send().await?;
+
+        /* Also covers redirects to hosts outside of the allow-list */
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(format!("Remote responded {status}").into());
+        }
+
+        let mut file = vault
+            .get_file(file_id)
+            .map_err(|_| "Unable to read the upload")?;
+
+        if file.is_length_deferred() {
+            let length = response
+                .content_length()
+                .ok_or("Remote content has no length")?;
+
+            if length > meteoritus.max_size().as_u64() {
+                return Err("Remote content exceeds the Tus-Max-Size".into());
+            }
+
+            file = vault
+                .declare_length(file_id, length)
+                .map_err(|_| "Unable to update the upload length")?;
+        } else if response
+            .content_length()
+            .is_some_and(|length| length != *file.length())
+        {
+            return Err(
+                "Remote content does not match the Upload-Length".into()
+            );
+        }
+
+        let mut offset = *file.offset();
+
+        while let Some(chunk) = response.chunk().await? {
+            if chunk.len() as u64 > file.length() - offset {
+                return Err("Remote content exceeds the Upload-Length".into());
+            }
+
+            let mut data = chunk.to_vec();
+
+            match vault
+                .patch_file(file_id, &mut data, offset)
+                .map_err(|_| "Unable to store the fetched chunk")?
+            {
+                PatchOption::Patched(patched) => {
+                    offset = patched;
+
+                    meteoritus.touch_upload(file_id);
+                    meteoritus.publish_event(
+                        UploadEventKind::Progress,
+                        &file,
+                        offset,
+                    );
+                }
+                PatchOption::Completed(completed) => {
+                    meteoritus.untrack_upload(file_id);
+                    meteoritus.await_ack(file_id);
+                    meteoritus.publish_event(
+                        UploadEventKind::Completed,
+                        &completed,
+                        *completed.length(),
+                    );
+
+                    if meteoritus.has_processors() {
+                        meteoritus.process_completed(completed);
+                    } else if meteoritus.terminate_completed(file_id).is_err() {
+                        warn_!(
+                            "Unable to terminate the completed upload: {}",
+                            file_id
+                        );
+                    }
+
+                    return Ok(());
+                }
+            }
+        }
+
+        Err("Remote content ended before the Upload-Length".into())
+    }
+}
+
+fn is_allowed(hosts: &[String], url: &Url) -> bool {
+    url.host_str().is_some_and(|host| {
+        hosts
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(host))
+    })
+}
@@ -0,0 +1,40 @@
+use std::{collections::HashMap, sync::Mutex};
+
+/// Indexes the uploads in progress by their requester and client fingerprint.
+#[derive(Default)]
+pub(crate) struct FingerprintIndex {
+    entries: Mutex<HashMap<(String, String), String>>,
+}
+
+impl FingerprintIndex {
+    pub(crate) fn find(
+        &self,
+        requester: &str,
+        fingerprint: &str,
+    ) -> Option<String> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&(requester.to_string(), fingerprint.to_string()))
+            .cloned()
+    }
+
+    pub(crate) fn insert(
+        &self,
+        requester: String,
+        fingerprint: String,
+        file_id: &str,
+    ) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert((requester, fingerprint), file_id.to_string());
+    }
+
+    pub(crate) fn remove(&self, requester: &str, fingerprint: &str) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&(requester.to_string(), fingerprint.to_string()));
+    }
+}
@@ -0,0 +1,479 @@
+use std::{
+    collections::HashMap,
+    io::{self, ErrorKind, Read},
+    path::Path,
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use super::{
+    file_info::{Built, Created, FileInfo, Quarantined, Terminated},
+    metadata::Metadata,
+    stats::UploadStats,
+    vault::{PatchOption, Vault, VaultError},
+};
+
+/* Records of the stored content, each starting with its tag */
+const LENGTH_RECORD: u8 = b'L';
+const FRAME_RECORD: u8 = b'F';
+
+/// The header of a record of the stored content.
+enum Record {
+    /// Declares the logical length of the upload.
+    Length(u64),
+    /// A compressed chunk, followed by its `compressed` bytes.
+    Frame { length: u64, compressed: u64 },
+}
+
+impl Record {
+    /// Reads the next record header, returning `None` at the end of the content.
+    fn read(content: &mut impl Read) -> io::Result<Option<Self>> {
+        let mut tag = [0];
+
+        if content.read(&mut tag)? == 0 {
+            return Ok(None);
+        }
+
+        let mut read_u64 = || -> io::Result<u64> {
+            let mut bytes = [0; 8];
+            content.read_exact(&mut bytes)?;
+            Ok(u64::from_le_bytes(bytes))
+        };
+
+        match tag[0] {
+            LENGTH_RECORD => Ok(Some(Self::Length(read_u64()?))),
+            FRAME_RECORD => Ok(Some(Self::Frame {
+                length: read_u64()?,
+                compressed: read_u64()?,
+            })),
+            _ => Err(ErrorKind::InvalidData.into()),
+        }
+    }
+
+    fn encode_length(length: u64) -> Vec<u8> {
+        let mut record = vec![LENGTH_RECORD];
+        record.extend_from_slice(&length.to_le_bytes());
+        record
+    }
+
+    fn encode_frame(chunk: &[u8], level: i32) -> io::Result<Vec<u8>> {
+        let compressed = zstd::bulk::compress(chunk, level)?;
+
+        let mut record = vec![FRAME_RECORD];
+        record.extend_from_slice(&(chunk.len() as u64).to_le_bytes());
+        record.extend_from_slice(&(compressed.len() as u64).to_le_bytes());
+        record.extend_from_slice(&compressed);
+
+        Ok(record)
+    }
+}
+
+/// The logical progress of an upload, apart from the stored offset of the inner vault.
+#[derive(Debug, Clone, Copy)]
+struct Progress {
+    length: Option<u64>,
+    offset: u64,
+    stored: u64,
+}
+
+/// A [`Vault`] storing the chunks of an `inner` vault compressed with zstd, reducing
+/// the storage costs of text-heavy uploads.
+///
+/// Each chunk is stored as a compressed frame, while the lengths and offsets reported
+/// to the tus handlers stay the uncompressed ones. The content is decompressed on read
+/// through [`Vault::open_content()`], also used when exporting uploads.
+///
+/// The `inner` vault must support [`Vault::declare_length()`], since it stores an
+/// upload of unknown compressed length until its last chunk.
+///
+/// **Note:** [`FileInfo::storage_ref()`] points to the compressed content, so callbacks
+/// must read uploads through the vault rather than from their path.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Ignite;
+/// use meteoritus::{CompressedVault, LocalVault, Meteoritus};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let vault = CompressedVault::new(LocalVault::new("./tmp/uploads")).with_level(9);
+///
+///     let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+///         .with_vault(vault)
+///         .build();
+///
+///     rocket::build().attach(meteoritus)
+/// }
+/// ```
+pub struct CompressedVault<V: Vault> {
+    inner: V,
+    level: i32,
+    progress: Mutex<HashMap<String, Progress>>,
+}
+
+impl<V: Vault> CompressedVault<V> {
+    /// Creates a [`CompressedVault`] compressing the chunks stored into `inner` with
+    /// the default zstd level.
+    pub fn new(inner: V) -> Self {
+        Self {
+            inner,
+            level: zstd::DEFAULT_COMPRESSION_LEVEL,
+            progress: Default::default(),
+        }
+    }
+
+    /// Sets the zstd compression `level`, trading speed for smaller content.
+    pub fn with_level(mut self, level: i32) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Returns the progress of the given upload, reading it from the stored content
+    /// when not known yet, like after a restart.
+    fn progress(&self, file_id: &str) -> Result<Progress, VaultError> {
+        if let Some(progress) = self
+            .progress
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(file_id)
+        {
+            return Ok(*progress);
+        }
+
+        let stored = *self.inner.get_file(file_id)?.offset();
+
+        let mut content = self.inner.open_content(file_id)?.take(stored);
+
+        let mut progress = Progress {
+            length: None,
+            offset: 0,
+            stored,
+        };
+
+        while let Some(record) = Record::read(&mut content)? {
+            match record {
+                Record::Length(length) => progress.length = Some(length),
+                Record::Frame { length, compressed } => {
+                    let skipped = io::copy(
+                        &mut (&mut content).take(compressed),
+                        &mut io::sink(),
+                    )?;
+
+                    if skipped != compressed {
+                        return Err(VaultError::Io(
+                            ErrorKind::UnexpectedEof.into(),
+                        ));
+                    }
+
+                    progress.offset += length;
+                }
+            }
+        }
+
+        self.track(file_id, progress);
+
+        Ok(progress)
+    }
+
+    fn track(&self, file_id: &str, progress: Progress) {
+        self.progress
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(file_id.to_string(), progress);
+    }
+
+    fn untrack(&self, file_id: &str) {
+        self.progress
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(file_id);
+    }
+
+    /// Replaces the stored length and offset of `file` by the logical ones.
+    fn logical<State>(
+        &self,
+        file: FileInfo<State>,
+    ) -> Result<FileInfo<State>, VaultError> {
+        let progress = self.progress(file.id())?;
+
+        Ok(file.with_progress(progress.length, progress.offset))
+    }
+}
+
+/// Reads the decompressed content out of the stored records.
+struct Decompressor {
+    content: io::Take<Box<dyn Read + Send>>,
+    frame: io::Cursor<Vec<u8>>,
+}
+
+impl Read for Decompressor {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let read = self.frame.read(buf)?;
+
+            if read > 0 || buf.is_empty() {
+                return Ok(read);
+            }
+
+            match Record::read(&mut self.content)? {
+                None => return Ok(0),
+                Some(Record::Length(_)) => continue,
+                Some(Record::Frame { length, compressed }) => {
+                    let mut data = Vec::new();
+                    (&mut self.content)
+                        .take(compressed)
+                        .read_to_end(&mut data)?;
+
+                    if data.len() as u64 != compressed {
+                        return Err(ErrorKind::UnexpectedEof.into());
+                    }
+
+                    let chunk = zstd::bulk::decompress(&data, length as usize)?;
+                    self.frame = io::Cursor::new(chunk);
+                }
+            }
+        }
+    }
+}
+
+impl<V: Vault> Vault for CompressedVault<V> {
+    fn build_file(
+        &self,
+        length: u64,
+        metadata: Option<&str>,
+    ) -> Result<FileInfo<Built>, VaultError> {
+        self.inner.build_file(length, metadata)
+    }
+
+    fn create_file(
+        &self,
+        file: FileInfo<Built>,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let length = (!file.is_length_deferred()).then(|| *file.length());
+
+        /* The compressed length is only known once the last chunk is stored */
+        let created = self.inner.create_file(file.with_deferred_length())?;
+
+        let mut progress = Progress {
+            length,
+            offset: 0,
+            stored: 0,
+        };
+
+        if let Some(length) = length {
+            let mut record = Record::encode_length(length);
+
+            if let Err(e) = self.inner.patch_file(created.id(), &mut record, 0)
+            {
+                let _ = self.inner.terminate_file(created.id());
+                return Err(e);
+            }
+
+            progress.stored = record.len() as u64;
+        }
+
+        self.track(created.id(), progress);
+
+        Ok(created.with_progress(length, 0))
+    }
+
+    fn exists(&self, file_id: &str) -> bool {
+        self.inner.exists(file_id)
+    }
+
+    fn get_file(&self, file_id: &str) -> Result<FileInfo<Created>, VaultError> {
+        self.logical(self.inner.get_file(file_id)?)
+    }
+
+    fn patch_file(
+        &self,
+        file_id: &str,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        let progress = self.progress(file_id)?;
+
+        if progress.offset != offset {
+            return Err(VaultError::Conflict(format!(
+                "upload is at offset {}",
+                progress.offset
+            )));
+        }
+
+        let offset = offset + buf.len() as u64;
+
+        if progress.length.is_some_and(|length| offset > length) {
+            return Err(VaultError::Invalid(
+                "chunk exceeds the upload length".into(),
+            ));
+        }
+
+        let mut record = Record::encode_frame(buf, self.level)?;
+
+        /* Fixing the stored length ahead of the last chunk, so the inner vault
+        completes along. Retried chunks compress into the same record */
+        if progress.length == Some(offset) {
+            self.inner.declare_length(
+                file_id,
+                progress.stored + record.len() as u64,
+            )?;
+        }
+
+        let patched =
+            self.inner
+                .patch_file(file_id, &mut record, progress.stored)?;
+
+        let stored = match &patched {
+            PatchOption::Patched(stored) => *stored,
+            PatchOption::Completed(file) => *file.length(),
+        };
+
+        self.track(
+            file_id,
+            Progress {
+                offset,
+                stored,
+                ..progress
+            },
+        );
+
+        match patched {
+            PatchOption::Patched(_) => Ok(PatchOption::Patched(offset)),
+            PatchOption::Completed(file) => Ok(PatchOption::Completed(
+                file.with_progress(progress.length, offset),
+            )),
+        }
+    }
+
+    fn terminate_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Terminated>, VaultError> {
+        let progress = self.progress(file_id)?;
+
+        let file = self.inner.terminate_file(file_id)?;
+
+        self.untrack(file_id);
+
+        Ok(file.with_progress(progress.length, progress.offset))
+    }
+
+    fn declare_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let progress = self.progress(file_id)?;
+
+        if progress.length.is_some() || length < progress.offset {
+            return Err(VaultError::Invalid(
+                io::Error::from(ErrorKind::InvalidInput).into(),
+            ));
+        }
+
+        let mut record = Record::encode_length(length);
+
+        let stored = match self.inner.patch_file(
+            file_id,
+            &mut record,
+            progress.stored,
+        )? {
+            PatchOption::Patched(stored) => stored,
+            PatchOption::Completed(file) => *file.length(),
+        };
+
+        self.track(
+            file_id,
+            Progress {
+                length: Some(length),
+                stored,
+                ..progress
+            },
+        );
+
+        self.get_file(file_id)
+    }
+
+    fn repair_offset(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        /* Reading the progress again from the repaired content */
+        self.untrack(file_id);
+
+        self.logical(self.inner.repair_offset(file_id)?)
+    }
+
+    fn quarantine_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Quarantined>, VaultError> {
+        self.logical(self.inner.quarantine_file(file_id)?)
+    }
+
+    fn open_content(
+        &self,
+        file_id: &str,
+    ) -> Result<Box<dyn Read + Send>, VaultError> {
+        let progress = self.progress(file_id)?;
+
+        let content = self.inner.open_content(file_id)?.take(progress.stored);
+
+        Ok(Box::new(Decompressor {
+            content,
+            frame: Default::default(),
+        }))
+    }
+
+    fn update_metadata(
+        &self,
+        file_id: &str,
+        metadata: Metadata,
+    ) -> Result<(), VaultError> {
+        self.inner.update_metadata(file_id, metadata)
+    }
+
+    fn update_upload_token(
+        &self,
+        file_id: &str,
+        token: &str,
+    ) -> Result<(), VaultError> {
+        self.inner.update_upload_token(file_id, token)
+    }
+
+    fn update_stats(
+        &self,
+        file_id: &str,
+        stats: &UploadStats,
+    ) -> Result<(), VaultError> {
+        self.inner.update_stats(file_id, stats)
+    }
+
+    fn schedule_termination(
+        &self,
+        file_id: &str,
+        time: SystemTime,
+    ) -> Result<(), VaultError> {
+        self.inner.schedule_termination(file_id, time)
+    }
+
+    fn list_quarantined(
+        &self,
+    ) -> Result<Vec<FileInfo<Quarantined>>, VaultError> {
+        self.inner
+            .list_quarantined()?
+            .into_iter()
+            .map(|file| self.logical(file))
+            .collect()
+    }
+
+    fn storage_path(&self) -> Option<&Path> {
+        self.inner.storage_path()
+    }
+
+    fn list_files(&self) -> Result<Vec<String>, VaultError> {
+        self.inner.list_files()
+    }
+}
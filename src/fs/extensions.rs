@@ -0,0 +1,182 @@
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::BTreeMap,
+    fmt,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use rocket::serde::{
+    de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::Value;
+
+/// A typed map of values attached to an upload by the application, so callbacks can
+/// share derived values, like the user id or tenant resolved by `on_creation`,
+/// without re-parsing the [`Metadata`](crate::Metadata).
+///
+/// Values are keyed by their type, holding a single value per type:
+/// - [`Extensions::insert()`] values are serialized along with the [`FileInfo`](crate::FileInfo),
+///   so they are persisted by vaults storing it, like [`LocalVault`](crate::LocalVault),
+///   and readable by every later callback of the upload.
+/// - [`Extensions::insert_local()`] values are held in memory by the current
+///   [`FileInfo`](crate::FileInfo) only, for values that can't be serialized but are
+///   needed along the request, like from `on_creation` to `on_created`.
+///
+/// Clones hold copies of the persisted values, while sharing the in-memory ones, and
+/// comparisons only consider the persisted values.
+///
+/// **Note:** Persisted values are keyed by their type name, so renaming or moving the
+/// type makes previously stored values unreachable.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::{serde::{Deserialize, Serialize}, Ignite};
+/// use meteoritus::Meteoritus;
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[serde(crate = "rocket::serde")]
+/// struct Tenant(String);
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+///         .on_creation(|ctx| {
+///             ctx.extensions().insert(Tenant("acme".to_string()))?;
+///             Ok(())
+///         })
+///         .on_completed(|ctx| {
+///             if let Some(Tenant(tenant)) = ctx.extensions().get::<Tenant>() {
+///                 println!("Upload completed for tenant: {tenant}");
+///             }
+///         })
+///         .build();
+///
+///     rocket::build().attach(meteoritus)
+/// }
+/// ```
+#[derive(Default)]
+pub struct Extensions {
+    entries: Mutex<Entries>,
+}
+
+#[derive(Default)]
+struct Entries {
+    persisted: BTreeMap<String, Value>,
+    local: BTreeMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl Extensions {
+    /// Stores a serializable `value`, replacing any previous value of the same type.
+    pub fn insert<T: Serialize + 'static>(
+        &self,
+        value: T,
+    ) -> Result<(), serde_json::Error> {
+        let value = serde_json::to_value(value)?;
+
+        self.entries()
+            .persisted
+            .insert(type_name::<T>().to_string(), value);
+
+        Ok(())
+    }
+
+    /// Returns the stored value of type `T`, if any.
+    pub fn get<T: DeserializeOwned + 'static>(&self) -> Option<T> {
+        let value = self.entries().persisted.get(type_name::<T>())?.to_owned();
+
+        serde_json::from_value(value).ok()
+    }
+
+    /// Holds a `value` in memory, replacing any previous value of the same type.
+    pub fn insert_local<T: Send + Sync + 'static>(&self, value: T) {
+        self.entries()
+            .local
+            .insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    /// Returns a copy of the in-memory value of type `T`, if any.
+    pub fn get_local<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+        self.entries()
+            .local
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>())
+            .cloned()
+    }
+
+    /// Returns `true` when no value is stored.
+    pub fn is_empty(&self) -> bool {
+        let entries = self.entries();
+
+        entries.persisted.is_empty() && entries.local.is_empty()
+    }
+
+    pub(crate) fn is_persisted_empty(&self) -> bool {
+        self.entries().persisted.is_empty()
+    }
+
+    fn entries(&self) -> MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Clone for Extensions {
+    fn clone(&self) -> Self {
+        let entries = self.entries();
+
+        Self {
+            entries: Mutex::new(Entries {
+                persisted: entries.persisted.clone(),
+                local: entries.local.clone(),
+            }),
+        }
+    }
+}
+
+impl PartialEq for Extensions {
+    fn eq(&self, other: &Self) -> bool {
+        if std::ptr::eq(self, other) {
+            return true;
+        }
+
+        self.entries().persisted == other.entries().persisted
+    }
+}
+
+impl Eq for Extensions {}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self.entries();
+
+        f.debug_struct("Extensions")
+            .field("persisted", &entries.persisted)
+            .field("local", &entries.local.len())
+            .finish()
+    }
+}
+
+impl Serialize for Extensions {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.entries().persisted.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Extensions {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let persisted = BTreeMap::deserialize(deserializer)?;
+
+        Ok(Self {
+            entries: Mutex::new(Entries {
+                persisted,
+                local: BTreeMap::new(),
+            }),
+        })
+    }
+}
@@ -1,40 +1,219 @@
 use rocket::serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::fs::metadata::Metadata;
+use crate::{
+    fs::{
+        extensions::Extensions, metadata::Metadata, ranges::ReceivedRanges,
+        stats::UploadStats, uploader::UploaderInfo,
+    },
+    storage_class::storage_class,
+    sweeper::{ScheduledTermination, UploadDeadline},
+};
 use std::{
+    collections::BTreeMap,
+    fmt,
     io::{Error, ErrorKind, Result},
     marker::PhantomData,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::SystemTime,
 };
 
-/// Indicates the [`FileInfo`] `Created` state.
-#[derive(Default, Debug)]
+/// Indicates the [`FileInfo`] `Building` state.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Building;
 
 /// Indicates the [`FileInfo`] `Built` state.
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Built;
 
 /// Indicates the [`FileInfo`] `Created` state.
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Created;
 
 /// Indicates the [`FileInfo`] `Completed` state.
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Completed;
 
 /// Indicates the [`FileInfo`] `Terminated` state.
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Terminated;
 
+/// Indicates the [`FileInfo`] `Quarantined` state.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quarantined;
+
+/// The lifecycle state of an upload, persisted along with its [`FileInfo`] so
+/// external systems reading the stored uploads reason about them consistently.
+///
+/// Unlike the typestates, like [`Created`], which the compiler enforces while an
+/// upload is handled, the state is known at runtime and follows these transitions:
+///
+/// ```text
+/// Created ──> InProgress ──> Completed
+///    │            │              │
+///    └────────────┴──────────────┴──> Terminated, Expired or Quarantined
+/// ```
+///
+/// Quarantined uploads can still be terminated, while [`UploadState::Terminated`]
+/// and [`UploadState::Expired`] are final.
+#[derive(
+    Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(crate = "rocket::serde", rename_all = "kebab-case")]
+pub enum UploadState {
+    /// The upload was created, without any stored byte yet.
+    #[default]
+    Created,
+    /// Some bytes of the upload were stored.
+    InProgress,
+    /// Every byte of the upload was stored.
+    Completed,
+    /// The upload was terminated, like by the client or the application.
+    Terminated,
+    /// The upload was terminated by the server, for missing its deadline or
+    /// stalling.
+    Expired,
+    /// The upload was flagged, like by a scanner, and can't be accessed through
+    /// the tus routes anymore.
+    Quarantined,
+}
+
+impl UploadState {
+    /// Returns `true` when the upload can move from this state to the `next` one.
+    pub fn can_transition_to(self, next: UploadState) -> bool {
+        use UploadState::*;
+
+        matches!(
+            (self, next),
+            (Created | InProgress, InProgress | Completed)
+                | (Created | InProgress | Completed, Expired | Quarantined)
+                | (Created | InProgress | Completed | Quarantined, Terminated)
+        )
+    }
+
+    /// Returns `true` for the final states, which have no transition.
+    pub fn is_final(self) -> bool {
+        matches!(self, UploadState::Terminated | UploadState::Expired)
+    }
+}
+
+/// The textual format of generated upload ids.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UuidFormat {
+    /// UUIDs without hyphens, like `67e5504410b1426f9247bb680e5fe0c8`.
+    #[default]
+    Simple,
+    /// UUIDs with hyphens, like `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+    Hyphenated,
+}
+
+impl UuidFormat {
+    /// Formats the given `uuid`.
+    pub(crate) fn format(&self, uuid: Uuid) -> String {
+        match self {
+            Self::Simple => uuid.simple().to_string(),
+            Self::Hyphenated => uuid.hyphenated().to_string(),
+        }
+    }
+}
+
+/// Locates the content of an upload inside its storage backend.
+///
+/// Locations holding a URI scheme, like `s3://bucket/uploads/<id>`, are exposed as
+/// backend specific keys, while any other location is a path on disk.
+///
+/// # Example
+///
+/// ```rust
+/// use std::path::Path;
+/// use meteoritus::StorageRef;
+///
+/// let local = StorageRef::from("./tmp/uploads/42/file");
+/// assert_eq!(local.as_path(), Some(Path::new("./tmp/uploads/42/file")));
+///
+/// let remote = StorageRef::from("s3://bucket/uploads/42");
+/// assert_eq!(remote, StorageRef::Key("s3://bucket/uploads/42".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageRef {
+    /// The upload content is a file at the given path.
+    Path(PathBuf),
+    /// The upload content is an object of a remote backend, like a bucket key.
+    Key(String),
+}
+
+impl StorageRef {
+    /// Returns the path of the upload content, when stored on disk.
+    pub fn as_path(&self) -> Option<&Path> {
+        match self {
+            Self::Path(path) => Some(path),
+            Self::Key(_) => None,
+        }
+    }
+}
+
+impl From<&str> for StorageRef {
+    fn from(location: &str) -> Self {
+        match location.split_once("://") {
+            Some((scheme, _))
+                if !scheme.is_empty()
+                    && scheme.chars().all(|c| {
+                        c.is_ascii_alphanumeric()
+                            || matches!(c, '+' | '-' | '.')
+                    }) =>
+            {
+                Self::Key(location.to_string())
+            }
+            _ => Self::Path(PathBuf::from(location)),
+        }
+    }
+}
+
+impl fmt::Display for StorageRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Path(path) => write!(f, "{}", path.display()),
+            Self::Key(key) => write!(f, "{key}"),
+        }
+    }
+}
+
 /// A struct representing a file and its metadata during various stages of processing.
 ///
-/// The struct has four possible states: [`Built`], [`Created`], [`Completed`] and [`Terminated`].
+/// The struct has six possible states: [`Building`], [`Built`], [`Created`], [`Completed`],
+/// [`Terminated`] and [`Quarantined`].
+/// - [`Building`] - The file instance is being built by a [`Vault`](crate::Vault).
 /// - [`Built`] - The file instances has been built and is ready to create information on disk.
 /// - [`Created`] - The file information has been saved on disk.
 /// - [`Completed`] - The file has been fully processed and is ready to be used.
 /// - [`Terminated`] - The file has been terminated and is no longer saved on disk.
-#[derive(Default, Debug, Serialize, Deserialize)]
+/// - [`Quarantined`] - The file has been flagged, like by a scanner, and can't be accessed
+///   through the tus routes anymore.
+///
+/// Those states are enforced at compile time, while the runtime [`UploadState`] of
+/// the upload is persisted along with it, see [`FileInfo::upload_state()`].
+///
+/// A [`FileInfo`] of any state can be cloned, like to move it into a spawned task,
+/// and serialized, deserializing back into an equal [`FileInfo`]. Only the values
+/// held in memory by its [`Extensions`] are not serialized, and they are ignored
+/// when comparing.
+///
+/// # Example
+///
+/// ```rust
+/// use meteoritus::{Built, FileInfo, LocalVault, Vault};
+///
+/// let vault = LocalVault::new("./tmp/uploads");
+/// let file = vault.build_file(1024, Some("filename cmVwb3J0LnBkZg=="))?;
+///
+/// let json = serde_json::to_string(&file)?;
+/// let restored: FileInfo<Built> = serde_json::from_str(&json)?;
+///
+/// assert_eq!(restored, file.clone());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(crate = "rocket::serde")]
 pub struct FileInfo<State = Building> {
     id: String,
@@ -42,11 +221,35 @@ pub struct FileInfo<State = Building> {
     length: u64,
     offset: u64,
     metadata: Option<Metadata>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    length_deferred: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    quarantined: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sniffed_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Extensions::is_persisted_empty")]
+    extensions: Extensions,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    captured_headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    upload_token: Option<String>,
+    #[serde(default, skip_serializing_if = "UploaderInfo::is_empty")]
+    uploader: UploaderInfo,
+    #[serde(default)]
+    upload_state: UploadState,
+    #[serde(default, skip_serializing_if = "UploadStats::is_empty")]
+    stats: UploadStats,
+    #[serde(default, skip_serializing_if = "ReceivedRanges::is_empty")]
+    received_ranges: ReceivedRanges,
 
     #[serde(skip)]
     state: PhantomData<State>,
 }
 
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
 impl<State> FileInfo<State> {
     pub fn id(&self) -> &str {
         &self.id
@@ -59,31 +262,147 @@ impl<State> FileInfo<State> {
     pub fn metadata(&self) -> &Option<Metadata> {
         &self.metadata
     }
+
+    /// Returns `true` while the upload length is not known yet, meaning it was created
+    /// with `Upload-Defer-Length` and [`FileInfo::length()`] is meaningless.
+    pub fn is_length_deferred(&self) -> bool {
+        self.length_deferred
+    }
+
+    /// Returns `true` when the upload has been quarantined.
+    pub fn is_quarantined(&self) -> bool {
+        self.quarantined
+    }
+
+    /// Returns the [`Extensions`] attached to the upload by the application.
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    /// Returns the deadline of the upload, as set by the `on_creation` callback
+    /// through [`HandlerContext::set_deadline()`](crate::HandlerContext::set_deadline).
+    pub fn deadline(&self) -> Option<SystemTime> {
+        self.extensions
+            .get::<UploadDeadline>()
+            .map(|deadline| deadline.time())
+    }
+
+    /// Returns when the completed upload is due to be terminated, as scheduled by
+    /// [`TerminationPolicy::Delayed`](crate::TerminationPolicy::Delayed).
+    pub fn scheduled_termination(&self) -> Option<SystemTime> {
+        self.extensions
+            .get::<ScheduledTermination>()
+            .map(|scheduled| scheduled.time())
+    }
+
+    pub(crate) fn schedule_termination(&mut self, time: SystemTime) {
+        /* Serializing the UNIX seconds can't fail */
+        let _ = self.extensions.insert(ScheduledTermination::new(time));
+    }
+
+    /// Returns the storage class hint of the upload, declared by the client through
+    /// the `meteoritus-storage-class` metadata, like `GLACIER` or `ARCHIVE`.
+    ///
+    /// Cloud vaults, like the `ColdStorage` of a `TieredVault`, can use it to store archival uploads directly in cheaper
+    /// tiers. Hints are only validated when
+    /// [`Meteoritus::with_storage_classes()`](crate::Meteoritus::with_storage_classes)
+    /// is configured.
+    pub fn storage_class(&self) -> Option<String> {
+        storage_class(self.metadata.as_ref())
+    }
+
+    /// Returns the request headers captured when the upload was created, keyed by
+    /// their configured name, see
+    /// [`Meteoritus::with_captured_headers()`](crate::Meteoritus::with_captured_headers).
+    pub fn captured_headers(&self) -> &BTreeMap<String, String> {
+        &self.captured_headers
+    }
+
+    /// Returns the information captured about the client which created the upload.
+    pub fn uploader(&self) -> &UploaderInfo {
+        &self.uploader
+    }
+
+    /// Returns the lifecycle state of the upload.
+    pub fn upload_state(&self) -> UploadState {
+        self.upload_state
+    }
+
+    /// Returns the transfer statistics of the upload.
+    pub fn stats(&self) -> &UploadStats {
+        &self.stats
+    }
+
+    pub(crate) fn set_stats(&mut self, stats: UploadStats) {
+        self.stats = stats;
+    }
+
+    /// Returns the ranges received past the offset through parallel chunks, see
+    /// [`Meteoritus::with_parallel_chunks()`](crate::Meteoritus::with_parallel_chunks).
+    pub fn received_ranges(&self) -> &ReceivedRanges {
+        &self.received_ranges
+    }
+
+    /// Moves the upload to the `next` state, ignoring invalid transitions.
+    fn transition(&mut self, next: UploadState) {
+        if self.upload_state.can_transition_to(next) {
+            self.upload_state = next;
+        }
+    }
+
+    pub(crate) fn set_metadata(&mut self, metadata: Metadata) {
+        self.metadata = Some(metadata);
+    }
+
+    /// Returns the one-time token required by the next `PATCH` request, when
+    /// [`Meteoritus::with_token_rotation()`](crate::Meteoritus::with_token_rotation)
+    /// is enabled.
+    pub(crate) fn upload_token(&self) -> Option<&str> {
+        self.upload_token.as_deref()
+    }
+
+    pub(crate) fn set_upload_token(&mut self, token: String) {
+        self.upload_token = Some(token);
+    }
+
+    /// Overrides the length and offset of the upload by the ones tracked by a vault
+    /// decorator, a `None` length meaning a deferred one.
+    #[cfg(feature = "compression")]
+    pub(crate) fn with_progress(
+        mut self,
+        length: Option<u64>,
+        offset: u64,
+    ) -> Self {
+        self.length = length.unwrap_or_default();
+        self.length_deferred = length.is_none();
+        self.offset = offset;
+        self
+    }
 }
 
 impl FileInfo<Building> {
-    pub(super) fn new(length: u64) -> Self {
+    pub(crate) fn new(length: u64) -> Self {
         Self {
             length,
             ..Default::default()
         }
     }
 
-    pub(super) fn with_uuid(self) -> Self {
+    pub(crate) fn with_uuid(self) -> Self {
         self.with_raw_id(Uuid::new_v4().simple().to_string())
     }
 
-    pub(super) fn with_raw_id(mut self, id: String) -> Self {
+    pub(crate) fn with_raw_id(mut self, id: String) -> Self {
         self.id = id;
         self
     }
 
-    pub(super) fn with_metadata(mut self, metadata: Metadata) -> Self {
+    pub(crate) fn with_metadata(mut self, metadata: Metadata) -> Self {
         self.metadata = Some(metadata);
         self
     }
 
-    pub(super) fn build(self) -> FileInfo<Built> {
+    pub(crate) fn build(self) -> FileInfo<Built> {
         FileInfo::<Built> {
             state: std::marker::PhantomData,
             id: self.id,
@@ -91,6 +410,16 @@ impl FileInfo<Building> {
             offset: self.offset,
             metadata: self.metadata,
             file_name: self.file_name,
+            length_deferred: self.length_deferred,
+            quarantined: self.quarantined,
+            sniffed_type: self.sniffed_type,
+            extensions: self.extensions,
+            captured_headers: self.captured_headers,
+            upload_token: self.upload_token,
+            uploader: self.uploader,
+            upload_state: self.upload_state,
+            stats: self.stats,
+            received_ranges: self.received_ranges,
         }
         /* Consider Update to: #![feature(type_changing_struct_update)]
         FileInfo::<Built> {
@@ -101,7 +430,36 @@ impl FileInfo<Building> {
 }
 
 impl FileInfo<Built> {
-    pub(super) fn mark_as_created(self, file_name: &str) -> FileInfo<Created> {
+    pub(crate) fn with_id(mut self, id: String) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub(crate) fn with_captured_headers(
+        mut self,
+        headers: BTreeMap<String, String>,
+    ) -> Self {
+        self.captured_headers = headers;
+        self
+    }
+
+    pub(crate) fn with_upload_token(mut self, token: String) -> Self {
+        self.upload_token = Some(token);
+        self
+    }
+
+    pub(crate) fn with_uploader(mut self, uploader: UploaderInfo) -> Self {
+        self.uploader = uploader;
+        self
+    }
+
+    pub(crate) fn with_deferred_length(mut self) -> Self {
+        self.length = 0;
+        self.length_deferred = true;
+        self
+    }
+
+    pub(crate) fn mark_as_created(self, file_name: &str) -> FileInfo<Created> {
         FileInfo::<Created> {
             file_name: file_name.to_string(),
             state: std::marker::PhantomData,
@@ -109,6 +467,16 @@ impl FileInfo<Built> {
             length: self.length,
             offset: self.offset,
             metadata: self.metadata,
+            length_deferred: self.length_deferred,
+            quarantined: self.quarantined,
+            sniffed_type: self.sniffed_type,
+            extensions: self.extensions,
+            captured_headers: self.captured_headers,
+            upload_token: self.upload_token,
+            uploader: self.uploader,
+            upload_state: self.upload_state,
+            stats: self.stats,
+            received_ranges: self.received_ranges,
         }
         /* Consider Update to: #![feature(type_changing_struct_update)]
         FileInfo::<Created> {
@@ -124,21 +492,80 @@ impl FileInfo<Created> {
         &self.offset
     }
 
-    pub(super) fn set_offset(&mut self, offset: u64) -> Result<()> {
-        if offset > self.length {
+    /// Returns where the file content is stored
+    pub fn storage_ref(&self) -> StorageRef {
+        StorageRef::from(self.file_name.as_str())
+    }
+
+    pub(crate) fn set_offset(&mut self, offset: u64) -> Result<()> {
+        if !self.length_deferred && offset > self.length {
             return Err(Error::from(ErrorKind::OutOfMemory));
         }
 
-        self.offset = offset;
+        /* Moving over the ranges already received past the offset */
+        self.offset = self.received_ranges.absorb(offset);
+        self.track_progress();
 
         Ok(())
     }
 
-    pub(crate) fn check_completion(self) -> Option<FileInfo<Completed>> {
-        if self.offset != self.length {
+    /// Records the `range` received through a parallel chunk, moving the offset
+    /// over the bytes stored contiguously.
+    pub(crate) fn receive_range(&mut self, range: Range<u64>) -> Result<()> {
+        if self.length_deferred || range.end > self.length {
+            return Err(Error::from(ErrorKind::InvalidInput));
+        }
+
+        self.received_ranges.insert(range);
+
+        self.set_offset(self.offset)
+    }
+
+    /// Moves the state of an upload in progress according to its offset.
+    fn track_progress(&mut self) {
+        match (self.length_deferred, self.offset) {
+            (false, offset) if offset == self.length => {
+                self.transition(UploadState::Completed)
+            }
+            (_, 0) => (),
+            _ => self.transition(UploadState::InProgress),
+        }
+    }
+
+    /// Fixes the length of an upload created with a deferred length.
+    pub(crate) fn declare_length(&mut self, length: u64) -> Result<()> {
+        if !self.length_deferred || length < self.offset {
+            return Err(Error::from(ErrorKind::InvalidInput));
+        }
+
+        self.length = length;
+        self.length_deferred = false;
+        self.track_progress();
+
+        Ok(())
+    }
+
+    /// Enlarges the declared length of an upload which isn't completed yet.
+    pub(crate) fn increase_length(&mut self, length: u64) -> Result<()> {
+        if self.length_deferred
+            || length <= self.length
+            || self.offset == self.length
+        {
+            return Err(Error::from(ErrorKind::InvalidInput));
+        }
+
+        self.length = length;
+
+        Ok(())
+    }
+
+    pub(crate) fn check_completion(mut self) -> Option<FileInfo<Completed>> {
+        if self.length_deferred || self.offset != self.length {
             return None;
         }
 
+        self.transition(UploadState::Completed);
+
         Some(FileInfo::<Completed> {
             state: std::marker::PhantomData,
             id: self.id,
@@ -146,6 +573,16 @@ impl FileInfo<Created> {
             offset: self.offset,
             metadata: self.metadata,
             file_name: self.file_name,
+            length_deferred: false,
+            quarantined: self.quarantined,
+            sniffed_type: self.sniffed_type,
+            extensions: self.extensions,
+            captured_headers: self.captured_headers,
+            upload_token: self.upload_token,
+            uploader: self.uploader,
+            upload_state: self.upload_state,
+            stats: self.stats,
+            received_ranges: self.received_ranges,
         })
         /* Consider Update to: #![feature(type_changing_struct_update)]
         Some(FileInfo::<Completed> {
@@ -153,6 +590,52 @@ impl FileInfo<Created> {
             ..self
         }) */
     }
+
+    pub(crate) fn mark_as_quarantined(mut self) -> FileInfo<Quarantined> {
+        self.transition(UploadState::Quarantined);
+
+        FileInfo::<Quarantined> {
+            state: std::marker::PhantomData,
+            id: self.id,
+            length: self.length,
+            offset: self.offset,
+            metadata: self.metadata,
+            file_name: self.file_name,
+            length_deferred: self.length_deferred,
+            quarantined: true,
+            sniffed_type: self.sniffed_type,
+            extensions: self.extensions,
+            captured_headers: self.captured_headers,
+            upload_token: self.upload_token,
+            uploader: self.uploader,
+            upload_state: self.upload_state,
+            stats: self.stats,
+            received_ranges: self.received_ranges,
+        }
+    }
+
+    pub(crate) fn mark_as_terminated(mut self) -> FileInfo<Terminated> {
+        self.transition(UploadState::Terminated);
+
+        FileInfo::<Terminated> {
+            state: std::marker::PhantomData,
+            id: self.id,
+            length: self.length,
+            offset: self.offset,
+            metadata: self.metadata,
+            file_name: self.file_name,
+            length_deferred: self.length_deferred,
+            quarantined: self.quarantined,
+            sniffed_type: self.sniffed_type,
+            extensions: self.extensions,
+            captured_headers: self.captured_headers,
+            upload_token: self.upload_token,
+            uploader: self.uploader,
+            upload_state: self.upload_state,
+            stats: self.stats,
+            received_ranges: self.received_ranges,
+        }
+    }
 }
 
 impl FileInfo<Completed> {
@@ -160,6 +643,36 @@ impl FileInfo<Completed> {
     pub fn file_name(&self) -> &String {
         &self.file_name
     }
+
+    /// Returns where the file content is stored
+    pub fn storage_ref(&self) -> StorageRef {
+        StorageRef::from(self.file_name.as_str())
+    }
+
+    /// Returns the content type detected from the content magic bytes, when MIME
+    /// sniffing is enabled and the type could be detected.
+    pub fn sniffed_type(&self) -> Option<&str> {
+        self.sniffed_type.as_deref()
+    }
+
+    #[cfg(feature = "mime-sniffing")]
+    pub(crate) fn with_sniffed_type(mut self, sniffed_type: String) -> Self {
+        self.sniffed_type = Some(sniffed_type);
+        self
+    }
+
+    #[cfg(feature = "mime-sniffing")]
+    pub(crate) fn with_quarantine(mut self) -> Self {
+        self.quarantined = true;
+        self.transition(UploadState::Quarantined);
+        self
+    }
+
+    #[cfg(feature = "tiered")]
+    pub(crate) fn with_file_name(mut self, file_name: String) -> Self {
+        self.file_name = file_name;
+        self
+    }
 }
 
 impl FileInfo<Terminated> {
@@ -167,8 +680,80 @@ impl FileInfo<Terminated> {
         &self.offset
     }
 
+    /// Flags an upload terminated by the server, like for missing its deadline.
+    pub(crate) fn mark_as_expired(mut self) -> Self {
+        self.upload_state = UploadState::Expired;
+        self
+    }
+
     /// Returns where the file was located
     pub fn file_name(&self) -> &String {
         &self.file_name
     }
+
+    /// Returns where the file content was stored
+    pub fn storage_ref(&self) -> StorageRef {
+        StorageRef::from(self.file_name.as_str())
+    }
+}
+
+impl FileInfo<Quarantined> {
+    pub fn offset(&self) -> &u64 {
+        &self.offset
+    }
+
+    /// Returns where the file content is stored
+    pub fn storage_ref(&self) -> StorageRef {
+        StorageRef::from(self.file_name.as_str())
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<State> FileInfo<State> {
+    /// Sets the deadline of the upload to the given `time`, so tests can simulate
+    /// uploads past their deadline, or about to reach it, without sleeping.
+    ///
+    /// The deadline is compared against the configured
+    /// [`Clock`](crate::Clock), like a [`ManualClock`](crate::testing::ManualClock),
+    /// truncated to the second.
+    ///
+    /// This method is only available when the `testing` feature is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::{Duration, SystemTime};
+    /// use meteoritus::testing::{upload_request, FileInfoBuilder, MockVault};
+    /// use meteoritus::Meteoritus;
+    /// use rocket::{http::Status, local::asynchronous::Client};
+    ///
+    /// # rocket::execute(async {
+    /// let vault = MockVault::new();
+    ///
+    /// let file = FileInfoBuilder::new(5)
+    ///     .id("expired")
+    ///     .created()
+    ///     .expired_at(SystemTime::now() - Duration::from_secs(60));
+    /// vault.insert(file, Vec::new());
+    ///
+    /// let meteoritus = Meteoritus::new()
+    ///     .mount_to("/files")
+    ///     .with_vault(vault)
+    ///     .build();
+    ///
+    /// let client = Client::tracked(rocket::build().attach(meteoritus))
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// let res = upload_request(&client, "/files/expired", 0, "hello")
+    ///     .dispatch()
+    ///     .await;
+    /// assert_eq!(res.status(), Status::Gone);
+    /// # });
+    /// ```
+    pub fn expired_at(self, time: SystemTime) -> Self {
+        /* Serializing the UNIX seconds can't fail */
+        let _ = self.extensions.insert(UploadDeadline::new(time));
+        self
+    }
 }
@@ -0,0 +1,223 @@
+use std::{
+    io::Read,
+    path::Path,
+    sync::Arc,
+    time::{Instant, SystemTime},
+};
+
+use crate::telemetry::{
+    TelemetrySink, VAULT_ERRORS_TOTAL, VAULT_OPERATION_SECONDS,
+};
+
+use super::{
+    file_info::{Built, Created, FileInfo, Quarantined, Terminated},
+    metadata::Metadata,
+    stats::UploadStats,
+    vault::{PatchOption, Vault, VaultError},
+};
+
+/// A [`Vault`] decorator recording the duration and failures of every operation
+/// of the `inner` vault into a [`TelemetrySink`], see
+/// [`Meteoritus::with_telemetry()`](crate::Meteoritus::with_telemetry).
+pub(crate) struct InstrumentedVault {
+    inner: Arc<dyn Vault>,
+    sink: Arc<dyn TelemetrySink>,
+}
+
+impl InstrumentedVault {
+    pub(crate) fn new(
+        inner: Arc<dyn Vault>,
+        sink: Arc<dyn TelemetrySink>,
+    ) -> Self {
+        Self { inner, sink }
+    }
+
+    /// Runs the given vault `operation`, recording its duration and failure.
+    fn measure<T>(
+        &self,
+        operation: &str,
+        f: impl FnOnce() -> Result<T, VaultError>,
+    ) -> Result<T, VaultError> {
+        let started = Instant::now();
+        let result = f();
+        let labels = [("operation", operation)];
+
+        self.sink.histogram(
+            VAULT_OPERATION_SECONDS,
+            started.elapsed().as_secs_f64(),
+            &labels,
+        );
+
+        if result.is_err() {
+            self.sink.counter(VAULT_ERRORS_TOTAL, 1, &labels);
+        }
+
+        result
+    }
+}
+
+impl Vault for InstrumentedVault {
+    fn build_file(
+        &self,
+        length: u64,
+        metadata: Option<&str>,
+    ) -> Result<FileInfo<Built>, VaultError> {
+        self.inner.build_file(length, metadata)
+    }
+
+    fn create_file(
+        &self,
+        file: FileInfo<Built>,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.measure("create_file", || self.inner.create_file(file))
+    }
+
+    fn exists(&self, file_id: &str) -> bool {
+        self.inner.exists(file_id)
+    }
+
+    fn get_file(&self, file_id: &str) -> Result<FileInfo<Created>, VaultError> {
+        self.measure("get_file", || self.inner.get_file(file_id))
+    }
+
+    fn patch_file(
+        &self,
+        file_id: &str,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        self.measure("patch_file", || {
+            self.inner.patch_file(file_id, buf, offset)
+        })
+    }
+
+    fn write_range(
+        &self,
+        file_id: &str,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        self.measure("write_range", || {
+            self.inner.write_range(file_id, buf, offset)
+        })
+    }
+
+    fn terminate_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Terminated>, VaultError> {
+        self.measure("terminate_file", || self.inner.terminate_file(file_id))
+    }
+
+    fn declare_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.measure("declare_length", || {
+            self.inner.declare_length(file_id, length)
+        })
+    }
+
+    fn increase_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.measure("increase_length", || {
+            self.inner.increase_length(file_id, length)
+        })
+    }
+
+    fn repair_offset(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.measure("repair_offset", || self.inner.repair_offset(file_id))
+    }
+
+    fn repair_range(
+        &self,
+        file_id: &str,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<(), VaultError> {
+        self.measure("repair_range", || {
+            self.inner.repair_range(file_id, buf, offset)
+        })
+    }
+
+    fn quarantine_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Quarantined>, VaultError> {
+        self.measure("quarantine_file", || self.inner.quarantine_file(file_id))
+    }
+
+    fn open_content(
+        &self,
+        file_id: &str,
+    ) -> Result<Box<dyn Read + Send>, VaultError> {
+        self.measure("open_content", || self.inner.open_content(file_id))
+    }
+
+    fn update_metadata(
+        &self,
+        file_id: &str,
+        metadata: Metadata,
+    ) -> Result<(), VaultError> {
+        self.measure("update_metadata", || {
+            self.inner.update_metadata(file_id, metadata)
+        })
+    }
+
+    fn update_upload_token(
+        &self,
+        file_id: &str,
+        token: &str,
+    ) -> Result<(), VaultError> {
+        self.measure("update_upload_token", || {
+            self.inner.update_upload_token(file_id, token)
+        })
+    }
+
+    fn update_stats(
+        &self,
+        file_id: &str,
+        stats: &UploadStats,
+    ) -> Result<(), VaultError> {
+        self.measure("update_stats", || self.inner.update_stats(file_id, stats))
+    }
+
+    fn schedule_termination(
+        &self,
+        file_id: &str,
+        time: SystemTime,
+    ) -> Result<(), VaultError> {
+        self.measure("schedule_termination", || {
+            self.inner.schedule_termination(file_id, time)
+        })
+    }
+
+    fn list_quarantined(
+        &self,
+    ) -> Result<Vec<FileInfo<Quarantined>>, VaultError> {
+        self.measure("list_quarantined", || self.inner.list_quarantined())
+    }
+
+    fn storage_path(&self) -> Option<&Path> {
+        self.inner.storage_path()
+    }
+
+    fn list_files(&self) -> Result<Vec<String>, VaultError> {
+        self.measure("list_files", || self.inner.list_files())
+    }
+
+    fn export_to(
+        &self,
+        target: &dyn Vault,
+        file_id: &str,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.measure("export_to", || self.inner.export_to(target, file_id))
+    }
+}
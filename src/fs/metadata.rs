@@ -5,7 +5,7 @@ use std::{collections::HashMap, error::Error, fmt::Display};
 /// A struct representing the metadata associated with an uploaded file.
 ///
 /// Metadata is a wrapper around a `HashMap` that holds metadata for a tus upload.
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(crate = "rocket::serde")]
 pub struct Metadata(HashMap<String, String>);
 
@@ -64,10 +64,48 @@ impl Metadata {
         }
     }
 
+    /// Inserts the given raw binary `value`, encoded as Base64, under the given key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use meteoritus::Metadata;
+    ///
+    /// let mut metadata = Metadata::new();
+    /// metadata.insert("thumbnail", b"thumbnails/42.png");
+    ///
+    /// assert_eq!(metadata.get_raw("thumbnail"), Ok(b"thumbnails/42.png".to_vec()));
+    /// ```
+    pub fn insert(&mut self, key: &str, value: &[u8]) {
+        let value = base64::engine::general_purpose::STANDARD.encode(value);
+        self.0.insert(key.to_string(), value);
+    }
+
+    /// Removes the entry of the given key, if any.
+    pub(crate) fn remove(&mut self, key: &str) {
+        self.0.remove(key);
+    }
+
     /// Returns the number of elements in the metadata.
     pub fn len(&self) -> usize {
         self.0.len()
     }
+
+    /// Returns `true` if the metadata contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns all values decoded as UTF-8 strings, skipping undecodable ones.
+    pub(crate) fn decoded(&self) -> HashMap<String, String> {
+        self.0
+            .keys()
+            .filter_map(|key| {
+                let value = self.get_raw(key).ok()?;
+                Some((key.to_owned(), String::from_utf8(value).ok()?))
+            })
+            .collect()
+    }
 }
 
 impl TryFrom<&str> for Metadata {
@@ -117,7 +155,7 @@ impl TryFrom<&str> for Metadata {
                 return Err(MetadataError::InvalidKey);
             }
 
-            if let (Some(key), value) = (parts.get(0), parts.get(1)) {
+            if let (Some(key), value) = (parts.first(), parts.get(1)) {
                 let value = match value {
                     Some(v) => v.to_string(),
                     None => String::default(),
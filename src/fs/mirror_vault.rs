@@ -0,0 +1,731 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{ErrorKind, Read},
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+        Arc, Mutex, MutexGuard, OnceLock,
+    },
+    thread::{self, JoinHandle},
+    time::SystemTime,
+};
+
+use super::{
+    file_info::{Built, Created, FileInfo, Quarantined, Terminated},
+    metadata::Metadata,
+    stats::UploadStats,
+    vault::{PatchOption, Vault, VaultError},
+};
+
+type DivergenceHandler = Arc<dyn Fn(&str, &Divergence) + Send + Sync>;
+
+/// The default number of operations queued for replication.
+const DEFAULT_QUEUE_CAPACITY: usize = 1024;
+
+/// How the copy of an upload in the secondary vault of a [`MirrorVault`] differs
+/// from the primary one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Divergence {
+    /// An operation couldn't be replicated, so the copy stopped being mirrored.
+    Replication(String),
+    /// The copy of the completed upload doesn't hold all of its bytes.
+    Offset { expected: u64, actual: u64 },
+    /// The content of the copy doesn't match the completed upload.
+    Content,
+    /// The replication queue was full, so an operation had to be dropped.
+    Overflow,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Replication(error) => {
+                write!(f, "replication failed: {error}")
+            }
+            Self::Offset { expected, actual } => {
+                write!(f, "offset {actual} differs from length {expected}")
+            }
+            Self::Content => write!(f, "content mismatch"),
+            Self::Overflow => write!(f, "replication queue is full"),
+        }
+    }
+}
+
+/// A [`Vault`] storing uploads in a `primary` vault and replicating every write
+/// to a `secondary` one in the background, so in-progress uploads have a hot
+/// copy in another backend.
+///
+/// Clients only wait for the primary vault, which serves all reads. Replicated
+/// operations are applied to the secondary vault in order by a single worker, and
+/// the copy of each completed upload is compared with the primary one.
+///
+/// An upload diverges when one of its operations can't be replicated, or when its
+/// completed copy doesn't match the primary content, in which case it stops being
+/// mirrored and the divergence is reported through
+/// [`MirrorVault::on_divergence()`], until the upload is terminated.
+///
+/// Operations wait for the secondary vault in a bounded queue, holding a copy of
+/// their chunks, see [`MirrorVault::with_queue_capacity()`]. Rather than stalling
+/// clients or buffering more chunks, uploads with an operation not fitting the
+/// queue diverge with [`Divergence::Overflow`].
+///
+/// The worker is started by the first replicated operation, and stops once the
+/// vault is dropped, like when Rocket shuts down, which waits for the queued
+/// operations to be replicated.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Ignite;
+/// use meteoritus::{LocalVault, Meteoritus, MirrorVault};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let vault = MirrorVault::new(
+///         LocalVault::new("./tmp/uploads"),
+///         LocalVault::new("/mnt/replica/uploads"),
+///     )
+///     .on_divergence(|file_id, divergence| {
+///         eprintln!("Mirror of {file_id} diverged: {divergence}");
+///     });
+///
+///     let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+///         .with_vault(vault)
+///         .build();
+///
+///     rocket::build().attach(meteoritus)
+/// }
+/// ```
+pub struct MirrorVault<A: Vault, B: Vault> {
+    primary: Arc<A>,
+    secondary: Arc<B>,
+    divergences: Divergences,
+    pending: Arc<AtomicUsize>,
+    queue_capacity: usize,
+    worker: OnceLock<(SyncSender<Replication>, JoinHandle<()>)>,
+}
+
+impl<A: Vault + 'static, B: Vault + 'static> MirrorVault<A, B> {
+    /// Creates a [`MirrorVault`] storing into `primary` and replicating into
+    /// `secondary`.
+    pub fn new(primary: A, secondary: B) -> Self {
+        Self {
+            primary: Arc::new(primary),
+            secondary: Arc::new(secondary),
+            divergences: Default::default(),
+            pending: Default::default(),
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+            worker: OnceLock::new(),
+        }
+    }
+
+    /// Sets how many operations can wait for the secondary vault, defaulting to
+    /// 1024, which bounds the memory held by chunks not replicated yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn with_queue_capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "the replication queue can't be empty");
+
+        self.queue_capacity = capacity;
+        self
+    }
+
+    /// Sets a callback invoked from the replication worker when the copy of an
+    /// upload diverges.
+    pub fn on_divergence<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&str, &Divergence) + Send + Sync + 'static,
+    {
+        self.divergences.handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Returns how the copy of the given upload diverged, if it did.
+    pub fn divergence(&self, file_id: &str) -> Option<Divergence> {
+        self.divergences.get(file_id)
+    }
+
+    /// Returns the number of operations waiting to be replicated.
+    pub fn pending_replications(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    /// Queues the given operation, starting the replication worker on first use.
+    fn replicate(&self, replication: Replication) {
+        let (sender, _) = self.worker.get_or_init(|| {
+            let (sender, receiver) = mpsc::sync_channel(self.queue_capacity);
+
+            let replicator = Replicator {
+                primary: self.primary.to_owned(),
+                secondary: self.secondary.to_owned(),
+                divergences: self.divergences.to_owned(),
+            };
+            let pending = self.pending.to_owned();
+
+            let worker =
+                thread::spawn(move || replicator.run(receiver, pending));
+
+            (sender, worker)
+        });
+
+        let file_id = replication.file_id().to_owned();
+
+        self.pending.fetch_add(1, Ordering::SeqCst);
+
+        let sent = match replication {
+            /* Waiting for room, so copies don't outlive their uploads */
+            Replication::Terminate(_) => sender
+                .send(replication)
+                .map_err(|e| TrySendError::Disconnected(e.0)),
+            _ => sender.try_send(replication),
+        };
+
+        if let Err(e) = sent {
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+
+            match e {
+                TrySendError::Full(_) => {
+                    self.divergences.record(&file_id, Divergence::Overflow)
+                }
+                TrySendError::Disconnected(_) => {
+                    warn_!("Mirror replication worker has stopped")
+                }
+            }
+        }
+    }
+
+    /// Replicates a stored chunk, verifying the copy once the upload completed.
+    fn replicate_chunk(&self, replication: Replication, patched: &PatchOption) {
+        let file_id = replication.file_id().to_owned();
+
+        self.replicate(replication);
+
+        if let PatchOption::Completed(file) = patched {
+            self.replicate(Replication::Verify {
+                file_id,
+                length: *file.length(),
+            });
+        }
+    }
+}
+
+/// An operation of the primary vault, applied to the secondary one.
+enum Replication {
+    Create(Box<FileInfo<Built>>),
+    Patch {
+        file_id: String,
+        buf: Vec<u8>,
+        offset: u64,
+    },
+    WriteRange {
+        file_id: String,
+        buf: Vec<u8>,
+        offset: u64,
+    },
+    Verify {
+        file_id: String,
+        length: u64,
+    },
+    Terminate(String),
+    DeclareLength {
+        file_id: String,
+        length: u64,
+    },
+    IncreaseLength {
+        file_id: String,
+        length: u64,
+    },
+    RepairOffset(String),
+    RepairRange {
+        file_id: String,
+        buf: Vec<u8>,
+        offset: u64,
+    },
+    Quarantine(String),
+    UpdateMetadata {
+        file_id: String,
+        metadata: Metadata,
+    },
+    UpdateUploadToken {
+        file_id: String,
+        token: String,
+    },
+    UpdateStats {
+        file_id: String,
+        stats: UploadStats,
+    },
+    ScheduleTermination {
+        file_id: String,
+        time: SystemTime,
+    },
+}
+
+impl Replication {
+    fn file_id(&self) -> &str {
+        match self {
+            Self::Create(file) => file.id(),
+            Self::Terminate(file_id)
+            | Self::RepairOffset(file_id)
+            | Self::Quarantine(file_id)
+            | Self::Patch { file_id, .. }
+            | Self::WriteRange { file_id, .. }
+            | Self::Verify { file_id, .. }
+            | Self::DeclareLength { file_id, .. }
+            | Self::IncreaseLength { file_id, .. }
+            | Self::RepairRange { file_id, .. }
+            | Self::UpdateMetadata { file_id, .. }
+            | Self::UpdateUploadToken { file_id, .. }
+            | Self::UpdateStats { file_id, .. }
+            | Self::ScheduleTermination { file_id, .. } => file_id,
+        }
+    }
+}
+
+impl<A: Vault, B: Vault> Drop for MirrorVault<A, B> {
+    fn drop(&mut self) {
+        /* Closing the queue, so the worker stops once it is drained */
+        if let Some((sender, worker)) = self.worker.take() {
+            drop(sender);
+
+            if worker.join().is_err() {
+                warn_!("Mirror replication worker has panicked");
+            }
+        }
+    }
+}
+
+/// The diverged uploads of a [`MirrorVault`], along with the callback reporting
+/// them.
+#[derive(Clone, Default)]
+struct Divergences {
+    entries: Arc<Mutex<HashMap<String, Divergence>>>,
+    handler: Option<DivergenceHandler>,
+}
+
+impl Divergences {
+    fn get(&self, file_id: &str) -> Option<Divergence> {
+        self.lock().get(file_id).cloned()
+    }
+
+    fn contains(&self, file_id: &str) -> bool {
+        self.lock().contains_key(file_id)
+    }
+
+    fn remove(&self, file_id: &str) {
+        self.lock().remove(file_id);
+    }
+
+    /// Records the first divergence of the given upload, reporting it.
+    fn record(&self, file_id: &str, divergence: Divergence) {
+        if self.contains(file_id) {
+            return;
+        }
+
+        warn_!("Mirror of upload {} diverged: {}", file_id, divergence);
+
+        if let Some(handler) = &self.handler {
+            handler(file_id, &divergence);
+        }
+
+        self.lock().insert(file_id.to_owned(), divergence);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Divergence>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The worker applying the replicated operations to the secondary vault.
+struct Replicator<A, B> {
+    primary: Arc<A>,
+    secondary: Arc<B>,
+    divergences: Divergences,
+}
+
+impl<A: Vault, B: Vault> Replicator<A, B> {
+    fn run(self, receiver: Receiver<Replication>, pending: Arc<AtomicUsize>) {
+        for replication in receiver {
+            self.apply(replication);
+            pending.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    fn apply(&self, replication: Replication) {
+        let file_id = replication.file_id().to_owned();
+
+        if let Replication::Terminate(_) = replication {
+            /* Even diverged copies must not outlive the upload */
+            if let Err(e) = self.secondary.terminate_file(&file_id) {
+                warn_!(
+                    "Unable to terminate mirror of upload {}: {}",
+                    file_id,
+                    e
+                );
+            }
+
+            self.divergences.remove(&file_id);
+
+            return;
+        }
+
+        /* Later operations would only fail on a copy left behind */
+        if self.divergences.contains(&file_id) {
+            return;
+        }
+
+        let secondary = &self.secondary;
+
+        let result = match replication {
+            Replication::Create(file) => secondary.create_file(*file).map(drop),
+            Replication::Patch {
+                file_id,
+                mut buf,
+                offset,
+            } => secondary.patch_file(&file_id, &mut buf, offset).map(drop),
+            Replication::WriteRange {
+                file_id,
+                buf,
+                offset,
+            } => secondary.write_range(&file_id, &buf, offset).map(drop),
+            Replication::Verify { file_id, length } => {
+                self.verify(&file_id, length)
+            }
+            Replication::Terminate(_) => Ok(()),
+            Replication::DeclareLength { file_id, length } => {
+                secondary.declare_length(&file_id, length).map(drop)
+            }
+            Replication::IncreaseLength { file_id, length } => {
+                secondary.increase_length(&file_id, length).map(drop)
+            }
+            Replication::RepairOffset(file_id) => {
+                secondary.repair_offset(&file_id).map(drop)
+            }
+            Replication::RepairRange {
+                file_id,
+                buf,
+                offset,
+            } => secondary.repair_range(&file_id, &buf, offset),
+            Replication::Quarantine(file_id) => {
+                secondary.quarantine_file(&file_id).map(drop)
+            }
+            Replication::UpdateMetadata { file_id, metadata } => {
+                secondary.update_metadata(&file_id, metadata)
+            }
+            Replication::UpdateUploadToken { file_id, token } => {
+                secondary.update_upload_token(&file_id, &token)
+            }
+            Replication::UpdateStats { file_id, stats } => {
+                secondary.update_stats(&file_id, &stats)
+            }
+            Replication::ScheduleTermination { file_id, time } => {
+                secondary.schedule_termination(&file_id, time)
+            }
+        };
+
+        if let Err(e) = result {
+            self.divergences
+                .record(&file_id, Divergence::Replication(e.to_string()));
+        }
+    }
+
+    /// Compares the copy of a completed upload with the primary one.
+    fn verify(&self, file_id: &str, length: u64) -> Result<(), VaultError> {
+        let copy = self.secondary.get_file(file_id)?;
+
+        if *copy.offset() != length {
+            self.divergences.record(
+                file_id,
+                Divergence::Offset {
+                    expected: length,
+                    actual: *copy.offset(),
+                },
+            );
+
+            return Ok(());
+        }
+
+        /* The primary may have moved on, like a terminated upload */
+        let expected = match digest(&*self.primary, file_id) {
+            Ok(Some(expected)) => expected,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                warn_!("Unable to verify mirror of upload {}: {}", file_id, e);
+                return Ok(());
+            }
+        };
+
+        match digest(&*self.secondary, file_id)? {
+            Some(actual) if actual != expected => {
+                self.divergences.record(file_id, Divergence::Content)
+            }
+            _ => info_!("Verified mirror of upload: {}", file_id),
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the CRC32 of the upload content, or `None` when the vault can't read
+/// it back.
+fn digest(vault: &dyn Vault, file_id: &str) -> Result<Option<u32>, VaultError> {
+    let mut content = match vault.open_content(file_id) {
+        Ok(content) => content,
+        Err(e) if e.io_error_kind() == Some(ErrorKind::Unsupported) => {
+            return Ok(None)
+        }
+        Err(e) => return Err(e),
+    };
+
+    let mut hasher = crc32fast::Hasher::new();
+    let mut buf = vec![0; 64 * 1024];
+
+    loop {
+        let read = content.read(&mut buf)?;
+
+        if read == 0 {
+            break;
+        }
+
+        hasher.update(&buf[..read]);
+    }
+
+    Ok(Some(hasher.finalize()))
+}
+
+impl<A: Vault + 'static, B: Vault + 'static> Vault for MirrorVault<A, B> {
+    fn build_file(
+        &self,
+        length: u64,
+        metadata: Option<&str>,
+    ) -> Result<FileInfo<Built>, VaultError> {
+        self.primary.build_file(length, metadata)
+    }
+
+    fn create_file(
+        &self,
+        file: FileInfo<Built>,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let copy = file.clone();
+        let created = self.primary.create_file(file)?;
+
+        self.replicate(Replication::Create(Box::new(copy)));
+
+        Ok(created)
+    }
+
+    fn exists(&self, file_id: &str) -> bool {
+        self.primary.exists(file_id)
+    }
+
+    fn get_file(&self, file_id: &str) -> Result<FileInfo<Created>, VaultError> {
+        self.primary.get_file(file_id)
+    }
+
+    fn patch_file(
+        &self,
+        file_id: &str,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        /* Copied beforehand, since the primary may transform the chunk */
+        let chunk = buf.to_vec();
+        let patched = self.primary.patch_file(file_id, buf, offset)?;
+
+        self.replicate_chunk(
+            Replication::Patch {
+                file_id: file_id.to_owned(),
+                buf: chunk,
+                offset,
+            },
+            &patched,
+        );
+
+        Ok(patched)
+    }
+
+    fn write_range(
+        &self,
+        file_id: &str,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        let patched = self.primary.write_range(file_id, buf, offset)?;
+
+        self.replicate_chunk(
+            Replication::WriteRange {
+                file_id: file_id.to_owned(),
+                buf: buf.to_vec(),
+                offset,
+            },
+            &patched,
+        );
+
+        Ok(patched)
+    }
+
+    fn terminate_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Terminated>, VaultError> {
+        let terminated = self.primary.terminate_file(file_id)?;
+
+        self.replicate(Replication::Terminate(file_id.to_owned()));
+
+        Ok(terminated)
+    }
+
+    fn declare_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let file = self.primary.declare_length(file_id, length)?;
+
+        self.replicate(Replication::DeclareLength {
+            file_id: file_id.to_owned(),
+            length,
+        });
+
+        Ok(file)
+    }
+
+    fn increase_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let file = self.primary.increase_length(file_id, length)?;
+
+        self.replicate(Replication::IncreaseLength {
+            file_id: file_id.to_owned(),
+            length,
+        });
+
+        Ok(file)
+    }
+
+    fn repair_offset(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let file = self.primary.repair_offset(file_id)?;
+
+        self.replicate(Replication::RepairOffset(file_id.to_owned()));
+
+        Ok(file)
+    }
+
+    fn repair_range(
+        &self,
+        file_id: &str,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<(), VaultError> {
+        self.primary.repair_range(file_id, buf, offset)?;
+
+        self.replicate(Replication::RepairRange {
+            file_id: file_id.to_owned(),
+            buf: buf.to_vec(),
+            offset,
+        });
+
+        Ok(())
+    }
+
+    fn quarantine_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Quarantined>, VaultError> {
+        let file = self.primary.quarantine_file(file_id)?;
+
+        self.replicate(Replication::Quarantine(file_id.to_owned()));
+
+        Ok(file)
+    }
+
+    fn open_content(
+        &self,
+        file_id: &str,
+    ) -> Result<Box<dyn Read + Send>, VaultError> {
+        self.primary.open_content(file_id)
+    }
+
+    fn update_metadata(
+        &self,
+        file_id: &str,
+        metadata: Metadata,
+    ) -> Result<(), VaultError> {
+        self.primary.update_metadata(file_id, metadata.to_owned())?;
+
+        self.replicate(Replication::UpdateMetadata {
+            file_id: file_id.to_owned(),
+            metadata,
+        });
+
+        Ok(())
+    }
+
+    fn update_upload_token(
+        &self,
+        file_id: &str,
+        token: &str,
+    ) -> Result<(), VaultError> {
+        self.primary.update_upload_token(file_id, token)?;
+
+        self.replicate(Replication::UpdateUploadToken {
+            file_id: file_id.to_owned(),
+            token: token.to_owned(),
+        });
+
+        Ok(())
+    }
+
+    fn update_stats(
+        &self,
+        file_id: &str,
+        stats: &UploadStats,
+    ) -> Result<(), VaultError> {
+        self.primary.update_stats(file_id, stats)?;
+
+        self.replicate(Replication::UpdateStats {
+            file_id: file_id.to_owned(),
+            stats: stats.to_owned(),
+        });
+
+        Ok(())
+    }
+
+    fn schedule_termination(
+        &self,
+        file_id: &str,
+        time: SystemTime,
+    ) -> Result<(), VaultError> {
+        self.primary.schedule_termination(file_id, time)?;
+
+        self.replicate(Replication::ScheduleTermination {
+            file_id: file_id.to_owned(),
+            time,
+        });
+
+        Ok(())
+    }
+
+    fn list_quarantined(
+        &self,
+    ) -> Result<Vec<FileInfo<Quarantined>>, VaultError> {
+        self.primary.list_quarantined()
+    }
+
+    fn storage_path(&self) -> Option<&Path> {
+        self.primary.storage_path()
+    }
+
+    fn list_files(&self) -> Result<Vec<String>, VaultError> {
+        self.primary.list_files()
+    }
+}
@@ -1,7 +1,53 @@
+#[cfg(feature = "compression")]
+mod compressed_vault;
+mod extensions;
 mod file_info;
+mod instrumented_vault;
 mod metadata;
+mod mirror_vault;
+mod promoter;
+mod ranges;
+#[cfg(feature = "redis")]
+mod redis_vault;
+mod retry_vault;
+#[cfg(feature = "s3")]
+mod s3_storage;
+#[cfg(feature = "sftp")]
+mod sftp_vault;
+#[cfg(any(feature = "sqlx_postgres", feature = "sqlx_sqlite"))]
+mod sql_vault;
+mod stats;
+#[cfg(feature = "tiered")]
+mod tiered_vault;
+mod uploader;
 mod vault;
 
-pub use file_info::{Built, Completed, Created, FileInfo, Terminated};
+#[cfg(feature = "compression")]
+pub use compressed_vault::CompressedVault;
+pub use extensions::Extensions;
+pub use file_info::{
+    Building, Built, Completed, Created, FileInfo, Quarantined, StorageRef,
+    Terminated, UploadState, UuidFormat,
+};
+pub(crate) use instrumented_vault::InstrumentedVault;
 pub use metadata::{Metadata, MetadataError};
-pub use vault::{LocalVault, PatchOption, Vault};
+pub use mirror_vault::{Divergence, MirrorVault};
+pub use promoter::{DirectoryPromoter, RelativePathPolicy};
+pub use ranges::ReceivedRanges;
+#[cfg(feature = "redis")]
+pub use redis_vault::RedisInfoVault;
+pub use retry_vault::{RetryPolicy, RetryVault};
+#[cfg(feature = "s3")]
+pub use s3_storage::S3ColdStorage;
+#[cfg(feature = "sftp")]
+pub use sftp_vault::SftpVault;
+#[cfg(any(feature = "sqlx_postgres", feature = "sqlx_sqlite"))]
+pub use sql_vault::SqlVault;
+pub use stats::UploadStats;
+#[cfg(feature = "tiered")]
+pub use tiered_vault::{ColdStorage, DirectoryColdStorage, TieredVault};
+pub use uploader::UploaderInfo;
+pub use vault::{
+    sanitize_filename, sanitize_relative_path, LocalVault, PatchOption,
+    Preallocation, Vault, VaultError,
+};
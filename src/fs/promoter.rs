@@ -0,0 +1,159 @@
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::{self, ErrorKind, Read},
+    path::PathBuf,
+};
+
+use crate::processor::{Artifact, Processor};
+
+use super::{
+    file_info::{Completed, FileInfo},
+    vault::{sanitize_filename, sanitize_relative_path},
+};
+
+/// Defines where [`DirectoryPromoter`] places uploads carrying the `relativePath`
+/// metadata, sent by folder uploads like Uppy's drag-and-drop.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RelativePathPolicy {
+    /// Recreates the uploaded directory structure inside the target directory.
+    #[default]
+    Preserve,
+    /// Places every upload directly inside the target directory, under its file name.
+    Flatten,
+}
+
+/// A [`Processor`] promoting completed uploads into a directory tree, like a shared
+/// folder or a network mount.
+///
+/// Uploads are copied under their sanitized `relativePath` or `filename` metadata,
+/// according to the [`RelativePathPolicy`], falling back to their id. Uploads whose
+/// destination is already taken are prefixed by their id. The promoted path is written
+/// back into the upload metadata as `promotedPath`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Ignite;
+/// use meteoritus::{DirectoryPromoter, Meteoritus, RelativePathPolicy};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+///         .with_processor(
+///             DirectoryPromoter::new("/mnt/shared/uploads")
+///                 .with_relative_path_policy(RelativePathPolicy::Preserve),
+///         )
+///         .build();
+///
+///     rocket::build().attach(meteoritus)
+/// }
+/// ```
+pub struct DirectoryPromoter {
+    path: PathBuf,
+    policy: RelativePathPolicy,
+}
+
+impl DirectoryPromoter {
+    /// Creates a [`DirectoryPromoter`] copying the uploads into `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            policy: RelativePathPolicy::default(),
+        }
+    }
+
+    /// Overrides how the `relativePath` metadata is handled, which is
+    /// [`RelativePathPolicy::Preserve`] by default.
+    pub fn with_relative_path_policy(
+        mut self,
+        policy: RelativePathPolicy,
+    ) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the path, relative to the target directory, where the upload is promoted.
+    pub fn destination(&self, file: &FileInfo<Completed>) -> PathBuf {
+        let value = |key: &str| {
+            file.metadata()
+                .as_ref()
+                .and_then(|metadata| metadata.get_raw(key).ok())
+                .map(|value| String::from_utf8_lossy(&value).into_owned())
+                /* Uppy sends `null` for files picked outside of a folder */
+                .filter(|value| !value.is_empty() && value != "null")
+        };
+
+        let relative_path = match self.policy {
+            RelativePathPolicy::Preserve => value("relativePath")
+                .and_then(|path| sanitize_relative_path(&path)),
+            RelativePathPolicy::Flatten => None,
+        };
+
+        relative_path
+            .or_else(|| {
+                value("filename")
+                    .or_else(|| value("relativePath"))
+                    .map(|name| PathBuf::from(sanitize_filename(&name)))
+            })
+            .unwrap_or_else(|| PathBuf::from(file.id()))
+    }
+
+    fn create_target(
+        &self,
+        file: &FileInfo<Completed>,
+    ) -> io::Result<(PathBuf, File)> {
+        let destination = self.path.join(self.destination(file));
+
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        match File::options()
+            .write(true)
+            .create_new(true)
+            .open(&destination)
+        {
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                let name = destination
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+
+                let destination = destination.with_file_name(format!(
+                    "{}_{}",
+                    file.id(),
+                    name
+                ));
+
+                let target = File::options()
+                    .write(true)
+                    .create_new(true)
+                    .open(&destination)?;
+
+                Ok((destination, target))
+            }
+            target => Ok((destination, target?)),
+        }
+    }
+}
+
+impl Processor for DirectoryPromoter {
+    fn process(
+        &self,
+        file: &FileInfo<Completed>,
+        content: &mut dyn Read,
+    ) -> Result<Vec<Artifact>, Box<dyn Error + Send + Sync>> {
+        let (destination, mut target) = self.create_target(file)?;
+
+        if let Err(e) = io::copy(content, &mut target) {
+            let _ = fs::remove_file(&destination);
+            return Err(e.into());
+        }
+
+        let promoted_path = destination.to_string_lossy().into_owned();
+
+        Ok(vec![Artifact::new("promotedPath", promoted_path)])
+    }
+}
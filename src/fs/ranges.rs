@@ -0,0 +1,98 @@
+use std::{fmt, ops::Range};
+
+use rocket::serde::{Deserialize, Serialize};
+
+/// The ranges of an upload received past its offset, through parallel chunks,
+/// see [`Meteoritus::with_parallel_chunks()`](crate::Meteoritus::with_parallel_chunks).
+///
+/// Ranges are sorted and merged, so they never overlap nor touch each other, and
+/// are absorbed by the upload offset as soon as the bytes before them are stored.
+/// They are formatted as comma separated inclusive ranges, like `100-199,300-349`.
+///
+/// # Example
+///
+/// ```rust
+/// use meteoritus::ReceivedRanges;
+///
+/// let mut ranges = ReceivedRanges::default();
+/// ranges.insert(300..350);
+/// ranges.insert(100..200);
+/// ranges.insert(200..250);
+///
+/// assert_eq!(ranges.to_string(), "100-249,300-349");
+/// assert_eq!(ranges.received_bytes(), 200);
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct ReceivedRanges(Vec<(u64, u64)>);
+
+impl ReceivedRanges {
+    /// Records the given `range` as received, merging it with the ones it overlaps
+    /// or touches.
+    pub fn insert(&mut self, range: Range<u64>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let (mut start, mut end) = (range.start, range.end);
+
+        self.0.retain(|&(s, e)| {
+            if s > end || e < start {
+                return true;
+            }
+
+            start = start.min(s);
+            end = end.max(e);
+            false
+        });
+
+        let position = self.0.partition_point(|&(s, _)| s < start);
+        self.0.insert(position, (start, end));
+    }
+
+    /// Removes the ranges starting at or before the given `offset`, returning the
+    /// offset extended over them.
+    pub(crate) fn absorb(&mut self, offset: u64) -> u64 {
+        let mut offset = offset;
+
+        self.0.retain(|&(start, end)| {
+            if start > offset {
+                return true;
+            }
+
+            offset = offset.max(end);
+            false
+        });
+
+        offset
+    }
+
+    /// Returns the received ranges, as `start..end` ranges.
+    pub fn iter(&self) -> impl Iterator<Item = Range<u64>> + '_ {
+        self.0.iter().map(|&(start, end)| start..end)
+    }
+
+    /// Returns the number of bytes received in all ranges.
+    pub fn received_bytes(&self) -> u64 {
+        self.0.iter().map(|(start, end)| end - start).sum()
+    }
+
+    /// Indicates if no range is pending.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Display for ReceivedRanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (start, end)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+
+            write!(f, "{}-{}", start, end - 1)?;
+        }
+
+        Ok(())
+    }
+}
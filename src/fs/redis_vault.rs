@@ -0,0 +1,561 @@
+use std::{
+    fs::{self, File},
+    io::{ErrorKind, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use redis::{Client, Commands, Connection, RedisError, Script};
+use uuid::Uuid;
+
+use super::{
+    file_info::{Built, Created, FileInfo, Quarantined, Terminated},
+    metadata::Metadata,
+    stats::UploadStats,
+    vault::{PatchOption, Vault, VaultError},
+};
+
+/// Claims the writer lock of an upload at the expected offset.
+///
+/// Returns `{0, 0}` for missing uploads, `{1, offset}` when the upload is at
+/// another offset, `{2, offset}` when another writer holds the lock and
+/// `{3, offset}` once claimed.
+const CLAIM_SCRIPT: &str = r#"
+local offset = redis.call('HGET', KEYS[1], 'offset')
+if not offset then
+    return {0, 0}
+end
+if offset ~= ARGV[1] then
+    return {1, tonumber(offset)}
+end
+if not redis.call('SET', KEYS[2], ARGV[2], 'NX', 'PX', ARGV[3]) then
+    return {2, tonumber(offset)}
+end
+return {3, tonumber(offset)}
+"#;
+
+/// Moves the offset of an upload and releases its writer lock, only while the
+/// lock is still held by the given writer.
+const COMMIT_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[2]) ~= ARGV[1] then
+    return 0
+end
+redis.call('HSET', KEYS[1], 'offset', ARGV[2])
+redis.call('DEL', KEYS[2])
+return 1
+"#;
+
+/// Releases the writer lock of an upload, when held by the given writer.
+const RELEASE_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[2]) == ARGV[1] then
+    redis.call('DEL', KEYS[2])
+end
+return 1
+"#;
+
+/// A [`Vault`] keeping the [`FileInfo`] and offset of uploads in [Redis](https://redis.io),
+/// while their content is stored on the local disk.
+///
+/// `HEAD` requests are served by a single Redis round trip, and concurrent `PATCH`
+/// requests, even across processes sharing the same Redis and volume, are made safe
+/// by a Lua scripted compare-and-set of the offset: a writer claims the upload at its
+/// expected offset, writes the chunk, then commits the new offset, so competing
+/// writers get a [`VaultError::Conflict`] without touching the content.
+///
+/// Each upload is stored as the `<prefix>:upload:<id>` hash, holding its `info` JSON
+/// and its `offset`, while its content is stored under `<save_path>/<id>`. Writer
+/// locks expire after 60 seconds by default, so uploads of crashed writers can be
+/// resumed.
+///
+/// **Note:** Since [`Vault`] operations are synchronous, Redis commands block the
+/// calling thread.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Ignite;
+/// use meteoritus::{Meteoritus, RedisInfoVault};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let vault = RedisInfoVault::new("redis://localhost:6379", "/var/uploads")
+///         .unwrap()
+///         .with_prefix("uploads");
+///
+///     let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+///         .with_vault(vault)
+///         .build();
+///
+///     rocket::build().attach(meteoritus)
+/// }
+/// ```
+pub struct RedisInfoVault {
+    client: Client,
+    connection: Mutex<Option<Connection>>,
+    prefix: String,
+    save_path: PathBuf,
+    lock_timeout: Duration,
+    claim: Script,
+    commit: Script,
+    release: Script,
+}
+
+impl RedisInfoVault {
+    /// Creates a [`RedisInfoVault`] connecting to the Redis server at `url`, like
+    /// `redis://localhost:6379`, and storing the content of the uploads into the
+    /// `save_path` directory.
+    ///
+    /// The connection is opened on the first operation.
+    pub fn new(
+        url: &str,
+        save_path: impl Into<PathBuf>,
+    ) -> Result<Self, RedisError> {
+        Ok(Self {
+            client: Client::open(url)?,
+            connection: Mutex::new(None),
+            prefix: "meteoritus".to_string(),
+            save_path: save_path.into(),
+            lock_timeout: Duration::from_secs(60),
+            claim: Script::new(CLAIM_SCRIPT),
+            commit: Script::new(COMMIT_SCRIPT),
+            release: Script::new(RELEASE_SCRIPT),
+        })
+    }
+
+    /// Sets the prefix of the Redis keys, by default `meteoritus`.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Sets how long a writer holds the lock of an upload before it expires, by
+    /// default 60 seconds.
+    ///
+    /// Should exceed the time needed to store the largest chunk, otherwise a slow
+    /// writer fails to commit its chunk.
+    pub fn with_lock_timeout(mut self, timeout: Duration) -> Self {
+        self.lock_timeout = timeout;
+        self
+    }
+
+    /// Runs the given commands on the shared connection, opening it when needed.
+    ///
+    /// The connection is dropped on I/O errors, so the next operation reconnects.
+    fn with_redis<T>(
+        &self,
+        operation: impl FnOnce(&mut Connection) -> Result<T, RedisError>,
+    ) -> Result<T, VaultError> {
+        let mut connection =
+            self.connection.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut con = match connection.take() {
+            Some(con) => con,
+            None => self.client.get_connection().map_err(backend)?,
+        };
+
+        let result = operation(&mut con);
+
+        let broken = result
+            .as_ref()
+            .is_err_and(|e| e.is_io_error() || e.is_connection_dropped());
+
+        if !broken {
+            *connection = Some(con);
+        }
+
+        result.map_err(backend)
+    }
+
+    fn info_key(&self, file_id: &str) -> String {
+        format!("{}:upload:{}", self.prefix, file_id)
+    }
+
+    fn lock_key(&self, file_id: &str) -> String {
+        format!("{}:lock:{}", self.prefix, file_id)
+    }
+
+    fn index_key(&self) -> String {
+        format!("{}:uploads", self.prefix)
+    }
+
+    fn content_path(&self, file_id: &str) -> PathBuf {
+        self.save_path.join(file_id)
+    }
+
+    /// Reads the stored `info` along with the `offset` of the given upload.
+    fn read_info(&self, file_id: &str) -> Result<(String, u64), VaultError> {
+        let (info, offset): (Option<String>, Option<u64>) =
+            self.with_redis(|con| {
+                con.hget(self.info_key(file_id), &["info", "offset"])
+            })?;
+
+        match (info, offset) {
+            (Some(info), Some(offset)) => Ok((info, offset)),
+            _ => Err(VaultError::NotFound),
+        }
+    }
+
+    fn read_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let (info, offset) = self.read_info(file_id)?;
+
+        let mut file: FileInfo<Created> = serde_json::from_str(&info)?;
+
+        /* The offset field is the source of truth, moved by each chunk */
+        file.set_offset(offset)
+            .map_err(|e| VaultError::Invalid(e.into()))?;
+
+        Ok(file)
+    }
+
+    /// Replaces the stored `info` of the given upload, keeping its offset.
+    fn write_info<State>(
+        &self,
+        file_info: &FileInfo<State>,
+    ) -> Result<(), VaultError> {
+        let info = serde_json::to_string(file_info)?;
+
+        self.with_redis(|con| {
+            con.hset(self.info_key(file_info.id()), "info", info)
+        })
+    }
+}
+
+fn backend(e: RedisError) -> VaultError {
+    VaultError::Backend(Box::new(e))
+}
+
+impl Vault for RedisInfoVault {
+    fn build_file(
+        &self,
+        length: u64,
+        metadata: Option<&str>,
+    ) -> Result<FileInfo<Built>, VaultError> {
+        let metadata = match metadata {
+            Some(metadata) => Metadata::try_from(metadata)
+                .map_err(|e| VaultError::Invalid(Box::new(e)))?,
+            None => Metadata::default(),
+        };
+
+        Ok(FileInfo::new(length)
+            .with_uuid()
+            .with_metadata(metadata)
+            .build())
+    }
+
+    fn create_file(
+        &self,
+        file_info: FileInfo<Built>,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        fs::create_dir_all(&self.save_path)?;
+
+        let content_path = self.content_path(file_info.id());
+
+        /* Creating file for upload */
+        File::options()
+            .write(true)
+            .create_new(true)
+            .open(&content_path)?;
+
+        let Some(file_name) = content_path.to_str() else {
+            let _ = fs::remove_file(&content_path);
+
+            return Err(VaultError::Io(ErrorKind::InvalidInput.into()));
+        };
+
+        let file_info = file_info.mark_as_created(file_name);
+
+        let store = || -> Result<(), VaultError> {
+            let info = serde_json::to_string(&file_info)?;
+
+            self.with_redis(|con| {
+                redis::pipe()
+                    .atomic()
+                    .hset_multiple(
+                        self.info_key(file_info.id()),
+                        &[("info", info.as_str()), ("offset", "0")],
+                    )
+                    .sadd(self.index_key(), file_info.id())
+                    .query(con)
+            })
+        };
+
+        store().inspect_err(|_| {
+            let _ = fs::remove_file(&content_path);
+        })?;
+
+        Ok(file_info)
+    }
+
+    fn exists(&self, file_id: &str) -> bool {
+        self.with_redis(|con| con.exists::<_, bool>(self.info_key(file_id)))
+            .unwrap_or(false)
+            && self.content_path(file_id).exists()
+    }
+
+    fn get_file(&self, file_id: &str) -> Result<FileInfo<Created>, VaultError> {
+        self.read_file(file_id)
+    }
+
+    fn patch_file(
+        &self,
+        file_id: &str,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        let info_key = self.info_key(file_id);
+        let lock_key = self.lock_key(file_id);
+        let writer = Uuid::new_v4().simple().to_string();
+
+        let (status, current): (u8, u64) = self.with_redis(|con| {
+            self.claim
+                .key(&info_key)
+                .key(&lock_key)
+                .arg(offset)
+                .arg(&writer)
+                .arg(self.lock_timeout.as_millis() as u64)
+                .invoke(con)
+        })?;
+
+        match status {
+            0 => return Err(VaultError::NotFound),
+            1 => {
+                return Err(VaultError::Conflict(format!(
+                    "upload is at offset {}",
+                    current
+                )))
+            }
+            2 => {
+                return Err(VaultError::Conflict(
+                    "upload is being written by another request".to_string(),
+                ))
+            }
+            _ => (),
+        }
+
+        let release = || {
+            let released: Result<u8, VaultError> = self.with_redis(|con| {
+                self.release
+                    .key(&info_key)
+                    .key(&lock_key)
+                    .arg(&writer)
+                    .invoke(con)
+            });
+
+            if released.is_err() {
+                warn_!("Unable to release the writer lock of: {}", file_id);
+            }
+        };
+
+        let mut file = match self.read_file(file_id) {
+            Ok(file) => file,
+            Err(e) => {
+                release();
+                return Err(e);
+            }
+        };
+
+        let new_offset = offset + buf.len() as u64;
+
+        if let Err(e) = file.set_offset(new_offset) {
+            release();
+            return Err(VaultError::Invalid(e.into()));
+        }
+
+        let content_path = self.content_path(file_id);
+
+        /* Rolling back the failed chunks, so uploads stay at their previous offset */
+        let rollback = |content: &File| {
+            if content.set_len(offset).is_err() {
+                warn_!("Unable to roll back the failed chunk of: {}", file_id);
+            }
+        };
+
+        let mut content = match File::options().write(true).open(content_path) {
+            Ok(content) => content,
+            Err(e) => {
+                release();
+                return Err(e.into());
+            }
+        };
+
+        if let Err(e) = content
+            .seek(SeekFrom::Start(offset))
+            .and_then(|_| content.write_all(buf))
+            .and_then(|_| content.flush())
+        {
+            rollback(&content);
+            release();
+            return Err(e.into());
+        }
+
+        let committed: u8 = match self.with_redis(|con| {
+            self.commit
+                .key(&info_key)
+                .key(&lock_key)
+                .arg(&writer)
+                .arg(new_offset)
+                .invoke(con)
+        }) {
+            Ok(committed) => committed,
+            Err(e) => {
+                rollback(&content);
+                release();
+                return Err(e);
+            }
+        };
+
+        /* The lock expired, another writer may already own the content */
+        if committed == 0 {
+            return Err(VaultError::Conflict(
+                "writer lock expired before the chunk was committed"
+                    .to_string(),
+            ));
+        }
+
+        match file.check_completion() {
+            Some(file) => Ok(PatchOption::Completed(file)),
+            None => Ok(PatchOption::Patched(new_offset)),
+        }
+    }
+
+    fn terminate_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Terminated>, VaultError> {
+        let file_info = self.read_file(file_id)?.mark_as_terminated();
+
+        self.with_redis(|con| {
+            redis::pipe()
+                .atomic()
+                .del(self.info_key(file_id))
+                .del(self.lock_key(file_id))
+                .srem(self.index_key(), file_id)
+                .query::<()>(con)
+        })?;
+
+        match fs::remove_file(self.content_path(file_id)) {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+            _ => (),
+        }
+
+        Ok(file_info)
+    }
+
+    fn declare_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let mut file = self.read_file(file_id)?;
+
+        file.declare_length(length)
+            .map_err(|e| VaultError::Invalid(e.into()))?;
+
+        self.write_info(&file)?;
+
+        Ok(file)
+    }
+
+    fn increase_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let mut file = self.read_file(file_id)?;
+
+        file.increase_length(length)
+            .map_err(|e| VaultError::Invalid(e.into()))?;
+
+        self.write_info(&file)?;
+
+        Ok(file)
+    }
+
+    fn quarantine_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Quarantined>, VaultError> {
+        let file = self.read_file(file_id)?.mark_as_quarantined();
+
+        self.write_info(&file)?;
+
+        Ok(file)
+    }
+
+    fn update_metadata(
+        &self,
+        file_id: &str,
+        metadata: Metadata,
+    ) -> Result<(), VaultError> {
+        let mut file = self.read_file(file_id)?;
+
+        file.set_metadata(metadata);
+
+        self.write_info(&file)
+    }
+
+    fn update_upload_token(
+        &self,
+        file_id: &str,
+        token: &str,
+    ) -> Result<(), VaultError> {
+        let mut file = self.read_file(file_id)?;
+
+        file.set_upload_token(token.to_string());
+
+        self.write_info(&file)
+    }
+
+    fn update_stats(
+        &self,
+        file_id: &str,
+        stats: &UploadStats,
+    ) -> Result<(), VaultError> {
+        let mut file = self.read_file(file_id)?;
+
+        file.set_stats(stats.clone());
+
+        self.write_info(&file)
+    }
+
+    fn schedule_termination(
+        &self,
+        file_id: &str,
+        time: SystemTime,
+    ) -> Result<(), VaultError> {
+        let mut file = self.read_file(file_id)?;
+
+        file.schedule_termination(time);
+
+        self.write_info(&file)
+    }
+
+    fn list_quarantined(
+        &self,
+    ) -> Result<Vec<FileInfo<Quarantined>>, VaultError> {
+        let quarantined = self
+            .list_files()?
+            .iter()
+            .filter_map(|file_id| self.read_info(file_id).ok())
+            .filter_map(|(info, _)| {
+                serde_json::from_str::<FileInfo<Quarantined>>(&info).ok()
+            })
+            .filter(|file| file.is_quarantined())
+            .collect();
+
+        Ok(quarantined)
+    }
+
+    fn storage_path(&self) -> Option<&Path> {
+        Some(&self.save_path)
+    }
+
+    fn list_files(&self) -> Result<Vec<String>, VaultError> {
+        self.with_redis(|con| con.smembers(self.index_key()))
+    }
+}
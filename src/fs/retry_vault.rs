@@ -0,0 +1,327 @@
+use std::{
+    fmt,
+    io::Read,
+    path::Path,
+    sync::Arc,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use rocket::tokio::{
+    runtime::{self, RuntimeFlavor},
+    task,
+};
+
+use super::{
+    file_info::{Built, Created, FileInfo, Quarantined, Terminated},
+    metadata::Metadata,
+    stats::UploadStats,
+    vault::{PatchOption, Vault, VaultError},
+};
+
+type RetryClassifier = Arc<dyn Fn(&VaultError) -> bool + Send + Sync>;
+
+/// Defines how failed operations of a [`RetryVault`] are retried.
+///
+/// By default, operations are attempted up to 3 times, waiting 100ms before the
+/// first retry and doubling the delay up to 5s, retrying only the [`VaultError::Io`]
+/// and [`VaultError::Backend`] errors, since other errors won't go away on retry.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: u32,
+    retry_on: RetryClassifier,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2,
+            retry_on: Arc::new(|error| {
+                matches!(error, VaultError::Io(_) | VaultError::Backend(_))
+            }),
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("multiplier", &self.multiplier)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RetryPolicy {
+    /// Creates the default [`RetryPolicy`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many times an operation is attempted, including the first one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_attempts` is zero.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        assert!(max_attempts > 0, "at least one attempt is required");
+
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets the delay before the first retry, and the maximum delay between retries.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Sets the factor applied to the delay after each retry.
+    pub fn with_multiplier(mut self, multiplier: u32) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Specifies which errors are transient, and so worth retrying.
+    pub fn retry_on<F>(mut self, classifier: F) -> Self
+    where
+        F: Fn(&VaultError) -> bool + Send + Sync + 'static,
+    {
+        self.retry_on = Arc::new(classifier);
+        self
+    }
+
+    /// Returns the delay before the given retry, starting from `1`.
+    fn backoff(&self, retry: u32) -> Duration {
+        let factor = self.multiplier.saturating_pow(retry - 1);
+
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// A [`Vault`] retrying the failed operations of an `inner` vault with an exponential
+/// backoff, so transient storage or network failures don't surface to clients.
+///
+/// Uploads creation isn't retried, since a failed creation is safely retried by
+/// clients before sending any content.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use std::time::Duration;
+/// use rocket::Ignite;
+/// use meteoritus::{LocalVault, Meteoritus, RetryPolicy, RetryVault, VaultError};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let policy = RetryPolicy::new()
+///         .with_max_attempts(5)
+///         .with_backoff(Duration::from_millis(50), Duration::from_secs(2))
+///         .retry_on(|error| matches!(error, VaultError::Io(_)));
+///
+///     let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+///         .with_vault(RetryVault::new(LocalVault::new("./tmp/uploads"), policy))
+///         .build();
+///
+///     rocket::build().attach(meteoritus)
+/// }
+/// ```
+pub struct RetryVault<V: Vault> {
+    inner: V,
+    policy: RetryPolicy,
+}
+
+impl<V: Vault> RetryVault<V> {
+    /// Creates a [`RetryVault`] retrying the operations of `inner` as defined by
+    /// `policy`.
+    pub fn new(inner: V, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    fn retry<T>(
+        &self,
+        mut operation: impl FnMut() -> Result<T, VaultError>,
+    ) -> Result<T, VaultError> {
+        let mut attempt = 1;
+
+        loop {
+            match operation() {
+                Err(e)
+                    if attempt < self.policy.max_attempts
+                        && (self.policy.retry_on)(&e) =>
+                {
+                    sleep(self.policy.backoff(attempt));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Waits from the synchronous [`Vault`] operations, without stalling the other
+/// tasks of a multi-threaded runtime.
+fn sleep(duration: Duration) {
+    match runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            task::block_in_place(|| thread::sleep(duration))
+        }
+        _ => thread::sleep(duration),
+    }
+}
+
+impl<V: Vault> Vault for RetryVault<V> {
+    fn build_file(
+        &self,
+        length: u64,
+        metadata: Option<&str>,
+    ) -> Result<FileInfo<Built>, VaultError> {
+        self.inner.build_file(length, metadata)
+    }
+
+    fn create_file(
+        &self,
+        file: FileInfo<Built>,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.inner.create_file(file)
+    }
+
+    fn exists(&self, file_id: &str) -> bool {
+        self.inner.exists(file_id)
+    }
+
+    fn get_file(&self, file_id: &str) -> Result<FileInfo<Created>, VaultError> {
+        self.retry(|| self.inner.get_file(file_id))
+    }
+
+    fn patch_file(
+        &self,
+        file_id: &str,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        /* Failed patches leave the upload at `offset`, so they can be replayed */
+        self.retry(|| self.inner.patch_file(file_id, buf, offset))
+    }
+
+    fn write_range(
+        &self,
+        file_id: &str,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        /* Rewriting a range stores the same bytes, so it can be replayed */
+        self.retry(|| self.inner.write_range(file_id, buf, offset))
+    }
+
+    fn terminate_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Terminated>, VaultError> {
+        self.retry(|| self.inner.terminate_file(file_id))
+    }
+
+    fn declare_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.retry(|| self.inner.declare_length(file_id, length))
+    }
+
+    fn increase_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.retry(|| self.inner.increase_length(file_id, length))
+    }
+
+    fn repair_offset(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.retry(|| self.inner.repair_offset(file_id))
+    }
+
+    fn repair_range(
+        &self,
+        file_id: &str,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<(), VaultError> {
+        self.retry(|| self.inner.repair_range(file_id, buf, offset))
+    }
+
+    fn quarantine_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Quarantined>, VaultError> {
+        self.retry(|| self.inner.quarantine_file(file_id))
+    }
+
+    fn open_content(
+        &self,
+        file_id: &str,
+    ) -> Result<Box<dyn Read + Send>, VaultError> {
+        self.retry(|| self.inner.open_content(file_id))
+    }
+
+    fn update_metadata(
+        &self,
+        file_id: &str,
+        metadata: Metadata,
+    ) -> Result<(), VaultError> {
+        self.retry(|| self.inner.update_metadata(file_id, metadata.to_owned()))
+    }
+
+    fn update_upload_token(
+        &self,
+        file_id: &str,
+        token: &str,
+    ) -> Result<(), VaultError> {
+        self.retry(|| self.inner.update_upload_token(file_id, token))
+    }
+
+    fn update_stats(
+        &self,
+        file_id: &str,
+        stats: &UploadStats,
+    ) -> Result<(), VaultError> {
+        self.retry(|| self.inner.update_stats(file_id, stats))
+    }
+
+    fn schedule_termination(
+        &self,
+        file_id: &str,
+        time: SystemTime,
+    ) -> Result<(), VaultError> {
+        self.retry(|| self.inner.schedule_termination(file_id, time))
+    }
+
+    fn list_quarantined(
+        &self,
+    ) -> Result<Vec<FileInfo<Quarantined>>, VaultError> {
+        self.retry(|| self.inner.list_quarantined())
+    }
+
+    fn storage_path(&self) -> Option<&Path> {
+        self.inner.storage_path()
+    }
+
+    fn list_files(&self) -> Result<Vec<String>, VaultError> {
+        self.retry(|| self.inner.list_files())
+    }
+}
@@ -0,0 +1,270 @@
+use std::{
+    env, error::Error, fs::File, path::Path, sync::OnceLock, time::SystemTime,
+};
+
+use hmac::{Hmac, Mac};
+use reqwest::blocking::{Body, Client};
+use rocket::time::OffsetDateTime;
+use sha2::{Digest, Sha256};
+
+use super::tiered_vault::ColdStorage;
+
+/// Payload hash signed for streamed bodies, which S3 accepts over HTTPS.
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// A [`ColdStorage`] uploading the offloaded uploads into an S3 bucket, or any S3
+/// compatible storage like MinIO or Cloudflare R2.
+///
+/// Objects are stored under `<prefix><file_id>` through a single `PUT` request,
+/// signed with [AWS Signature Version 4](https://docs.aws.amazon.com/AmazonS3/latest/API/sig-v4-authenticating-requests.html)
+/// and streaming the content from the hot vault. The `storage_class` hinted by an
+/// upload is forwarded as its `x-amz-storage-class`.
+///
+/// Credentials default to the `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY`
+/// environment variables.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Ignite;
+/// use meteoritus::{LocalVault, Meteoritus, S3ColdStorage, TieredVault};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let bucket = S3ColdStorage::new("my-bucket", "eu-west-1")
+///         .with_prefix("uploads/");
+///
+///     let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+///         .with_vault(TieredVault::new(LocalVault::new("./tmp/spool"), bucket))
+///         .build();
+///
+///     rocket::build().attach(meteoritus)
+/// }
+/// ```
+pub struct S3ColdStorage {
+    bucket: String,
+    region: String,
+    endpoint: String,
+    prefix: String,
+    access_key_id: String,
+    secret_access_key: String,
+    /* Created on the first offload, since blocking clients can't live in async contexts */
+    client: OnceLock<Client>,
+}
+
+impl S3ColdStorage {
+    /// Creates a new [`S3ColdStorage`] for the `bucket` of the given AWS `region`.
+    pub fn new(bucket: impl Into<String>, region: impl Into<String>) -> Self {
+        let region = region.into();
+
+        Self {
+            bucket: bucket.into(),
+            endpoint: format!("https://s3.{}.amazonaws.com", region),
+            region,
+            prefix: String::new(),
+            access_key_id: env::var("AWS_ACCESS_KEY_ID").unwrap_or_default(),
+            secret_access_key: env::var("AWS_SECRET_ACCESS_KEY")
+                .unwrap_or_default(),
+            client: OnceLock::new(),
+        }
+    }
+
+    /// Specifies the endpoint of a S3 compatible storage, like
+    /// `http://localhost:9000` for a local MinIO.
+    ///
+    /// Buckets are always addressed in the path, as `<endpoint>/<bucket>/<key>`.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Specifies the prefix of the object keys, like `uploads/`.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Specifies the credentials signing the requests, instead of the environment.
+    pub fn with_credentials(
+        mut self,
+        access_key_id: impl Into<String>,
+        secret_access_key: impl Into<String>,
+    ) -> Self {
+        self.access_key_id = access_key_id.into();
+        self.secret_access_key = secret_access_key.into();
+        self
+    }
+
+    /// Returns the key of the object storing the given upload.
+    fn key(&self, file_id: &str) -> String {
+        format!("{}{}", self.prefix, file_id)
+    }
+
+    /// Builds the `Authorization` header signing a `PUT` request of the given
+    /// `path` and headers, which must be sorted by name.
+    fn authorization(
+        &self,
+        host: &str,
+        path: &str,
+        headers: &[(&str, &str)],
+        time: OffsetDateTime,
+    ) -> String {
+        let date = format!(
+            "{:04}{:02}{:02}",
+            time.year(),
+            time.month() as u8,
+            time.day()
+        );
+        let amz_date = amz_date(time);
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+
+        let mut canonical_headers = format!("host:{}\n", host);
+        let mut signed_headers = String::from("host");
+
+        for (name, value) in headers {
+            canonical_headers.push_str(&format!("{}:{}\n", name, value.trim()));
+            signed_headers.push(';');
+            signed_headers.push_str(name);
+        }
+
+        let canonical_request = format!(
+            "PUT\n{}\n\n{}\n{}\n{}",
+            path, canonical_headers, signed_headers, UNSIGNED_PAYLOAD
+        );
+
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let secret = format!("AWS4{}", self.secret_access_key);
+        let key = [date.as_str(), &self.region, "s3", "aws4_request"]
+            .iter()
+            .fold(secret.into_bytes(), |key, part| hmac_sha256(&key, part));
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key_id,
+            scope,
+            signed_headers,
+            hex(&hmac_sha256(&key, &string_to_sign))
+        )
+    }
+}
+
+impl ColdStorage for S3ColdStorage {
+    fn location(&self, file_id: &str) -> String {
+        format!("s3://{}/{}", self.bucket, self.key(file_id))
+    }
+
+    fn offload(
+        &self,
+        file_id: &str,
+        source: &Path,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.offload_to_class(file_id, source, None)
+    }
+
+    fn offload_to_class(
+        &self,
+        file_id: &str,
+        source: &Path,
+        storage_class: Option<&str>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let file = File::open(source)?;
+        let length = file.metadata()?.len();
+
+        let path = format!(
+            "/{}/{}",
+            uri_encode(&self.bucket),
+            uri_encode(&self.key(file_id))
+        );
+        let url = reqwest::Url::parse(&format!("{}{}", self.endpoint, path))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err("S3 endpoint has no host".into()),
+        };
+
+        let time = OffsetDateTime::from(SystemTime::now());
+        let amz_date = amz_date(time);
+
+        let mut headers = vec![
+            ("x-amz-content-sha256", UNSIGNED_PAYLOAD),
+            ("x-amz-date", amz_date.as_str()),
+        ];
+
+        if let Some(storage_class) = storage_class {
+            headers.push(("x-amz-storage-class", storage_class));
+        }
+
+        let authorization = self.authorization(&host, &path, &headers, time);
+
+        let mut request = self
+            .client
+            .get_or_init(Client::new)
+            .put(url)
+            .header("Authorization", authorization)
+            .body(Body::sized(file, length));
+
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+
+        let res = request.send()?;
+
+        if !res.status().is_success() {
+            return Err(
+                format!("S3 rejected the object: {}", res.status()).into()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats the given `time` as the `x-amz-date` basic ISO 8601 timestamp.
+fn amz_date(time: OffsetDateTime) -> String {
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        time.year(),
+        time.month() as u8,
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second()
+    )
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    /* HMAC accepts keys of any length */
+    let mut mac = Hmac::<Sha256>::new_from_slice(key)
+        .expect("HMAC can take key of any size");
+
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Encodes the given object key as a canonical URI path, keeping its `/`.
+fn uri_encode(key: &str) -> String {
+    key.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'_'
+            | b'.'
+            | b'~'
+            | b'/' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
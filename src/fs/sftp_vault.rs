@@ -0,0 +1,563 @@
+use std::{
+    io::{self, ErrorKind, Read, Seek, SeekFrom, Write},
+    net::TcpStream,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use ssh2::{FileStat, OpenFlags, OpenType, Session, Sftp};
+
+use super::{
+    file_info::{Built, Created, FileInfo, Quarantined, Terminated},
+    metadata::Metadata,
+    stats::UploadStats,
+    vault::{PatchOption, Vault, VaultError},
+};
+
+/// How the [`SftpVault`] authenticates against the SSH server.
+enum Auth {
+    Password(String),
+    PrivateKey {
+        path: PathBuf,
+        passphrase: Option<String>,
+    },
+    Agent,
+}
+
+/// An authenticated SFTP connection, kept in the pool of a [`SftpVault`].
+struct Connection {
+    /* The session must outlive its SFTP channel */
+    sftp: Sftp,
+    _session: Session,
+}
+
+/// A [`Vault`] storing the uploads on a remote server through SSH/SFTP, like the
+/// drop zone of an archival system.
+///
+/// Each upload is stored under `<root>/<id>`, holding its `info.json` and its `file`
+/// content, the same layout as [`LocalVault`](super::LocalVault). Connections are
+/// opened on demand and pooled, keeping up to 4 idle connections by default.
+///
+/// The [`FileInfo::storage_ref()`] of uploads is a `sftp://<host>/<path>` key, so
+/// callbacks read their content through [`Vault::open_content()`].
+///
+/// **Note:** Since [`Vault`] operations are synchronous, transfers block the calling
+/// thread.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Ignite;
+/// use meteoritus::{Meteoritus, SftpVault};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let vault = SftpVault::new("dropzone.example.com:22", "uploader", "/incoming")
+///         .with_private_key("/etc/meteoritus/id_ed25519", None)
+///         .with_max_idle_connections(8);
+///
+///     let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+///         .with_vault(vault)
+///         .build();
+///
+///     rocket::build().attach(meteoritus)
+/// }
+/// ```
+pub struct SftpVault {
+    address: String,
+    username: String,
+    root: PathBuf,
+    auth: Auth,
+    timeout: Duration,
+    max_idle_connections: usize,
+    idle: Mutex<Vec<Connection>>,
+}
+
+impl SftpVault {
+    /// Creates a [`SftpVault`] connecting to the SSH server at `address`, like
+    /// `host:22`, as `username` and storing the uploads into the remote `root`
+    /// directory.
+    ///
+    /// Authenticates through the running SSH agent, unless a password or a private
+    /// key is given.
+    pub fn new(
+        address: impl Into<String>,
+        username: impl Into<String>,
+        root: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            address: address.into(),
+            username: username.into(),
+            root: root.into(),
+            auth: Auth::Agent,
+            timeout: Duration::from_secs(30),
+            max_idle_connections: 4,
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Authenticates with the given `password`.
+    pub fn with_password(mut self, password: impl Into<String>) -> Self {
+        self.auth = Auth::Password(password.into());
+        self
+    }
+
+    /// Authenticates with the PEM encoded private key stored at `path`, decrypted by
+    /// the given `passphrase`, if any.
+    pub fn with_private_key(
+        mut self,
+        path: impl Into<PathBuf>,
+        passphrase: Option<&str>,
+    ) -> Self {
+        self.auth = Auth::PrivateKey {
+            path: path.into(),
+            passphrase: passphrase.map(str::to_string),
+        };
+        self
+    }
+
+    /// Sets the timeout of the SSH operations, by default 30 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets how many idle connections are kept open for later operations, by
+    /// default 4.
+    pub fn with_max_idle_connections(mut self, max: usize) -> Self {
+        self.max_idle_connections = max;
+        self
+    }
+
+    fn connect(&self) -> Result<Connection, VaultError> {
+        let backend = |e: ssh2::Error| VaultError::Backend(e.into());
+
+        let stream = TcpStream::connect(&self.address)?;
+
+        let mut session = Session::new().map_err(backend)?;
+        session.set_tcp_stream(stream);
+        session
+            .set_timeout(self.timeout.as_millis().min(u32::MAX as u128) as u32);
+        session.handshake().map_err(backend)?;
+
+        match &self.auth {
+            Auth::Password(password) => {
+                session.userauth_password(&self.username, password)
+            }
+            Auth::PrivateKey { path, passphrase } => session
+                .userauth_pubkey_file(
+                    &self.username,
+                    None,
+                    path,
+                    passphrase.as_deref(),
+                ),
+            Auth::Agent => session.userauth_agent(&self.username),
+        }
+        .map_err(backend)?;
+
+        let sftp = session.sftp().map_err(backend)?;
+
+        Ok(Connection {
+            sftp,
+            _session: session,
+        })
+    }
+
+    /// Runs the given operation on a pooled connection, opening a new one when none
+    /// is idle.
+    ///
+    /// Connections failing with I/O or backend errors are dropped rather than pooled,
+    /// since they may be broken.
+    fn with_sftp<T>(
+        &self,
+        operation: impl FnOnce(&Sftp) -> Result<T, VaultError>,
+    ) -> Result<T, VaultError> {
+        let idle = self.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+
+        let connection = match idle {
+            Some(connection) => connection,
+            None => self.connect()?,
+        };
+
+        let result = operation(&connection.sftp);
+
+        if !matches!(result, Err(VaultError::Io(_) | VaultError::Backend(_))) {
+            let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+
+            if idle.len() < self.max_idle_connections {
+                idle.push(connection);
+            }
+        }
+
+        result
+    }
+
+    fn file_dir(&self, file_id: &str) -> PathBuf {
+        self.root.join(file_id)
+    }
+
+    fn info_path(&self, file_id: &str) -> PathBuf {
+        self.file_dir(file_id).join("info.json")
+    }
+
+    fn content_path(&self, file_id: &str) -> PathBuf {
+        self.file_dir(file_id).join("file")
+    }
+
+    /// Returns the `sftp://<host>/<path>` key of the given upload content.
+    fn content_key(&self, file_id: &str) -> String {
+        let host = self
+            .address
+            .rsplit_once(':')
+            .map_or(self.address.as_str(), |(host, _)| host);
+
+        let path = self.content_path(file_id);
+
+        format!(
+            "sftp://{}/{}",
+            host,
+            path.to_string_lossy().trim_start_matches('/')
+        )
+    }
+
+    fn read_file<State>(
+        &self,
+        sftp: &Sftp,
+        file_id: &str,
+    ) -> Result<FileInfo<State>, VaultError> {
+        let mut info = String::new();
+
+        sftp.open(self.info_path(file_id))
+            .map_err(io::Error::from)?
+            .read_to_string(&mut info)?;
+
+        serde_json::from_str(&info).map_err(VaultError::from)
+    }
+
+    fn write_file<State>(
+        &self,
+        sftp: &Sftp,
+        file_info: &FileInfo<State>,
+    ) -> Result<(), VaultError> {
+        let info = serde_json::to_vec(file_info)?;
+
+        sftp.create(&self.info_path(file_info.id()))
+            .map_err(io::Error::from)?
+            .write_all(&info)?;
+
+        Ok(())
+    }
+}
+
+impl Vault for SftpVault {
+    fn build_file(
+        &self,
+        length: u64,
+        metadata: Option<&str>,
+    ) -> Result<FileInfo<Built>, VaultError> {
+        let metadata = match metadata {
+            Some(metadata) => Metadata::try_from(metadata)
+                .map_err(|e| VaultError::Invalid(Box::new(e)))?,
+            None => Metadata::default(),
+        };
+
+        Ok(FileInfo::new(length)
+            .with_uuid()
+            .with_metadata(metadata)
+            .build())
+    }
+
+    fn create_file(
+        &self,
+        file_info: FileInfo<Built>,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let content_key = self.content_key(file_info.id());
+        let file_info = file_info.mark_as_created(&content_key);
+
+        self.with_sftp(|sftp| {
+            let file_dir = self.file_dir(file_info.id());
+
+            sftp.mkdir(&file_dir, 0o755).map_err(io::Error::from)?;
+
+            let create = || -> Result<(), VaultError> {
+                /* Creating file for upload */
+                sftp.open_mode(
+                    self.content_path(file_info.id()),
+                    OpenFlags::WRITE | OpenFlags::EXCLUSIVE,
+                    0o644,
+                    OpenType::File,
+                )
+                .map_err(io::Error::from)?;
+
+                self.write_file(sftp, &file_info)
+            };
+
+            create().inspect_err(|_| {
+                let _ = sftp.unlink(&self.content_path(file_info.id()));
+                let _ = sftp.rmdir(&file_dir);
+            })
+        })?;
+
+        Ok(file_info)
+    }
+
+    fn exists(&self, file_id: &str) -> bool {
+        self.with_sftp(|sftp| {
+            sftp.stat(&self.content_path(file_id))
+                .map_err(|e| VaultError::from(io::Error::from(e)))
+        })
+        .is_ok()
+    }
+
+    fn get_file(&self, file_id: &str) -> Result<FileInfo<Created>, VaultError> {
+        self.with_sftp(|sftp| self.read_file(sftp, file_id))
+    }
+
+    fn patch_file(
+        &self,
+        file_id: &str,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        self.with_sftp(|sftp| {
+            let mut file = self.read_file::<Created>(sftp, file_id)?;
+
+            if *file.offset() != offset {
+                return Err(VaultError::Conflict(format!(
+                    "upload is at offset {}",
+                    file.offset()
+                )));
+            }
+
+            let new_offset = offset + buf.len() as u64;
+
+            file.set_offset(new_offset)
+                .map_err(|e| VaultError::Invalid(e.into()))?;
+
+            let content_path = self.content_path(file_id);
+
+            /* Rolling back the failed chunks, so uploads stay at their previous offset */
+            let rollback = || {
+                let truncated = FileStat {
+                    size: Some(offset),
+                    uid: None,
+                    gid: None,
+                    perm: None,
+                    atime: None,
+                    mtime: None,
+                };
+
+                if sftp.setstat(&content_path, truncated).is_err() {
+                    warn_!("Unable to roll back the failed chunk of: {}", file_id);
+                }
+            };
+
+            let write = || -> io::Result<()> {
+                let mut content = sftp.open_mode(
+                    &content_path,
+                    OpenFlags::WRITE,
+                    0o644,
+                    OpenType::File,
+                )?;
+
+                content.seek(SeekFrom::Start(offset))?;
+                content.write_all(buf)?;
+                content.flush()
+            };
+
+            if let Err(e) = write() {
+                rollback();
+                return Err(e.into());
+            }
+
+            if let Err(e) = self.write_file(sftp, &file) {
+                rollback();
+                return Err(e);
+            }
+
+            match file.check_completion() {
+                Some(file) => Ok(PatchOption::Completed(file)),
+                None => Ok(PatchOption::Patched(new_offset)),
+            }
+        })
+    }
+
+    fn terminate_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Terminated>, VaultError> {
+        self.with_sftp(|sftp| {
+            let file_info = self
+                .read_file::<Created>(sftp, file_id)?
+                .mark_as_terminated();
+
+            let file_dir = self.file_dir(file_id);
+
+            for (path, _) in sftp.readdir(&file_dir).map_err(io::Error::from)? {
+                sftp.unlink(&path).map_err(io::Error::from)?;
+            }
+
+            sftp.rmdir(&file_dir).map_err(io::Error::from)?;
+
+            Ok(file_info)
+        })
+    }
+
+    fn declare_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.with_sftp(|sftp| {
+            let mut file = self.read_file::<Created>(sftp, file_id)?;
+
+            file.declare_length(length)
+                .map_err(|e| VaultError::Invalid(e.into()))?;
+
+            self.write_file(sftp, &file)?;
+
+            Ok(file)
+        })
+    }
+
+    fn increase_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.with_sftp(|sftp| {
+            let mut file = self.read_file::<Created>(sftp, file_id)?;
+
+            file.increase_length(length)
+                .map_err(|e| VaultError::Invalid(e.into()))?;
+
+            self.write_file(sftp, &file)?;
+
+            Ok(file)
+        })
+    }
+
+    fn quarantine_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Quarantined>, VaultError> {
+        self.with_sftp(|sftp| {
+            let file = self
+                .read_file::<Created>(sftp, file_id)?
+                .mark_as_quarantined();
+
+            self.write_file(sftp, &file)?;
+
+            Ok(file)
+        })
+    }
+
+    fn open_content(
+        &self,
+        file_id: &str,
+    ) -> Result<Box<dyn Read + Send>, VaultError> {
+        self.with_sftp(|sftp| {
+            let content = sftp
+                .open(self.content_path(file_id))
+                .map_err(io::Error::from)?;
+
+            Ok(Box::new(content) as Box<dyn Read + Send>)
+        })
+    }
+
+    fn update_metadata(
+        &self,
+        file_id: &str,
+        metadata: Metadata,
+    ) -> Result<(), VaultError> {
+        self.with_sftp(|sftp| {
+            let mut file = self.read_file::<Created>(sftp, file_id)?;
+
+            file.set_metadata(metadata);
+
+            self.write_file(sftp, &file)
+        })
+    }
+
+    fn update_upload_token(
+        &self,
+        file_id: &str,
+        token: &str,
+    ) -> Result<(), VaultError> {
+        self.with_sftp(|sftp| {
+            let mut file = self.read_file::<Created>(sftp, file_id)?;
+
+            file.set_upload_token(token.to_string());
+
+            self.write_file(sftp, &file)
+        })
+    }
+
+    fn update_stats(
+        &self,
+        file_id: &str,
+        stats: &UploadStats,
+    ) -> Result<(), VaultError> {
+        self.with_sftp(|sftp| {
+            let mut file = self.read_file::<Created>(sftp, file_id)?;
+
+            file.set_stats(stats.clone());
+
+            self.write_file(sftp, &file)
+        })
+    }
+
+    fn schedule_termination(
+        &self,
+        file_id: &str,
+        time: SystemTime,
+    ) -> Result<(), VaultError> {
+        self.with_sftp(|sftp| {
+            let mut file = self.read_file::<Created>(sftp, file_id)?;
+
+            file.schedule_termination(time);
+
+            self.write_file(sftp, &file)
+        })
+    }
+
+    fn list_quarantined(
+        &self,
+    ) -> Result<Vec<FileInfo<Quarantined>>, VaultError> {
+        let file_ids = self.list_files()?;
+
+        self.with_sftp(|sftp| {
+            Ok(file_ids
+                .iter()
+                .filter_map(|file_id| {
+                    self.read_file::<Quarantined>(sftp, file_id).ok()
+                })
+                .filter(|file| file.is_quarantined())
+                .collect())
+        })
+    }
+
+    fn list_files(&self) -> Result<Vec<String>, VaultError> {
+        self.with_sftp(|sftp| {
+            let entries = match sftp.readdir(&self.root) {
+                Ok(entries) => entries,
+                Err(e) => match io::Error::from(e) {
+                    e if e.kind() == ErrorKind::NotFound => {
+                        return Ok(Vec::new())
+                    }
+                    e => return Err(VaultError::Io(e)),
+                },
+            };
+
+            Ok(entries
+                .iter()
+                .filter(|(_, stat)| stat.is_dir())
+                .filter_map(|(path, _)| path.file_name()?.to_str())
+                .filter(|file_id| !file_id.starts_with('.'))
+                .map(str::to_string)
+                .collect())
+        })
+    }
+}
@@ -0,0 +1,516 @@
+use std::{
+    fs::{self, File},
+    future::Future,
+    io::{ErrorKind, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use rocket::tokio::{runtime, task};
+use serde_json::json;
+use sqlx::{Database, Pool, Row};
+
+use super::{
+    file_info::{Built, Created, FileInfo, Terminated, UploadState},
+    metadata::Metadata,
+    vault::{PatchOption, Vault, VaultError},
+};
+
+/// A [`Vault`] storing the upload information as rows of a SQL table.
+///
+/// Upload state survives redeploys and is queryable with SQL, while the upload
+/// content is stored on disk under `save_path`, pointed by the `file_name` column.
+/// Both Postgres and SQLite pools are supported, through the `sqlx_postgres` and
+/// `sqlx_sqlite` features.
+///
+/// The table is named `meteoritus_uploads` by default and can be created with
+/// [`SqlVault::migrate()`]:
+///
+/// ```sql
+/// CREATE TABLE IF NOT EXISTS meteoritus_uploads (
+///     id TEXT PRIMARY KEY,
+///     length BIGINT NOT NULL,
+///     upload_offset BIGINT NOT NULL,
+///     metadata TEXT,
+///     file_name TEXT NOT NULL
+/// )
+/// ```
+///
+/// **Note:** Since [`Vault`] operations are synchronous, queries block the calling
+/// thread through [`task::block_in_place()`], requiring a multi-threaded runtime like
+/// the one used by Rocket.
+///
+/// # Example
+///
+/// Sharing the pool of a [`rocket_db_pools`](https://api.rocket.rs/v0.5/rocket_db_pools/) database:
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::fairing::AdHoc;
+/// use rocket_db_pools::{sqlx, Database};
+/// use meteoritus::{Meteoritus, SqlVault};
+///
+/// #[derive(Database)]
+/// #[database("uploads")]
+/// struct Uploads(sqlx::SqlitePool);
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build()
+///         .attach(Uploads::init())
+///         .attach(AdHoc::try_on_ignite("Meteoritus", |rocket| async {
+///             let Some(db) = Uploads::fetch(&rocket) else {
+///                 return Err(rocket);
+///             };
+///
+///             let vault = SqlVault::new(db.0.clone(), "./tmp/uploads");
+///
+///             if vault.migrate().await.is_err() {
+///                 return Err(rocket);
+///             }
+///
+///             let meteoritus = Meteoritus::new().with_vault(vault).build();
+///
+///             Ok(rocket.attach(meteoritus))
+///         }))
+/// }
+/// ```
+pub struct SqlVault<DB: Database> {
+    pool: Pool<DB>,
+    table: String,
+    save_path: &'static str,
+}
+
+impl<DB: Database> SqlVault<DB> {
+    /// Creates a [`SqlVault`] using the given connection `pool`, storing the upload
+    /// content into `save_path`.
+    pub fn new(pool: Pool<DB>, save_path: &'static str) -> Self {
+        Self {
+            pool,
+            table: "meteoritus_uploads".to_string(),
+            save_path,
+        }
+    }
+
+    /// Specifies the table storing the upload information, by default `meteoritus_uploads`.
+    pub fn with_table(mut self, table: impl Into<String>) -> Self {
+        self.table = table.into();
+        self
+    }
+
+    /// Returns the connection pool used by the vault.
+    pub fn pool(&self) -> &Pool<DB> {
+        &self.pool
+    }
+
+    fn content_path(&self, file_id: &str) -> std::path::PathBuf {
+        Path::new(self.save_path).join(file_id)
+    }
+}
+
+/// Runs the given future to completion from the synchronous [`Vault`] operations.
+fn block_on<F: Future>(future: F) -> F::Output {
+    match runtime::Handle::try_current() {
+        Ok(handle) => task::block_in_place(|| handle.block_on(future)),
+        Err(_) => runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("tokio runtime should be created")
+            .block_on(future),
+    }
+}
+
+/// Maps the database failures, reporting missing rows as [`VaultError::NotFound`].
+fn backend_error(e: sqlx::Error) -> VaultError {
+    match e {
+        sqlx::Error::RowNotFound => VaultError::NotFound,
+        e => VaultError::Backend(e.into()),
+    }
+}
+
+fn file_info_from_row<State>(
+    id: String,
+    length: i64,
+    offset: i64,
+    metadata: Option<String>,
+    file_name: String,
+) -> Result<FileInfo<State>, VaultError> {
+    let metadata = match metadata {
+        Some(metadata) => Some(serde_json::from_str::<Metadata>(&metadata)?),
+        None => None,
+    };
+
+    /* The state is derived from the stored progress */
+    let upload_state = match (length, offset) {
+        (length, offset) if length >= 0 && offset == length => {
+            UploadState::Completed
+        }
+        (_, 0) => UploadState::Created,
+        _ => UploadState::InProgress,
+    };
+
+    /* Deferred lengths are stored as negative values */
+    serde_json::from_value(json!({
+        "id": id,
+        "file_name": file_name,
+        "length": length.max(0),
+        "offset": offset,
+        "metadata": metadata,
+        "length_deferred": length < 0,
+        "upload_state": upload_state,
+    }))
+    .map_err(VaultError::from)
+}
+
+macro_rules! impl_sql_vault {
+    ($db:ty) => {
+        impl SqlVault<$db> {
+            /// Creates the uploads table, if it does not exist.
+            pub async fn migrate(&self) -> Result<(), sqlx::Error> {
+                sqlx::query(&format!(
+                    "CREATE TABLE IF NOT EXISTS {} (
+                        id TEXT PRIMARY KEY,
+                        length BIGINT NOT NULL,
+                        upload_offset BIGINT NOT NULL,
+                        metadata TEXT,
+                        file_name TEXT NOT NULL
+                    )",
+                    self.table
+                ))
+                .execute(&self.pool)
+                .await?;
+
+                Ok(())
+            }
+
+            fn read_file<State>(
+                &self,
+                file_id: &str,
+            ) -> Result<FileInfo<State>, VaultError> {
+                let query = format!(
+                    "SELECT id, length, upload_offset, metadata, file_name
+                     FROM {} WHERE id = $1",
+                    self.table
+                );
+
+                let row = block_on(
+                    sqlx::query(&query).bind(file_id).fetch_one(&self.pool),
+                )
+                .map_err(backend_error)?;
+
+                let read = || -> Result<_, sqlx::Error> {
+                    Ok((
+                        row.try_get("id")?,
+                        row.try_get("length")?,
+                        row.try_get("upload_offset")?,
+                        row.try_get("metadata")?,
+                        row.try_get("file_name")?,
+                    ))
+                };
+
+                let (id, length, offset, metadata, file_name) =
+                    read().map_err(backend_error)?;
+
+                file_info_from_row(id, length, offset, metadata, file_name)
+            }
+        }
+
+        impl Vault for SqlVault<$db> {
+            fn build_file(
+                &self,
+                length: u64,
+                metadata: Option<&str>,
+            ) -> Result<FileInfo<Built>, VaultError> {
+                let metadata = match metadata {
+                    Some(metadata) => Metadata::try_from(metadata)
+                        .map_err(|e| VaultError::Invalid(Box::new(e)))?,
+                    None => Metadata::default(),
+                };
+
+                Ok(FileInfo::new(length)
+                    .with_uuid()
+                    .with_metadata(metadata)
+                    .build())
+            }
+
+            fn create_file(
+                &self,
+                file_info: FileInfo<Built>,
+            ) -> Result<FileInfo<Created>, VaultError> {
+                let length = match file_info.is_length_deferred() {
+                    true => -1,
+                    false => i64::try_from(*file_info.length())
+                        .map_err(|e| VaultError::Invalid(e.into()))?,
+                };
+
+                let metadata = match file_info.metadata() {
+                    Some(metadata) => Some(
+                        serde_json::to_string(metadata)?,
+                    ),
+                    None => None,
+                };
+
+                fs::create_dir_all(self.save_path)?;
+
+                let file_path = self.content_path(file_info.id());
+
+                /* Creating file for upload */
+                File::options()
+                    .write(true)
+                    .create_new(true)
+                    .open(&file_path)
+                    .and_then(|file| file.set_len(*file_info.length()))?;
+
+                let Some(file_name) = file_path.to_str() else {
+                    return Err(VaultError::Io(ErrorKind::InvalidInput.into()));
+                };
+
+                let file_info = file_info.mark_as_created(file_name);
+
+                let query = format!(
+                    "INSERT INTO {}
+                     (id, length, upload_offset, metadata, file_name)
+                     VALUES ($1, $2, $3, $4, $5)",
+                    self.table
+                );
+
+                block_on(
+                    sqlx::query(&query)
+                        .bind(file_info.id())
+                        .bind(length)
+                        .bind(0_i64)
+                        .bind(metadata)
+                        .bind(file_name)
+                        .execute(&self.pool),
+                )
+                .map_err(|e| {
+                    let _ = fs::remove_file(&file_path);
+                    backend_error(e)
+                })?;
+
+                Ok(file_info)
+            }
+
+            fn exists(&self, file_id: &str) -> bool {
+                self.read_file::<Created>(file_id).is_ok()
+            }
+
+            fn get_file(
+                &self,
+                file_id: &str,
+            ) -> Result<FileInfo<Created>, VaultError> {
+                self.read_file(file_id)
+            }
+
+            fn patch_file(
+                &self,
+                file_id: &str,
+                buf: &mut [u8],
+                offset: u64,
+            ) -> Result<PatchOption, VaultError> {
+                let mut file = self.get_file(file_id)?;
+
+                if *file.offset() != offset {
+                    return Err(VaultError::Conflict(format!(
+                        "upload is at offset {}",
+                        file.offset()
+                    )));
+                }
+
+                let new_offset = offset + buf.len() as u64;
+
+                file.set_offset(new_offset)
+                    .map_err(|e| VaultError::Invalid(e.into()))?;
+
+                let mut content = File::options()
+                    .write(true)
+                    .open(self.content_path(file_id))?;
+
+                /* Tracking the content length, so a failed chunk can be rolled back */
+                let content_length = content.metadata()?.len();
+
+                let rollback = |content: &File| {
+                    if content.set_len(content_length).is_err() {
+                        warn_!(
+                            "Unable to roll back the failed chunk of: {}",
+                            file_id
+                        );
+                    }
+                };
+
+                if let Err(e) = content
+                    .seek(SeekFrom::Start(offset))
+                    .and_then(|_| content.write_all(buf))
+                {
+                    rollback(&content);
+                    return Err(e.into());
+                }
+
+                /* Only moving the offset forward when no other patch did it first */
+                let query = format!(
+                    "UPDATE {} SET upload_offset = $1
+                     WHERE id = $2 AND upload_offset = $3",
+                    self.table
+                );
+
+                let result = block_on(
+                    sqlx::query(&query)
+                        .bind(new_offset as i64)
+                        .bind(file_id)
+                        .bind(offset as i64)
+                        .execute(&self.pool),
+                )
+                .map_err(|e| {
+                    rollback(&content);
+                    backend_error(e)
+                })?;
+
+                /* Another patch already moved the offset and owns the written region */
+                if result.rows_affected() != 1 {
+                    return Err(VaultError::Conflict(
+                        "upload offset was moved by another patch".to_string(),
+                    ));
+                }
+
+                match file.check_completion() {
+                    Some(file) => Ok(PatchOption::Completed(file)),
+                    None => Ok(PatchOption::Patched(new_offset)),
+                }
+            }
+
+            fn terminate_file(
+                &self,
+                file_id: &str,
+            ) -> Result<FileInfo<Terminated>, VaultError> {
+                let file_info = self.get_file(file_id)?.mark_as_terminated();
+
+                let query = format!("DELETE FROM {} WHERE id = $1", self.table);
+
+                block_on(sqlx::query(&query).bind(file_id).execute(&self.pool))
+                    .map_err(backend_error)?;
+
+                match fs::remove_file(self.content_path(file_id)) {
+                    Err(e) if e.kind() != ErrorKind::NotFound => {
+                        Err(VaultError::Io(e))
+                    }
+                    _ => Ok(file_info),
+                }
+            }
+
+            fn declare_length(
+                &self,
+                file_id: &str,
+                length: u64,
+            ) -> Result<FileInfo<Created>, VaultError> {
+                let mut file = self.get_file(file_id)?;
+
+                file.declare_length(length)
+                    .map_err(|e| VaultError::Invalid(e.into()))?;
+
+                let query = format!(
+                    "UPDATE {} SET length = $1 WHERE id = $2 AND length < 0",
+                    self.table
+                );
+
+                let result = block_on(
+                    sqlx::query(&query)
+                        .bind(length as i64)
+                        .bind(file_id)
+                        .execute(&self.pool),
+                )
+                .map_err(backend_error)?;
+
+                if result.rows_affected() != 1 {
+                    return Err(VaultError::Conflict(
+                        "upload length is already declared".to_string(),
+                    ));
+                }
+
+                Ok(file)
+            }
+
+            fn increase_length(
+                &self,
+                file_id: &str,
+                length: u64,
+            ) -> Result<FileInfo<Created>, VaultError> {
+                let mut file = self.get_file(file_id)?;
+
+                file.increase_length(length)
+                    .map_err(|e| VaultError::Invalid(e.into()))?;
+
+                let query = format!(
+                    "UPDATE {} SET length = $1 \
+                     WHERE id = $2 AND length >= 0 AND length < $1 \
+                     AND upload_offset < length",
+                    self.table
+                );
+
+                let result = block_on(
+                    sqlx::query(&query)
+                        .bind(length as i64)
+                        .bind(file_id)
+                        .execute(&self.pool),
+                )
+                .map_err(backend_error)?;
+
+                if result.rows_affected() != 1 {
+                    return Err(VaultError::Conflict(
+                        "upload length changed concurrently".to_string(),
+                    ));
+                }
+
+                Ok(file)
+            }
+
+            fn update_metadata(
+                &self,
+                file_id: &str,
+                metadata: Metadata,
+            ) -> Result<(), VaultError> {
+                let metadata = serde_json::to_string(&metadata)?;
+
+                let query = format!(
+                    "UPDATE {} SET metadata = $1 WHERE id = $2",
+                    self.table
+                );
+
+                let result = block_on(
+                    sqlx::query(&query)
+                        .bind(metadata)
+                        .bind(file_id)
+                        .execute(&self.pool),
+                )
+                .map_err(backend_error)?;
+
+                if result.rows_affected() != 1 {
+                    return Err(VaultError::NotFound);
+                }
+
+                Ok(())
+            }
+
+            fn storage_path(&self) -> Option<&Path> {
+                Some(Path::new(self.save_path))
+            }
+
+            fn list_files(&self) -> Result<Vec<String>, VaultError> {
+                let query = format!("SELECT id FROM {}", self.table);
+
+                let rows =
+                    block_on(sqlx::query(&query).fetch_all(&self.pool))
+                        .map_err(backend_error)?;
+
+                rows.iter()
+                    .map(|row| row.try_get("id"))
+                    .collect::<Result<_, sqlx::Error>>()
+                    .map_err(backend_error)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "sqlx_postgres")]
+impl_sql_vault!(sqlx::Postgres);
+
+#[cfg(feature = "sqlx_sqlite")]
+impl_sql_vault!(sqlx::Sqlite);
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use rocket::serde::{Deserialize, Serialize};
+
+/// Transfer statistics of an upload, recorded on each `PATCH` request and persisted
+/// with its [`FileInfo`](crate::FileInfo), like to analyze the performance of
+/// clients and tune their chunk sizes.
+///
+/// Statistics are only persisted by vaults supporting
+/// [`Vault::update_stats()`](crate::Vault::update_stats).
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use meteoritus::{Completed, HandlerContext, Meteoritus};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let meteoritus = Meteoritus::new()
+///         .on_completed(|ctx: HandlerContext<Completed>| {
+///             let stats = ctx.file_info.stats();
+///
+///             println!(
+///                 "Uploaded in {:?} through {} requests of {:?} bytes, {} retried",
+///                 stats.transfer_duration(),
+///                 stats.patch_requests(),
+///                 stats.average_chunk_size(),
+///                 stats.retries(),
+///             );
+///         })
+///         .build();
+///
+///     rocket::build().attach(meteoritus)
+/// }
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct UploadStats {
+    #[serde(default)]
+    transfer_ms: u64,
+    #[serde(default)]
+    patch_requests: u64,
+    #[serde(default)]
+    received_bytes: u64,
+    #[serde(default)]
+    retries: u64,
+    #[serde(default, skip_serializing_if = "is_false")]
+    interrupted: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+impl UploadStats {
+    /// Records a `PATCH` request which stored `bytes` in `duration`, and whether
+    /// its body was `interrupted`, like by a disconnected client.
+    pub(crate) fn record(
+        &mut self,
+        bytes: u64,
+        duration: Duration,
+        interrupted: bool,
+    ) {
+        /* Requests resuming an interrupted one are retries of its chunk */
+        if self.interrupted {
+            self.retries += 1;
+        }
+
+        let millis = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+
+        self.transfer_ms = self.transfer_ms.saturating_add(millis);
+        self.patch_requests += 1;
+        self.received_bytes = self.received_bytes.saturating_add(bytes);
+        self.interrupted = interrupted;
+    }
+
+    /// Returns the total time spent receiving and storing the `PATCH` requests of
+    /// the upload, excluding the time clients spent between requests.
+    pub fn transfer_duration(&self) -> Duration {
+        Duration::from_millis(self.transfer_ms)
+    }
+
+    /// Returns the number of `PATCH` requests which stored bytes of the upload.
+    pub fn patch_requests(&self) -> u64 {
+        self.patch_requests
+    }
+
+    /// Returns the number of bytes received through `PATCH` requests.
+    pub fn received_bytes(&self) -> u64 {
+        self.received_bytes
+    }
+
+    /// Returns the average number of bytes stored by each `PATCH` request, if any.
+    pub fn average_chunk_size(&self) -> Option<u64> {
+        self.received_bytes.checked_div(self.patch_requests)
+    }
+
+    /// Returns the number of `PATCH` requests resuming the upload after an
+    /// interrupted one.
+    pub fn retries(&self) -> u64 {
+        self.retries
+    }
+
+    /// Indicates if no `PATCH` request was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.patch_requests == 0
+    }
+}
@@ -0,0 +1,370 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::SystemTime,
+};
+
+use super::{
+    file_info::{Built, Created, FileInfo, Quarantined, Terminated},
+    metadata::Metadata,
+    stats::UploadStats,
+    vault::{PatchOption, Vault, VaultError},
+};
+
+/// A cold storage backend receiving the uploads offloaded by [`TieredVault`],
+/// like an S3 or GCS bucket.
+///
+/// # Example
+///
+/// ```rust
+/// use std::{error::Error, path::Path};
+/// use meteoritus::ColdStorage;
+///
+/// struct BucketStorage {
+///     bucket: String,
+/// }
+///
+/// impl ColdStorage for BucketStorage {
+///     fn location(&self, file_id: &str) -> String {
+///         format!("s3://{}/uploads/{}", self.bucket, file_id)
+///     }
+///
+///     fn offload(
+///         &self,
+///         file_id: &str,
+///         source: &Path,
+///     ) -> Result<(), Box<dyn Error + Send + Sync>> {
+///         // Upload the `source` file to the bucket using your S3 client
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait ColdStorage: Send + Sync {
+    /// Returns the final location of the given upload in the cold storage.
+    ///
+    /// It must be known before the offload happens, since it is surfaced to the
+    /// `on_completed` callback while the offload runs in background.
+    fn location(&self, file_id: &str) -> String;
+
+    /// Copies the completed upload content from the local `source` path into the cold storage.
+    fn offload(
+        &self,
+        file_id: &str,
+        source: &Path,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    /// Copies the completed upload content into the cold storage, within the
+    /// `storage_class` hinted by the upload, see [`FileInfo::storage_class()`].
+    ///
+    /// Storages supporting classes, like S3 or GCS buckets, should override it to
+    /// store archival uploads directly in cheaper tiers. The default implementation
+    /// ignores the hint and calls [`ColdStorage::offload()`].
+    fn offload_to_class(
+        &self,
+        file_id: &str,
+        source: &Path,
+        storage_class: Option<&str>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let _ = storage_class;
+        self.offload(file_id, source)
+    }
+}
+
+/// A [`ColdStorage`] copying the uploads into another directory, like a network mount.
+pub struct DirectoryColdStorage {
+    path: PathBuf,
+}
+
+impl DirectoryColdStorage {
+    /// Creates a [`DirectoryColdStorage`] copying the uploads into `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl ColdStorage for DirectoryColdStorage {
+    fn location(&self, file_id: &str) -> String {
+        self.path.join(file_id).to_string_lossy().into_owned()
+    }
+
+    fn offload(
+        &self,
+        file_id: &str,
+        source: &Path,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        fs::create_dir_all(&self.path)?;
+        fs::copy(source, self.path.join(file_id))?;
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct PendingOffload {
+    terminate: bool,
+}
+
+/// A [`Vault`] spooling chunks into a fast `hot` vault, then offloading the
+/// completed uploads to a [`ColdStorage`] in background.
+///
+/// The [`FileInfo`] of completed uploads points to the final [`ColdStorage::location()`],
+/// surfaced to the `on_completed` callback through [`FileInfo::storage_ref()`].
+/// Terminating an upload only removes its hot copy, once its offload has finished.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Ignite;
+/// use meteoritus::{DirectoryColdStorage, LocalVault, Meteoritus, TieredVault};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let vault = TieredVault::new(
+///         LocalVault::new("./tmp/spool"),
+///         DirectoryColdStorage::new("/mnt/archive/uploads"),
+///     );
+///
+///     let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+///         .with_vault(vault)
+///         .on_completed(|ctx| {
+///             println!("Upload stored at: {}", ctx.file_info.storage_ref());
+///         })
+///         .build();
+///
+///     rocket::build().attach(meteoritus)
+/// }
+/// ```
+pub struct TieredVault<H: Vault, C: ColdStorage> {
+    hot: Arc<H>,
+    cold: Arc<C>,
+    pending: Arc<Mutex<HashMap<String, PendingOffload>>>,
+}
+
+impl<H: Vault + 'static, C: ColdStorage + 'static> TieredVault<H, C> {
+    /// Creates a [`TieredVault`] spooling into `hot` and offloading into `cold`.
+    pub fn new(hot: H, cold: C) -> Self {
+        Self {
+            hot: Arc::new(hot),
+            cold: Arc::new(cold),
+            pending: Default::default(),
+        }
+    }
+
+    /// Returns `true` while the given upload is being offloaded.
+    pub fn is_offloading(&self, file_id: &str) -> bool {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(file_id)
+    }
+
+    /// Offloads the upload once completed, pointing it to its cold location.
+    fn offload_completed(
+        &self,
+        file_id: &str,
+        patched: PatchOption,
+    ) -> PatchOption {
+        let PatchOption::Completed(file) = patched else {
+            return patched;
+        };
+
+        let source = PathBuf::from(file.file_name());
+        self.spawn_offload(file_id.to_string(), source, file.storage_class());
+
+        let location = self.cold.location(file_id);
+        PatchOption::Completed(file.with_file_name(location))
+    }
+
+    fn spawn_offload(
+        &self,
+        file_id: String,
+        source: PathBuf,
+        storage_class: Option<String>,
+    ) {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(file_id.to_owned(), PendingOffload::default());
+
+        let hot = self.hot.to_owned();
+        let cold = self.cold.to_owned();
+        let pending = self.pending.to_owned();
+
+        thread::spawn(move || {
+            if let Err(e) = cold.offload_to_class(
+                &file_id,
+                &source,
+                storage_class.as_deref(),
+            ) {
+                warn_!("Unable to offload upload {}: {}", file_id, e);
+            }
+
+            let offload = pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&file_id);
+
+            if offload.is_some_and(|offload| offload.terminate)
+                && hot.terminate_file(&file_id).is_err()
+            {
+                warn_!("Unable to terminate offloaded upload: {}", file_id);
+            }
+        });
+    }
+}
+
+impl<H: Vault + 'static, C: ColdStorage + 'static> Vault for TieredVault<H, C> {
+    fn build_file(
+        &self,
+        length: u64,
+        metadata: Option<&str>,
+    ) -> Result<FileInfo<Built>, VaultError> {
+        self.hot.build_file(length, metadata)
+    }
+
+    fn create_file(
+        &self,
+        file: FileInfo<Built>,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.hot.create_file(file)
+    }
+
+    fn exists(&self, file_id: &str) -> bool {
+        self.hot.exists(file_id)
+    }
+
+    fn get_file(&self, file_id: &str) -> Result<FileInfo<Created>, VaultError> {
+        self.hot.get_file(file_id)
+    }
+
+    fn patch_file(
+        &self,
+        file_id: &str,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        let patched = self.hot.patch_file(file_id, buf, offset)?;
+
+        Ok(self.offload_completed(file_id, patched))
+    }
+
+    fn write_range(
+        &self,
+        file_id: &str,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        let patched = self.hot.write_range(file_id, buf, offset)?;
+
+        Ok(self.offload_completed(file_id, patched))
+    }
+
+    fn terminate_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Terminated>, VaultError> {
+        let mut pending =
+            self.pending.lock().unwrap_or_else(|e| e.into_inner());
+
+        /* Deferring the termination until the offload has finished */
+        if let Some(offload) = pending.get_mut(file_id) {
+            offload.terminate = true;
+
+            return Ok(self.hot.get_file(file_id)?.mark_as_terminated());
+        }
+
+        drop(pending);
+
+        self.hot.terminate_file(file_id)
+    }
+
+    fn declare_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.hot.declare_length(file_id, length)
+    }
+
+    fn increase_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.hot.increase_length(file_id, length)
+    }
+
+    fn repair_offset(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.hot.repair_offset(file_id)
+    }
+
+    fn repair_range(
+        &self,
+        file_id: &str,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<(), VaultError> {
+        self.hot.repair_range(file_id, buf, offset)
+    }
+
+    fn update_metadata(
+        &self,
+        file_id: &str,
+        metadata: Metadata,
+    ) -> Result<(), VaultError> {
+        self.hot.update_metadata(file_id, metadata)
+    }
+
+    fn update_upload_token(
+        &self,
+        file_id: &str,
+        token: &str,
+    ) -> Result<(), VaultError> {
+        self.hot.update_upload_token(file_id, token)
+    }
+
+    fn update_stats(
+        &self,
+        file_id: &str,
+        stats: &UploadStats,
+    ) -> Result<(), VaultError> {
+        self.hot.update_stats(file_id, stats)
+    }
+
+    fn schedule_termination(
+        &self,
+        file_id: &str,
+        time: SystemTime,
+    ) -> Result<(), VaultError> {
+        self.hot.schedule_termination(file_id, time)
+    }
+
+    fn quarantine_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Quarantined>, VaultError> {
+        self.hot.quarantine_file(file_id)
+    }
+
+    fn list_quarantined(
+        &self,
+    ) -> Result<Vec<FileInfo<Quarantined>>, VaultError> {
+        self.hot.list_quarantined()
+    }
+
+    fn storage_path(&self) -> Option<&Path> {
+        self.hot.storage_path()
+    }
+
+    fn list_files(&self) -> Result<Vec<String>, VaultError> {
+        self.hot.list_files()
+    }
+}
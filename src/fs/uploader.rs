@@ -0,0 +1,60 @@
+use std::net::IpAddr;
+
+use rocket::{
+    serde::{Deserialize, Serialize},
+    Request,
+};
+
+/// The header of the optional device id, provided by clients on creation.
+const DEVICE_ID_HEADER: &str = "Upload-Device-Id";
+
+/// Information about the client creating an upload, captured from the creation
+/// request and persisted with its [`FileInfo`](crate::FileInfo), like for abuse
+/// investigations.
+///
+/// The device id is provided by clients through the `Upload-Device-Id` header, so
+/// like the user agent it is only informative and must not be trusted.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct UploaderInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    user_agent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    client_ip: Option<IpAddr>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    device_id: Option<String>,
+}
+
+impl UploaderInfo {
+    /// Captures the uploader of the given creation request.
+    pub(crate) fn from_request(req: &Request<'_>) -> Self {
+        let header = |name| req.headers().get_one(name).map(str::to_string);
+
+        Self {
+            user_agent: header("User-Agent"),
+            client_ip: req.client_ip(),
+            device_id: header(DEVICE_ID_HEADER),
+        }
+    }
+
+    /// Returns the `User-Agent` of the client.
+    pub fn user_agent(&self) -> Option<&str> {
+        self.user_agent.as_deref()
+    }
+
+    /// Returns the IP address of the client, as resolved by Rocket, which honors
+    /// the configured `ip_header`.
+    pub fn client_ip(&self) -> Option<IpAddr> {
+        self.client_ip
+    }
+
+    /// Returns the device id provided by the client.
+    pub fn device_id(&self) -> Option<&str> {
+        self.device_id.as_deref()
+    }
+
+    /// Indicates if nothing was captured about the uploader.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
@@ -1,45 +1,222 @@
 use std::{
     error::Error,
     fs::{self, File},
-    io::{BufReader, ErrorKind, Seek, SeekFrom, Write},
-    path::Path,
+    io::{self, BufReader, ErrorKind, Read, Seek, SeekFrom, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    time::SystemTime,
 };
 
+use rocket::time::OffsetDateTime;
+
+use crate::clock::{Clock, SystemClock};
+
 use super::{
-    file_info::{Built, Completed, Created, FileInfo, Terminated},
+    file_info::{Built, Completed, Created, FileInfo, Quarantined, Terminated},
     metadata::Metadata,
+    stats::UploadStats,
 };
 
+/// The outcome of a successful [`Vault::patch_file()`] call.
+#[allow(clippy::large_enum_variant)]
 pub enum PatchOption {
+    /// The chunk was stored and the upload now sits at the given offset.
     Patched(u64),
+    /// The chunk was stored and the upload reached its declared length.
     Completed(FileInfo<Completed>),
 }
 
-#[derive(Debug)]
+/// An error reported by a [`Vault`].
+///
+/// The tus handlers map each variant to the closest HTTP response, like `404 Not Found`
+/// for [`VaultError::NotFound`] or `409 Conflict` for [`VaultError::Conflict`], so
+/// custom vaults should report the most specific variant.
+///
+/// I/O and JSON errors convert into a [`VaultError`] through `?`, an I/O error of
+/// kind [`ErrorKind::NotFound`] being reported as [`VaultError::NotFound`].
+///
+/// # Example
+///
+/// ```rust
+/// use meteoritus::VaultError;
+///
+/// fn read_offset(file_id: &str) -> Result<u64, VaultError> {
+///     let offset = std::fs::read_to_string(format!("./tmp/{file_id}.offset"))?;
+///
+///     offset
+///         .trim()
+///         .parse()
+///         .map_err(|e| VaultError::Backend(Box::new(e)))
+/// }
+///
+/// assert!(matches!(read_offset("missing"), Err(VaultError::NotFound)));
+/// ```
+#[derive(Debug, thiserror::Error)]
 pub enum VaultError {
-    CreationError(Box<dyn Error>),
-    ReadError(Box<dyn Error>),
-    TerminationError(Box<dyn Error>),
-    Error,
+    /// The upload doesn't exist in the vault.
+    #[error("upload not found")]
+    NotFound,
+
+    /// The operation conflicts with the current state of the upload, like a chunk
+    /// written at an outdated offset.
+    #[error("upload conflict: {0}")]
+    Conflict(String),
+
+    /// The upload provided by the client is invalid, like a malformed metadata.
+    #[error("invalid upload: {0}")]
+    Invalid(#[source] Box<dyn Error + Send + Sync>),
+
+    /// The underlying storage failed to perform an I/O operation.
+    #[error("storage I/O error: {0}")]
+    Io(#[source] io::Error),
+
+    /// The upload information couldn't be serialized or deserialized.
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// Any other failure of the vault backend, like a database error.
+    #[error("vault backend error: {0}")]
+    Backend(#[source] Box<dyn Error + Send + Sync>),
+}
+
+impl VaultError {
+    /// Returns the kind of the underlying I/O error, if any.
+    pub(crate) fn io_error_kind(&self) -> Option<ErrorKind> {
+        match self {
+            Self::Io(e) => Some(e.kind()),
+            Self::Serialization(e) => e.io_error_kind(),
+            Self::Backend(e) => {
+                e.downcast_ref::<io::Error>().map(io::Error::kind)
+            }
+            _ => None,
+        }
+    }
+
+    /// Creates the error reported by vaults not supporting an operation.
+    pub(crate) fn unsupported() -> Self {
+        Self::Io(ErrorKind::Unsupported.into())
+    }
 }
 
-#[doc(hidden)]
+impl From<io::Error> for VaultError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            ErrorKind::NotFound => Self::NotFound,
+            _ => Self::Io(e),
+        }
+    }
+}
+
+/// The storage backend used by [`Meteoritus`](crate::Meteoritus) to persist uploads.
+///
+/// This is the single trait a custom storage has to implement. The default
+/// implementation is [`LocalVault`], which can also be wrapped to add behavior
+/// on top of it.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Ignite;
+/// use meteoritus::{
+///     Built, Created, FileInfo, LocalVault, Meteoritus, PatchOption, Terminated,
+///     Vault, VaultError,
+/// };
+///
+/// pub struct LoggingVault {
+///     inner: LocalVault,
+/// }
+///
+/// impl Vault for LoggingVault {
+///     fn build_file(
+///         &self,
+///         length: u64,
+///         metadata: Option<&str>,
+///     ) -> Result<FileInfo<Built>, VaultError> {
+///         self.inner.build_file(length, metadata)
+///     }
+///
+///     fn create_file(
+///         &self,
+///         file: FileInfo<Built>,
+///     ) -> Result<FileInfo<Created>, VaultError> {
+///         println!("Creating file: {}", file.id());
+///         self.inner.create_file(file)
+///     }
+///
+///     fn exists(&self, file_id: &str) -> bool {
+///         self.inner.exists(file_id)
+///     }
+///
+///     fn get_file(&self, file_id: &str) -> Result<FileInfo<Created>, VaultError> {
+///         self.inner.get_file(file_id)
+///     }
+///
+///     fn patch_file(
+///         &self,
+///         file_id: &str,
+///         buf: &mut [u8],
+///         offset: u64,
+///     ) -> Result<PatchOption, VaultError> {
+///         println!("Patching {} bytes into: {}", buf.len(), file_id);
+///         self.inner.patch_file(file_id, buf, offset)
+///     }
+///
+///     fn terminate_file(
+///         &self,
+///         file_id: &str,
+///     ) -> Result<FileInfo<Terminated>, VaultError> {
+///         self.inner.terminate_file(file_id)
+///     }
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+///         .with_vault(LoggingVault {
+///             inner: LocalVault::new("./tmp/uploads"),
+///         })
+///         .build();
+///
+///     rocket::build().attach(meteoritus)
+/// }
+/// ```
 pub trait Vault: Send + Sync {
+    /// Builds a new [`FileInfo`] for an upload of `length` bytes, parsing the raw
+    /// `Upload-Metadata` header value when supplied.
+    ///
+    /// Nothing should be persisted at this point, since the `on_creation` callback
+    /// may still reject the upload.
     fn build_file(
         &self,
         length: u64,
         metadata: Option<&str>,
     ) -> Result<FileInfo<Built>, VaultError>;
 
+    /// Persists the given [`FileInfo`] and reserves storage for its content.
     fn create_file(
         &self,
         file: FileInfo<Built>,
     ) -> Result<FileInfo<Created>, VaultError>;
 
+    /// Returns `true` if an upload with the given id is stored.
     fn exists(&self, file_id: &str) -> bool;
 
+    /// Returns the stored [`FileInfo`] of the given upload.
     fn get_file(&self, file_id: &str) -> Result<FileInfo<Created>, VaultError>;
 
+    /// Writes `buf` at the given `offset` of the upload content, advancing the
+    /// upload offset.
+    ///
+    /// Verifying and advancing the offset must be atomic, like a compare-and-set:
+    /// the `offset` must match the current upload offset, otherwise a
+    /// [`VaultError::Conflict`] should be returned without writing any byte, so out
+    /// of concurrent calls at the same `offset` exactly one succeeds. Vaults shared
+    /// by multiple threads or processes should hold a lock, or rely on an equivalent
+    /// guarantee of their storage, from reading the offset to storing the new one.
+    ///
+    /// When the chunk can't be fully stored, an error should be returned leaving
+    /// the upload exactly at its previous offset.
     fn patch_file(
         &self,
         file_id: &str,
@@ -47,38 +224,765 @@ pub trait Vault: Send + Sync {
         offset: u64,
     ) -> Result<PatchOption, VaultError>;
 
+    /// Writes `buf` at the given `offset` of the upload content, which may be past
+    /// the upload offset, recording the range as received and advancing the
+    /// offset over the bytes stored contiguously, see
+    /// [`Meteoritus::with_parallel_chunks()`](crate::Meteoritus::with_parallel_chunks).
+    ///
+    /// The range must fit the declared length and can't start before the upload
+    /// offset, otherwise a [`VaultError::Conflict`] should be returned without
+    /// writing any byte. Concurrent calls may write distinct ranges of the same
+    /// upload, so recording them must be atomic.
+    ///
+    /// The default implementation fails, for vaults not supporting parallel chunks.
+    fn write_range(
+        &self,
+        file_id: &str,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        let _ = (file_id, buf, offset);
+        Err(VaultError::unsupported())
+    }
+
+    /// Removes the given upload and all of its content from storage.
     fn terminate_file(
         &self,
         file_id: &str,
     ) -> Result<FileInfo<Terminated>, VaultError>;
+
+    /// Fixes the total `length` of an upload created with a deferred length,
+    /// returning its updated [`FileInfo`].
+    ///
+    /// The default implementation fails, for vaults not supporting the
+    /// `creation-defer-length` extension.
+    fn declare_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let _ = (file_id, length);
+        Err(VaultError::unsupported())
+    }
+
+    /// Enlarges the declared `length` of an upload which isn't completed yet,
+    /// returning its updated [`FileInfo`], see
+    /// [`Meteoritus::with_length_increase()`](crate::Meteoritus::with_length_increase).
+    ///
+    /// The default implementation fails, for vaults not supporting length increases.
+    fn increase_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let _ = (file_id, length);
+        Err(VaultError::unsupported())
+    }
+
+    /// Verifies the stored content of the given upload against its recorded offset,
+    /// rewinding the offset to the last intact byte when corruption is detected.
+    ///
+    /// The default implementation trusts the recorded offset.
+    fn repair_offset(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.get_file(file_id)
+    }
+
+    /// Overwrites the stored bytes of the given upload starting at `offset` with
+    /// `buf`, like to fix a corrupted range, leaving its offset unchanged.
+    ///
+    /// The range must be within the stored content, otherwise the call fails with
+    /// a [`VaultError::Conflict`].
+    ///
+    /// The default implementation fails, for vaults not supporting range repairs.
+    fn repair_range(
+        &self,
+        file_id: &str,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<(), VaultError> {
+        let _ = (file_id, buf, offset);
+        Err(VaultError::unsupported())
+    }
+
+    /// Flags the given upload as quarantined, keeping its content in storage while
+    /// blocking any further access through the tus routes.
+    ///
+    /// The default implementation fails, for vaults not supporting quarantines.
+    fn quarantine_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Quarantined>, VaultError> {
+        let _ = file_id;
+        Err(VaultError::unsupported())
+    }
+
+    /// Opens the stored content of the given upload for reading.
+    ///
+    /// The default implementation opens the content from its [`FileInfo::storage_ref()`],
+    /// when stored on disk.
+    fn open_content(
+        &self,
+        file_id: &str,
+    ) -> Result<Box<dyn Read + Send>, VaultError> {
+        let file = self.get_file(file_id)?;
+
+        let Some(path) = file.storage_ref().as_path().map(Path::to_path_buf)
+        else {
+            return Err(VaultError::unsupported());
+        };
+
+        let content = File::open(path)?;
+
+        Ok(Box::new(content))
+    }
+
+    /// Replaces the stored [`Metadata`] of the given upload.
+    ///
+    /// The default implementation fails, for vaults not supporting metadata updates.
+    fn update_metadata(
+        &self,
+        file_id: &str,
+        metadata: Metadata,
+    ) -> Result<(), VaultError> {
+        let _ = (file_id, metadata);
+        Err(VaultError::unsupported())
+    }
+
+    /// Replaces the one-time token required by the next `PATCH` request of the
+    /// given upload, see [`Meteoritus::with_token_rotation()`](crate::Meteoritus::with_token_rotation).
+    ///
+    /// The default implementation fails with an [`ErrorKind::Unsupported`]
+    /// [`VaultError::Io`], for vaults not persisting upload tokens.
+    fn update_upload_token(
+        &self,
+        file_id: &str,
+        token: &str,
+    ) -> Result<(), VaultError> {
+        let _ = (file_id, token);
+        Err(VaultError::unsupported())
+    }
+
+    /// Replaces the transfer [`UploadStats`] of the given upload, recorded after
+    /// each `PATCH` request.
+    ///
+    /// The default implementation fails, for vaults not persisting statistics.
+    fn update_stats(
+        &self,
+        file_id: &str,
+        stats: &UploadStats,
+    ) -> Result<(), VaultError> {
+        let _ = (file_id, stats);
+        Err(VaultError::unsupported())
+    }
+
+    /// Records when the given completed upload is due to be terminated, so the
+    /// background sweeper still terminates it after a restart, see
+    /// [`TerminationPolicy::Delayed`](crate::TerminationPolicy::Delayed).
+    ///
+    /// The default implementation fails, for vaults not persisting termination
+    /// schedules.
+    fn schedule_termination(
+        &self,
+        file_id: &str,
+        time: SystemTime,
+    ) -> Result<(), VaultError> {
+        let _ = (file_id, time);
+        Err(VaultError::unsupported())
+    }
+
+    /// Returns the [`FileInfo`] of every quarantined upload.
+    ///
+    /// The default implementation returns none, for vaults not supporting quarantines.
+    fn list_quarantined(
+        &self,
+    ) -> Result<Vec<FileInfo<Quarantined>>, VaultError> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the local directory where the uploads are stored, if any.
+    ///
+    /// Used to prevent multiple instances from sharing the same storage. The default
+    /// implementation returns none.
+    fn storage_path(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Returns the id of every stored upload.
+    ///
+    /// The default implementation fails, for vaults not able to enumerate uploads.
+    fn list_files(&self) -> Result<Vec<String>, VaultError> {
+        Err(VaultError::unsupported())
+    }
+
+    /// Copies the given upload into the `target` vault, preserving its id, metadata
+    /// and offset, so it can be resumed from there.
+    ///
+    /// The copy is removed from `target` when it can't be fully stored.
+    fn export_to(
+        &self,
+        target: &dyn Vault,
+        file_id: &str,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        const CHUNK_SIZE: usize = 1024 * 1024;
+
+        let file = self.get_file(file_id)?;
+
+        let mut copy = target
+            .build_file(*file.length(), None)?
+            .with_id(file.id().to_string())
+            .with_captured_headers(file.captured_headers().clone());
+
+        if file.is_length_deferred() {
+            copy = copy.with_deferred_length();
+        }
+
+        if let Some(metadata) = file.metadata() {
+            copy.set_metadata(metadata.to_owned());
+        }
+
+        target.create_file(copy)?;
+
+        let copy_content = || -> Result<(), VaultError> {
+            let mut content = self.open_content(file_id)?.take(*file.offset());
+            let mut buf = vec![0; CHUNK_SIZE];
+            let mut offset = 0;
+
+            loop {
+                let read = content.read(&mut buf)?;
+
+                if read == 0 {
+                    break;
+                }
+
+                target.patch_file(file_id, &mut buf[..read], offset)?;
+                offset += read as u64;
+            }
+
+            if offset != *file.offset() {
+                return Err(VaultError::Io(ErrorKind::UnexpectedEof.into()));
+            }
+
+            if file.is_quarantined() {
+                target.quarantine_file(file_id)?;
+            }
+
+            Ok(())
+        };
+
+        if let Err(e) = copy_content() {
+            let _ = target.terminate_file(file_id);
+            return Err(e);
+        }
+
+        target.get_file(file_id)
+    }
+}
+
+/// How a [`LocalVault`] reserves the disk space of an upload on creation, and
+/// when its length is increased.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Preallocation {
+    /// Sets the file length to the `Upload-Length`, without allocating its blocks
+    /// on filesystems supporting sparse files.
+    #[default]
+    Sparse,
+    /// Allocates the blocks of the whole `Upload-Length` through `fallocate`, without
+    /// writing them, so creations fail with `507 Insufficient Storage` when the space
+    /// isn't available.
+    ///
+    /// **Note:** Only Linux filesystems supporting `fallocate` reserve the blocks,
+    /// otherwise this falls back to [`Preallocation::Sparse`].
+    Fallocate,
+    /// Creates an empty file, growing as the chunks are written, so uploads that
+    /// never proceed don't count against the disk quota.
+    None,
+}
+
+impl Preallocation {
+    /// Reserves `length` bytes for the given `file`, growing it from its current
+    /// length.
+    fn apply(self, file: &mut File, length: u64) -> io::Result<()> {
+        match self {
+            Self::Sparse => file.set_len(length),
+            Self::Fallocate => allocate(file, length),
+            Self::None => Ok(()),
+        }
+    }
+}
+
+/// Allocates the blocks of the given `file` up to `length`, falling back to a sparse
+/// length when the filesystem doesn't support it.
+#[cfg(target_os = "linux")]
+fn allocate(file: &File, length: u64) -> io::Result<()> {
+    use rustix::{
+        fs::{fallocate, FallocateFlags},
+        io::Errno,
+    };
+
+    let current = file.metadata()?.len();
+
+    if length <= current {
+        return Ok(());
+    }
+
+    match fallocate(file, FallocateFlags::empty(), current, length - current) {
+        Ok(()) => Ok(()),
+        Err(Errno::OPNOTSUPP | Errno::NOSYS) => file.set_len(length),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn allocate(file: &File, length: u64) -> io::Result<()> {
+    file.set_len(length)
 }
 
+/// The default [`Vault`], storing uploads on the local file system.
+///
+/// Each upload is saved under `<save_path>/<id>/` as a `file` holding its
+/// content next to an `info.json` holding its [`FileInfo`].
 pub struct LocalVault {
     save_path: &'static str,
+    clock: Box<dyn Clock>,
+    path_template: Option<&'static str>,
+    metadata_file_names: bool,
+    rolling_checksums: bool,
+    preallocation: Preallocation,
 }
 
 impl LocalVault {
+    /// Creates a [`LocalVault`] storing uploads into `save_path`.
     pub fn new(save_path: &'static str) -> Self {
-        Self { save_path }
+        Self {
+            save_path,
+            clock: Box::new(SystemClock),
+            path_template: None,
+            metadata_file_names: false,
+            rolling_checksums: false,
+            preallocation: Preallocation::default(),
+        }
+    }
+
+    /// Organizes the uploads on disk using a path `template`, resolved per upload
+    /// relative to the `save_path`.
+    ///
+    /// The following placeholders are available:
+    ///
+    /// * `{id}`: the upload id, which is required to keep paths unique.
+    /// * `{year}`, `{month}` and `{day}`: the UTC creation date.
+    /// * `{metadata.<key>}`: the sanitized value of the given metadata key, or `unknown`.
+    ///
+    /// By default uploads are stored under `{id}`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `template` does not contain the `{id}` placeholder.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use meteoritus::LocalVault;
+    ///
+    /// let vault = LocalVault::new("./tmp/uploads")
+    ///     .with_path_template("{year}/{month}/{metadata.userId}/{id}");
+    /// ```
+    pub fn with_path_template(mut self, template: &'static str) -> Self {
+        assert!(
+            template.contains("{id}"),
+            "path template must contain the {{id}} placeholder"
+        );
+
+        self.path_template = Some(template);
+        self
+    }
+
+    /// Overrides the [`Clock`] resolving the dates of [`LocalVault::with_path_template()`],
+    /// which reads the system time by default.
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Names the upload content after the sanitized `filename` metadata, like
+    /// `<id>/video.mp4` instead of `<id>/file`.
+    ///
+    /// When no `filename` is given, the extension is guessed from the `filetype`
+    /// metadata, like `<id>/file.mp4`, falling back to `<id>/file`.
+    /// See [`sanitize_filename()`] for the applied sanitization.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use meteoritus::LocalVault;
+    ///
+    /// let vault = LocalVault::new("./tmp/uploads").with_metadata_file_names();
+    /// ```
+    pub fn with_metadata_file_names(mut self) -> Self {
+        self.metadata_file_names = true;
+        self
+    }
+
+    /// Stores a rolling CRC32 checksum of the content after each chunk, so
+    /// [`Vault::repair_offset()`] can detect corrupted content and rewind the upload
+    /// to the last intact chunk.
+    ///
+    /// Without it, only content shorter than the recorded offset is detected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use meteoritus::LocalVault;
+    ///
+    /// let vault = LocalVault::new("./tmp/uploads").with_rolling_checksums();
+    /// ```
+    pub fn with_rolling_checksums(mut self) -> Self {
+        self.rolling_checksums = true;
+        self
+    }
+
+    /// Sets how the disk space of uploads is reserved on creation, by default
+    /// [`Preallocation::Sparse`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use meteoritus::{LocalVault, Preallocation};
+    ///
+    /// let vault = LocalVault::new("./tmp/uploads")
+    ///     .with_preallocation(Preallocation::None);
+    /// ```
+    pub fn with_preallocation(mut self, preallocation: Preallocation) -> Self {
+        self.preallocation = preallocation;
+        self
+    }
+
+    fn content_name<State>(&self, file_info: &FileInfo<State>) -> String {
+        let metadata = match (self.metadata_file_names, file_info.metadata()) {
+            (true, Some(metadata)) => metadata,
+            _ => return "file".to_string(),
+        };
+
+        let read = |key| {
+            metadata
+                .get_raw(key)
+                .ok()
+                .map(|value| String::from_utf8_lossy(&value).into_owned())
+        };
+
+        let name = match (read("filename"), read("filetype")) {
+            (Some(file_name), _) => sanitize_filename(&file_name),
+            (None, Some(file_type)) => {
+                let extension = file_type
+                    .split(['/', '+', ';'])
+                    .nth(1)
+                    .map(|subtype| sanitize_segment(subtype.trim()))
+                    .unwrap_or_default();
+
+                match extension.as_str() {
+                    "" | "_" => "file".to_string(),
+                    extension => format!("file.{extension}"),
+                }
+            }
+            (None, None) => "file".to_string(),
+        };
+
+        /* Never overwriting the stored upload info */
+        match name.starts_with("info.") {
+            true => format!("_{name}"),
+            false => name,
+        }
+    }
+
+    /* Templated uploads are indexed by id, pointing to their resolved directory */
+    fn index_path(&self, file_id: &str) -> PathBuf {
+        Path::new(self.save_path).join(".index").join(file_id)
+    }
+
+    fn file_dir(&self, file_id: &str) -> Result<PathBuf, VaultError> {
+        if self.path_template.is_none() {
+            return Ok(Path::new(self.save_path).join(file_id));
+        }
+
+        let relative_dir = fs::read_to_string(self.index_path(file_id))?;
+
+        Ok(Path::new(self.save_path).join(relative_dir))
+    }
+
+    fn resolve_template<State>(
+        &self,
+        template: &str,
+        file_info: &FileInfo<State>,
+    ) -> String {
+        let today = OffsetDateTime::from(self.clock.now());
+
+        let mut resolved = template
+            .replace("{id}", file_info.id())
+            .replace("{year}", &format!("{:04}", today.year()))
+            .replace("{month}", &format!("{:02}", today.month() as u8))
+            .replace("{day}", &format!("{:02}", today.day()));
+
+        while let Some(start) = resolved.find("{metadata.") {
+            let Some(end) = resolved[start..].find('}').map(|end| start + end)
+            else {
+                break;
+            };
+
+            let key = &resolved[start + "{metadata.".len()..end];
+
+            let value = file_info
+                .metadata()
+                .as_ref()
+                .and_then(|metadata| metadata.get_raw(key).ok())
+                .map(|value| sanitize_segment(&String::from_utf8_lossy(&value)))
+                .unwrap_or_else(|| "unknown".to_string());
+
+            resolved.replace_range(start..=end, &value);
+        }
+
+        resolved
+    }
+
+    /// Replaces the stored upload info atomically, so a failure never leaves it
+    /// partially written.
+    fn write_info<State>(
+        file_dir: &Path,
+        file_info: &FileInfo<State>,
+    ) -> std::io::Result<()> {
+        let info_path = file_dir.join("info").with_extension("json");
+        let staging_path = file_dir.join("info").with_extension("json.tmp");
+
+        fs::write(&staging_path, serde_json::to_vec(file_info)?)?;
+        fs::rename(staging_path, info_path)
+    }
+
+    /// Reads the `<offset> <crc32>` checkpoints stored after each chunk, if any.
+    fn read_checkpoints(
+        file_dir: &Path,
+    ) -> io::Result<Option<Vec<(u64, u32)>>> {
+        let checkpoints = match fs::read_to_string(file_dir.join("info.crc")) {
+            Ok(checkpoints) => checkpoints,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        checkpoints
+            .lines()
+            .map(|line| {
+                line.split_once(' ')
+                    .and_then(|(offset, crc)| {
+                        Some((offset.parse().ok()?, crc.parse().ok()?))
+                    })
+                    .ok_or_else(|| io::Error::from(ErrorKind::InvalidData))
+            })
+            .collect::<io::Result<_>>()
+            .map(Some)
+    }
+
+    /// Returns the rolling checksum of the checkpoint at the given `offset`, when
+    /// it's the last one.
+    ///
+    /// Uploads whose previous checkpoint is missing, like the ones created before
+    /// enabling rolling checksums, are not checkpointed.
+    fn last_checkpoint(
+        file_dir: &Path,
+        offset: u64,
+    ) -> io::Result<Option<u32>> {
+        Ok(match Self::read_checkpoints(file_dir)? {
+            None if offset == 0 => Some(0),
+            Some(checkpoints) => match checkpoints.last() {
+                None if offset == 0 => Some(0),
+                Some((last, crc)) if *last == offset => Some(*crc),
+                _ => None,
+            },
+            None => None,
+        })
+    }
+
+    /// Appends the `<offset> <crc32>` checkpoint to the stored ones.
+    fn write_checkpoint(
+        file_dir: &Path,
+        offset: u64,
+        crc: u32,
+    ) -> io::Result<()> {
+        let mut checkpoints = File::options()
+            .create(true)
+            .append(true)
+            .open(file_dir.join("info.crc"))?;
+
+        writeln!(checkpoints, "{} {}", offset, crc)
+    }
+
+    /// Appends the rolling checksum of the content once `chunk` is stored at `offset`.
+    fn append_checkpoint(
+        file_dir: &Path,
+        offset: u64,
+        chunk: &[u8],
+    ) -> io::Result<()> {
+        let Some(previous) = Self::last_checkpoint(file_dir, offset)? else {
+            return Ok(());
+        };
+
+        let mut hasher = crc32fast::Hasher::new_with_initial(previous);
+        hasher.update(chunk);
+
+        Self::write_checkpoint(
+            file_dir,
+            offset + chunk.len() as u64,
+            hasher.finalize(),
+        )
+    }
+
+    /// Appends the rolling checksum of the content once the stored `range` follows
+    /// the previous checkpoint, reading it back from the content.
+    fn append_stored_checkpoint(
+        file_dir: &Path,
+        content_path: &Path,
+        range: Range<u64>,
+    ) -> io::Result<()> {
+        let Some(previous) = Self::last_checkpoint(file_dir, range.start)?
+        else {
+            return Ok(());
+        };
+
+        let mut content = File::open(content_path)?;
+        content.seek(SeekFrom::Start(range.start))?;
+
+        let mut hasher = crc32fast::Hasher::new_with_initial(previous);
+        let mut content = content.take(range.end - range.start);
+        let mut buf = vec![0; 64 * 1024];
+
+        loop {
+            match content.read(&mut buf)? {
+                0 => break,
+                read => hasher.update(&buf[..read]),
+            }
+        }
+
+        Self::write_checkpoint(file_dir, range.end, hasher.finalize())
+    }
+
+    /// Truncates the checkpoints back to their `length` before a failed write,
+    /// removing them when there were none.
+    fn truncate_checkpoints(checkpoints_path: &Path, length: Option<u64>) {
+        let _ = match length {
+            Some(length) => File::options()
+                .write(true)
+                .open(checkpoints_path)
+                .and_then(|checkpoints| checkpoints.set_len(length)),
+            None => fs::remove_file(checkpoints_path),
+        };
+    }
+
+    /// Returns the offset of the last checkpoint matching the stored content,
+    /// up to the given `limit`.
+    fn verify_checkpoints(
+        content_path: &Path,
+        checkpoints: &[(u64, u32)],
+        limit: u64,
+    ) -> io::Result<u64> {
+        let mut content = BufReader::new(File::open(content_path)?);
+        let mut hasher = crc32fast::Hasher::new();
+        let mut buf = vec![0; 64 * 1024];
+
+        let mut position = 0;
+        let mut verified = 0;
+
+        for (offset, crc) in checkpoints {
+            if *offset > limit || *offset < position {
+                break;
+            }
+
+            while position < *offset {
+                let len = (*offset - position).min(buf.len() as u64) as usize;
+                content.read_exact(&mut buf[..len])?;
+                hasher.update(&buf[..len]);
+                position += len as u64;
+            }
+
+            if hasher.clone().finalize() != *crc {
+                break;
+            }
+
+            verified = *offset;
+        }
+
+        Ok(verified)
+    }
+
+    /// Recomputes the rolling checksums of the stored content at the offsets of
+    /// the given checkpoints, formatted as stored.
+    fn rebuild_checkpoints(
+        content_path: &Path,
+        checkpoints: &[(u64, u32)],
+    ) -> io::Result<String> {
+        let mut content = BufReader::new(File::open(content_path)?);
+        let mut hasher = crc32fast::Hasher::new();
+        let mut buf = vec![0; 64 * 1024];
+
+        let mut position = 0;
+        let mut rebuilt = String::new();
+
+        for (offset, _) in checkpoints {
+            if *offset < position {
+                break;
+            }
+
+            while position < *offset {
+                let len = (*offset - position).min(buf.len() as u64) as usize;
+                content.read_exact(&mut buf[..len])?;
+                hasher.update(&buf[..len]);
+                position += len as u64;
+            }
+
+            rebuilt.push_str(&format!(
+                "{} {}\n",
+                offset,
+                hasher.clone().finalize()
+            ));
+        }
+
+        Ok(rebuilt)
     }
 
     fn read_file<State>(
         &self,
         file_id: &str,
     ) -> Result<FileInfo<State>, VaultError> {
-        let file_dir = Path::new(self.save_path).join(file_id);
+        let file_dir = self.file_dir(file_id)?;
 
         let info_path = file_dir.join("info").with_extension("json");
 
-        let file = match File::open(info_path) {
-            Ok(file) => file,
-            Err(e) => return Err(VaultError::ReadError(e.into())),
-        };
+        let reader = BufReader::new(File::open(info_path)?);
 
-        let reader = BufReader::new(file);
+        serde_json::from_reader(reader).map_err(VaultError::from)
+    }
+
+    /// Locks the given upload exclusively, across the threads and processes sharing
+    /// the storage, returning its [`FileInfo`] read under the lock along with its
+    /// content opened for writing.
+    ///
+    /// The lock is held on the content until the returned [`File`] is dropped, so
+    /// the stored info can be read, modified and written back without racing.
+    fn lock_file(
+        &self,
+        file_id: &str,
+    ) -> Result<(FileInfo<Created>, File), VaultError> {
+        let file = self.read_file::<Created>(file_id)?;
 
-        serde_json::from_reader(reader)
-            .map_err(|e| VaultError::ReadError(e.into()))
+        let content_path =
+            self.file_dir(file_id)?.join(self.content_name(&file));
+
+        let content = File::options().write(true).open(content_path)?;
+
+        content.lock()?;
+
+        /* Re-reading the info, which may have changed while waiting for the lock */
+        let file = self.read_file(file_id)?;
+
+        Ok((file, content))
     }
 }
 
@@ -91,7 +995,7 @@ impl Vault for LocalVault {
         let metadata = match metadata {
             Some(metadata) => match Metadata::try_from(metadata) {
                 Ok(m) => m,
-                Err(e) => return Err(VaultError::CreationError(Box::new(e))),
+                Err(e) => return Err(VaultError::Invalid(Box::new(e))),
             },
 
             None => Metadata::default(),
@@ -109,63 +1013,74 @@ impl Vault for LocalVault {
         &self,
         file_info: FileInfo<Built>,
     ) -> Result<FileInfo<Created>, VaultError> {
-        let file_dir = Path::new(self.save_path).join(&file_info.id());
+        let file_dir = match self.path_template {
+            Some(template) => {
+                let relative_dir = self.resolve_template(template, &file_info);
+
+                let index_path = self.index_path(file_info.id());
+
+                if let Some(index_dir) = index_path.parent() {
+                    fs::create_dir_all(index_dir)?;
+                }
+
+                fs::write(index_path, &relative_dir)?;
+
+                Path::new(self.save_path).join(relative_dir)
+            }
+            None => Path::new(self.save_path).join(file_info.id()),
+        };
 
         if !file_dir.exists() {
-            if let Err(e) = fs::create_dir_all(&file_dir).map_err(|e| e.into())
-            {
-                return Err(VaultError::CreationError(e));
-            };
+            fs::create_dir_all(&file_dir)?;
         }
 
-        let file_name = file_dir.join("file");
+        let file_name = file_dir.join(self.content_name(&file_info));
 
         /* Creating file for upload */
-        if let Err(e) = match File::options()
+        let mut content = File::options()
             .read(true)
             .write(true)
             .create_new(true)
-            .open(&file_name)
+            .open(&file_name)?;
+
+        if let Err(e) =
+            self.preallocation.apply(&mut content, *file_info.length())
         {
-            Ok(file) => file.set_len(*file_info.length()).map_err(|e| e.into()),
-            Err(e) => Err(e.into()),
-        } {
-            return Err(VaultError::CreationError(e));
-        };
+            let _ = fs::remove_file(&file_name);
+            return Err(e.into());
+        }
 
         /* Retrieving disk file_name as &str */
         let Some(file_name) = file_name.as_path().to_str() else {
-            return Err(VaultError::CreationError(Box::new(
-                std::io::Error::from(ErrorKind::InvalidInput), // ErrorKind::InvalidFilename
-            )))
+            return Err(VaultError::Io(
+                ErrorKind::InvalidInput.into(), // ErrorKind::InvalidFilename
+            ));
         };
 
         let file_info = file_info.mark_as_created(file_name);
 
         /* Storing file info */
-        if let Err(e) = match File::options()
+        let info = File::options()
             .read(true)
             .write(true)
             .create_new(true)
-            .open(file_dir.join("info").with_extension("json"))
-        {
-            Ok(info) => {
-                serde_json::to_writer(info, &file_info).map_err(|e| e.into())
-            }
-            Err(e) => Err(e.into()),
-        } {
-            return Err(VaultError::CreationError(e));
-        };
+            .open(file_dir.join("info").with_extension("json"))?;
+
+        serde_json::to_writer(info, &file_info)?;
 
         Ok(file_info)
     }
 
     fn exists(&self, file_id: &str) -> bool {
-        let file_dir = Path::new(self.save_path).join(file_id);
-        let file_path = file_dir.join("file");
-        let file_info_path = file_dir.join("info").with_extension("json");
+        let Ok(file_dir) = self.file_dir(file_id) else {
+            return false;
+        };
+        let Ok(file_info) = self.read_file::<Created>(file_id) else {
+            return false;
+        };
+        let file_path = file_dir.join(self.content_name(&file_info));
 
-        file_dir.exists() && file_path.exists() && file_info_path.exists()
+        file_path.exists()
     }
 
     fn get_file(&self, file_id: &str) -> Result<FileInfo<Created>, VaultError> {
@@ -178,36 +1093,155 @@ impl Vault for LocalVault {
         buf: &mut [u8],
         offset: u64,
     ) -> Result<PatchOption, VaultError> {
-        let mut file = self.get_file(file_id)?;
+        /* Holding the lock from the offset check until the new offset is stored */
+        let (mut file, mut file_content) = self.lock_file(file_id)?;
 
         if *file.offset() != offset {
-            return Err(VaultError::Error);
+            return Err(VaultError::Conflict(format!(
+                "upload is at offset {}",
+                file.offset()
+            )));
+        }
+
+        let file_dir = self.file_dir(file_id)?;
+
+        /* Tracking the content length, so a failed chunk can be rolled back */
+        let content_length = file_content.metadata()?.len();
+
+        let rollback = |content: &File| {
+            if content.set_len(content_length).is_err() {
+                warn_!("Unable to roll back the failed chunk of: {}", file_id);
+            }
+        };
+
+        if let Err(e) = file_content
+            .seek(SeekFrom::Start(offset))
+            .and_then(|_| file_content.write_all(buf))
+        {
+            rollback(&file_content);
+            return Err(e.into());
+        }
+
+        let checkpoints_path = file_dir.join("info.crc");
+        let checkpoints_length = fs::metadata(&checkpoints_path)
+            .map(|metadata| metadata.len())
+            .ok();
+
+        if self.rolling_checksums {
+            if let Err(e) = Self::append_checkpoint(&file_dir, offset, buf) {
+                rollback(&file_content);
+                return Err(e.into());
+            }
+        }
+
+        let offset = offset + buf.len() as u64;
+
+        if let Err(e) = file
+            .set_offset(offset)
+            .and_then(|_| Self::write_info(&file_dir, &file))
+        {
+            rollback(&file_content);
+
+            if self.rolling_checksums {
+                Self::truncate_checkpoints(
+                    &checkpoints_path,
+                    checkpoints_length,
+                );
+            }
+
+            return Err(e.into());
+        }
+
+        /* The offset may have moved over ranges received past it */
+        let offset = *file.offset();
+
+        match file.check_completion() {
+            Some(file) => Ok(PatchOption::Completed(file)),
+            None => Ok(PatchOption::Patched(offset)),
         }
+    }
 
-        let file_dir = Path::new(self.save_path).join(file_id);
+    fn write_range(
+        &self,
+        file_id: &str,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        let (mut file, mut file_content) = self.lock_file(file_id)?;
 
-        let file_path = file_dir.join("file");
+        let end = offset.checked_add(buf.len() as u64);
 
-        let mut file_content =
-            File::options().write(true).open(file_path).unwrap();
+        if file.is_length_deferred()
+            || offset < *file.offset()
+            || end.is_none_or(|end| end > *file.length())
+        {
+            return Err(VaultError::Conflict(format!(
+                "range doesn't fit upload at offset {} of {}",
+                file.offset(),
+                file.length()
+            )));
+        }
+
+        let file_dir = self.file_dir(file_id)?;
 
-        file_content.seek(SeekFrom::Start(offset)).unwrap();
+        /* Tracking the content length, so a failed range can be rolled back
+        before it is recorded */
+        let content_length = file_content.metadata()?.len();
 
-        let written_bytes = file_content.write(buf).unwrap();
+        let rollback = |content: &File| {
+            if content.set_len(content_length).is_err() {
+                warn_!("Unable to roll back the failed range of: {}", file_id);
+            }
+        };
 
-        if written_bytes >= u64::MAX as usize {
-            return Err(VaultError::Error);
+        if let Err(e) = file_content
+            .seek(SeekFrom::Start(offset))
+            .and_then(|_| file_content.write_all(buf))
+        {
+            rollback(&file_content);
+            return Err(e.into());
         }
 
-        let offset = offset + written_bytes as u64;
-        file.set_offset(offset).unwrap();
+        let previous = *file.offset();
 
-        let file_info_path = file_dir.join("info").with_extension("json");
+        if let Err(e) = file.receive_range(offset..offset + buf.len() as u64) {
+            rollback(&file_content);
+            return Err(e.into());
+        }
 
-        let mut file_info =
-            File::options().write(true).open(file_info_path).unwrap();
+        let checkpoints_path = file_dir.join("info.crc");
+        let checkpoints_length = fs::metadata(&checkpoints_path)
+            .map(|metadata| metadata.len())
+            .ok();
 
-        serde_json::to_writer(&mut file_info, &file).unwrap();
+        /* Checkpointing the bytes now stored contiguously, if they were */
+        if self.rolling_checksums && *file.offset() > previous {
+            let content_path = file_dir.join(self.content_name(&file));
+
+            if let Err(e) = Self::append_stored_checkpoint(
+                &file_dir,
+                &content_path,
+                previous..*file.offset(),
+            ) {
+                rollback(&file_content);
+                return Err(e.into());
+            }
+        }
+
+        if let Err(e) = Self::write_info(&file_dir, &file) {
+            rollback(&file_content);
+
+            if self.rolling_checksums {
+                Self::truncate_checkpoints(
+                    &checkpoints_path,
+                    checkpoints_length,
+                );
+            }
+
+            return Err(e.into());
+        }
+
+        let offset = *file.offset();
 
         match file.check_completion() {
             Some(file) => Ok(PatchOption::Completed(file)),
@@ -219,13 +1253,327 @@ impl Vault for LocalVault {
         &self,
         file_id: &str,
     ) -> Result<FileInfo<Terminated>, VaultError> {
-        let file_info = self.read_file::<Terminated>(file_id)?;
+        let file_info = self.get_file(file_id)?.mark_as_terminated();
+
+        let file_dir = self.file_dir(file_id)?;
 
-        let file_dir = Path::new(self.save_path).join(file_id);
+        fs::remove_dir_all(file_dir)?;
 
-        fs::remove_dir_all(file_dir)
-            .map_err(|e| VaultError::TerminationError(e.into()))?;
+        if self.path_template.is_some() {
+            fs::remove_file(self.index_path(file_id))?;
+        }
 
         Ok(file_info)
     }
+
+    fn declare_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let (mut file, _lock) = self.lock_file(file_id)?;
+
+        file.declare_length(length)
+            .map_err(|e| VaultError::Invalid(e.into()))?;
+
+        Self::write_info(&self.file_dir(file_id)?, &file)?;
+
+        Ok(file)
+    }
+
+    fn increase_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let (mut file, mut file_content) = self.lock_file(file_id)?;
+
+        file.increase_length(length)
+            .map_err(|e| VaultError::Invalid(e.into()))?;
+
+        self.preallocation.apply(&mut file_content, length)?;
+
+        Self::write_info(&self.file_dir(file_id)?, &file)?;
+
+        Ok(file)
+    }
+
+    fn repair_offset(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let (mut file, _lock) = self.lock_file(file_id)?;
+
+        let file_dir = self.file_dir(file_id)?;
+        let content_path = file_dir.join(self.content_name(&file));
+
+        let content_length = fs::metadata(&content_path)?.len();
+
+        /* Content shorter than the offset was truncated */
+        let mut intact = (*file.offset()).min(content_length);
+
+        let checkpoints = Self::read_checkpoints(&file_dir)?;
+
+        if let Some(checkpoints) =
+            checkpoints.filter(|_| self.rolling_checksums)
+        {
+            intact =
+                Self::verify_checkpoints(&content_path, &checkpoints, intact)?;
+
+            if intact < *file.offset() {
+                let kept: String = checkpoints
+                    .iter()
+                    .take_while(|(offset, _)| *offset <= intact)
+                    .map(|(offset, crc)| format!("{offset} {crc}\n"))
+                    .collect();
+
+                fs::write(file_dir.join("info.crc"), kept)?;
+            }
+        }
+
+        if intact < *file.offset() {
+            warn_!(
+                "Upload {} is corrupted, rewinding its offset from {} to {}",
+                file_id,
+                file.offset(),
+                intact
+            );
+
+            file.set_offset(intact)?;
+
+            Self::write_info(&file_dir, &file)?;
+        }
+
+        Ok(file)
+    }
+
+    fn repair_range(
+        &self,
+        file_id: &str,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<(), VaultError> {
+        let (file, mut file_content) = self.lock_file(file_id)?;
+
+        let end = offset.checked_add(buf.len() as u64);
+
+        if end.is_none_or(|end| end > *file.offset()) {
+            return Err(VaultError::Conflict(format!(
+                "upload is at offset {}",
+                file.offset()
+            )));
+        }
+
+        file_content.seek(SeekFrom::Start(offset))?;
+        file_content.write_all(buf)?;
+        file_content.sync_data()?;
+
+        let file_dir = self.file_dir(file_id)?;
+
+        /* Checkpoints past the repaired range hash the replaced bytes */
+        if let Some(checkpoints) = Self::read_checkpoints(&file_dir)? {
+            let content_path = file_dir.join(self.content_name(&file));
+
+            let checkpoints =
+                Self::rebuild_checkpoints(&content_path, &checkpoints)?;
+
+            fs::write(file_dir.join("info.crc"), checkpoints)?;
+        }
+
+        Ok(())
+    }
+
+    fn update_metadata(
+        &self,
+        file_id: &str,
+        metadata: Metadata,
+    ) -> Result<(), VaultError> {
+        let (mut file, _lock) = self.lock_file(file_id)?;
+
+        file.set_metadata(metadata);
+
+        Self::write_info(&self.file_dir(file_id)?, &file)
+            .map_err(VaultError::from)
+    }
+
+    fn update_upload_token(
+        &self,
+        file_id: &str,
+        token: &str,
+    ) -> Result<(), VaultError> {
+        let (mut file, _lock) = self.lock_file(file_id)?;
+
+        file.set_upload_token(token.to_string());
+
+        Self::write_info(&self.file_dir(file_id)?, &file)
+            .map_err(VaultError::from)
+    }
+
+    fn update_stats(
+        &self,
+        file_id: &str,
+        stats: &UploadStats,
+    ) -> Result<(), VaultError> {
+        let (mut file, _lock) = self.lock_file(file_id)?;
+
+        file.set_stats(stats.clone());
+
+        Self::write_info(&self.file_dir(file_id)?, &file)
+            .map_err(VaultError::from)
+    }
+
+    fn schedule_termination(
+        &self,
+        file_id: &str,
+        time: SystemTime,
+    ) -> Result<(), VaultError> {
+        let (mut file, _lock) = self.lock_file(file_id)?;
+
+        file.schedule_termination(time);
+
+        Self::write_info(&self.file_dir(file_id)?, &file)
+            .map_err(VaultError::from)
+    }
+
+    fn quarantine_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Quarantined>, VaultError> {
+        let (file, _lock) = self.lock_file(file_id)?;
+        let file = file.mark_as_quarantined();
+
+        Self::write_info(&self.file_dir(file_id)?, &file)?;
+
+        Ok(file)
+    }
+
+    fn list_quarantined(
+        &self,
+    ) -> Result<Vec<FileInfo<Quarantined>>, VaultError> {
+        let quarantined = self
+            .list_files()?
+            .iter()
+            .filter_map(|file_id| self.read_file::<Quarantined>(file_id).ok())
+            .filter(|file| file.is_quarantined())
+            .collect();
+
+        Ok(quarantined)
+    }
+
+    fn storage_path(&self) -> Option<&Path> {
+        Some(Path::new(self.save_path))
+    }
+
+    fn list_files(&self) -> Result<Vec<String>, VaultError> {
+        /* Templated uploads are only reachable through their index entries */
+        let ids_path = match self.path_template {
+            Some(_) => Path::new(self.save_path).join(".index"),
+            None => PathBuf::from(self.save_path),
+        };
+
+        let entries = match fs::read_dir(ids_path) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(VaultError::Io(e)),
+        };
+
+        let file_ids = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|file_id| !file_id.starts_with('.'))
+            .collect();
+
+        Ok(file_ids)
+    }
+}
+
+/// Sanitizes a client provided file name, so it can be safely used as the name of
+/// a file on disk.
+///
+/// Any directory component is dropped, characters other than ASCII letters, digits,
+/// `-`, `_` and `.` are replaced by `_`, and names are capped at 255 bytes while
+/// keeping their extension.
+///
+/// # Example
+///
+/// ```rust
+/// use meteoritus::sanitize_filename;
+///
+/// assert_eq!(sanitize_filename("../../etc/passwd"), "passwd");
+/// assert_eq!(sanitize_filename("C:\\Users\\me\\my video.mp4"), "my_video.mp4");
+/// assert_eq!(sanitize_filename(".."), "_");
+/// ```
+pub fn sanitize_filename(file_name: &str) -> String {
+    const MAX_LEN: usize = 255;
+
+    let base_name = file_name.rsplit(['/', '\\']).next().unwrap_or_default();
+
+    let mut sanitized = sanitize_segment(base_name);
+
+    if sanitized.len() > MAX_LEN {
+        let extension = match sanitized.rsplit_once('.') {
+            Some((_, extension)) if extension.len() < 16 => {
+                format!(".{extension}")
+            }
+            _ => String::new(),
+        };
+
+        sanitized.truncate(MAX_LEN - extension.len());
+        sanitized.push_str(&extension);
+    }
+
+    sanitized
+}
+
+/// Sanitizes a client provided relative path, like the `relativePath` metadata sent by
+/// folder uploads, so it can be safely joined to a directory on disk.
+///
+/// Empty, `.` and `..` components are dropped, so the path never escapes the directory
+/// it is joined to, while every other component is sanitized by [`sanitize_filename()`].
+/// Returns `None` when no component is left.
+///
+/// # Example
+///
+/// ```rust
+/// use std::path::PathBuf;
+/// use meteoritus::sanitize_relative_path;
+///
+/// assert_eq!(
+///     sanitize_relative_path("photos/2024/beach day.jpg"),
+///     Some(PathBuf::from("photos/2024/beach_day.jpg"))
+/// );
+/// assert_eq!(
+///     sanitize_relative_path("/../../etc/passwd"),
+///     Some(PathBuf::from("etc/passwd"))
+/// );
+/// assert_eq!(sanitize_relative_path("../.."), None);
+/// ```
+pub fn sanitize_relative_path(path: &str) -> Option<PathBuf> {
+    let sanitized: PathBuf = path
+        .split(['/', '\\'])
+        .filter(|segment| !matches!(*segment, "" | "." | ".."))
+        .map(sanitize_filename)
+        .collect();
+
+    match sanitized.as_os_str().is_empty() {
+        true => None,
+        false => Some(sanitized),
+    }
+}
+
+/// Replaces the characters not allowed in a single path segment, preventing
+/// metadata values from escaping the upload directory.
+fn sanitize_segment(value: &str) -> String {
+    let sanitized: String = value
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+            _ => '_',
+        })
+        .collect();
+
+    match sanitized.trim_matches('.') {
+        "" => "_".to_string(),
+        _ => sanitized,
+    }
 }
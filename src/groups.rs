@@ -0,0 +1,124 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use rocket::http::Status;
+
+use crate::{fs::Metadata, ErrorCode, FileInfo, TusError};
+
+/// Metadata key holding the id of the group an upload belongs to.
+pub(crate) const GROUP_KEY: &str = "group";
+/// Metadata key holding the number of uploads of the group.
+pub(crate) const SIZE_KEY: &str = "group-size";
+
+pub(crate) type GroupCompletedCallback =
+    Arc<dyn Fn(&CompletedGroup) + Send + Sync>;
+
+/// A group of related uploads whose members are all completed, see
+/// [`Meteoritus::on_group_completed()`](crate::Meteoritus::on_group_completed).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletedGroup {
+    id: String,
+    members: Vec<String>,
+}
+
+impl CompletedGroup {
+    /// Returns the id of the group, as given by the `group` metadata.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the ids of the group uploads, in completion order.
+    pub fn members(&self) -> &[String] {
+        &self.members
+    }
+}
+
+/// The group membership of an upload, read from its metadata.
+struct GroupMember {
+    group: String,
+    size: usize,
+}
+
+impl GroupMember {
+    /// Reads the group membership of an upload, if any.
+    ///
+    /// Returns an error when the membership is incomplete or malformed.
+    fn of(metadata: &Option<Metadata>) -> Result<Option<Self>, ()> {
+        let Some(metadata) = metadata else {
+            return Ok(None);
+        };
+
+        let read = |key| {
+            metadata
+                .get_raw(key)
+                .ok()
+                .and_then(|value| String::from_utf8(value).ok())
+        };
+
+        let group = match read(GROUP_KEY) {
+            Some(group) if !group.is_empty() => group,
+            Some(_) => return Err(()),
+            None => return Ok(None),
+        };
+
+        match read(SIZE_KEY).and_then(|size| size.parse().ok()) {
+            Some(size) if size > 0 => Ok(Some(Self { group, size })),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Tracks the completed members of upload groups, see
+/// [`Meteoritus::on_group_completed()`](crate::Meteoritus::on_group_completed).
+#[derive(Default)]
+pub(crate) struct UploadGroups {
+    completed: Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl UploadGroups {
+    /// Checks the group membership of an upload being created.
+    pub(crate) fn validate<S>(file: &FileInfo<S>) -> Result<(), TusError> {
+        match GroupMember::of(file.metadata()) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(TusError::new(
+                Status::BadRequest,
+                ErrorCode::InvalidUploadGroup,
+                format!("Invalid {} or {} metadata", GROUP_KEY, SIZE_KEY),
+            )),
+        }
+    }
+
+    /// Records a completed upload, returning its group once every member is
+    /// completed.
+    ///
+    /// The group is forgotten once returned, so it is completed a single time.
+    pub(crate) fn complete<S>(
+        &self,
+        file: &FileInfo<S>,
+    ) -> Option<CompletedGroup> {
+        let member = GroupMember::of(file.metadata()).ok()??;
+
+        let mut completed =
+            self.completed.lock().unwrap_or_else(|e| e.into_inner());
+
+        let members = completed.entry(member.group.to_owned()).or_default();
+
+        /* Ignoring members completed again, like replayed completions */
+        if !members.iter().any(|id| id == file.id()) {
+            members.push(file.id().to_string());
+        }
+
+        if members.len() < member.size {
+            return None;
+        }
+
+        completed
+            .remove(&member.group)
+            .map(|members| CompletedGroup {
+                id: member.group,
+                members,
+            })
+    }
+}
@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use rocket::{
+    http::Status,
+    outcome::{try_outcome, Outcome},
+    request::{self, FromRequest},
+    Request,
+};
+
+use crate::Vault;
+
+use super::{ErrorCode, MeteoritusGuard, TusError};
+
+/// Specifies how requests of unknown or foreign uploads are responded, see
+/// [`Meteoritus::with_disclosure_policy()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisclosurePolicy {
+    /// Responds the precise reason, easing debugging: `403 Forbidden` to uploads
+    /// rejected by the [`Meteoritus::with_ownership_check()`], `404 Not Found` to
+    /// unknown uploads and `410 Gone` to `DELETE` requests of unknown uploads.
+    #[default]
+    Precise,
+    /// Responds `404 Not Found` to all of them, never leaking whether an upload exists.
+    ///
+    /// Only requesters passing the ownership check are told an upload is quarantined,
+    /// paused or past its deadline, and removed uploads are never answered from their
+    /// tombstones, since those don't tell who created them.
+    Private,
+}
+
+impl DisclosurePolicy {
+    /// Returns the failure of requests rejected by the ownership check.
+    pub(crate) fn forbidden(self) -> TusError {
+        match self {
+            Self::Precise => TusError::new(
+                Status::Forbidden,
+                ErrorCode::UploadForbidden,
+                "Upload is not accessible",
+            ),
+            Self::Private => Self::not_found(),
+        }
+    }
+
+    /// Returns the failure of requests of terminated uploads, and of `DELETE` requests
+    /// of unknown ones.
+    pub(crate) fn gone(self) -> TusError {
+        match self {
+            Self::Precise => TusError::new(
+                Status::Gone,
+                ErrorCode::UploadGone,
+                "Upload not found or already terminated",
+            ),
+            Self::Private => Self::not_found(),
+        }
+    }
+
+    pub(crate) fn not_found() -> TusError {
+        TusError::new(
+            Status::NotFound,
+            ErrorCode::UploadNotFound,
+            "Upload not found",
+        )
+    }
+}
+
+/// A request guard succeeding when the request is allowed by the authorizer,
+/// see [`Meteoritus::with_authorizer()`].
+pub(crate) struct Authorized;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Authorized {
+    type Error = TusError;
+
+    async fn from_request(
+        req: &'r Request<'_>,
+    ) -> request::Outcome<Self, Self::Error> {
+        let meteoritus = try_outcome!(req.guard::<MeteoritusGuard<'r>>().await);
+
+        if !meteoritus.is_authorized(req) {
+            return TusError::new(
+                Status::Forbidden,
+                ErrorCode::Unauthorized,
+                "Request is not authorized",
+            )
+            .fail(req);
+        }
+
+        Outcome::Success(Authorized)
+    }
+}
+
+/// A request guard succeeding when the requested upload is allowed by the
+/// ownership check, see [`Meteoritus::with_ownership_check()`].
+///
+/// Unknown uploads are left to the handlers, which respond them as defined by the
+/// [`DisclosurePolicy`].
+pub(crate) struct Owned {
+    /* Whether the ownership check passed on the stored upload */
+    verified: bool,
+}
+
+impl Owned {
+    /// Returns the guard of the requester creating the upload, which owns it.
+    pub(crate) fn creator() -> Self {
+        Owned { verified: true }
+    }
+
+    /// Returns the given failure, revealing that the upload exists, unless the
+    /// [`DisclosurePolicy::Private`] hides it from requesters not verified as owners.
+    ///
+    /// Removed uploads are never verified, since their tombstones don't tell who
+    /// created them.
+    pub(crate) fn disclose(
+        &self,
+        policy: DisclosurePolicy,
+        error: TusError,
+    ) -> TusError {
+        match self.conceals(policy) {
+            true => DisclosurePolicy::not_found(),
+            false => error,
+        }
+    }
+
+    /// Indicates if the existence of the upload must be hidden from the requester.
+    pub(crate) fn conceals(&self, policy: DisclosurePolicy) -> bool {
+        policy == DisclosurePolicy::Private && !self.verified
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Owned {
+    type Error = TusError;
+
+    async fn from_request(
+        req: &'r Request<'_>,
+    ) -> request::Outcome<Self, Self::Error> {
+        let meteoritus = try_outcome!(req.guard::<MeteoritusGuard<'r>>().await);
+
+        let unverified = Outcome::Success(Owned { verified: false });
+
+        if !meteoritus.has_ownership_check() {
+            return unverified;
+        }
+
+        let (Some(Ok(file_id)), Some(vault)) =
+            (req.param::<&str>(0), req.rocket().state::<Arc<dyn Vault>>())
+        else {
+            return unverified;
+        };
+
+        match vault.get_file(file_id) {
+            Ok(file) if !meteoritus.is_owner(req, &file) => {
+                meteoritus.disclosure_policy().forbidden().fail(req)
+            }
+            Ok(_) => Outcome::Success(Owned { verified: true }),
+            Err(_) => unverified,
+        }
+    }
+}
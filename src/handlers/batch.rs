@@ -0,0 +1,268 @@
+use std::{collections::BTreeMap, io::Cursor, sync::Arc};
+
+use base64::Engine as _;
+use rocket::{
+    data::ByteUnit,
+    http::{ContentType, Status},
+    outcome::try_outcome,
+    outcome::Outcome,
+    request::{self, FromRequest},
+    response::{self, Responder},
+    serde::Deserialize,
+    Data, Ignite, Orbit, Request, Rocket, Sentinel, State,
+};
+use serde_json::json;
+
+use crate::{
+    fs::{Created, FileInfo, UploaderInfo},
+    policy::{validate_policy, UploadPolicy},
+    Vault,
+};
+
+#[cfg(feature = "grpc-hooks")]
+use crate::hooks::HookHttpRequest;
+
+use super::{
+    creation::{create_upload, CreationRequest, OwnerCheck},
+    ErrorCode, MeteoritusGuard, TusError,
+};
+
+/// The maximum body size of a batch creation request.
+const BATCH_BODY_LIMIT: ByteUnit = ByteUnit::Mebibyte(1);
+
+/// An upload to be created by a batch creation request.
+#[derive(Debug, Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct BatchEntry {
+    length: u64,
+    #[serde(default)]
+    metadata: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct BatchCreation {
+    uploads: Vec<BatchEntry>,
+}
+
+impl BatchEntry {
+    /// Encodes the entry metadata as an `Upload-Metadata` header value.
+    fn upload_metadata(&self) -> Option<String> {
+        if self.metadata.is_empty() {
+            return None;
+        }
+
+        let pairs: Vec<String> = self
+            .metadata
+            .iter()
+            .map(|(key, value)| match value.is_empty() {
+                true => key.to_owned(),
+                false => format!(
+                    "{} {}",
+                    key,
+                    base64::engine::general_purpose::STANDARD.encode(value)
+                ),
+            })
+            .collect();
+
+        Some(pairs.join(","))
+    }
+}
+
+#[post("/batch", data = "<data>")]
+pub async fn batch_creation_handler(
+    req: BatchCreationRequest<'_>,
+    meteoritus: MeteoritusGuard<'_>,
+    vault: &State<Arc<dyn Vault>>,
+    data: Data<'_>,
+) -> BatchCreationResponder {
+    let Some(max_uploads) = meteoritus.batch_creation() else {
+        return BatchCreationResponder::Failure(TusError::new(
+            Status::NotFound,
+            ErrorCode::BatchCreationDisabled,
+            "Batch creation is not enabled",
+        ));
+    };
+
+    let batch = match data.open(BATCH_BODY_LIMIT).into_string().await {
+        Ok(body) if body.is_complete() => {
+            serde_json::from_str::<BatchCreation>(&body)
+        }
+        _ => {
+            return BatchCreationResponder::Failure(TusError::new(
+                Status::PayloadTooLarge,
+                ErrorCode::BatchTooLarge,
+                "Batch body exceeds the allowed size",
+            ))
+        }
+    };
+
+    let Ok(batch) = batch else {
+        return BatchCreationResponder::Failure(TusError::new(
+            Status::BadRequest,
+            ErrorCode::InvalidBatch,
+            "Batch body is not a valid JSON document",
+        ));
+    };
+
+    if batch.uploads.is_empty() || batch.uploads.len() > max_uploads {
+        return BatchCreationResponder::Failure(TusError::new(
+            Status::BadRequest,
+            ErrorCode::InvalidBatchSize,
+            format!("Batch must hold between 1 and {max_uploads} uploads"),
+        ));
+    }
+
+    if batch
+        .uploads
+        .iter()
+        .any(|entry| entry.length > meteoritus.max_size().as_u64())
+    {
+        return BatchCreationResponder::Failure(TusError::new(
+            Status::PayloadTooLarge,
+            ErrorCode::UploadLengthExceeded,
+            "Upload length exceeds the Tus-Max-Size",
+        ));
+    }
+
+    let mut created = Vec::with_capacity(batch.uploads.len());
+
+    for entry in &batch.uploads {
+        let metadata = entry.upload_metadata();
+
+        let creation = CreationRequest {
+            rocket: req.rocket,
+            upload_length: entry.length,
+            defer_length: false,
+            metadata: metadata.as_deref(),
+            client_ip: req.client_ip,
+            captured_headers: req.captured_headers.clone(),
+            uploader: req.uploader.clone(),
+            policy: req.policy.clone(),
+            validate_only: false,
+            owner_check: req.owner_check.clone(),
+            initial: None,
+            #[cfg(feature = "grpc-hooks")]
+            http: req.http.clone(),
+        };
+
+        match create_upload(&creation, &meteoritus, vault.inner()).await {
+            Ok((location, file, _)) => created.push((location, file)),
+            Err(error) => {
+                /* Rolling back the uploads created by the failed batch */
+                for (_, file) in &created {
+                    meteoritus.forget_upload(file.id());
+
+                    if vault.terminate_file(file.id()).is_err() {
+                        warn_!("Unable to roll back upload: {}", file.id());
+                    }
+                }
+
+                return BatchCreationResponder::Failure(error);
+            }
+        }
+    }
+
+    BatchCreationResponder::Success(created)
+}
+
+#[derive(Debug)]
+pub struct BatchCreationRequest<'r> {
+    rocket: &'r Rocket<Orbit>,
+    client_ip: Option<std::net::IpAddr>,
+    captured_headers: BTreeMap<String, String>,
+    uploader: UploaderInfo,
+    policy: Option<UploadPolicy>,
+    owner_check: OwnerCheck<'r>,
+    #[cfg(feature = "grpc-hooks")]
+    http: HookHttpRequest,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for BatchCreationRequest<'r> {
+    type Error = TusError;
+
+    async fn from_request(
+        req: &'r Request<'_>,
+    ) -> request::Outcome<Self, Self::Error> {
+        let tus_resumable_header = req.headers().get_one("Tus-Resumable");
+        if tus_resumable_header.is_none()
+            || tus_resumable_header.unwrap() != "1.0.0"
+        {
+            return TusError::new(
+                Status::BadRequest,
+                ErrorCode::InvalidTusResumable,
+                "Missing or invalid Tus-Resumable header",
+            )
+            .fail(req);
+        }
+
+        let meteoritus = try_outcome!(req.guard::<MeteoritusGuard<'r>>().await);
+
+        let policy = match meteoritus.policy_validator() {
+            Some(validator) => {
+                match validate_policy(validator, req.headers()) {
+                    Ok(policy) => Some(policy),
+                    Err(error) => return error.fail(req),
+                }
+            }
+            None => None,
+        };
+
+        Outcome::Success(BatchCreationRequest {
+            rocket: req.rocket(),
+            client_ip: req.client_ip(),
+            captured_headers: meteoritus.capture_headers(req),
+            uploader: UploaderInfo::from_request(req),
+            policy,
+            owner_check: OwnerCheck::of(req),
+            #[cfg(feature = "grpc-hooks")]
+            http: HookHttpRequest::from(req),
+        })
+    }
+}
+
+pub enum BatchCreationResponder {
+    Success(Vec<(String, FileInfo<Created>)>),
+    Failure(TusError),
+}
+
+impl Sentinel for BatchCreationResponder {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        super::abort_launch(rocket)
+    }
+}
+
+impl<'r> Responder<'r, 'static> for BatchCreationResponder {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let meteoritus = MeteoritusGuard::of(req)?;
+
+        let created = match self {
+            Self::Success(created) => created,
+            Self::Failure(error) => return error.respond_to(req),
+        };
+
+        let uploads: Vec<_> = created
+            .iter()
+            .map(|(uri, file)| match file.upload_token() {
+                Some(token) => {
+                    json!({ "id": file.id(), "location": uri, "token": token })
+                }
+                None => json!({ "id": file.id(), "location": uri }),
+            })
+            .collect();
+
+        let body = json!({ "uploads": uploads }).to_string();
+
+        let mut res = rocket::Response::build();
+
+        res.header(meteoritus.get_protocol_resumable_version())
+            .header(ContentType::JSON)
+            .status(Status::Created)
+            .sized_body(body.len(), Cursor::new(body));
+
+        meteoritus.decorate_response(None, &mut res);
+
+        res.ok()
+    }
+}
@@ -0,0 +1,172 @@
+use std::{
+    io::{self, Cursor, Read},
+    ops::Range,
+    sync::Arc,
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rocket::{
+    http::{ContentType, Status},
+    response::{self, Responder},
+    Ignite, Request, Rocket, Sentinel, State,
+};
+use serde_json::{json, Value};
+
+use crate::{
+    fs::{Created, FileInfo},
+    ErrorCode, TusError, Vault, VaultError,
+};
+
+use super::{
+    authorization::{Authorized, Owned},
+    MeteoritusGuard,
+};
+
+/// Responds the digest of a stored range of an upload, so clients can verify which
+/// prefix of an interrupted upload is intact before resuming it.
+///
+/// The inclusive `range`, like `0-1048575`, defaults to the whole stored content,
+/// and is limited to the maximum chunk size.
+#[get("/<id>/checksum?<algo>&<range>")]
+pub fn checksum_handler(
+    id: &str,
+    algo: &str,
+    range: Option<&str>,
+    _authorized: Authorized,
+    _owned: Owned,
+    meteoritus: MeteoritusGuard<'_>,
+    vault: &State<Arc<dyn Vault>>,
+) -> ChecksumResponder {
+    let Some(algorithm) = meteoritus.checksum_algorithms().find(algo) else {
+        return ChecksumResponder::Failure(TusError::new(
+            Status::BadRequest,
+            ErrorCode::UnsupportedChecksumAlgorithm,
+            "Unsupported checksum algorithm",
+        ));
+    };
+
+    let file = match vault.get_file(id) {
+        Ok(file) => file,
+        Err(e) => return ChecksumResponder::Failure(TusError::from_vault(&e)),
+    };
+
+    let range = match range {
+        Some(range) => match parse_range(range) {
+            Some(range) => range,
+            None => {
+                return ChecksumResponder::Failure(TusError::new(
+                    Status::BadRequest,
+                    ErrorCode::InvalidChecksumRange,
+                    "Invalid checksum range",
+                ))
+            }
+        },
+        None => 0..*file.offset(),
+    };
+
+    /* Only the stored bytes can be verified */
+    if range.end > *file.offset() {
+        return ChecksumResponder::Failure(TusError::new(
+            Status::RangeNotSatisfiable,
+            ErrorCode::ChecksumRangeNotStored,
+            "Checksum range exceeds the current upload offset",
+        ));
+    }
+
+    if range.end - range.start > meteoritus.max_chunk_size().as_u64() {
+        return ChecksumResponder::Failure(TusError::new(
+            Status::BadRequest,
+            ErrorCode::ChecksumRangeTooLarge,
+            "Checksum range exceeds the maximum chunk size",
+        ));
+    }
+
+    let content = match read_range(vault.as_ref(), id, &range) {
+        Ok(content) => content,
+        Err(e) => return ChecksumResponder::Failure(TusError::from_vault(&e)),
+    };
+
+    let digest = json!({
+        "algorithm": algorithm.name(),
+        "range": format_range(&range),
+        "offset": file.offset(),
+        "digest": STANDARD.encode(algorithm.digest(&content)),
+    });
+
+    ChecksumResponder::Success(file, digest)
+}
+
+/// Parses an inclusive `<start>-<end>` range into an exclusive one.
+fn parse_range(value: &str) -> Option<Range<u64>> {
+    let (start, end) = value.split_once('-')?;
+    let start: u64 = start.trim().parse().ok()?;
+    let end: u64 = end.trim().parse().ok()?;
+
+    match start <= end {
+        true => Some(start..end.checked_add(1)?),
+        false => None,
+    }
+}
+
+/// Formats an exclusive range back into an inclusive `<start>-<end>` one, empty
+/// ranges being formatted as `<start>-`.
+fn format_range(range: &Range<u64>) -> String {
+    match range.is_empty() {
+        true => format!("{}-", range.start),
+        false => format!("{}-{}", range.start, range.end - 1),
+    }
+}
+
+fn read_range(
+    vault: &dyn Vault,
+    file_id: &str,
+    range: &Range<u64>,
+) -> Result<Vec<u8>, VaultError> {
+    let mut content = vault.open_content(file_id)?;
+
+    io::copy(&mut content.by_ref().take(range.start), &mut io::sink())?;
+
+    let mut buffer = Vec::with_capacity((range.end - range.start) as usize);
+    content
+        .take(range.end - range.start)
+        .read_to_end(&mut buffer)?;
+
+    Ok(buffer)
+}
+
+#[allow(clippy::large_enum_variant)]
+pub enum ChecksumResponder {
+    Success(FileInfo<Created>, Value),
+    Failure(TusError),
+}
+
+impl Sentinel for ChecksumResponder {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        super::abort_launch(rocket)
+    }
+}
+
+impl<'r> Responder<'r, 'static> for ChecksumResponder {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let meteoritus = MeteoritusGuard::of(req)?;
+
+        let mut res = rocket::Response::build();
+
+        res.header(meteoritus.get_protocol_resumable_version());
+
+        match self {
+            Self::Success(file, digest) => {
+                let body = digest.to_string();
+
+                res.status(Status::Ok)
+                    .header(ContentType::JSON)
+                    .sized_body(body.len(), Cursor::new(body));
+
+                meteoritus.decorate_response(Some(&file), &mut res)
+            }
+            Self::Failure(error) => return error.respond_to(req),
+        };
+
+        res.ok()
+    }
+}
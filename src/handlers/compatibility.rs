@@ -0,0 +1,304 @@
+use std::{convert::Infallible, io::Cursor};
+
+use rocket::{
+    http::{ContentType, Status},
+    request::{self, FromRequest},
+    response::{self, Responder},
+    Ignite, Orbit, Request, Rocket, Sentinel,
+};
+use serde_json::{json, Value};
+
+use crate::{token::TOKEN_HEADER, ErrorCode, Meteoritus, TusError};
+
+use super::{authorization::Authorized, MeteoritusGuard};
+
+/// Reports how the configuration fits the given front-end tus `client`, see
+/// [`Meteoritus::with_compatibility_check()`](crate::Meteoritus::with_compatibility_check).
+#[get("/compatibility?<client>")]
+pub fn compatibility_handler(
+    client: Option<&str>,
+    probe: ProbeHeaders,
+    _authorized: Authorized,
+    meteoritus: MeteoritusGuard<'_>,
+) -> CompatibilityResponder {
+    if !meteoritus.compatibility_check() {
+        return CompatibilityResponder::Failure(TusError::new(
+            Status::NotFound,
+            ErrorCode::CompatibilityCheckDisabled,
+            "Compatibility check is not enabled",
+        ));
+    }
+
+    let Some(client) = client.and_then(ClientKind::parse) else {
+        return CompatibilityResponder::Failure(TusError::new(
+            Status::BadRequest,
+            ErrorCode::UnknownClient,
+            format!(
+                "Unknown client, expected one of: {}",
+                ClientKind::ALL
+                    .iter()
+                    .map(|client| client.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        ));
+    };
+
+    CompatibilityResponder::Success(report(&meteoritus, client, &probe))
+}
+
+/// The front-end tus clients known to the compatibility report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClientKind {
+    Uppy,
+    TusJsClient,
+    TusJavaClient,
+}
+
+impl ClientKind {
+    const ALL: &'static [ClientKind] = &[
+        ClientKind::Uppy,
+        ClientKind::TusJsClient,
+        ClientKind::TusJavaClient,
+    ];
+
+    fn parse(hint: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|client| client.as_str().eq_ignore_ascii_case(hint.trim()))
+            .copied()
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Uppy => "uppy",
+            Self::TusJsClient => "tus-js-client",
+            Self::TusJavaClient => "tus-java-client",
+        }
+    }
+
+    /// Indicates if the client implements the given tus extension.
+    fn supports(self, extension: &str) -> bool {
+        matches!(
+            (self, extension),
+            (_, "creation")
+                | (Self::TusJsClient, "creation-defer-length")
+                | (Self::Uppy | Self::TusJsClient, "termination")
+        )
+    }
+
+    /// Returns the option setting custom request headers.
+    fn headers_option(self) -> &'static str {
+        match self {
+            Self::Uppy | Self::TusJsClient => "headers",
+            Self::TusJavaClient => "TusClient.setHeaders()",
+        }
+    }
+
+    /// Returns the option setting the size of `PATCH` bodies, along with its
+    /// default, `None` sending the whole upload at once.
+    fn chunk_size_option(self) -> (&'static str, Option<u64>) {
+        match self {
+            Self::Uppy | Self::TusJsClient => ("chunkSize", None),
+            Self::TusJavaClient => (
+                "TusUploader.setRequestPayloadSize()",
+                Some(10 * 1024 * 1024),
+            ),
+        }
+    }
+
+    /// Returns the hook reading response headers, if the client has one.
+    fn response_hook(self) -> Option<&'static str> {
+        match self {
+            Self::Uppy | Self::TusJsClient => Some("onAfterResponse"),
+            Self::TusJavaClient => None,
+        }
+    }
+}
+
+/// Builds the compatibility report of the given `client`.
+fn report(
+    meteoritus: &Meteoritus<Orbit>,
+    client: ClientKind,
+    probe: &ProbeHeaders,
+) -> Value {
+    /* Client options the configuration can't work without */
+    let mut issues: Vec<String> = Vec::new();
+    /* Client options the configuration may require, depending on the app */
+    let mut hints: Vec<String> = Vec::new();
+
+    let mut extensions: Vec<Value> = meteoritus
+        .protocol_extensions()
+        .iter()
+        .map(|extension| {
+            json!({
+                "name": extension,
+                "client_support": client.supports(extension),
+            })
+        })
+        .collect();
+
+    /* Vendor extensions aren't implemented by any known client */
+    for (name, enabled) in [
+        (
+            "meteoritus-creation-batch",
+            meteoritus.batch_creation().is_some(),
+        ),
+        ("meteoritus-length-increase", meteoritus.length_increase()),
+        ("meteoritus-parallel-chunks", meteoritus.parallel_chunks()),
+    ] {
+        if enabled {
+            extensions.push(json!({ "name": name, "client_support": false }));
+        }
+    }
+
+    let mut headers: Vec<Value> = Vec::new();
+
+    let mut header = |name: &str, requests: &[&str], required: bool| {
+        headers.push(json!({
+            "name": name,
+            "requests": requests,
+            "required": required,
+            "present": probe.contains(name),
+        }));
+    };
+
+    if let Some(validator) = meteoritus.policy_validator() {
+        header(validator.header_name(), &["POST"], true);
+
+        hints.push(format!(
+            "Send the signed policy in the {} header of creations, through \
+             the {} option",
+            validator.header_name(),
+            client.headers_option()
+        ));
+    }
+
+    if meteoritus.token_rotation() {
+        header(TOKEN_HEADER, &["PATCH"], true);
+
+        match client.response_hook() {
+            Some(hook) => hints.push(format!(
+                "Read the {TOKEN_HEADER} header of every response through the \
+                 {hook} option, sending it on the next PATCH request"
+            )),
+            None => issues.push(format!(
+                "The client can't read the rotated {TOKEN_HEADER} header of \
+                 responses, so its PATCH requests will be rejected"
+            )),
+        }
+    }
+
+    for name in meteoritus.captured_headers() {
+        header(name, &["POST"], false);
+    }
+
+    if meteoritus.has_authorizer() || meteoritus.has_ownership_check() {
+        hints.push(format!(
+            "Requests are checked by the server, send their credentials through \
+             the {} option",
+            client.headers_option()
+        ));
+    }
+
+    let metadata_keys = meteoritus.metadata_validators().keys();
+
+    if !metadata_keys.is_empty() {
+        hints.push(format!(
+            "Creations are validated against the metadata keys: {}",
+            metadata_keys.join(", ")
+        ));
+    }
+
+    let max_chunk_size = meteoritus.max_chunk_size().as_u64();
+
+    /* Bodies are bound by the max size anyway */
+    if max_chunk_size < meteoritus.max_size().as_u64() {
+        let (option, default) = client.chunk_size_option();
+
+        if default.is_none_or(|default| default > max_chunk_size) {
+            issues.push(format!(
+                "Set the {option} option to at most {max_chunk_size} bytes, since \
+                 larger PATCH bodies are rejected"
+            ));
+        }
+    }
+
+    json!({
+        "client": client.as_str(),
+        "compatible": issues.is_empty(),
+        "tus_version": "1.0.0",
+        "max_size": meteoritus.max_size().as_u64(),
+        "max_chunk_size": max_chunk_size,
+        "extensions": extensions,
+        "headers": headers,
+        "metadata_keys": metadata_keys,
+        "storage_classes": meteoritus.storage_classes(),
+        "issues": issues,
+        "hints": hints,
+    })
+}
+
+/// The names of the headers carried by the checking request, so it can be sent
+/// with the same headers as the client.
+pub struct ProbeHeaders(Vec<String>);
+
+impl ProbeHeaders {
+    fn contains(&self, name: &str) -> bool {
+        self.0
+            .iter()
+            .any(|header| header.eq_ignore_ascii_case(name))
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ProbeHeaders {
+    type Error = Infallible;
+
+    async fn from_request(
+        req: &'r Request<'_>,
+    ) -> request::Outcome<Self, Self::Error> {
+        request::Outcome::Success(ProbeHeaders(
+            req.headers()
+                .iter()
+                .map(|header| header.name().to_string())
+                .collect(),
+        ))
+    }
+}
+
+pub enum CompatibilityResponder {
+    Success(Value),
+    Failure(TusError),
+}
+
+impl Sentinel for CompatibilityResponder {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        super::abort_launch(rocket)
+    }
+}
+
+impl<'r> Responder<'r, 'static> for CompatibilityResponder {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let meteoritus = MeteoritusGuard::of(req)?;
+
+        let mut res = rocket::Response::build();
+
+        res.header(meteoritus.get_protocol_resumable_version());
+
+        match self {
+            Self::Success(report) => {
+                let body = report.to_string();
+
+                res.status(Status::Ok)
+                    .header(ContentType::JSON)
+                    .sized_body(body.len(), Cursor::new(body));
+
+                meteoritus.decorate_response(None, &mut res)
+            }
+            Self::Failure(error) => return error.respond_to(req),
+        };
+
+        res.ok()
+    }
+}
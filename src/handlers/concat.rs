@@ -0,0 +1,133 @@
+use std::{io::Cursor, sync::Arc};
+
+use rocket::{
+    http::{ContentType, Status},
+    response::{self, Responder},
+    Ignite, Request, Rocket, Sentinel, State,
+};
+use serde_json::{json, Value};
+
+use crate::{ErrorCode, TusError, Vault};
+
+use super::{authorization::Authorized, MeteoritusGuard};
+
+#[get("/concat/<group>", rank = 2)]
+pub fn concat_progress_handler(
+    group: &str,
+    _authorized: Authorized,
+    meteoritus: MeteoritusGuard<'_>,
+    vault: &State<Arc<dyn Vault>>,
+) -> ConcatProgressResponder {
+    let not_found = || {
+        ConcatProgressResponder::Failure(TusError::new(
+            Status::NotFound,
+            ErrorCode::ConcatGroupNotFound,
+            "Concatenation group not found",
+        ))
+    };
+
+    let Some(groups) = meteoritus.concat_groups() else {
+        return not_found();
+    };
+
+    let parts = match groups.parts_of(vault.as_ref(), group) {
+        Ok(parts) => parts,
+        Err(e) => {
+            return ConcatProgressResponder::Failure(TusError::from_vault(&e))
+        }
+    };
+
+    if parts.is_empty() {
+        let result = match groups.result_of(vault.as_ref(), group) {
+            Ok(Some(result)) => result,
+            Ok(None) => return not_found(),
+            Err(e) => {
+                return ConcatProgressResponder::Failure(TusError::from_vault(
+                    &e,
+                ))
+            }
+        };
+
+        let location =
+            match meteoritus.upload_location(result.id(), result.metadata()) {
+                Ok(location) => location,
+                Err(e) => return ConcatProgressResponder::Failure(e),
+            };
+
+        return ConcatProgressResponder::Success(json!({
+            "group": group,
+            "state": "concatenated",
+            "length": result.length(),
+            "offset": result.offset(),
+            "result": location,
+        }));
+    }
+
+    let expected = parts[0].0.parts;
+
+    let mut received: Vec<usize> =
+        parts.iter().map(|(part, _)| part.index).collect();
+    received.dedup();
+
+    let completed = parts
+        .iter()
+        .filter(|(_, file)| {
+            !file.is_length_deferred() && file.offset() == file.length()
+        })
+        .count();
+
+    let length = match received.len() == expected
+        && parts.iter().all(|(_, file)| !file.is_length_deferred())
+    {
+        true => Some(parts.iter().map(|(_, file)| *file.length()).sum::<u64>()),
+        false => None,
+    };
+
+    let offset: u64 = parts.iter().map(|(_, file)| *file.offset()).sum();
+
+    ConcatProgressResponder::Success(json!({
+        "group": group,
+        "state": "in-progress",
+        "parts": expected,
+        "received": received.len(),
+        "completed": completed,
+        "length": length,
+        "offset": offset,
+    }))
+}
+
+pub enum ConcatProgressResponder {
+    Success(Value),
+    Failure(TusError),
+}
+
+impl Sentinel for ConcatProgressResponder {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        super::abort_launch(rocket)
+    }
+}
+
+impl<'r> Responder<'r, 'static> for ConcatProgressResponder {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let meteoritus = MeteoritusGuard::of(req)?;
+
+        let mut res = rocket::Response::build();
+
+        res.header(meteoritus.get_protocol_resumable_version());
+
+        match self {
+            Self::Success(progress) => {
+                let body = progress.to_string();
+
+                res.status(Status::Ok)
+                    .header(ContentType::JSON)
+                    .sized_body(body.len(), Cursor::new(body));
+
+                meteoritus.decorate_response(None, &mut res)
+            }
+            Self::Failure(error) => return error.respond_to(req),
+        };
+
+        res.ok()
+    }
+}
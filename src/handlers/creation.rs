@@ -1,165 +1,709 @@
 use rocket::{
-    http::{
-        uri::{Origin, Reference},
-        Status,
-    },
+    http::{ContentType, Header, Status},
+    outcome::try_outcome,
     request::{self, FromRequest, Outcome},
     response::{self, Responder},
-    Orbit, Request, Response, Rocket, State,
+    Data, Ignite, Orbit, Request, Response, Rocket, Sentinel, State,
+};
+use std::{
+    borrow::Cow, collections::BTreeMap, fmt, net::IpAddr, sync::Arc,
+    time::Instant,
 };
-use std::{io::Cursor, sync::Arc};
 
 use crate::meteoritus::Meteoritus;
-use crate::{handlers::upload::*, Vault};
+use crate::{
+    fs::{Built, Created, FileInfo, UploaderInfo},
+    groups::UploadGroups,
+    policy::{validate_policy, UploadPolicy},
+    storage_class::validate_storage_class,
+    token::{generate_token, TOKEN_HEADER},
+    UploadEventKind, Vault,
+};
+
+#[cfg(feature = "grpc-hooks")]
+use crate::hooks::{HookHttpRequest, HookType};
 
-use super::HandlerContext;
+#[cfg(feature = "expiration")]
+use super::upload_expires;
+use super::{
+    authorization::Owned,
+    upload::{write_chunk, UploadRequest},
+    ErrorCode, HandlerContext, MeteoritusGuard, TusError,
+};
+
+/// Header requesting a dry run of the creation, running its checks and the
+/// `on_creation` callback without creating the upload.
+const VALIDATE_ONLY_HEADER: &str = "Upload-Validate-Only";
 
-#[post("/")]
-pub fn creation_handler(
-    req: CreationRequest,
-    meteoritus: &State<Meteoritus<Orbit>>,
+#[post("/", data = "<data>")]
+pub async fn creation_handler(
+    mut req: CreationRequest<'_>,
+    meteoritus: MeteoritusGuard<'_>,
+    data: Data<'_>,
     vault: &State<Arc<dyn Vault>>,
 ) -> CreationResponder {
-    let file = match vault.build_file(req.upload_length, req.metadata) {
-        Ok(file) => file,
-        Err(_) => {
-            return CreationResponder::Failure(
-                Status::InternalServerError,
-                "creation error".to_string(),
-            )
-        }
+    if req.validate_only {
+        return match validate_upload(&req, &meteoritus, vault.inner()) {
+            Ok(()) => CreationResponder::Validated,
+            Err(error) => CreationResponder::Failure(error),
+        };
+    }
+
+    let initial = req.initial.take();
+
+    let (uri, file, headers) =
+        match create_upload(&req, &meteoritus, vault.inner()).await {
+            Ok(created) => created,
+            Err(error) => return CreationResponder::Failure(error),
+        };
+
+    let Some(initial) = initial else {
+        return CreationResponder::Success(uri, file, headers);
     };
 
-    let base_uri = match Origin::parse(meteoritus.base_route()) {
-        Ok(base) => base,
-        Err(_) => {
-            return CreationResponder::Failure(
-                Status::InternalServerError,
-                "some error".to_string(),
-            );
+    /* Redirected clients resume their existing upload from its offset instead */
+    if *file.offset() != 0 {
+        return CreationResponder::Uploaded(uri, file, headers);
+    }
+
+    let id = file.id().to_string();
+
+    let written = write_chunk(
+        initial.with_token(file.upload_token()),
+        &id,
+        Owned::creator(),
+        &meteoritus,
+        data,
+        vault.inner(),
+    )
+    .await;
+
+    match written.into_written() {
+        Ok(file) => CreationResponder::Uploaded(uri, file, headers),
+        Err(error) => {
+            /* Handing out the upload created anyway, so clients resume it from
+            the bytes stored before the failure instead of creating another */
+            let error = match vault.get_file(&id) {
+                Ok(stored) => {
+                    let error = error.with_header("Location", uri).with_header(
+                        "Upload-Offset",
+                        stored.offset().to_string(),
+                    );
+
+                    match stored.upload_token() {
+                        Some(token) => error.with_header(TOKEN_HEADER, token),
+                        None => error,
+                    }
+                }
+                Err(_) => error,
+            };
+
+            CreationResponder::Failure(error)
         }
+    }
+}
+
+/// Runs the checks of an upload creation, including the `on_creation` callback,
+/// without creating anything.
+fn validate_upload(
+    req: &CreationRequest<'_>,
+    meteoritus: &Meteoritus<Orbit>,
+    vault: &Arc<dyn Vault>,
+) -> Result<(), TusError> {
+    let file = build_upload(req, meteoritus, vault)?;
+
+    #[cfg(feature = "remote-fetch")]
+    if let Some(fetch) = meteoritus.remote_fetch() {
+        fetch.source_of(&file)?;
+    }
+
+    check_client_quota(req, meteoritus, vault)?;
+    run_on_creation(req, meteoritus, &file)
+}
+
+/// Creates an upload, returning its location along with its [`FileInfo`] and the
+/// extra headers returned by the `on_created` callback.
+pub(crate) async fn create_upload(
+    req: &CreationRequest<'_>,
+    meteoritus: &Meteoritus<Orbit>,
+    vault: &Arc<dyn Vault>,
+) -> Result<(String, FileInfo<Created>, ResponseHeaders), TusError> {
+    let file = build_upload(req, meteoritus, vault)?;
+
+    #[cfg(feature = "remote-fetch")]
+    let source = match meteoritus.remote_fetch() {
+        Some(fetch) => fetch.source_of(&file)?,
+        None => None,
     };
 
-    let uri = uri!(base_uri, upload_handler(id = file.id()));
-    let uri: Reference = uri.into();
+    check_client_quota(req, meteoritus, vault)?;
+    run_on_creation(req, meteoritus, &file)?;
 
-    if let Some(callback) = &meteoritus.on_creation() {
-        if let Err(error) = callback(HandlerContext {
-            rocket: req.rocket,
-            file_info: &file,
-        }) {
-            return CreationResponder::Failure(
-                Status::UnprocessableEntity,
-                error.to_string(),
-            );
+    #[cfg(feature = "expiration")]
+    meteoritus.apply_expiration(&file);
+
+    let fingerprint = meteoritus
+        .fingerprint_of(&file)
+        .zip(requester_of(req, meteoritus));
+
+    /* Redirecting clients re-creating their interrupted upload to the existing one */
+    if let Some((fingerprint, requester)) = &fingerprint {
+        let index = meteoritus.fingerprint_index();
+
+        if let Some(id) = index.find(requester, fingerprint) {
+            match vault.get_file(&id) {
+                Ok(mut existing)
+                    if existing.length() == file.length()
+                        && existing.offset() < existing.length()
+                        && !existing.is_quarantined()
+                        && req.owner_check.is_owner(meteoritus, &existing) =>
+                {
+                    let location = meteoritus
+                        .upload_location(existing.id(), existing.metadata())?;
+
+                    /* Never handing out the token the upload was resumed with,
+                    while keeping it valid when the rotated one can't be stored */
+                    if existing.upload_token().is_some() {
+                        let token = generate_token();
+
+                        if let Err(e) =
+                            vault.update_upload_token(existing.id(), &token)
+                        {
+                            warn_!("Unable to rotate upload token: {}", e);
+
+                            return Err(TusError::new(
+                                Status::InternalServerError,
+                                ErrorCode::StorageFailed,
+                                "Unable to rotate the upload token",
+                            ));
+                        }
+
+                        existing.set_upload_token(token);
+                    }
+
+                    return Ok((location, existing, ResponseHeaders::new()));
+                }
+                _ => index.remove(requester, fingerprint),
+            }
         }
     }
 
-    match vault.create_file(file) {
+    let location = meteoritus.upload_location(file.id(), file.metadata())?;
+
+    #[cfg(feature = "grpc-hooks")]
+    if let Some(hooks) = meteoritus.grpc_hooks() {
+        hooks
+            .invoke(HookType::PreCreate, &file, 0, req.http.clone())
+            .await?;
+    }
+
+    let file_id = file.id().to_string();
+    let length = *file.length();
+
+    let creating = Instant::now();
+    let created = vault.create_file(file);
+
+    meteoritus.check_storage_latency(
+        "create_file",
+        &file_id,
+        length,
+        creating.elapsed(),
+    );
+
+    match created {
         Ok(file) => {
-            if let Some(callback) = &meteoritus.on_created() {
-                callback(HandlerContext {
+            /* Tokens the vault doesn't persist would never be enforced */
+            if file.upload_token().is_some() && !persists_token(vault, &file) {
+                if vault.terminate_file(file.id()).is_err() {
+                    warn_!("Unable to roll back upload: {}", file.id());
+                }
+
+                return Err(TusError::new(
+                    Status::NotImplemented,
+                    ErrorCode::UploadTokenUnsupported,
+                    "The storage does not support upload tokens",
+                ));
+            }
+
+            if let Some((fingerprint, requester)) = fingerprint {
+                meteoritus.fingerprint_index().insert(
+                    requester,
+                    fingerprint,
+                    file.id(),
+                );
+            }
+
+            meteoritus.touch_upload(file.id());
+            meteoritus.track_deadline(&file);
+            meteoritus.publish_event(UploadEventKind::Created, &file, 0);
+
+            #[cfg(feature = "grpc-hooks")]
+            if let Some(hooks) = meteoritus.grpc_hooks() {
+                hooks.notify(HookType::PostCreate, &file, 0, req.http.clone());
+            }
+
+            if let Some(client) = req.client_ip {
+                meteoritus.client_uploads().insert(client, file.id());
+            }
+
+            let headers = match &meteoritus.on_created() {
+                Some(callback) => callback(HandlerContext {
                     rocket: req.rocket,
                     file_info: &file,
-                });
+                }),
+                None => ResponseHeaders::new(),
+            };
+
+            #[cfg(feature = "remote-fetch")]
+            if let (Some(fetch), Some(source)) =
+                (meteoritus.remote_fetch(), source)
+            {
+                fetch.to_owned().spawn(
+                    meteoritus.launch(),
+                    vault.to_owned(),
+                    file.id(),
+                    source,
+                );
             }
 
-            CreationResponder::Success(uri.to_string())
+            Ok((location, file, headers))
+        }
+        Err(e) => Err(TusError::from_vault(&e)),
+    }
+}
+
+/// Builds the [`FileInfo`] of an upload out of the creation request, checking it
+/// against the upload policy, groups and metadata validators.
+fn build_upload(
+    req: &CreationRequest<'_>,
+    meteoritus: &Meteoritus<Orbit>,
+    vault: &Arc<dyn Vault>,
+) -> Result<FileInfo<Built>, TusError> {
+    let file = match vault.build_file(req.upload_length, req.metadata) {
+        Ok(file) => file,
+        Err(e) => return Err(TusError::from_vault(&e)),
+    };
+
+    let file = match meteoritus.generate_id() {
+        Some(id) => file.with_id(id),
+        None => file,
+    };
+
+    let file = match req.defer_length {
+        true => file.with_deferred_length(),
+        false => file,
+    };
+
+    let file = file
+        .with_captured_headers(req.captured_headers.clone())
+        .with_uploader(req.uploader.clone());
+
+    let file = match meteoritus.token_rotation() {
+        true => file.with_upload_token(generate_token()),
+        false => file,
+    };
+
+    if let Some(policy) = &req.policy {
+        policy.check(&file)?;
+    }
+
+    if let Some(groups) = meteoritus.concat_groups() {
+        groups.validate(&file)?;
+    }
+
+    if meteoritus.has_upload_groups() {
+        UploadGroups::validate(&file)?;
+    }
+
+    if let Some(allowed) = meteoritus.storage_classes() {
+        validate_storage_class(&file, allowed)?;
+    }
+
+    meteoritus.metadata_validators().validate(&file)?;
+
+    Ok(file)
+}
+
+/// Checks that the client didn't reach its maximum number of uploads in progress.
+fn check_client_quota(
+    req: &CreationRequest<'_>,
+    meteoritus: &Meteoritus<Orbit>,
+    vault: &Arc<dyn Vault>,
+) -> Result<(), TusError> {
+    if let (Some(limit), Some(client)) =
+        (meteoritus.max_uploads_per_client(), req.client_ip)
+    {
+        if meteoritus
+            .client_uploads()
+            .in_progress(client, vault.as_ref())
+            >= limit
+        {
+            return Err(TusError::new(
+                Status::TooManyRequests,
+                ErrorCode::TooManyUploads,
+                "Maximum number of uploads in progress reached",
+            ));
         }
-        Err(_) => CreationResponder::Failure(
-            Status::InternalServerError,
-            "some vault error".to_string(),
-        ),
+    }
+
+    Ok(())
+}
+
+/// Indicates if the vault stored the token of the created upload.
+fn persists_token(vault: &Arc<dyn Vault>, file: &FileInfo<Created>) -> bool {
+    vault
+        .get_file(file.id())
+        .is_ok_and(|stored| stored.upload_token() == file.upload_token())
+}
+
+/// Returns the identity of the client creating an upload, scoping its fingerprints
+/// so it is never redirected to the uploads of others.
+///
+/// Clients without an address are only told apart by the ownership check, so
+/// their creations are never deduplicated without one.
+fn requester_of(
+    req: &CreationRequest<'_>,
+    meteoritus: &Meteoritus<Orbit>,
+) -> Option<String> {
+    match req.client_ip {
+        Some(client) => Some(client.to_string()),
+        None if meteoritus.has_ownership_check() => Some(String::new()),
+        None => None,
     }
 }
 
+/// Runs the `on_creation` callback, rejecting the creation on failure.
+fn run_on_creation(
+    req: &CreationRequest<'_>,
+    meteoritus: &Meteoritus<Orbit>,
+    file: &FileInfo<Built>,
+) -> Result<(), TusError> {
+    if let Some(callback) = &meteoritus.on_creation() {
+        if let Err(error) = callback(HandlerContext {
+            rocket: req.rocket,
+            file_info: file,
+        }) {
+            return Err(TusError::new(
+                Status::UnprocessableEntity,
+                ErrorCode::CreationRejected,
+                error.to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Debug)]
 pub struct CreationRequest<'r> {
-    rocket: &'r Rocket<Orbit>,
-    upload_length: u64,
-    metadata: Option<&'r str>,
+    pub(crate) rocket: &'r Rocket<Orbit>,
+    pub(crate) upload_length: u64,
+    pub(crate) defer_length: bool,
+    pub(crate) metadata: Option<&'r str>,
+    pub(crate) client_ip: Option<IpAddr>,
+    pub(crate) captured_headers: BTreeMap<String, String>,
+    pub(crate) uploader: UploaderInfo,
+    pub(crate) policy: Option<UploadPolicy>,
+    pub(crate) validate_only: bool,
+    pub(crate) owner_check: OwnerCheck<'r>,
+    /* The chunk sent along the creation, see `creation-with-upload` */
+    pub(crate) initial: Option<UploadRequest<'r>>,
+    #[cfg(feature = "grpc-hooks")]
+    pub(crate) http: HookHttpRequest,
+}
+
+type OwnerCheckFn<'r> =
+    dyn Fn(&Meteoritus<Orbit>, &FileInfo<Created>) -> bool + Send + Sync + 'r;
+
+/// Runs the ownership check of existing uploads against the creation request.
+#[derive(Clone)]
+pub(crate) struct OwnerCheck<'r>(Arc<OwnerCheckFn<'r>>);
+
+impl<'r> OwnerCheck<'r> {
+    pub(crate) fn of(req: &'r Request<'_>) -> Self {
+        Self(Arc::new(|meteoritus, file| meteoritus.is_owner(req, file)))
+    }
+
+    fn is_owner(
+        &self,
+        meteoritus: &Meteoritus<Orbit>,
+        file: &FileInfo<Created>,
+    ) -> bool {
+        (self.0)(meteoritus, file)
+    }
+}
+
+impl fmt::Debug for OwnerCheck<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OwnerCheck")
+    }
 }
 
 #[rocket::async_trait]
 impl<'r> FromRequest<'r> for CreationRequest<'r> {
-    type Error = &'static str;
+    type Error = TusError;
 
     async fn from_request(
         req: &'r Request<'_>,
     ) -> request::Outcome<Self, Self::Error> {
-        let meteoritus = req.rocket().state::<Meteoritus<Orbit>>().unwrap();
+        let meteoritus = try_outcome!(req.guard::<MeteoritusGuard<'r>>().await);
 
         let tus_resumable_header = req.headers().get_one("Tus-Resumable");
         if tus_resumable_header.is_none()
             || tus_resumable_header.unwrap() != "1.0.0"
         {
-            return Outcome::Error((
+            return TusError::new(
                 Status::BadRequest,
+                ErrorCode::InvalidTusResumable,
                 "Missing or invalid Tus-Resumable header",
-            ));
+            )
+            .fail(req);
         }
 
+        let defer_length = match req.headers().get_one("Upload-Defer-Length") {
+            None => false,
+            Some("1") => true,
+            Some(_) => {
+                return TusError::new(
+                    Status::BadRequest,
+                    ErrorCode::InvalidUploadDeferLength,
+                    "Invalid Upload-Defer-Length header",
+                )
+                .fail(req)
+            }
+        };
+
+        /* Dry runs only check if the upload would be accepted */
+        let validate_only = match req.headers().get_one(VALIDATE_ONLY_HEADER) {
+            None => false,
+            Some("1") => true,
+            Some(_) => {
+                return TusError::new(
+                    Status::BadRequest,
+                    ErrorCode::InvalidUploadValidateOnly,
+                    "Invalid Upload-Validate-Only header",
+                )
+                .fail(req)
+            }
+        };
+
         let upload_length = match req.headers().get_one("Upload-Length") {
+            Some(_) if defer_length => return TusError::new(
+                Status::BadRequest,
+                ErrorCode::ConflictingUploadLength,
+                "Upload-Length and Upload-Defer-Length are mutually exclusive",
+            )
+            .fail(req),
             Some(value) => match value.parse::<u64>() {
                 Ok(value) => value,
                 Err(_) => {
-                    return Outcome::Error((
+                    return TusError::new(
                         Status::BadRequest,
+                        ErrorCode::InvalidUploadLength,
                         "Invalid Upload-Length header",
-                    ))
+                    )
+                    .fail(req)
                 }
             },
+            None if defer_length => 0,
             None => {
-                return Outcome::Error((
+                return TusError::new(
                     Status::BadRequest,
+                    ErrorCode::MissingUploadLength,
                     "Missing Upload-Length header",
-                ))
+                )
+                .fail(req)
             }
         };
 
         if upload_length > meteoritus.max_size().as_u64() {
-            return Outcome::Error((
+            return TusError::new(
                 Status::PayloadTooLarge,
+                ErrorCode::UploadLengthExceeded,
                 "Upload-Length exceeds the Tus-Max-Size",
-            ));
+            )
+            .fail(req);
         }
 
         let metadata = match req.headers().get_one("Upload-Metadata") {
-            None => None,
-            Some(metadata) if metadata.is_empty() => None,
+            None | Some("") => None,
             Some(metadata) => Some(metadata),
         };
 
+        let policy = match meteoritus.policy_validator() {
+            Some(validator) => {
+                match validate_policy(validator, req.headers()) {
+                    Ok(policy) => Some(policy),
+                    Err(error) => return error.fail(req),
+                }
+            }
+            None => None,
+        };
+
+        /* Bodies of other types aren't uploads, so they are ignored */
+        let initial = match req.content_type() {
+            Some(value)
+                if value
+                    == &ContentType::new(
+                        "application",
+                        "offset+octet-stream",
+                    ) =>
+            {
+                Some(try_outcome!(UploadRequest::initial(req).await))
+            }
+            _ => None,
+        };
+
         let creation_values = CreationRequest {
             rocket: req.rocket(),
             upload_length,
+            defer_length,
             metadata,
+            client_ip: req.client_ip(),
+            captured_headers: meteoritus.capture_headers(req),
+            uploader: UploaderInfo::from_request(req),
+            policy,
+            validate_only,
+            owner_check: OwnerCheck::of(req),
+            initial,
+            #[cfg(feature = "grpc-hooks")]
+            http: HookHttpRequest::from(req),
         };
 
         Outcome::Success(creation_values)
     }
 }
 
+/// Extra headers merged into the `201 Created` response of an upload creation, as
+/// returned by the [`Meteoritus::on_created()`](crate::Meteoritus::on_created)
+/// callback.
+///
+/// Those headers allow to hand the client supplementary data, like an upload token
+/// or expiry hints, without a second request. They can't override the headers of
+/// the tus protocol, like `Location` or `Tus-Resumable`.
+///
+/// # Example
+///
+/// ```rust
+/// use meteoritus::ResponseHeaders;
+///
+/// let headers = ResponseHeaders::new()
+///     .with("X-Upload-Token", "secret")
+///     .with("X-Upload-Expires", "3600");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ResponseHeaders {
+    headers: Vec<Header<'static>>,
+}
+
+impl ResponseHeaders {
+    /// Creates an empty set of headers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the header `name` with the given `value`.
+    pub fn with(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        value: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.headers.push(Header::new(name, value));
+        self
+    }
+
+    /// Returns an iterator over the headers, in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = &Header<'static>> {
+        self.headers.iter()
+    }
+
+    /// Indicates if no header was added.
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+}
+
+impl From<()> for ResponseHeaders {
+    fn from(_: ()) -> Self {
+        Self::default()
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
 pub enum CreationResponder {
-    Success(String),
-    Failure(Status, String),
+    Success(String, FileInfo<Created>, ResponseHeaders),
+    Uploaded(String, FileInfo<Created>, ResponseHeaders),
+    Validated,
+    Failure(TusError),
+}
+
+impl Sentinel for CreationResponder {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        super::abort_launch(rocket)
+    }
 }
 
 impl<'r> Responder<'r, 'static> for CreationResponder {
     fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
-        let meteoritus = req.rocket().state::<Meteoritus<Orbit>>().unwrap();
+        let meteoritus = MeteoritusGuard::of(req)?;
 
         match self {
-            Self::Failure(status, error) => rocket::Response::build()
-                .status(status)
-                .sized_body(error.len(), Cursor::new(error))
-                .ok(),
-
-            Self::Success(uri) => Response::build()
-                .header(meteoritus.get_protocol_resumable_version())
-                .raw_header("Location", uri)
-                .status(Status::Created)
-                .ok(),
+            Self::Failure(error) => error.respond_to(req),
+
+            Self::Validated => {
+                let mut res = Response::build();
+
+                res.header(meteoritus.get_protocol_resumable_version())
+                    .status(Status::NoContent);
+
+                meteoritus.decorate_response(None, &mut res);
+
+                res.ok()
+            }
+
+            Self::Success(uri, file, headers) => {
+                created(&meteoritus, uri, file, headers, false)
+            }
+
+            Self::Uploaded(uri, file, headers) => {
+                created(&meteoritus, uri, file, headers, true)
+            }
         }
     }
 }
+
+/// Builds the `201 Created` response of an upload, along with its `Upload-Offset`
+/// when an initial chunk was sent.
+fn created(
+    meteoritus: &Meteoritus<Orbit>,
+    uri: String,
+    file: FileInfo<Created>,
+    headers: ResponseHeaders,
+    uploaded: bool,
+) -> response::Result<'static> {
+    let mut res = Response::build();
+
+    /* Adding extra headers first, so protocol headers override them */
+    for header in headers.headers {
+        res.header_adjoin(header);
+    }
+
+    res.header(meteoritus.get_protocol_resumable_version())
+        .raw_header("Location", uri)
+        .status(Status::Created);
+
+    if uploaded {
+        res.raw_header("Upload-Offset", file.offset().to_string());
+    }
+
+    if let Some(token) = file.upload_token() {
+        res.raw_header(TOKEN_HEADER, token.to_string());
+    }
+
+    #[cfg(feature = "expiration")]
+    upload_expires(&file, &mut res);
+
+    meteoritus.decorate_response(Some(&file), &mut res);
+
+    res.ok()
+}
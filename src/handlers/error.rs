@@ -0,0 +1,244 @@
+use std::io::{Cursor, ErrorKind};
+
+use rocket::{
+    http::{ContentType, Status},
+    request,
+    response::{self, Responder},
+    Request, Response,
+};
+
+use serde_json::json;
+
+use crate::VaultError;
+
+use super::{ErrorCode, MeteoritusGuard};
+
+/// A failure produced by the tus handlers.
+///
+/// By default it responds with a [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807)
+/// `application/problem+json` body, carrying a machine-readable `code` member out
+/// of the [`ErrorCode`] catalog:
+///
+/// ```json
+/// {
+///   "type": "about:blank",
+///   "title": "Not Found",
+///   "status": 404,
+///   "detail": "Upload not found",
+///   "code": "upload-not-found",
+///   "instance": "/api/files/0b6b4d8e"
+/// }
+/// ```
+///
+/// `409 Conflict` responses of `PATCH` requests, sent at an offset other than the
+/// current one or to a completed upload, also carry the current `Upload-Offset`
+/// header, so clients can resume right away without a `HEAD` request. Likewise,
+/// creation requests failing to store their initial chunk still carry the
+/// `Location` of the created upload along with the `Upload-Offset` stored so far.
+///
+/// Consider [`Meteoritus::with_plain_text_errors()`](crate::Meteoritus::with_plain_text_errors)
+/// to respond with the `detail` as a `text/plain` body instead, or
+/// [`Meteoritus::use_catchers()`](crate::Meteoritus::use_catchers) to forward
+/// the failure to the application catchers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TusError {
+    status: Status,
+    code: ErrorCode,
+    detail: String,
+    headers: Vec<(&'static str, String)>,
+}
+
+struct CachedTusError(Option<TusError>);
+
+impl TusError {
+    /// Creates a new [`TusError`] with the given `status`, machine-readable `code`
+    /// and human-readable `detail`.
+    pub fn new(
+        status: Status,
+        code: ErrorCode,
+        detail: impl Into<String>,
+    ) -> Self {
+        Self {
+            status,
+            code,
+            detail: detail.into(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Adds a header to the response of the error, kept when the failure is
+    /// forwarded to the application catchers.
+    pub(crate) fn with_header(
+        mut self,
+        name: &'static str,
+        value: impl Into<String>,
+    ) -> Self {
+        self.headers.retain(|(header, _)| *header != name);
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    /// Sets the headers of the error into the given response.
+    pub(crate) fn apply_headers(&self, res: &mut Response<'_>) {
+        for (name, value) in &self.headers {
+            res.set_raw_header(*name, value.to_owned());
+        }
+    }
+
+    /// Returns the HTTP status of the error.
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Returns the machine-readable error code.
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    /// Returns the human-readable error description.
+    pub fn detail(&self) -> &str {
+        &self.detail
+    }
+
+    /// Maps a [`VaultError`] to the closest HTTP error.
+    pub(crate) fn from_vault(error: &VaultError) -> Self {
+        match error {
+            VaultError::NotFound => Self::new(
+                Status::NotFound,
+                ErrorCode::UploadNotFound,
+                "Upload not found",
+            ),
+            VaultError::Conflict(reason) => Self::new(
+                Status::Conflict,
+                ErrorCode::UploadConflict,
+                reason.to_owned(),
+            ),
+            VaultError::Invalid(e) => Self::new(
+                Status::BadRequest,
+                ErrorCode::InvalidUpload,
+                e.to_string(),
+            ),
+            _ if matches!(
+                error.io_error_kind(),
+                Some(ErrorKind::StorageFull | ErrorKind::QuotaExceeded)
+            ) =>
+            {
+                Self::new(
+                    Status::InsufficientStorage,
+                    ErrorCode::InsufficientStorage,
+                    "Not enough storage space for the upload",
+                )
+            }
+            _ => Self::new(
+                Status::InternalServerError,
+                ErrorCode::StorageFailed,
+                "Unable to access the upload storage",
+            ),
+        }
+    }
+
+    /// Fails a request guard with this error.
+    ///
+    /// Since guard failures are responded by catchers, the error is cached into
+    /// the request so it can be retrieved by [`TusError::cached()`].
+    pub(crate) fn fail<T>(
+        self,
+        req: &Request<'_>,
+    ) -> request::Outcome<T, Self> {
+        let status = self.status;
+        req.local_cache(|| CachedTusError(Some(self.clone())));
+
+        request::Outcome::Error((status, self))
+    }
+
+    /// Returns the [`TusError`] forwarded to the application catchers while
+    /// handling the given request, if any.
+    ///
+    /// This includes failures of the tus request guards, like missing or invalid headers.
+    ///
+    /// # Example
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::{http::Status, Request};
+    ///   use meteoritus::{Meteoritus, TusError};
+    ///
+    ///   #[catch(default)]
+    ///   fn default_catcher(status: Status, req: &Request) -> String {
+    ///       match TusError::cached(req) {
+    ///           Some(error) => format!("{}: {}", error.code(), error.detail()),
+    ///           None => status.to_string(),
+    ///       }
+    ///   }
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus = Meteoritus::new().use_catchers(true).build();
+    ///
+    ///       rocket::build()
+    ///           .attach(meteoritus)
+    ///           .register("/", catchers![default_catcher])
+    ///   }
+    ///   ```
+    pub fn cached<'r>(req: &'r Request<'_>) -> Option<&'r TusError> {
+        req.local_cache(|| CachedTusError(None)).0.as_ref()
+    }
+}
+
+impl<'r> Responder<'r, 'static> for TusError {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let meteoritus = MeteoritusGuard::of(req)?;
+
+        if meteoritus.use_catchers() {
+            let status = self.status;
+            req.local_cache(|| CachedTusError(Some(self)));
+
+            return Err(status);
+        }
+
+        let mut res = Response::build();
+
+        res.header(meteoritus.get_protocol_resumable_version())
+            .status(self.status);
+
+        for (name, value) in &self.headers {
+            res.raw_header(*name, value.to_owned());
+        }
+
+        if meteoritus.plain_text_errors() {
+            res.header(ContentType::Plain)
+                .sized_body(self.detail.len(), Cursor::new(self.detail));
+        } else {
+            let body = json!({
+                "type": "about:blank",
+                "title": self.status.reason_lossy(),
+                "status": self.status.code,
+                "detail": self.detail,
+                "code": self.code.as_str(),
+                "instance": req.uri().path().as_str(),
+            })
+            .to_string();
+
+            res.header(ContentType::new("application", "problem+json"))
+                .sized_body(body.len(), Cursor::new(body));
+        }
+
+        meteoritus.decorate_response(None, &mut res);
+
+        res.ok()
+    }
+}
+
+/// Responds failures of the tus routes that never reach a handler, like
+/// request guard failures, using the cached [`TusError`] when available.
+#[catch(default)]
+pub fn tus_catcher(status: Status, req: &Request) -> TusError {
+    match TusError::cached(req) {
+        Some(error) => error.clone(),
+        None => TusError::new(
+            status,
+            ErrorCode::RequestFailed,
+            status.reason_lossy().to_string(),
+        ),
+    }
+}
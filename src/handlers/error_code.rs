@@ -0,0 +1,205 @@
+use std::fmt;
+
+/// Defines the [`ErrorCode`] variants along with their machine-readable codes.
+macro_rules! error_codes {
+    ($($(#[$doc:meta])* $variant:ident => $code:literal,)*) => {
+        /// The stable machine-readable code of a [`TusError`](crate::TusError),
+        /// carried by the `code` member of the error responses.
+        ///
+        /// Codes never change once released, so client apps can map them to
+        /// localized messages and tests can assert on them rather than on the
+        /// English `detail`. Every code is listed by [`ErrorCode::ALL`].
+        ///
+        /// # Example
+        ///
+        /// ```rust
+        /// use meteoritus::ErrorCode;
+        ///
+        /// assert_eq!(ErrorCode::UploadNotFound.as_str(), "upload-not-found");
+        /// assert_eq!(ErrorCode::parse("upload-not-found"), Some(ErrorCode::UploadNotFound));
+        /// ```
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum ErrorCode {
+            $($(#[$doc])* $variant,)*
+        }
+
+        impl ErrorCode {
+            /// The catalog of every error code.
+            pub const ALL: &'static [ErrorCode] = &[$(Self::$variant,)*];
+
+            /// Returns the machine-readable code, like `upload-not-found`.
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $code,)*
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    /// A request failed before reaching a handler, without a more precise reason.
+    RequestFailed => "request-failed",
+    /// The tus routes are mounted without the Meteoritus fairing attached.
+    MissingFairing => "missing-fairing",
+    /// The configured base route can't build upload locations.
+    InvalidBaseRoute => "invalid-base-route",
+    /// The `Tus-Resumable` header is missing or unsupported.
+    InvalidTusResumable => "invalid-tus-resumable",
+    /// The request was rejected by the authorizer.
+    Unauthorized => "unauthorized",
+    /// The upload was rejected by the ownership check.
+    UploadForbidden => "upload-forbidden",
+    /// The upload doesn't exist.
+    UploadNotFound => "upload-not-found",
+    /// The upload doesn't exist anymore, or was never created.
+    UploadGone => "upload-gone",
+    /// The request conflicts with the current state of the upload.
+    UploadConflict => "upload-conflict",
+    /// The upload was rejected by the vault as invalid.
+    InvalidUpload => "invalid-upload",
+    /// The storage has not enough space for the upload.
+    InsufficientStorage => "insufficient-storage",
+    /// The upload storage failed.
+    StorageFailed => "storage-failed",
+    /// The creation declares neither `Upload-Length` nor `Upload-Defer-Length`.
+    MissingUploadLength => "missing-upload-length",
+    /// The `Upload-Length` header is malformed.
+    InvalidUploadLength => "invalid-upload-length",
+    /// The creation declares both `Upload-Length` and `Upload-Defer-Length`.
+    ConflictingUploadLength => "conflicting-upload-length",
+    /// The `Upload-Defer-Length` header is malformed.
+    InvalidUploadDeferLength => "invalid-upload-defer-length",
+    /// The upload length exceeds the `Tus-Max-Size`.
+    UploadLengthExceeded => "upload-length-exceeded",
+    /// The `Upload-Length` of a `PATCH` request differs from the upload length.
+    UploadLengthMismatch => "upload-length-mismatch",
+    /// The vault doesn't support enlarging the upload length.
+    LengthIncreaseUnsupported => "length-increase-unsupported",
+    /// The vault doesn't support parallel chunks.
+    ParallelChunksUnsupported => "parallel-chunks-unsupported",
+    /// The validate-only header of a creation is malformed.
+    InvalidUploadValidateOnly => "invalid-upload-validate-only",
+    /// The creation was rejected by the `on_creation` callback.
+    CreationRejected => "creation-rejected",
+    /// The client reached its maximum number of uploads in progress.
+    TooManyUploads => "too-many-uploads",
+    /// The upload metadata was rejected by the metadata validators.
+    InvalidMetadata => "invalid-metadata",
+    /// The storage class hint of the upload isn't allowed.
+    InvalidStorageClass => "invalid-storage-class",
+    /// The group membership metadata of the upload is malformed.
+    InvalidUploadGroup => "invalid-upload-group",
+    /// The concatenation group metadata of the upload is malformed.
+    InvalidConcatGroup => "invalid-concat-group",
+    /// The concatenation group doesn't exist.
+    ConcatGroupNotFound => "concat-group-not-found",
+    /// The parts of a concatenation group couldn't be concatenated.
+    ConcatenationFailed => "concatenation-failed",
+    /// The creation lacks the required policy document.
+    MissingPolicy => "missing-policy",
+    /// The policy document was rejected by the policy validator.
+    InvalidPolicy => "invalid-policy",
+    /// The upload violates the constraints of its policy.
+    PolicyViolation => "policy-violation",
+    /// Batch creation is not enabled.
+    BatchCreationDisabled => "batch-creation-disabled",
+    /// The batch creation body exceeds the allowed size.
+    BatchTooLarge => "batch-too-large",
+    /// The batch creation body is malformed.
+    InvalidBatch => "invalid-batch",
+    /// The batch holds no uploads, or more than allowed.
+    InvalidBatchSize => "invalid-batch-size",
+    /// The remote fetch source URL is malformed.
+    InvalidSourceUrl => "invalid-source-url",
+    /// The remote fetch source host isn't allowed.
+    SourceHostNotAllowed => "source-host-not-allowed",
+    /// The upload is being fetched from a remote source.
+    UploadFetching => "upload-fetching",
+    /// The `Upload-Offset` header is missing.
+    MissingUploadOffset => "missing-upload-offset",
+    /// The `Upload-Offset` header is malformed.
+    InvalidUploadOffset => "invalid-upload-offset",
+    /// The `Upload-Offset` differs from the current upload offset.
+    OffsetMismatch => "offset-mismatch",
+    /// The vault reported an offset beyond the upload length.
+    InvalidOffset => "invalid-offset",
+    /// The `Content-Type` header is missing.
+    MissingContentType => "missing-content-type",
+    /// The `Content-Type` isn't `application/offset+octet-stream`.
+    InvalidContentType => "invalid-content-type",
+    /// The `Content-Length` header is malformed.
+    InvalidContentLength => "invalid-content-length",
+    /// The request body couldn't be read, or is empty.
+    InvalidBody => "invalid-body",
+    /// The request body exceeds the maximum chunk size.
+    ChunkTooLarge => "chunk-too-large",
+    /// The `Content-Length` exceeds the remaining upload length.
+    ChunkExceedsUploadLength => "chunk-exceeds-upload-length",
+    /// The request body holds more bytes than its `Content-Length`, or than
+    /// remain in the upload.
+    OverlongBody => "overlong-body",
+    /// The upload is already completed.
+    UploadCompleted => "upload-completed",
+    /// The upload is paused.
+    UploadPaused => "upload-paused",
+    /// The upload is quarantined.
+    UploadQuarantined => "upload-quarantined",
+    /// The upload deadline has passed.
+    UploadDeadlineExceeded => "upload-deadline-exceeded",
+    /// The one-time upload token is missing.
+    MissingUploadToken => "missing-upload-token",
+    /// The one-time upload token doesn't match.
+    InvalidUploadToken => "invalid-upload-token",
+    /// The vault doesn't persist upload tokens.
+    UploadTokenUnsupported => "upload-token-unsupported",
+    /// The detected content type differs from the declared one.
+    FiletypeMismatch => "filetype-mismatch",
+    /// The completed upload couldn't be terminated.
+    TerminationFailed => "termination-failed",
+    /// The checksum algorithm isn't supported.
+    UnsupportedChecksumAlgorithm => "unsupported-checksum-algorithm",
+    /// The `Upload-Checksum` header is malformed.
+    InvalidUploadChecksum => "invalid-upload-checksum",
+    /// The request lacks the required `Upload-Checksum` header.
+    MissingUploadChecksum => "missing-upload-checksum",
+    /// The request body doesn't match its `Upload-Checksum`.
+    ChecksumMismatch => "checksum-mismatch",
+    /// The checksum range is malformed.
+    InvalidChecksumRange => "invalid-checksum-range",
+    /// The checksum range exceeds the stored bytes.
+    ChecksumRangeNotStored => "checksum-range-not-stored",
+    /// The checksum range exceeds the maximum chunk size.
+    ChecksumRangeTooLarge => "checksum-range-too-large",
+    /// Range repair is not enabled.
+    RangeRepairDisabled => "range-repair-disabled",
+    /// The vault doesn't support range repairs.
+    RangeRepairUnsupported => "range-repair-unsupported",
+    /// The repair range exceeds the stored bytes.
+    RepairRangeNotStored => "repair-range-not-stored",
+    /// The compatibility report is not enabled.
+    CompatibilityCheckDisabled => "compatibility-check-disabled",
+    /// The client hint of a compatibility report is missing or unknown.
+    UnknownClient => "unknown-client",
+    /// The request was rejected by a hook.
+    HookRejected => "hook-rejected",
+    /// A hook couldn't be invoked.
+    HookFailed => "hook-failed",
+}
+
+impl ErrorCode {
+    /// Returns the error code of the given machine-readable code, if any.
+    pub fn parse(code: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|known| known.as_str() == code)
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -1,36 +1,81 @@
-use std::sync::Arc;
+use std::{fs, sync::Arc};
 
 use rocket::{
     http::Status,
     response::{self, Responder},
-    Orbit, Request, State,
+    Ignite, Request, Rocket, Sentinel, State,
 };
 
 use crate::{
     fs::{Created, FileInfo},
-    meteoritus::Meteoritus,
-    Vault,
+    tombstone::Tombstone,
+    ErrorCode, TusError, Vault, VaultError,
 };
 
+#[cfg(feature = "expiration")]
+use super::upload_expires;
+use super::{authorization::Owned, http_date, MeteoritusGuard};
+
 #[head("/<id>")]
 pub fn file_info_handler(
     id: &str,
+    owned: Owned,
+    meteoritus: MeteoritusGuard<'_>,
     vault: &State<Arc<dyn Vault>>,
 ) -> FileInfoResponder {
-    match vault.get_file(id) {
+    let policy = meteoritus.disclosure_policy();
+
+    let file = match meteoritus.resume_verification() {
+        true => vault.repair_offset(id),
+        false => vault.get_file(id),
+    };
+
+    match file {
+        Ok(file) if file.is_quarantined() => {
+            FileInfoResponder::Failure(owned.disclose(
+                policy,
+                TusError::new(
+                    meteoritus.quarantine_status(),
+                    ErrorCode::UploadQuarantined,
+                    "Upload is quarantined",
+                ),
+            ))
+        }
         Ok(file) => FileInfoResponder::Success(file),
-        Err(_) => FileInfoResponder::Failure(Status::NotFound),
+        /* Answering recently removed uploads from their tombstones */
+        Err(VaultError::NotFound) if !owned.conceals(policy) => {
+            match meteoritus.tombstone(id) {
+                Some(Tombstone::Completed(length)) => {
+                    FileInfoResponder::Completed(length)
+                }
+                Some(Tombstone::Terminated) => {
+                    FileInfoResponder::Failure(policy.gone())
+                }
+                None => FileInfoResponder::Failure(TusError::from_vault(
+                    &VaultError::NotFound,
+                )),
+            }
+        }
+        Err(e) => FileInfoResponder::Failure(TusError::from_vault(&e)),
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 pub enum FileInfoResponder {
     Success(FileInfo<Created>),
-    Failure(Status),
+    Completed(u64),
+    Failure(TusError),
+}
+
+impl Sentinel for FileInfoResponder {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        super::abort_launch(rocket)
+    }
 }
 
 impl<'r> Responder<'r, 'static> for FileInfoResponder {
     fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
-        let meteoritus = req.rocket().state::<Meteoritus<Orbit>>().unwrap();
+        let meteoritus = MeteoritusGuard::of(req)?;
 
         let mut res = rocket::Response::build();
 
@@ -39,10 +84,54 @@ impl<'r> Responder<'r, 'static> for FileInfoResponder {
         match self {
             Self::Success(file) => {
                 res.status(Status::NoContent);
-                res.raw_header("Upload-Length", file.length().to_string());
-                res.raw_header("Upload-Offset", file.offset().to_string())
+                match file.is_length_deferred() {
+                    true => res.raw_header("Upload-Defer-Length", "1"),
+                    false => res
+                        .raw_header("Upload-Length", file.length().to_string()),
+                };
+                res.raw_header("Upload-Offset", file.offset().to_string());
+
+                #[cfg(feature = "expiration")]
+                upload_expires(&file, &mut res);
+
+                if !file.received_ranges().is_empty() {
+                    res.raw_header(
+                        "Meteoritus-Received-Ranges",
+                        file.received_ranges().to_string(),
+                    );
+                }
+
+                if meteoritus.progress_headers() && !file.is_length_deferred() {
+                    let progress = match *file.length() {
+                        0 => 100,
+                        length => *file.offset() as u128 * 100 / length as u128,
+                    };
+
+                    res.raw_header("Upload-Progress", progress.to_string());
+
+                    let modified =
+                        file.storage_ref().as_path().and_then(|path| {
+                            fs::metadata(path).and_then(|m| m.modified()).ok()
+                        });
+
+                    if let Some(modified) = modified {
+                        res.raw_header(
+                            "Upload-Last-Modified",
+                            http_date(modified),
+                        );
+                    }
+                }
+
+                meteoritus.decorate_response(Some(&file), &mut res)
+            }
+            Self::Completed(length) => {
+                res.status(Status::NoContent);
+                res.raw_header("Upload-Length", length.to_string());
+                res.raw_header("Upload-Offset", length.to_string());
+
+                meteoritus.decorate_response(None, &mut res)
             }
-            Self::Failure(status) => res.status(status),
+            Self::Failure(error) => return error.respond_to(req),
         };
 
         res.ok()
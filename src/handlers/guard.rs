@@ -0,0 +1,62 @@
+use std::ops::Deref;
+
+use rocket::{
+    http::Status,
+    request::{self, FromRequest, Outcome},
+    Orbit, Request,
+};
+
+use crate::Meteoritus;
+
+use super::{ErrorCode, TusError};
+
+/// A request guard resolving the [`Meteoritus`] managed by the request's Rocket
+/// instance, used by every tus handler, request guard and responder.
+///
+/// Requests reaching a Rocket instance without the fairing attached fail with
+/// `500 Internal Server Error` instead of panicking.
+#[derive(Clone, Copy)]
+pub(crate) struct MeteoritusGuard<'r>(&'r Meteoritus<Orbit>);
+
+impl<'r> MeteoritusGuard<'r> {
+    /// Resolves the [`Meteoritus`] of the given request, failing with
+    /// `500 Internal Server Error` when it isn't managed.
+    pub(crate) fn of(req: &'r Request<'_>) -> Result<Self, Status> {
+        match req.rocket().state::<Meteoritus<Orbit>>() {
+            Some(meteoritus) => Ok(Self(meteoritus)),
+            None => {
+                error_!("Meteoritus isn't managed by Rocket");
+                Err(Status::InternalServerError)
+            }
+        }
+    }
+}
+
+impl Deref for MeteoritusGuard<'_> {
+    type Target = Meteoritus<Orbit>;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for MeteoritusGuard<'r> {
+    type Error = TusError;
+
+    async fn from_request(
+        req: &'r Request<'_>,
+    ) -> request::Outcome<Self, Self::Error> {
+        match Self::of(req) {
+            Ok(meteoritus) => Outcome::Success(meteoritus),
+            Err(status) => Outcome::Error((
+                status,
+                TusError::new(
+                    status,
+                    ErrorCode::MissingFairing,
+                    "Meteoritus fairing isn't attached",
+                ),
+            )),
+        }
+    }
+}
@@ -1,24 +1,62 @@
-use rocket::{http::Status, response::Responder, Orbit, Request, Response};
+use rocket::{
+    http::Status, response::Responder, Ignite, Request, Response, Rocket,
+    Sentinel,
+};
 
-use crate::meteoritus::Meteoritus;
+use crate::MeteoritusHeaders;
+
+use super::MeteoritusGuard;
 
 #[options("/")]
 pub fn info_handler() -> InfoResponder {
     InfoResponder {}
 }
 
+#[options("/<_>")]
+pub fn upload_info_handler() -> InfoResponder {
+    InfoResponder {}
+}
+
 pub struct InfoResponder {}
 
+impl Sentinel for InfoResponder {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        super::abort_launch(rocket)
+    }
+}
+
 impl<'r> Responder<'r, 'static> for InfoResponder {
-    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
-        let meteoritus = req.rocket().state::<Meteoritus<Orbit>>().unwrap();
+    fn respond_to(
+        self,
+        req: &'r Request<'_>,
+    ) -> rocket::response::Result<'static> {
+        let meteoritus = MeteoritusGuard::of(req)?;
 
-        Response::build()
-            .header(meteoritus.get_protocol_resumable_version())
+        let mut res = Response::build();
+
+        res.header(meteoritus.get_protocol_resumable_version())
             .header(meteoritus.get_protocol_version())
             .header(meteoritus.get_protocol_extensions())
             .header(meteoritus.get_protocol_max_size())
-            .status(Status::NoContent)
-            .ok()
+            .status(Status::NoContent);
+
+        #[cfg(feature = "checksum")]
+        res.header(meteoritus.get_protocol_checksum_algorithms());
+
+        if let Some(max_uploads) = meteoritus.batch_creation() {
+            res.header(MeteoritusHeaders::CreationBatch(max_uploads));
+        }
+
+        if meteoritus.length_increase() {
+            res.header(MeteoritusHeaders::LengthIncrease);
+        }
+
+        if meteoritus.parallel_chunks() {
+            res.header(MeteoritusHeaders::ParallelChunks);
+        }
+
+        meteoritus.decorate_response(None, &mut res);
+
+        res.ok()
     }
 }
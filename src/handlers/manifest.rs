@@ -0,0 +1,112 @@
+use std::{fs, io::Cursor, sync::Arc};
+
+use rocket::{
+    http::{ContentType, Status},
+    response::{self, Responder},
+    Ignite, Request, Rocket, Sentinel, State,
+};
+use serde_json::{json, Value};
+
+use crate::{
+    fs::{Created, FileInfo},
+    timeline::rfc3339,
+    TusError, Vault,
+};
+
+use super::{authorization::Authorized, MeteoritusGuard};
+
+#[get("/<id>/info")]
+pub fn manifest_handler(
+    id: &str,
+    _authorized: Authorized,
+    meteoritus: MeteoritusGuard<'_>,
+    vault: &State<Arc<dyn Vault>>,
+) -> ManifestResponder {
+    let file = match vault.get_file(id) {
+        Ok(file) => file,
+        Err(e) => return ManifestResponder::Failure(TusError::from_vault(&e)),
+    };
+
+    let state = match () {
+        _ if file.is_quarantined() => "quarantined",
+        _ if meteoritus.is_paused(id) => "paused",
+        #[cfg(feature = "remote-fetch")]
+        _ if meteoritus
+            .remote_fetch()
+            .is_some_and(|fetch| fetch.is_fetching(id)) =>
+        {
+            "fetching"
+        }
+        _ if !file.is_length_deferred() && file.offset() == file.length() => {
+            "completed"
+        }
+        _ => "in-progress",
+    };
+
+    let length = match file.is_length_deferred() {
+        true => None,
+        false => Some(*file.length()),
+    };
+
+    let metadata = file
+        .metadata()
+        .as_ref()
+        .map(|metadata| metadata.decoded())
+        .unwrap_or_default();
+
+    let last_modified = file
+        .storage_ref()
+        .as_path()
+        .and_then(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .map(rfc3339);
+
+    let manifest = json!({
+        "id": file.id(),
+        "length": length,
+        "offset": file.offset(),
+        "metadata": metadata,
+        "state": state,
+        "last_modified": last_modified,
+        "headers": file.captured_headers(),
+        "uploader": file.uploader(),
+    });
+
+    ManifestResponder::Success(file, manifest)
+}
+
+#[allow(clippy::large_enum_variant)]
+pub enum ManifestResponder {
+    Success(FileInfo<Created>, Value),
+    Failure(TusError),
+}
+
+impl Sentinel for ManifestResponder {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        super::abort_launch(rocket)
+    }
+}
+
+impl<'r> Responder<'r, 'static> for ManifestResponder {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let meteoritus = MeteoritusGuard::of(req)?;
+
+        let mut res = rocket::Response::build();
+
+        res.header(meteoritus.get_protocol_resumable_version());
+
+        match self {
+            Self::Success(file, manifest) => {
+                let body = manifest.to_string();
+
+                res.status(Status::Ok)
+                    .header(ContentType::JSON)
+                    .sized_body(body.len(), Cursor::new(body));
+
+                meteoritus.decorate_response(Some(&file), &mut res)
+            }
+            Self::Failure(error) => return error.respond_to(req),
+        };
+
+        res.ok()
+    }
+}
@@ -1,17 +1,62 @@
+mod authorization;
+mod batch;
+#[cfg(feature = "checksum")]
+mod checksum;
+mod compatibility;
+mod concat;
 mod creation;
+mod error;
+mod error_code;
 mod file_info;
+mod guard;
 mod info;
+mod manifest;
+#[cfg(feature = "checksum")]
+mod repair;
 mod termination;
 mod upload;
 
-pub use creation::creation_handler;
+pub use authorization::DisclosurePolicy;
+pub use batch::batch_creation_handler;
+#[cfg(feature = "checksum")]
+pub use checksum::checksum_handler;
+pub use compatibility::compatibility_handler;
+pub use concat::concat_progress_handler;
+pub use creation::{creation_handler, ResponseHeaders};
+pub(crate) use error::tus_catcher;
+pub use error::TusError;
+pub use error_code::ErrorCode;
 pub use file_info::file_info_handler;
-pub use info::info_handler;
-use rocket::{Orbit, Rocket};
+pub(crate) use guard::MeteoritusGuard;
+pub use info::{info_handler, upload_info_handler};
+pub use manifest::manifest_handler;
+#[cfg(feature = "checksum")]
+pub use repair::repair_handler;
+use rocket::{
+    http::{
+        ext::IntoOwned,
+        uri::{Origin, Reference},
+        Status,
+    },
+    time::OffsetDateTime,
+    Ignite, Orbit, Rocket,
+};
 pub use termination::termination_handler;
 pub use upload::upload_handler;
 
-use crate::fs::FileInfo;
+use std::{sync::Arc, time::SystemTime};
+
+use crate::{
+    fs::{Built, Completed, Extensions, FileInfo},
+    sweeper::UploadDeadline,
+    Meteoritus, UploadTimeline, Vault,
+};
+
+#[cfg(feature = "expiration")]
+use rocket::response;
+
+#[cfg(feature = "expiration")]
+use crate::fs::Created;
 
 /// Represents the context of a file upload handler.
 ///
@@ -22,3 +67,209 @@ pub struct HandlerContext<'a, S> {
     pub rocket: &'a Rocket<Orbit>,
     pub file_info: &'a FileInfo<S>,
 }
+
+impl<S> HandlerContext<'_, S> {
+    /// Returns the [`Extensions`] attached to the upload, see [`FileInfo::extensions()`].
+    pub fn extensions(&self) -> &Extensions {
+        self.file_info.extensions()
+    }
+
+    /// Returns the timestamps of the upload lifecycle observed so far, like its
+    /// creation and the chunks stored, once the upload was created.
+    pub fn timeline(&self) -> Option<UploadTimeline> {
+        self.file_info.extensions().get_local::<UploadTimeline>()
+    }
+
+    /// Returns the location of the upload, as resolved by the configured
+    /// [`LocationResolver`](crate::LocationResolver).
+    ///
+    /// Returns `None` when the location can't be built, like for an invalid
+    /// `base_route`.
+    pub fn location(&self) -> Option<String> {
+        let meteoritus = self.rocket.state::<Meteoritus<Orbit>>()?;
+
+        meteoritus
+            .upload_location(self.file_info.id(), self.file_info.metadata())
+            .ok()
+    }
+}
+
+impl<S: Clone> HandlerContext<'_, S> {
+    /// Detaches the context from the Rocket instance, cloning the [`FileInfo`] and
+    /// resolving the upload location, so callbacks can move it into spawned tasks.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::{tokio, Ignite};
+    /// use meteoritus::Meteoritus;
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///         .on_completed(|ctx| {
+    ///             let ctx = ctx.into_owned();
+    ///
+    ///             tokio::spawn(async move {
+    ///                 // Long running work, like transcoding the upload
+    ///                 println!("Processing upload at: {:?}", ctx.location());
+    ///             });
+    ///         })
+    ///         .build();
+    ///
+    ///     rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn into_owned(self) -> OwnedHandlerContext<S> {
+        OwnedHandlerContext {
+            location: self.location(),
+            file_info: self.file_info.clone(),
+        }
+    }
+}
+
+impl HandlerContext<'_, Built> {
+    /// Sets a hard `deadline` for the whole upload, from the `on_creation` callback.
+    ///
+    /// `PATCH` requests received after the deadline are rejected with `410 Gone`,
+    /// and the background sweeper terminates the upload once past it, so slow-drip
+    /// uploads can't hold storage indefinitely. The deadline is persisted with the
+    /// upload, truncated to the second.
+    pub fn set_deadline(&self, deadline: SystemTime) {
+        /* Serializing the UNIX seconds can't fail */
+        let _ = self.extensions().insert(UploadDeadline::new(deadline));
+    }
+}
+
+impl HandlerContext<'_, Completed> {
+    /// Returns the content type detected from the upload magic bytes, when
+    /// [`Meteoritus::with_mime_sniffing()`](crate::Meteoritus::with_mime_sniffing)
+    /// is enabled and the type could be detected.
+    pub fn sniffed_type(&self) -> Option<&str> {
+        self.file_info.sniffed_type()
+    }
+}
+
+/// A [`HandlerContext`] owning its data, independent from the Rocket instance, as
+/// returned by [`HandlerContext::into_owned()`].
+#[derive(Debug, Clone)]
+pub struct OwnedHandlerContext<S> {
+    pub file_info: FileInfo<S>,
+    location: Option<String>,
+}
+
+impl<S> OwnedHandlerContext<S> {
+    /// Returns the [`Extensions`] attached to the upload, see [`FileInfo::extensions()`].
+    ///
+    /// In-memory values are shared with the originating [`HandlerContext`].
+    pub fn extensions(&self) -> &Extensions {
+        self.file_info.extensions()
+    }
+
+    /// Returns the timestamps of the upload lifecycle observed until the context
+    /// was detached.
+    pub fn timeline(&self) -> Option<UploadTimeline> {
+        self.file_info.extensions().get_local::<UploadTimeline>()
+    }
+
+    /// Returns the location of the upload, as resolved when the context was
+    /// detached.
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+}
+
+impl OwnedHandlerContext<Completed> {
+    /// Returns the content type detected from the upload magic bytes, see
+    /// [`HandlerContext::sniffed_type()`].
+    pub fn sniffed_type(&self) -> Option<&str> {
+        self.file_info.sniffed_type()
+    }
+}
+
+/// Checks, as a [`Sentinel`](rocket::Sentinel), that the tus handlers can respond
+/// before launching, returning `true` to abort the launch otherwise.
+///
+/// Issues of the configuration which don't prevent responding, like shadowed
+/// routes, are only reported as warnings.
+pub(crate) fn abort_launch(rocket: &Rocket<Ignite>) -> bool {
+    let Some(meteoritus) = rocket.state::<Meteoritus<Orbit>>() else {
+        error_!("Meteoritus routes are mounted without its fairing attached");
+        return true;
+    };
+
+    if rocket.state::<Arc<dyn Vault>>().is_none() {
+        error_!("Meteoritus vault isn't managed by Rocket");
+        return true;
+    }
+
+    meteoritus.check_launch(rocket);
+
+    false
+}
+
+/// Builds the URI of an upload, the `base_route` followed by its id, as routed to
+/// the [`upload_handler`].
+pub(crate) fn upload_origin(
+    base_route: &str,
+    file_id: &str,
+) -> Option<Origin<'static>> {
+    let base_uri = Origin::parse(base_route).ok()?;
+
+    Some(uri!(base_uri, upload::upload_handler(id = file_id)).into_owned())
+}
+
+/// Builds the default location of an upload, the `base_route` followed by its id.
+pub(crate) fn default_location(
+    base_route: &str,
+    file_id: &str,
+) -> Result<String, TusError> {
+    let Some(origin) = upload_origin(base_route, file_id) else {
+        return Err(TusError::new(
+            Status::InternalServerError,
+            ErrorCode::InvalidBaseRoute,
+            "Unable to build the upload location",
+        ));
+    };
+
+    let location: Reference = origin.into();
+
+    Ok(location.to_string())
+}
+
+/// Formats the given `time` as an [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-5.6.7) HTTP date.
+pub(crate) fn http_date(time: SystemTime) -> String {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct",
+        "Nov", "Dec",
+    ];
+
+    let time = OffsetDateTime::from(time);
+
+    format!(
+        "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+        &time.weekday().to_string()[..3],
+        time.day(),
+        MONTHS[time.month() as usize - 1],
+        time.year(),
+        time.hour(),
+        time.minute(),
+        time.second(),
+    )
+}
+
+/// Adds the `Upload-Expires` header of the tus `expiration` extension to the
+/// response of an upload in progress having a deadline.
+#[cfg(feature = "expiration")]
+pub(crate) fn upload_expires(
+    file: &FileInfo<Created>,
+    res: &mut response::Builder<'static>,
+) {
+    let completed =
+        !file.is_length_deferred() && file.offset() == file.length();
+
+    if let Some(deadline) = file.deadline().filter(|_| !completed) {
+        res.raw_header("Upload-Expires", http_date(deadline));
+    }
+}
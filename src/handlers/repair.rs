@@ -0,0 +1,226 @@
+use std::{io::ErrorKind, sync::Arc};
+
+use rocket::{
+    http::Status,
+    outcome::try_outcome,
+    request::{self, FromRequest},
+    response::{self, Responder},
+    Data, Ignite, Request, Rocket, Sentinel, State,
+};
+
+use crate::{
+    checksum::{ChecksumError, UploadChecksum},
+    fs::{Created, FileInfo},
+    ErrorCode, TusError, Vault,
+};
+
+use super::{authorization::Authorized, MeteoritusGuard};
+
+/// Overwrites an earlier range of an upload, starting at the `Upload-Offset`, with
+/// the request body verified against its mandatory `Upload-Checksum`, see
+/// [`Meteoritus::with_range_repair()`](crate::Meteoritus::with_range_repair).
+#[patch("/<id>/repair", data = "<data>")]
+pub async fn repair_handler(
+    id: &str,
+    req: RepairRequest,
+    _authorized: Authorized,
+    meteoritus: MeteoritusGuard<'_>,
+    vault: &State<Arc<dyn Vault>>,
+    data: Data<'_>,
+) -> RepairResponder {
+    if !meteoritus.range_repair() {
+        return RepairResponder::Failure(TusError::new(
+            Status::NotFound,
+            ErrorCode::RangeRepairDisabled,
+            "Range repair is not enabled",
+        ));
+    }
+
+    /* Open inspection routes are fine, but repairs must never be */
+    if !meteoritus.has_authorizer() {
+        return RepairResponder::Failure(TusError::new(
+            Status::Forbidden,
+            ErrorCode::Unauthorized,
+            "Range repair requires an authorizer",
+        ));
+    }
+
+    let file = match vault.get_file(id) {
+        Ok(file) => file,
+        Err(e) => return RepairResponder::Failure(TusError::from_vault(&e)),
+    };
+
+    let body = match data.open(meteoritus.max_chunk_size()).into_bytes().await {
+        Ok(body) if body.is_complete() => body.into_inner(),
+        Ok(_) => {
+            return RepairResponder::Failure(TusError::new(
+                Status::PayloadTooLarge,
+                ErrorCode::ChunkTooLarge,
+                "Request body exceeds the maximum chunk size",
+            ))
+        }
+        Err(_) => {
+            return RepairResponder::Failure(TusError::new(
+                Status::UnprocessableEntity,
+                ErrorCode::InvalidBody,
+                "Unable to read the request body",
+            ))
+        }
+    };
+
+    if body.is_empty() {
+        return RepairResponder::Failure(TusError::new(
+            Status::BadRequest,
+            ErrorCode::InvalidBody,
+            "Repair range must not be empty",
+        ));
+    }
+
+    /* Only the stored bytes can be repaired */
+    let end = req.offset.checked_add(body.len() as u64);
+
+    if end.is_none_or(|end| end > *file.offset()) {
+        return RepairResponder::Failure(TusError::new(
+            Status::RangeNotSatisfiable,
+            ErrorCode::RepairRangeNotStored,
+            "Repair range exceeds the current upload offset",
+        ));
+    }
+
+    if !req.checksum.verify(&body) {
+        return RepairResponder::Failure(TusError::new(
+            Status::new(460),
+            ErrorCode::ChecksumMismatch,
+            "Upload-Checksum does not match the request body",
+        ));
+    }
+
+    match vault.repair_range(id, &body, req.offset) {
+        Ok(()) => {
+            info_!(
+                "Repaired {} bytes at offset {} of upload: {}",
+                body.len(),
+                req.offset,
+                id
+            );
+
+            RepairResponder::Success(file)
+        }
+        Err(e) if e.io_error_kind() == Some(ErrorKind::Unsupported) => {
+            RepairResponder::Failure(TusError::new(
+                Status::NotImplemented,
+                ErrorCode::RangeRepairUnsupported,
+                "The storage does not support range repairs",
+            ))
+        }
+        Err(e) => RepairResponder::Failure(TusError::from_vault(&e)),
+    }
+}
+
+/// The headers of a range repair request.
+pub struct RepairRequest {
+    offset: u64,
+    checksum: UploadChecksum,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RepairRequest {
+    type Error = TusError;
+
+    async fn from_request(
+        req: &'r Request<'_>,
+    ) -> request::Outcome<Self, Self::Error> {
+        let meteoritus = try_outcome!(req.guard::<MeteoritusGuard<'r>>().await);
+
+        let offset = match req.headers().get_one("Upload-Offset") {
+            Some(value) => match value.parse::<u64>() {
+                Ok(value) => value,
+                Err(_) => {
+                    return TusError::new(
+                        Status::BadRequest,
+                        ErrorCode::InvalidUploadOffset,
+                        "Invalid Upload-Offset header",
+                    )
+                    .fail(req)
+                }
+            },
+            None => {
+                return TusError::new(
+                    Status::BadRequest,
+                    ErrorCode::MissingUploadOffset,
+                    "Missing Upload-Offset header",
+                )
+                .fail(req)
+            }
+        };
+
+        let checksum = match req.headers().get_one("Upload-Checksum") {
+            Some(value) => match UploadChecksum::parse(
+                value,
+                meteoritus.checksum_algorithms(),
+            ) {
+                Ok(checksum) => checksum,
+                Err(ChecksumError::UnsupportedAlgorithm) => {
+                    return TusError::new(
+                        Status::BadRequest,
+                        ErrorCode::UnsupportedChecksumAlgorithm,
+                        "Unsupported Upload-Checksum algorithm",
+                    )
+                    .fail(req)
+                }
+                Err(ChecksumError::Malformed) => {
+                    return TusError::new(
+                        Status::BadRequest,
+                        ErrorCode::InvalidUploadChecksum,
+                        "Invalid Upload-Checksum header",
+                    )
+                    .fail(req)
+                }
+            },
+            None => {
+                return TusError::new(
+                    Status::BadRequest,
+                    ErrorCode::MissingUploadChecksum,
+                    "Range repairs require an Upload-Checksum header",
+                )
+                .fail(req)
+            }
+        };
+
+        request::Outcome::Success(RepairRequest { offset, checksum })
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+pub enum RepairResponder {
+    Success(FileInfo<Created>),
+    Failure(TusError),
+}
+
+impl Sentinel for RepairResponder {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        super::abort_launch(rocket)
+    }
+}
+
+impl<'r> Responder<'r, 'static> for RepairResponder {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let meteoritus = MeteoritusGuard::of(req)?;
+
+        let mut res = rocket::Response::build();
+
+        res.header(meteoritus.get_protocol_resumable_version());
+
+        match self {
+            Self::Success(file) => {
+                res.status(Status::NoContent)
+                    .raw_header("Upload-Offset", file.offset().to_string());
+
+                meteoritus.decorate_response(Some(&file), &mut res)
+            }
+            Self::Failure(error) => return error.respond_to(req),
+        };
+
+        res.ok()
+    }
+}
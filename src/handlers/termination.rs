@@ -5,21 +5,49 @@ use rocket::{
     outcome::Outcome,
     request::{self, FromRequest},
     response::{self, Responder},
-    Orbit, Request, Rocket, State,
+    Ignite, Orbit, Request, Rocket, Sentinel, State,
 };
 
-use crate::{HandlerContext, Meteoritus, Vault};
+use crate::{ErrorCode, HandlerContext, TusError, Vault, VaultError};
+
+#[cfg(feature = "grpc-hooks")]
+use crate::hooks::HookHttpRequest;
+
+use super::{authorization::Owned, MeteoritusGuard};
 
 #[delete("/<id>")]
 pub fn termination_handler(
     id: &str,
+    owned: Owned,
     req: TerminationRequest,
     vault: &State<Arc<dyn Vault>>,
-    meteoritus: &State<Meteoritus<Orbit>>,
+    meteoritus: MeteoritusGuard<'_>,
 ) -> TerminationResponder {
+    /* Quarantined uploads are kept for inspection */
+    if vault.get_file(id).is_ok_and(|file| file.is_quarantined()) {
+        return TerminationResponder::Failure(owned.disclose(
+            meteoritus.disclosure_policy(),
+            TusError::new(
+                meteoritus.quarantine_status(),
+                ErrorCode::UploadQuarantined,
+                "Upload is quarantined",
+            ),
+        ));
+    }
+
     match vault.terminate_file(id) {
-        Err(_) => TerminationResponder::Failure,
+        Err(VaultError::NotFound) => {
+            TerminationResponder::Failure(meteoritus.disclosure_policy().gone())
+        }
+        Err(e) => TerminationResponder::Failure(TusError::from_vault(&e)),
         Ok(file) => {
+            meteoritus.bury_terminated(id);
+            meteoritus.report_termination(
+                &file,
+                #[cfg(feature = "grpc-hooks")]
+                req.http,
+            );
+
             if let Some(callback) = &meteoritus.on_termination() {
                 callback(HandlerContext {
                     rocket: req.rocket,
@@ -35,6 +63,8 @@ pub fn termination_handler(
 #[derive(Debug)]
 pub struct TerminationRequest<'r> {
     rocket: &'r Rocket<Orbit>,
+    #[cfg(feature = "grpc-hooks")]
+    http: HookHttpRequest,
 }
 
 #[rocket::async_trait]
@@ -46,18 +76,26 @@ impl<'r> FromRequest<'r> for TerminationRequest<'r> {
     ) -> request::Outcome<Self, Self::Error> {
         Outcome::Success(TerminationRequest {
             rocket: req.rocket(),
+            #[cfg(feature = "grpc-hooks")]
+            http: HookHttpRequest::from(req),
         })
     }
 }
 
 pub enum TerminationResponder {
     Success,
-    Failure,
+    Failure(TusError),
+}
+
+impl Sentinel for TerminationResponder {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        super::abort_launch(rocket)
+    }
 }
 
 impl<'r> Responder<'r, 'static> for TerminationResponder {
     fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
-        let meteoritus = req.rocket().state::<Meteoritus<Orbit>>().unwrap();
+        let meteoritus = MeteoritusGuard::of(req)?;
 
         let mut res = rocket::Response::build();
 
@@ -65,9 +103,11 @@ impl<'r> Responder<'r, 'static> for TerminationResponder {
 
         match self {
             Self::Success => res.status(Status::NoContent),
-            Self::Failure => res.status(Status::Gone),
+            Self::Failure(error) => return error.respond_to(req),
         };
 
+        meteoritus.decorate_response(None, &mut res);
+
         res.ok()
     }
 }
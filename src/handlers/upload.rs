@@ -1,71 +1,760 @@
-use std::sync::Arc;
+use std::{
+    fmt,
+    io::ErrorKind,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use rocket::{
+    data::ByteUnit,
     http::{ContentType, Status},
     request::{self, FromRequest, Outcome},
     response::{self, Responder},
-    Data, Orbit, Request, Rocket, State,
+    tokio::{self, io::AsyncReadExt},
+    Data, Ignite, Orbit, Request, Rocket, Sentinel, State,
+};
+
+use crate::{
+    concat::PartCompletion,
+    fs::{Created, FileInfo, PatchOption},
+    telemetry::RECEIVED_BYTES_TOTAL,
+    token::{check_token, generate_token, TOKEN_HEADER},
+    tombstone::Tombstone,
+    Meteoritus, UploadEventKind, Vault, VaultError,
 };
 
-use crate::{fs::PatchOption, Meteoritus, Vault};
+#[cfg(feature = "checksum")]
+use crate::checksum::{ChecksumError, UploadChecksum};
+#[cfg(feature = "grpc-hooks")]
+use crate::hooks::{HookHttpRequest, HookType};
+#[cfg(feature = "mime-sniffing")]
+use crate::sniffing::{is_mismatch, sniff, MimeMismatchPolicy};
 
-use super::HandlerContext;
+#[cfg(feature = "expiration")]
+use super::upload_expires;
+use super::{
+    authorization::{DisclosurePolicy, Owned},
+    ErrorCode, HandlerContext, MeteoritusGuard, TusError,
+};
 
 #[patch("/<id>", data = "<data>")]
 pub async fn upload_handler(
     req: UploadRequest<'_>,
     id: &str,
-    meteoritus: &State<Meteoritus<Orbit>>,
+    owned: Owned,
+    meteoritus: MeteoritusGuard<'_>,
     data: Data<'_>,
     vault: &State<Arc<dyn Vault>>,
 ) -> UploadResponder {
-    if !vault.exists(id) {
-        return UploadResponder::Failure(Status::NotFound);
+    write_chunk(req, id, owned, &meteoritus, data, vault.inner()).await
+}
+
+/// Writes the chunk of the request body into the upload `id`, at the offset of
+/// the request.
+///
+/// Also writes the initial chunk of creation requests, see the
+/// `creation-with-upload` extension.
+pub(crate) async fn write_chunk(
+    req: UploadRequest<'_>,
+    id: &str,
+    owned: Owned,
+    meteoritus: &Meteoritus<Orbit>,
+    data: Data<'_>,
+    vault: &Arc<dyn Vault>,
+) -> UploadResponder {
+    let started = Instant::now();
+
+    let policy = meteoritus.disclosure_policy();
+
+    let mut file = match vault.get_file(id) {
+        Ok(file) => file,
+        /* Answering recently removed uploads from their tombstones */
+        Err(VaultError::NotFound) if !owned.conceals(policy) => {
+            return match meteoritus.tombstone(id) {
+                Some(Tombstone::Completed(length)) if length == req.offset => {
+                    UploadResponder::Completed(length)
+                }
+                Some(Tombstone::Completed(length)) => {
+                    UploadResponder::Conflict(
+                        TusError::new(
+                            Status::Conflict,
+                            ErrorCode::UploadCompleted,
+                            "Upload is already completed",
+                        ),
+                        length,
+                    )
+                }
+                Some(Tombstone::Terminated) => {
+                    UploadResponder::Failure(policy.gone())
+                }
+                None => UploadResponder::Failure(TusError::from_vault(
+                    &VaultError::NotFound,
+                )),
+            }
+        }
+        Err(e) => return UploadResponder::Failure(TusError::from_vault(&e)),
+    };
+
+    if file.is_quarantined() {
+        return UploadResponder::Failure(owned.disclose(
+            policy,
+            TusError::new(
+                meteoritus.quarantine_status(),
+                ErrorCode::UploadQuarantined,
+                "Upload is quarantined",
+            ),
+        ));
+    }
+
+    if let Err(error) = check_token(&file, req.token.as_deref()) {
+        return UploadResponder::Failure(error);
+    }
+
+    let is_completed =
+        !file.is_length_deferred() && file.offset() == file.length();
+
+    if !is_completed && meteoritus.is_past_deadline(&file) {
+        meteoritus.expire_upload(id);
+
+        return UploadResponder::Failure(owned.disclose(
+            policy,
+            TusError::new(
+                Status::Gone,
+                ErrorCode::UploadDeadlineExceeded,
+                "Upload deadline has passed",
+            ),
+        ));
+    }
+
+    if meteoritus.is_paused(id) {
+        if owned.conceals(policy) {
+            return UploadResponder::Failure(DisclosurePolicy::not_found());
+        }
+
+        return UploadResponder::Paused(meteoritus.pause_retry_after());
+    }
+
+    #[cfg(feature = "remote-fetch")]
+    if meteoritus
+        .remote_fetch()
+        .is_some_and(|fetch| fetch.is_fetching(id))
+    {
+        return UploadResponder::Failure(TusError::new(
+            Status::Locked,
+            ErrorCode::UploadFetching,
+            "Upload content is being fetched by the server",
+        ));
+    }
+
+    let mut length_declared = false;
+
+    /* Fixing the length of deferred uploads, once known by the client */
+    if let Some(length) = req.upload_length {
+        if !file.is_length_deferred()
+            && length > *file.length()
+            && meteoritus.length_increase()
+        {
+            /* Enlarging the length of uploads not completed yet, if allowed */
+            if file.offset() == file.length() {
+                return UploadResponder::Conflict(
+                    TusError::new(
+                        Status::Conflict,
+                        ErrorCode::UploadCompleted,
+                        "Upload is already completed",
+                    ),
+                    *file.length(),
+                );
+            }
+
+            if length > meteoritus.max_size().as_u64() {
+                return UploadResponder::Failure(TusError::new(
+                    Status::PayloadTooLarge,
+                    ErrorCode::UploadLengthExceeded,
+                    "Upload-Length exceeds the Tus-Max-Size",
+                ));
+            }
+
+            file = match vault.increase_length(id, length) {
+                Ok(file) => file,
+                Err(e) if e.io_error_kind() == Some(ErrorKind::Unsupported) => {
+                    return UploadResponder::Failure(TusError::new(
+                        Status::NotImplemented,
+                        ErrorCode::LengthIncreaseUnsupported,
+                        "The storage does not support length increases",
+                    ))
+                }
+                Err(e) => {
+                    return UploadResponder::Failure(TusError::from_vault(&e))
+                }
+            };
+
+            info_!("Increased length of upload {} to {}", id, length);
+
+            length_declared = true;
+        } else if !file.is_length_deferred() {
+            if length != *file.length() {
+                return UploadResponder::Failure(TusError::new(
+                    Status::BadRequest,
+                    ErrorCode::UploadLengthMismatch,
+                    "Upload-Length does not match the upload length",
+                ));
+            }
+        } else if length > meteoritus.max_size().as_u64() {
+            return UploadResponder::Failure(TusError::new(
+                Status::PayloadTooLarge,
+                ErrorCode::UploadLengthExceeded,
+                "Upload-Length exceeds the Tus-Max-Size",
+            ));
+        } else if length < *file.offset() {
+            return UploadResponder::Failure(TusError::new(
+                Status::BadRequest,
+                ErrorCode::InvalidUploadLength,
+                "Upload-Length is smaller than the current upload offset",
+            ));
+        } else {
+            file = match vault.declare_length(id, length) {
+                Ok(file) => file,
+                Err(e) => {
+                    return UploadResponder::Failure(TusError::from_vault(&e))
+                }
+            };
+
+            length_declared = true;
+        }
+    }
+
+    /* Replaying the final offset for clients retrying after a lost response */
+    if !length_declared
+        && !file.is_length_deferred()
+        && file.offset() == file.length()
+    {
+        if req.offset == *file.length() {
+            return UploadResponder::Success(file);
+        }
+
+        return UploadResponder::Conflict(
+            TusError::new(
+                Status::Conflict,
+                ErrorCode::UploadCompleted,
+                "Upload is already completed",
+            ),
+            *file.length(),
+        );
+    }
+
+    /* Writing ranges once some were received past the offset, so they're merged */
+    let ranged = meteoritus.parallel_chunks()
+        && !file.is_length_deferred()
+        && (req.parallel || !file.received_ranges().is_empty());
+
+    if ranged && (req.offset < *file.offset() || req.offset > *file.length()) {
+        return UploadResponder::Conflict(
+            TusError::new(
+                Status::Conflict,
+                ErrorCode::OffsetMismatch,
+                "Upload-Offset is outside the missing bytes of the upload",
+            ),
+            *file.offset(),
+        );
     }
 
-    let Ok(mut data) = data.open(meteoritus.max_size()).into_bytes().await
-    else {
-        return UploadResponder::Failure(Status::UnprocessableEntity);
+    if !ranged && req.offset != *file.offset() {
+        return UploadResponder::Conflict(
+            TusError::new(
+                Status::Conflict,
+                ErrorCode::OffsetMismatch,
+                "Upload-Offset does not match the current upload offset",
+            ),
+            *file.offset(),
+        );
+    }
+
+    let remaining = match file.is_length_deferred() {
+        true => meteoritus
+            .max_size()
+            .as_u64()
+            .saturating_sub(*file.offset()),
+        false => file.length() - req.offset,
     };
 
-    let Ok(result) = vault.patch_file(id, &mut data, req.offset) else {
-        return UploadResponder::Failure(Status::UnprocessableEntity);
+    if req.content_length.is_some_and(|length| length > remaining) {
+        return UploadResponder::Failure(TusError::new(
+            Status::PayloadTooLarge,
+            ErrorCode::ChunkExceedsUploadLength,
+            "Content-Length exceeds the remaining Upload-Length",
+        ));
+    }
+
+    let max_chunk_size = meteoritus.max_chunk_size();
+
+    if req
+        .content_length
+        .is_some_and(|length| length > max_chunk_size)
+    {
+        return UploadResponder::Failure(TusError::new(
+            Status::PayloadTooLarge,
+            ErrorCode::ChunkTooLarge,
+            "Content-Length exceeds the maximum chunk size",
+        ));
+    }
+
+    /* Reading one extra byte to detect bodies overflowing the limits */
+    let mut limit =
+        (max_chunk_size + 1).min(ByteUnit::from(remaining.saturating_add(1)));
+
+    if let Some(length) = req.content_length {
+        limit = limit.min(ByteUnit::from(length.saturating_add(1)));
+    }
+
+    /* Keeping the bytes received before an interruption, so resuming the upload
+    only re-sends the missing tail */
+    let reading = Instant::now();
+    let (body, interruption) = read_body(
+        data,
+        limit,
+        meteoritus.read_timeout(),
+        meteoritus.min_transfer_rate(),
+    )
+    .await;
+    req.timing.record("read", reading.elapsed());
+
+    let interrupted = interruption.is_some();
+
+    if let Some(interruption) = interruption {
+        warn_!(
+            "{} after sending {} bytes of upload: {}",
+            interruption,
+            body.len(),
+            id
+        );
+    }
+
+    if interrupted && body.is_empty() {
+        return UploadResponder::Failure(TusError::new(
+            Status::UnprocessableEntity,
+            ErrorCode::InvalidBody,
+            "Unable to read the request body",
+        ));
+    }
+
+    let mut data = body;
+
+    /* Rejecting overlong bodies rather than storing their truncated prefix, which
+    would hide client bugs */
+    if req
+        .content_length
+        .is_some_and(|length| data.len() as u64 > length)
+    {
+        return UploadResponder::Failure(TusError::new(
+            Status::BadRequest,
+            ErrorCode::OverlongBody,
+            "Request body exceeds its Content-Length",
+        ));
+    }
+
+    if data.len() as u64 > max_chunk_size {
+        return UploadResponder::Failure(TusError::new(
+            Status::PayloadTooLarge,
+            ErrorCode::ChunkTooLarge,
+            "Request body exceeds the maximum chunk size",
+        ));
+    }
+
+    if data.len() as u64 > remaining {
+        return UploadResponder::Failure(TusError::new(
+            Status::BadRequest,
+            ErrorCode::OverlongBody,
+            "Request body exceeds the remaining Upload-Length",
+        ));
+    }
+
+    /* Verifying the whole chunk before storing any of its bytes */
+    #[cfg(feature = "checksum")]
+    if let Some(checksum) = &req.checksum {
+        /* Partial chunks of interrupted requests can't be verified */
+        if interrupted {
+            return UploadResponder::Failure(TusError::new(
+                Status::UnprocessableEntity,
+                ErrorCode::InvalidBody,
+                "Unable to read the request body",
+            ));
+        }
+
+        let verifying = Instant::now();
+        let verified = checksum.verify(&data);
+        req.timing.record("checksum", verifying.elapsed());
+
+        if !verified {
+            return UploadResponder::Failure(TusError::new(
+                Status::new(460),
+                ErrorCode::ChecksumMismatch,
+                "Upload-Checksum does not match the request body",
+            ));
+        }
+    }
+
+    let chunk_size = data.len() as u64;
+    let previous = *file.offset();
+
+    let writing = Instant::now();
+    let result = match ranged {
+        true => vault.write_range(id, &data, req.offset),
+        false => vault.patch_file(id, &mut data, req.offset),
+    };
+    let written = writing.elapsed();
+
+    let operation = if ranged { "write_range" } else { "patch_file" };
+
+    req.timing.record("vault", written);
+    meteoritus.check_storage_latency(operation, id, chunk_size, written);
+
+    let result = match result {
+        Ok(result) => result,
+        Err(e)
+            if ranged && e.io_error_kind() == Some(ErrorKind::Unsupported) =>
+        {
+            return UploadResponder::Failure(TusError::new(
+                Status::NotImplemented,
+                ErrorCode::ParallelChunksUnsupported,
+                "The storage does not support parallel chunks",
+            ))
+        }
+        Err(e) => return UploadResponder::PatchFailure(e),
     };
 
-    let final_offset = match result {
-        PatchOption::Patched(offset) => offset,
-        PatchOption::Completed(file) => {
-            if let Some(callback) = &meteoritus.on_completed() {
-                callback(HandlerContext {
-                    rocket: req.rocket,
-                    file_info: &file,
-                });
+    let offset = match &result {
+        PatchOption::Patched(offset) => *offset,
+        PatchOption::Completed(completed) => *completed.length(),
+    };
+
+    /* Ranges past the offset are stored without moving it */
+    let received = match ranged {
+        true => chunk_size,
+        false => offset.saturating_sub(req.offset),
+    };
+
+    meteoritus
+        .telemetry()
+        .counter(RECEIVED_BYTES_TOTAL, received, &[]);
+
+    /* Mirroring the range recorded by the vault, which fits the upload length */
+    if ranged {
+        let _ = file.receive_range(req.offset..req.offset + chunk_size);
+    }
+
+    if file.set_offset(offset).is_err() {
+        return UploadResponder::Failure(TusError::new(
+            Status::InternalServerError,
+            ErrorCode::InvalidOffset,
+            "Vault reported an offset beyond the Upload-Length",
+        ));
+    }
+
+    let mut stats = file.stats().clone();
+    stats.record(received, started.elapsed(), interrupted);
+
+    /* Statistics are informative, so failing to store them is not fatal */
+    match vault.update_stats(id, &stats) {
+        Err(e) if e.io_error_kind() != Some(ErrorKind::Unsupported) => {
+            warn_!("Unable to store upload statistics: {}", e)
+        }
+        _ => {}
+    }
+
+    file.set_stats(stats);
+
+    if let Some(callback) = meteoritus.on_upload_started(previous, offset) {
+        callback(HandlerContext {
+            rocket: req.rocket,
+            file_info: &file,
+        });
+    }
+
+    if let Some(callback) = meteoritus.on_checkpoint(previous, offset) {
+        callback(HandlerContext {
+            rocket: req.rocket,
+            file_info: &file,
+        });
+    }
+
+    match result {
+        PatchOption::Patched(offset) => {
+            /* Keeping the previous token valid when the rotated one can't be stored,
+            or can't be received by a disconnected client */
+            if file.upload_token().is_some() && !interrupted {
+                let token = generate_token();
+
+                match vault.update_upload_token(id, &token) {
+                    Ok(()) => file.set_upload_token(token),
+                    Err(e) => warn_!("Unable to rotate upload token: {}", e),
+                }
+            }
+
+            meteoritus.touch_upload(id);
+            meteoritus.publish_event(UploadEventKind::Progress, &file, offset);
+
+            #[cfg(feature = "grpc-hooks")]
+            if let Some(hooks) = meteoritus.grpc_hooks() {
+                hooks.notify(HookType::PostReceive, &file, offset, req.http);
+            }
+        }
+        PatchOption::Completed(mut completed) => {
+            meteoritus.untrack_upload(id);
+
+            completed.set_stats(file.stats().clone());
+
+            #[cfg(feature = "mime-sniffing")]
+            let completed = match meteoritus.mime_sniffing() {
+                Some(policy) => {
+                    let completed = match sniff(vault.as_ref(), id) {
+                        Some(sniffed) => completed.with_sniffed_type(sniffed),
+                        None => completed,
+                    };
+
+                    match (is_mismatch(&completed), policy) {
+                        (true, MimeMismatchPolicy::Reject) => {
+                            meteoritus.forget_upload(id);
+
+                            if vault.terminate_file(id).is_err() {
+                                warn_!(
+                                    "Unable to terminate rejected upload: {}",
+                                    id
+                                );
+                            }
+
+                            return UploadResponder::Failure(TusError::new(
+                                Status::UnsupportedMediaType,
+                                ErrorCode::FiletypeMismatch,
+                                "Upload content does not match its declared filetype",
+                            ));
+                        }
+                        (true, MimeMismatchPolicy::Quarantine) => {
+                            match meteoritus.quarantine(id) {
+                                Ok(_) => completed.with_quarantine(),
+                                Err(_) => {
+                                    warn_!(
+                                        "Unable to quarantine upload: {}",
+                                        id
+                                    );
+                                    completed
+                                }
+                            }
+                        }
+                        _ => completed,
+                    }
+                }
+                None => completed,
             };
 
-            if meteoritus.auto_terminate() {
-                if let Err(_) = vault.terminate_file(id) {
-                    return UploadResponder::Failure(
-                        Status::InternalServerError,
-                    );
+            meteoritus.bury_completed(id, *completed.length());
+
+            /* Deferring the completion of grouped parts until their group is concatenated */
+            let completed = match meteoritus.concat_groups() {
+                Some(groups) => {
+                    match groups.complete(vault.as_ref(), completed) {
+                        Ok(PartCompletion::Ungrouped(completed)) => {
+                            Some(completed)
+                        }
+                        /* Parts never report their own completion */
+                        Ok(PartCompletion::Concatenated(completed)) => {
+                            meteoritus.forget_upload(id);
+                            Some(completed)
+                        }
+                        Ok(PartCompletion::Pending) => {
+                            meteoritus.forget_upload(id);
+                            None
+                        }
+                        Err(e) => {
+                            error_!(
+                                "Unable to concatenate upload group: {}",
+                                e
+                            );
+
+                            return UploadResponder::Failure(TusError::new(
+                                Status::InternalServerError,
+                                ErrorCode::ConcatenationFailed,
+                                "Unable to concatenate the upload group",
+                            ));
+                        }
+                    }
+                }
+                None => Some(completed),
+            };
+
+            if let Some(completed) = completed {
+                let completed_id = completed.id().to_string();
+
+                meteoritus.await_ack(completed.id());
+                meteoritus.publish_event(
+                    UploadEventKind::Completed,
+                    &completed,
+                    *completed.length(),
+                );
+
+                if let Some(callback) = &meteoritus.on_completed() {
+                    callback(HandlerContext {
+                        rocket: req.rocket,
+                        file_info: &completed,
+                    });
                 };
-            }
 
-            *file.length()
+                meteoritus.complete_group_member(&completed);
+
+                #[cfg(feature = "grpc-hooks")]
+                if let Some(hooks) = meteoritus.grpc_hooks() {
+                    let offset = *completed.length();
+                    hooks.notify(
+                        HookType::PostFinish,
+                        &completed,
+                        offset,
+                        req.http,
+                    );
+                }
+
+                if meteoritus.has_processors() {
+                    meteoritus.process_completed(completed);
+                } else if meteoritus.terminate_completed(&completed_id).is_err()
+                {
+                    return UploadResponder::Failure(TusError::new(
+                        Status::InternalServerError,
+                        ErrorCode::TerminationFailed,
+                        "Unable to terminate the completed upload",
+                    ));
+                }
+            }
         }
     };
 
-    UploadResponder::Success(final_offset)
+    UploadResponder::Success(file)
+}
+
+/// The durations of the server side steps of a `PATCH` request, responded in the
+/// `Server-Timing` header when
+/// [`Meteoritus::with_server_timing()`](crate::Meteoritus::with_server_timing) is
+/// enabled.
+#[derive(Debug, Default)]
+struct ServerTiming {
+    entries: Mutex<Vec<(&'static str, Duration)>>,
+}
+
+impl ServerTiming {
+    fn record(&self, name: &'static str, duration: Duration) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((name, duration));
+    }
+
+    /// Formats the recorded entries as a `Server-Timing` header value, with their
+    /// durations in milliseconds.
+    fn header_value(&self) -> Option<String> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+        if entries.is_empty() {
+            return None;
+        }
+
+        let value = entries
+            .iter()
+            .map(|(name, duration)| {
+                format!("{};dur={:.3}", name, duration.as_secs_f64() * 1000.0)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Some(value)
+    }
+}
+
+/// How long a body is streamed before enforcing the minimum transfer rate.
+const MIN_RATE_GRACE: Duration = Duration::from_secs(10);
+
+/// Why the body of a `PATCH` request was cut short.
+enum Interruption {
+    Disconnected,
+    Stalled,
+}
+
+impl fmt::Display for Interruption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disconnected => write!(f, "Client disconnected"),
+            Self::Stalled => write!(f, "Client stalled"),
+        }
+    }
+}
+
+/// Reads the body of a `PATCH` request up to `limit`, returning the bytes received
+/// along with the interruption cutting it short, if any.
+///
+/// Reads waiting longer than the `read_timeout`, or dropping the average rate below
+/// the `min_rate` per second, stall the body.
+async fn read_body(
+    data: Data<'_>,
+    limit: ByteUnit,
+    read_timeout: Option<Duration>,
+    min_rate: Option<ByteUnit>,
+) -> (Vec<u8>, Option<Interruption>) {
+    let mut stream = data.open(limit);
+    let mut body = Vec::new();
+    let mut buf = vec![0; 64 * 1024];
+
+    let started = Instant::now();
+
+    loop {
+        /* The instant the average rate drops below the minimum, without new bytes */
+        let rate_deadline = min_rate.map(|rate| {
+            let expected = body.len() as f64 / rate.as_u64().max(1) as f64;
+
+            started + MIN_RATE_GRACE.max(Duration::from_secs_f64(expected))
+        });
+
+        let wait = [
+            read_timeout,
+            rate_deadline.map(|deadline| {
+                deadline.saturating_duration_since(Instant::now())
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+
+        let read = match wait {
+            Some(wait) => {
+                match tokio::time::timeout(wait, stream.read(&mut buf)).await {
+                    Ok(read) => read,
+                    Err(_) => return (body, Some(Interruption::Stalled)),
+                }
+            }
+            None => stream.read(&mut buf).await,
+        };
+
+        match read {
+            Ok(0) => return (body, None),
+            Ok(read) => body.extend_from_slice(&buf[..read]),
+            Err(_) => return (body, Some(Interruption::Disconnected)),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct UploadRequest<'r> {
     rocket: &'r Rocket<Orbit>,
     offset: u64,
+    upload_length: Option<u64>,
+    content_length: Option<u64>,
+    token: Option<String>,
+    parallel: bool,
+    timing: &'r ServerTiming,
+    #[cfg(feature = "checksum")]
+    checksum: Option<UploadChecksum>,
+    #[cfg(feature = "grpc-hooks")]
+    http: HookHttpRequest,
 }
 
 #[rocket::async_trait]
 impl<'r> FromRequest<'r> for UploadRequest<'r> {
-    type Error = &'static str;
+    type Error = TusError;
 
     async fn from_request(
         req: &'r Request<'_>,
@@ -74,36 +763,131 @@ impl<'r> FromRequest<'r> for UploadRequest<'r> {
         if tus_resumable_header.is_none()
             || tus_resumable_header.unwrap() != "1.0.0"
         {
-            return Outcome::Error((
+            return TusError::new(
                 Status::BadRequest,
+                ErrorCode::InvalidTusResumable,
                 "Missing or invalid Tus-Resumable header",
-            ));
+            )
+            .fail(req);
         }
 
         let offset = match req.headers().get_one("Upload-Offset") {
             Some(value) => match value.parse::<u64>() {
                 Ok(value) => value,
                 Err(_) => {
-                    return Outcome::Error((
+                    return TusError::new(
                         Status::BadRequest,
+                        ErrorCode::InvalidUploadOffset,
                         "Invalid Upload-Offset header",
-                    ))
+                    )
+                    .fail(req)
                 }
             },
             None => {
-                return Outcome::Error((
+                return TusError::new(
                     Status::BadRequest,
+                    ErrorCode::MissingUploadOffset,
                     "Missing Upload-Offset header",
-                ))
+                )
+                .fail(req)
             }
         };
 
+        let upload_length = match req.headers().get_one("Upload-Length") {
+            Some(value) => match value.parse::<u64>() {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    return TusError::new(
+                        Status::BadRequest,
+                        ErrorCode::InvalidUploadLength,
+                        "Invalid Upload-Length header",
+                    )
+                    .fail(req)
+                }
+            },
+            None => None,
+        };
+
+        Self::parse(req, offset, upload_length).await
+    }
+}
+
+impl<'r> UploadRequest<'r> {
+    /// Parses the initial chunk sent along a creation request, written at offset
+    /// `0` as defined by the `creation-with-upload` extension.
+    pub(crate) async fn initial(
+        req: &'r Request<'_>,
+    ) -> request::Outcome<Self, TusError> {
+        Self::parse(req, 0, None).await
+    }
+
+    /// Sets the upload token the chunk is sent with.
+    pub(crate) fn with_token(mut self, token: Option<&str>) -> Self {
+        self.token = token.map(str::to_string);
+        self
+    }
+
+    /// Parses the chunk headers of the request, once its offset is known.
+    async fn parse(
+        req: &'r Request<'_>,
+        offset: u64,
+        upload_length: Option<u64>,
+    ) -> request::Outcome<Self, TusError> {
+        let content_length = match req.headers().get_one("Content-Length") {
+            Some(value) => match value.parse::<u64>() {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    return TusError::new(
+                        Status::BadRequest,
+                        ErrorCode::InvalidContentLength,
+                        "Invalid Content-Length header",
+                    )
+                    .fail(req)
+                }
+            },
+            None => None,
+        };
+
+        #[cfg(feature = "checksum")]
+        let meteoritus = rocket::outcome::try_outcome!(
+            req.guard::<MeteoritusGuard<'r>>().await
+        );
+
+        #[cfg(feature = "checksum")]
+        let checksum = match req.headers().get_one("Upload-Checksum") {
+            Some(value) => match UploadChecksum::parse(
+                value,
+                meteoritus.checksum_algorithms(),
+            ) {
+                Ok(checksum) => Some(checksum),
+                Err(ChecksumError::UnsupportedAlgorithm) => {
+                    return TusError::new(
+                        Status::BadRequest,
+                        ErrorCode::UnsupportedChecksumAlgorithm,
+                        "Unsupported Upload-Checksum algorithm",
+                    )
+                    .fail(req)
+                }
+                Err(ChecksumError::Malformed) => {
+                    return TusError::new(
+                        Status::BadRequest,
+                        ErrorCode::InvalidUploadChecksum,
+                        "Invalid Upload-Checksum header",
+                    )
+                    .fail(req)
+                }
+            },
+            None => None,
+        };
+
         match req.content_type() {
             None => {
-                return Outcome::Error((
+                return TusError::new(
                     Status::BadRequest,
+                    ErrorCode::MissingContentType,
                     "Missing Content-Type header",
-                ))
+                )
+                .fail(req)
             }
             Some(value)
                 if value
@@ -112,10 +896,12 @@ impl<'r> FromRequest<'r> for UploadRequest<'r> {
                         "offset+octet-stream",
                     ) =>
             {
-                return Outcome::Error((
+                return TusError::new(
                     Status::UnsupportedMediaType,
+                    ErrorCode::InvalidContentType,
                     "Invalid Content-Type header",
-                ))
+                )
+                .fail(req)
             }
             Some(_) => (),
         };
@@ -123,33 +909,128 @@ impl<'r> FromRequest<'r> for UploadRequest<'r> {
         let upload_values = UploadRequest {
             rocket: req.rocket(),
             offset,
+            upload_length,
+            content_length,
+            token: req.headers().get_one(TOKEN_HEADER).map(str::to_string),
+            parallel: req.headers().get_one("Meteoritus-Parallel-Chunks")
+                == Some("1"),
+            timing: req.local_cache(ServerTiming::default),
+            #[cfg(feature = "checksum")]
+            checksum,
+            #[cfg(feature = "grpc-hooks")]
+            http: HookHttpRequest::from(req),
         };
 
         Outcome::Success(upload_values)
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 pub enum UploadResponder {
-    Success(u64),
-    Failure(Status),
+    Success(FileInfo<Created>),
+    Completed(u64),
+    Paused(Duration),
+    Conflict(TusError, u64),
+    PatchFailure(VaultError),
+    Failure(TusError),
+}
+
+impl Sentinel for UploadResponder {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        super::abort_launch(rocket)
+    }
 }
 
 impl<'r> Responder<'r, 'static> for UploadResponder {
     fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
-        let meteoritus = req.rocket().state::<Meteoritus<Orbit>>().unwrap();
+        let meteoritus = MeteoritusGuard::of(req)?;
+
+        let mut res = self.respond(req, &meteoritus)?;
+
+        if meteoritus.server_timing() {
+            if let Some(timing) =
+                req.local_cache(ServerTiming::default).header_value()
+            {
+                res.set_raw_header("Server-Timing", timing);
+            }
+        }
 
+        Ok(res)
+    }
+}
+
+impl UploadResponder {
+    fn respond(
+        self,
+        req: &Request<'_>,
+        meteoritus: &MeteoritusGuard<'_>,
+    ) -> response::Result<'static> {
         let mut res = rocket::Response::build();
 
         res.header(meteoritus.get_protocol_resumable_version());
 
-        match self {
-            Self::Success(offset) => {
+        let file = match self {
+            Self::Completed(length) => {
                 res.status(Status::NoContent);
-                res.raw_header("Upload-Offset", offset.to_string())
+                res.raw_header("Upload-Offset", length.to_string());
+
+                meteoritus.decorate_response(None, &mut res);
+
+                return res.ok();
             }
-            Self::Failure(status) => res.status(status),
+            written => match written.into_written() {
+                Ok(file) => file,
+                Err(error) => return error.respond_to(req),
+            },
         };
 
+        res.status(Status::NoContent);
+        res.raw_header("Upload-Offset", file.offset().to_string());
+
+        if let Some(token) = file.upload_token() {
+            res.raw_header(TOKEN_HEADER, token.to_string());
+        }
+
+        #[cfg(feature = "expiration")]
+        upload_expires(&file, &mut res);
+
+        meteoritus.decorate_response(Some(&file), &mut res);
+
         res.ok()
     }
+
+    /// Returns the upload the chunk was written into, or the failure to respond.
+    ///
+    /// Uploads answered from their tombstones were removed, so they are not found.
+    pub(crate) fn into_written(self) -> Result<FileInfo<Created>, TusError> {
+        match self {
+            Self::Success(file) => Ok(file),
+            Self::Completed(_) => {
+                Err(TusError::from_vault(&VaultError::NotFound))
+            }
+            Self::Paused(retry_after) => Err(TusError::new(
+                Status::Locked,
+                ErrorCode::UploadPaused,
+                "Upload is paused, retry later",
+            )
+            .with_header(
+                "Retry-After",
+                retry_after.as_secs().max(1).to_string(),
+            )),
+            Self::Conflict(error, offset) => {
+                /* Hinting the offset to resume from, sparing clients a HEAD request */
+                Err(error.with_header("Upload-Offset", offset.to_string()))
+            }
+            Self::PatchFailure(error) => {
+                let failure = TusError::from_vault(&error);
+
+                if failure.status().class().is_server_error() {
+                    error_!("Unable to store the uploaded chunk: {}", error);
+                }
+
+                Err(failure)
+            }
+            Self::Failure(error) => Err(error),
+        }
+    }
 }
@@ -0,0 +1,266 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use prost::Message;
+use rocket::{http::Status, tokio, Request};
+use tonic::{
+    client::Grpc,
+    codec::ProstCodec,
+    codegen::http::uri::PathAndQuery,
+    transport::{Channel, Endpoint},
+};
+
+use crate::{ErrorCode, FileInfo, TusError};
+
+/* Messages of the tusd hooks v2 protocol: github.com/tus/tusd/pkg/hooks/grpc/proto */
+
+#[derive(Clone, PartialEq, Message)]
+struct HookRequest {
+    #[prost(string, tag = "1")]
+    r#type: String,
+    #[prost(message, optional, tag = "2")]
+    event: Option<Event>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Event {
+    #[prost(message, optional, tag = "1")]
+    upload: Option<HookFileInfo>,
+    #[prost(message, optional, tag = "2")]
+    http_request: Option<HookHttpRequest>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct HookFileInfo {
+    #[prost(string, tag = "1")]
+    id: String,
+    #[prost(int64, tag = "2")]
+    size: i64,
+    #[prost(bool, tag = "3")]
+    size_is_deferred: bool,
+    #[prost(int64, tag = "4")]
+    offset: i64,
+    #[prost(map = "string, string", tag = "5")]
+    meta_data: HashMap<String, String>,
+    #[prost(bool, tag = "6")]
+    is_partial: bool,
+    #[prost(bool, tag = "7")]
+    is_final: bool,
+    #[prost(string, repeated, tag = "8")]
+    partial_uploads: Vec<String>,
+    #[prost(map = "string, string", tag = "9")]
+    storage: HashMap<String, String>,
+}
+
+/// The HTTP request that triggered a hook.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct HookHttpRequest {
+    #[prost(string, tag = "1")]
+    method: String,
+    #[prost(string, tag = "2")]
+    uri: String,
+    #[prost(string, tag = "3")]
+    remote_addr: String,
+    #[prost(map = "string, string", tag = "4")]
+    header: HashMap<String, String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct HookResponse {
+    #[prost(message, optional, tag = "1")]
+    http_response: Option<HttpResponse>,
+    #[prost(bool, tag = "2")]
+    reject_upload: bool,
+    #[prost(bool, tag = "3")]
+    stop_upload: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct HttpResponse {
+    #[prost(int64, tag = "1")]
+    status_code: i64,
+    #[prost(map = "string, string", tag = "2")]
+    headers: HashMap<String, String>,
+    #[prost(string, tag = "3")]
+    body: String,
+}
+
+impl From<&Request<'_>> for HookHttpRequest {
+    fn from(req: &Request<'_>) -> Self {
+        Self {
+            method: req.method().as_str().to_string(),
+            uri: req.uri().to_string(),
+            remote_addr: req
+                .remote()
+                .map(|addr| addr.to_string())
+                .unwrap_or_default(),
+            header: req
+                .headers()
+                .iter()
+                .map(|h| (h.name().to_string(), h.value().to_string()))
+                .collect(),
+        }
+    }
+}
+
+/// The tusd hook types sent by Meteoritus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HookType {
+    PreCreate,
+    PostCreate,
+    PostReceive,
+    PostFinish,
+    PostTerminate,
+}
+
+impl HookType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::PreCreate => "pre-create",
+            Self::PostCreate => "post-create",
+            Self::PostReceive => "post-receive",
+            Self::PostFinish => "post-finish",
+            Self::PostTerminate => "post-terminate",
+        }
+    }
+}
+
+/// A client of a tusd compatible gRPC hook service.
+pub(crate) struct GrpcHooks {
+    endpoint: Endpoint,
+    channel: OnceLock<Channel>,
+}
+
+impl GrpcHooks {
+    /// # Panics
+    ///
+    /// Panics if the `endpoint` is not a valid URI.
+    pub(crate) fn new(endpoint: &str) -> Self {
+        let endpoint = Endpoint::from_shared(endpoint.to_string())
+            .expect("gRPC hooks endpoint should be a valid URI");
+
+        Self {
+            endpoint,
+            channel: OnceLock::new(),
+        }
+    }
+
+    /// Invokes a blocking hook, failing with a [`TusError`] when the hook service
+    /// is unreachable or rejects the upload.
+    pub(crate) async fn invoke<S>(
+        &self,
+        hook: HookType,
+        file: &FileInfo<S>,
+        offset: u64,
+        http_request: HookHttpRequest,
+    ) -> Result<(), TusError> {
+        let request = Self::request(hook, file, offset, http_request);
+
+        let response = match self.call(request).await {
+            Ok(response) => response,
+            Err(status) => {
+                warn_!("gRPC hook {} failed: {}", hook.as_str(), status);
+
+                return Err(TusError::new(
+                    Status::InternalServerError,
+                    ErrorCode::HookFailed,
+                    "Unable to invoke the upload hook",
+                ));
+            }
+        };
+
+        if !response.reject_upload && !response.stop_upload {
+            return Ok(());
+        }
+
+        let http_response = response.http_response.unwrap_or_default();
+
+        let status = u16::try_from(http_response.status_code)
+            .ok()
+            .and_then(Status::from_code)
+            .unwrap_or(Status::BadRequest);
+
+        let detail = match http_response.body.is_empty() {
+            true => "Upload rejected by the hook".to_string(),
+            false => http_response.body,
+        };
+
+        Err(TusError::new(status, ErrorCode::HookRejected, detail))
+    }
+
+    /// Notifies the hook service without waiting for its response.
+    pub(crate) fn notify<S>(
+        &self,
+        hook: HookType,
+        file: &FileInfo<S>,
+        offset: u64,
+        http_request: HookHttpRequest,
+    ) {
+        let request = Self::request(hook, file, offset, http_request);
+        let mut grpc = Grpc::new(self.channel());
+
+        tokio::spawn(async move {
+            if let Err(status) = Self::unary(&mut grpc, request).await {
+                warn_!("gRPC hook {} failed: {}", hook.as_str(), status);
+            }
+        });
+    }
+
+    fn channel(&self) -> Channel {
+        self.channel
+            .get_or_init(|| self.endpoint.connect_lazy())
+            .clone()
+    }
+
+    async fn call(
+        &self,
+        request: HookRequest,
+    ) -> Result<HookResponse, tonic::Status> {
+        Self::unary(&mut Grpc::new(self.channel()), request).await
+    }
+
+    async fn unary(
+        grpc: &mut Grpc<Channel>,
+        request: HookRequest,
+    ) -> Result<HookResponse, tonic::Status> {
+        grpc.ready()
+            .await
+            .map_err(|e| tonic::Status::unavailable(e.to_string()))?;
+
+        let response = grpc
+            .unary(
+                tonic::Request::new(request),
+                PathAndQuery::from_static("/v2.HookHandler/InvokeHook"),
+                ProstCodec::default(),
+            )
+            .await?;
+
+        Ok(response.into_inner())
+    }
+
+    fn request<S>(
+        hook: HookType,
+        file: &FileInfo<S>,
+        offset: u64,
+        http_request: HookHttpRequest,
+    ) -> HookRequest {
+        let meta_data = match file.metadata() {
+            Some(metadata) => metadata.decoded(),
+            None => HashMap::new(),
+        };
+
+        HookRequest {
+            r#type: hook.as_str().to_string(),
+            event: Some(Event {
+                upload: Some(HookFileInfo {
+                    id: file.id().to_string(),
+                    size: *file.length() as i64,
+                    size_is_deferred: file.is_length_deferred(),
+                    offset: offset as i64,
+                    meta_data,
+                    ..Default::default()
+                }),
+                http_request: Some(http_request),
+            }),
+        }
+    }
+}
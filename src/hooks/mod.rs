@@ -0,0 +1,3 @@
+mod grpc;
+
+pub(crate) use grpc::{GrpcHooks, HookHttpRequest, HookType};
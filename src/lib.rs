@@ -57,28 +57,150 @@
 //!         .mount("/", routes![hello])
 //! }
 //! ```
+//!
+//! ## Cargo features
+//!
+//! Optional backends and subsystems are behind cargo features, so applications only
+//! compile the dependencies they use:
+//!
+//! | Feature         | Default | Description                                                  |
+//! |-----------------|---------|--------------------------------------------------------------|
+//! | `checksum`      | Yes     | The tus `checksum` extension, verifying chunk digests.       |
+//! | `expiration`    | Yes     | The tus `expiration` extension, see `with_expiration()`.     |
+//! | `tiered`        | Yes     | `TieredVault` offloading completed uploads to cold storage.   |
+//! | `s3`            | No      | `S3ColdStorage` offloading tiered uploads to S3 buckets.     |
+//! | `compression`   | No      | `CompressedVault` storing chunks compressed with zstd.       |
+//! | `mime-sniffing` | No      | Verifies completed uploads against their `filetype`.         |
+//! | `remote-fetch`  | No      | Creates uploads by downloading a remote `sourceUrl`.         |
+//! | `migrate`       | No      | `migrate()` moving uploads between vaults.                   |
+//! | `grpc-hooks`    | No      | Invokes tusd compatible gRPC hooks.                          |
+//! | `nats`          | No      | `NatsPublisher` publishing upload events to NATS.            |
+//! | `kafka`         | No      | `KafkaPublisher` publishing upload events to Kafka.          |
+//! | `webhooks`      | No      | `WebhookPublisher` posting upload events to HTTP endpoints.  |
+//! | `redis`         | No      | Redis pub/sub progress and `RedisInfoVault`.                 |
+//! | `sqlx_postgres` | No      | `SqlVault` backed by Postgres.                               |
+//! | `sqlx_sqlite`   | No      | `SqlVault` backed by SQLite.                                 |
+//! | `sftp`          | No      | `SftpVault` storing uploads on a SSH/SFTP server.            |
+//! | `cli`           | No      | `meteoritus` binary managing the uploads of a `LocalVault`.  |
+//! | `testing`       | No      | Utilities to unit test applications, see `testing`.          |
+//! | `conformance`   | No      | tus protocol conformance suite, see `conformance`.           |
+//! | `full`          | No      | Enables every feature, except `testing` and `conformance`.   |
+//!
+//! Embedded deployments only storing uploads on the local filesystem can opt out of the
+//! defaults:
+//!
+//! ```toml
+//! [dependencies]
+//! meteoritus = { version = "0.2.1", default-features = false }
+//! ```
+//!
 //! [`Rocket`]: https://api.rocket.rs/v0.5/rocket/index.html
 //! [`Fairing`]: https://api.rocket.rs/v0.5/rocket/fairing/index.html
 
-/// These are public dependencies! Update docs if these are changed, especially
-/// figment's version number in docs.
+// These are public dependencies! Update docs if these are changed, especially
+// figment's version number in docs.
 
 #[macro_use]
 extern crate rocket;
 
 use rocket::http::Header;
 
+mod clock;
+pub use crate::clock::{Clock, SystemClock};
+
 mod meteoritus;
 pub use crate::meteoritus::Meteoritus;
 
 mod fs;
+#[cfg(feature = "compression")]
+pub use crate::fs::CompressedVault;
+#[cfg(feature = "redis")]
+pub use crate::fs::RedisInfoVault;
+#[cfg(feature = "s3")]
+pub use crate::fs::S3ColdStorage;
+#[cfg(feature = "sftp")]
+pub use crate::fs::SftpVault;
+#[cfg(any(feature = "sqlx_postgres", feature = "sqlx_sqlite"))]
+pub use crate::fs::SqlVault;
 pub use crate::fs::{
-    Built, Completed, Created, FileInfo, Metadata, MetadataError, Terminated,
-    Vault,
+    sanitize_filename, sanitize_relative_path, Building, Built, Completed,
+    Created, DirectoryPromoter, Divergence, Extensions, FileInfo, LocalVault,
+    Metadata, MetadataError, MirrorVault, PatchOption, Preallocation,
+    Quarantined, ReceivedRanges, RelativePathPolicy, RetryPolicy, RetryVault,
+    StorageRef, Terminated, UploadState, UploadStats, UploaderInfo, UuidFormat,
+    Vault, VaultError,
 };
+#[cfg(feature = "tiered")]
+pub use crate::fs::{ColdStorage, DirectoryColdStorage, TieredVault};
+
+mod events;
+#[cfg(feature = "kafka")]
+pub use crate::events::KafkaPublisher;
+#[cfg(feature = "nats")]
+pub use crate::events::NatsPublisher;
+#[cfg(feature = "webhooks")]
+pub use crate::events::WebhookPublisher;
+pub use crate::events::{EventPublisher, UploadEvent, UploadEventKind};
+#[cfg(feature = "redis")]
+pub use crate::events::{
+    ProgressSubscriber, RedisProgressPublisher, UploadProgress,
+};
+
+mod ack;
+mod archive;
+pub use crate::archive::{ArchiveFormat, GroupArchive};
+#[cfg(feature = "checksum")]
+mod checksum;
+#[cfg(feature = "checksum")]
+pub use crate::checksum::ChecksumAlgorithm;
+mod concat;
+#[cfg(feature = "remote-fetch")]
+mod fetch;
+mod fingerprint;
+mod groups;
+pub use crate::groups::CompletedGroup;
+mod limiter;
+mod location;
+pub use crate::location::LocationResolver;
+#[cfg(feature = "migrate")]
+mod migrate;
+mod pause;
+#[cfg(feature = "migrate")]
+pub use crate::migrate::{migrate, MigrationReport};
+#[cfg(feature = "mime-sniffing")]
+mod sniffing;
+#[cfg(feature = "mime-sniffing")]
+pub use crate::sniffing::MimeMismatchPolicy;
+mod policy;
+pub use crate::policy::{PolicyValidator, UploadPolicy};
+mod processor;
+pub use crate::processor::{Artifact, Processor};
+mod slow_storage;
+mod storage_class;
+pub use crate::slow_storage::SlowStorageWarning;
+mod sweeper;
+pub use crate::sweeper::TerminationPolicy;
+mod telemetry;
+pub use crate::telemetry::{Labels, NoopSink, PrometheusSink, TelemetrySink};
+mod timeline;
+pub use crate::timeline::{Timestamp, UploadTimeline};
+mod token;
+mod tombstone;
+mod validation;
+
+#[cfg(feature = "grpc-hooks")]
+mod hooks;
 
 mod handlers;
-pub use crate::handlers::HandlerContext;
+pub use crate::handlers::{
+    DisclosurePolicy, ErrorCode, HandlerContext, OwnedHandlerContext,
+    ResponseHeaders, TusError,
+};
+
+#[cfg(feature = "conformance")]
+pub mod conformance;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 /// Represents the tus protocol headers.
 pub enum MeteoritusHeaders {
@@ -86,11 +208,15 @@ pub enum MeteoritusHeaders {
     Extensions(&'static [&'static str]),
     Version(&'static [&'static str]),
     Resumable(&'static str),
+    ChecksumAlgorithms(Vec<String>),
+    CreationBatch(usize),
+    LengthIncrease,
+    ParallelChunks,
 }
 
-impl Into<Header<'_>> for MeteoritusHeaders {
-    fn into(self) -> Header<'static> {
-        match self {
+impl From<MeteoritusHeaders> for Header<'_> {
+    fn from(value: MeteoritusHeaders) -> Self {
+        match value {
             MeteoritusHeaders::MaxSize(size) => {
                 Header::new("Tus-Max-Size", size.to_string())
             }
@@ -103,6 +229,19 @@ impl Into<Header<'_>> for MeteoritusHeaders {
             MeteoritusHeaders::Resumable(ver) => {
                 Header::new("Tus-Resumable", ver)
             }
+            MeteoritusHeaders::ChecksumAlgorithms(algorithms) => {
+                Header::new("Tus-Checksum-Algorithm", algorithms.join(","))
+            }
+            MeteoritusHeaders::CreationBatch(max_uploads) => Header::new(
+                "Meteoritus-Creation-Batch",
+                max_uploads.to_string(),
+            ),
+            MeteoritusHeaders::LengthIncrease => {
+                Header::new("Meteoritus-Length-Increase", "1")
+            }
+            MeteoritusHeaders::ParallelChunks => {
+                Header::new("Meteoritus-Parallel-Chunks", "1")
+            }
         }
     }
 }
@@ -0,0 +1,72 @@
+use std::{
+    collections::{HashMap, HashSet},
+    net::IpAddr,
+    sync::{Mutex, MutexGuard},
+};
+
+use crate::Vault;
+
+/// Tracks the uploads in progress created by each client.
+#[derive(Default)]
+pub(crate) struct ClientUploads {
+    uploads: Mutex<HashMap<IpAddr, HashSet<String>>>,
+}
+
+impl ClientUploads {
+    /// Returns the number of uploads still in progress for the given client.
+    ///
+    /// Uploads completed or terminated since their creation are pruned.
+    pub(crate) fn in_progress(
+        &self,
+        client: IpAddr,
+        vault: &dyn Vault,
+    ) -> usize {
+        /* Querying the vault without holding the lock, so other clients aren't
+        serialized behind slow storage */
+        let snapshot = match self.lock().get(&client) {
+            Some(ids) => ids.clone(),
+            None => return 0,
+        };
+
+        let finished: Vec<_> = snapshot
+            .iter()
+            .filter(|id| match vault.get_file(id) {
+                Ok(file) => {
+                    file.is_quarantined()
+                        || (!file.is_length_deferred()
+                            && file.offset() >= file.length())
+                }
+                Err(_) => true,
+            })
+            .collect();
+
+        let mut uploads = self.lock();
+
+        let Some(ids) = uploads.get_mut(&client) else {
+            return 0;
+        };
+
+        for id in finished {
+            ids.remove(id);
+        }
+
+        let count = ids.len();
+
+        if count == 0 {
+            uploads.remove(&client);
+        }
+
+        count
+    }
+
+    pub(crate) fn insert(&self, client: IpAddr, file_id: &str) {
+        self.lock()
+            .entry(client)
+            .or_default()
+            .insert(file_id.to_string());
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<IpAddr, HashSet<String>>> {
+        self.uploads.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,45 @@
+use crate::fs::Metadata;
+
+/// A resolver of the `Location` of uploads, as responded on creation and exposed
+/// through [`HandlerContext::location()`](crate::HandlerContext::location).
+///
+/// By default, uploads are located at the `base_route` followed by their id, like
+/// `/meteoritus/<id>`. Custom resolvers allow to emit CDN fronted URLs, per region
+/// hosts or obfuscated paths, as long as requests to the resolved location reach
+/// the tus routes of the upload.
+///
+/// Resolvers are registered through [`Meteoritus::with_location_resolver()`](crate::Meteoritus::with_location_resolver).
+///
+/// # Example
+///
+/// ```rust
+/// use meteoritus::{LocationResolver, Metadata};
+///
+/// struct CdnResolver {
+///     host: String,
+/// }
+///
+/// impl LocationResolver for CdnResolver {
+///     fn resolve(
+///         &self,
+///         base_route: &str,
+///         file_id: &str,
+///         _metadata: Option<&Metadata>,
+///     ) -> String {
+///         format!("https://{}{}/{}", self.host, base_route, file_id)
+///     }
+/// }
+/// ```
+pub trait LocationResolver: Send + Sync {
+    /// Returns the location of the upload `file_id`, whose routes are mounted at
+    /// `base_route`.
+    ///
+    /// The upload `metadata` is available, allowing to locate uploads by region
+    /// or tenant.
+    fn resolve(
+        &self,
+        base_route: &str,
+        file_id: &str,
+        metadata: Option<&Metadata>,
+    ) -> String;
+}
@@ -1,26 +1,99 @@
-use std::{error::Error, marker::PhantomData, sync::Arc};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    error::Error,
+    fs, io,
+    marker::PhantomData,
+    path::{self, PathBuf},
+    sync::{Arc, Mutex, OnceLock, Weak},
+    time::Duration,
+};
 
 use rocket::{
-    data::ByteUnit,
+    data::{ByteUnit, Data},
     fairing::{self, Fairing, Info, Kind},
-    Build, Ignite, Orbit, Phase, Rocket,
+    http::{
+        uri::{Absolute, Origin},
+        Method, Status, StatusClass,
+    },
+    response, tokio, Build, Ignite, Orbit, Phase, Request, Response, Rocket,
+    Route,
 };
 
+use uuid::Uuid;
+
 use crate::{
-    fs::Terminated,
+    fs::{InstrumentedVault, Quarantined, Terminated, UuidFormat},
     handlers::{
-        creation_handler, file_info_handler, info_handler, termination_handler,
-        upload_handler,
+        batch_creation_handler, compatibility_handler, concat_progress_handler,
+        creation_handler, file_info_handler, info_handler, manifest_handler,
+        termination_handler, tus_catcher, upload_handler, upload_info_handler,
     },
 };
 
+#[cfg(feature = "checksum")]
+use crate::checksum::{ChecksumAlgorithm, ChecksumRegistry};
+#[cfg(feature = "remote-fetch")]
+use crate::fetch::RemoteFetch;
+#[cfg(feature = "grpc-hooks")]
+use crate::hooks::{GrpcHooks, HookHttpRequest, HookType};
+#[cfg(feature = "mime-sniffing")]
+use crate::sniffing::MimeMismatchPolicy;
+
 #[allow(unused_imports)]
 use crate::{
-    fs::{Built, Completed, Created, LocalVault, Metadata},
-    handlers::HandlerContext,
-    MeteoritusHeaders, Vault,
+    ack::PendingAcks,
+    concat::ConcatGroups,
+    events::{EventPublisher, UploadEvent, UploadEventKind},
+    fingerprint::FingerprintIndex,
+    fs::{
+        Built, Completed, Created, FileInfo, LocalVault, Metadata, VaultError,
+    },
+    groups::{CompletedGroup, GroupCompletedCallback, UploadGroups},
+    handlers::{
+        default_location, upload_origin, DisclosurePolicy, HandlerContext,
+        ResponseHeaders, TusError,
+    },
+    limiter::ClientUploads,
+    location::LocationResolver,
+    pause::PausedUploads,
+    policy::PolicyValidator,
+    processor::{run_processors, ProcessedCallback, Processor},
+    slow_storage::{SlowStorageCallback, SlowStorageWarning},
+    sweeper::{
+        ActivityTracker, Deadlines, ExpiredCallback, StalledCallback, Sweeper,
+        SweeperQueue, SweptUploads, TerminationPolicy, UploadDeadline,
+    },
+    telemetry::{
+        NoopSink, TelemetrySink, REQUESTS_TOTAL, UPLOADS_IN_PROGRESS,
+        UPLOAD_DURATION_SECONDS, UPLOAD_EVENTS_TOTAL,
+    },
+    timeline::{Timelines, Timestamp},
+    tombstone::{Tombstone, Tombstones},
+    validation::MetadataValidators,
+    Clock, MeteoritusHeaders, SystemClock, Vault,
 };
 
+type CreationCallback = Arc<
+    dyn Fn(HandlerContext<Built>) -> Result<(), Box<dyn Error>> + Send + Sync,
+>;
+
+type EventCallback<S> = Arc<dyn Fn(HandlerContext<S>) + Send + Sync>;
+
+type CreatedCallback =
+    Arc<dyn Fn(HandlerContext<Created>) -> ResponseHeaders + Send + Sync>;
+
+type Authorizer = Arc<dyn Fn(&Request<'_>) -> bool + Send + Sync>;
+
+type OwnershipCheck =
+    Arc<dyn Fn(&Request<'_>, &FileInfo<Created>) -> bool + Send + Sync>;
+
+type ResponseDecorator = Arc<
+    dyn Fn(Option<&FileInfo<Created>>, &mut response::Builder<'static>)
+        + Send
+        + Sync,
+>;
+
 /// The tus fairing itself.
 ///
 /// # Phases
@@ -34,7 +107,7 @@ use crate::{
 ///
 ///> * setting mount route and configuration options like: temp path and max upload size
 ///> * registering callbacks for events
-/* ///# > * adding custom implementation for [`Vault`] */
+///> * adding custom implementation for [`Vault`]
 ///
 ///> This is the _only_ phase in which an instance can be modified. To finalize changes,
 ///> an instance is ignited via [` Meteoritus::build()`], progressing it into the <i>ignite</i>
@@ -83,45 +156,12 @@ use crate::{
 ///   # #[macro_use] extern crate rocket;
 ///   use rocket::{Build, Ignite, data::ByteUnit};
 ///   use meteoritus::{Built, Completed, Created, Terminated, HandlerContext, Meteoritus};
-///   # //
-///   # // use std::io::Result;
-///   # // use meteoritus::{Vault, CometFile};
-///   # // pub struct MyCustomVault {}
-///   # //
-///   # // impl MyCustomVault {
-///   # //     pub fn new() -> Self {
-///   # //         Self {}
-///   # //     }
-///   # // }
-///   # //
-///   # // impl Vault for MyCustomVault {
-///   # //     fn add(&self, file: &CometFile) -> Result<()> {
-///   # //         // Save file information on some persistent storage
-///   # //         todo!()
-///   # //     }
-///   # //
-///   # //     fn take(&self, id: String) -> Result<CometFile> {
-///   # //         // Get the file information from persistent storage
-///   # //         todo!()
-///   # //     }
-///   # //
-///   # //     fn remove(&self, file: &CometFile) -> Result<()> {
-///   # //         // Remove file information and all data from persistent storage
-///   # //         todo!()
-///   # //     }
-///   # //
-///   # //     fn update(&self, file: &mut CometFile, buf: &mut [u8]) -> std::io::Result<()> {
-///   # //         // Patch the file content based on current offset
-///   # //         todo!()
-///   # //     }
-///   # // }
 ///
 ///   #[launch]
 ///   fn rocket() -> _ {
 ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
 ///           .mount_to("/api/files")
 ///           .with_temp_path("./tmp/uploads")
-///   # //           .with_vault(MyCustomVault::new())
 ///           .with_max_size(ByteUnit::Gibibyte(1))
 ///           .on_creation(|ctx: HandlerContext<Built>| {
 ///                 println!("on_creation: {:?}", ctx);
@@ -145,19 +185,85 @@ use crate::{
 pub struct Meteoritus<P: Phase> {
     auto_terminate: bool,
     base_route: &'static str,
+    route_rank_offset: Option<isize>,
+    public_url: Option<&'static str>,
     max_size: ByteUnit,
     vault: Arc<dyn Vault>,
-    on_creation: Option<
-        Arc<
-            dyn Fn(HandlerContext<Built>) -> Result<(), Box<dyn Error>>
-                + Send
-                + Sync,
-        >,
-    >,
-    on_created: Option<Arc<dyn Fn(HandlerContext<Created>) + Send + Sync>>,
-    on_completed: Option<Arc<dyn Fn(HandlerContext<Completed>) + Send + Sync>>,
-    on_termination:
-        Option<Arc<dyn Fn(HandlerContext<Terminated>) + Send + Sync>>,
+    on_creation: Option<CreationCallback>,
+    on_created: Option<CreatedCallback>,
+    on_completed: Option<EventCallback<Completed>>,
+    on_termination: Option<EventCallback<Terminated>>,
+    plain_text_errors: bool,
+    use_catchers: bool,
+    termination_policy: TerminationPolicy,
+    sweep_interval: Duration,
+    sweeper_queue: Arc<SweeperQueue>,
+    response_decorator: Option<ResponseDecorator>,
+    fingerprint_key: Option<&'static str>,
+    fingerprint_index: Arc<FingerprintIndex>,
+    max_uploads_per_client: Option<usize>,
+    max_retained_uploads: Option<usize>,
+    client_uploads: Arc<ClientUploads>,
+    stall_timeout: Option<Duration>,
+    #[cfg(feature = "expiration")]
+    expiration: Option<Duration>,
+    read_timeout: Option<Duration>,
+    min_transfer_rate: Option<ByteUnit>,
+    activity: Arc<ActivityTracker>,
+    deadlines: Arc<Deadlines>,
+    on_stalled: Option<StalledCallback>,
+    on_expired: Option<ExpiredCallback>,
+    #[cfg(feature = "grpc-hooks")]
+    grpc_hooks: Option<Arc<GrpcHooks>>,
+    event_publishers: Vec<Arc<dyn EventPublisher>>,
+    progress_headers: bool,
+    server_timing: bool,
+    max_chunk_size: Option<ByteUnit>,
+    uuid_format: Option<UuidFormat>,
+    time_ordered_ids: bool,
+    clock: Arc<dyn Clock>,
+    resume_verification: bool,
+    quarantine_status: Status,
+    paused_uploads: Arc<PausedUploads>,
+    pause_retry_after: Duration,
+    processors: Vec<Arc<dyn Processor>>,
+    on_processed: Option<ProcessedCallback>,
+    #[cfg(feature = "mime-sniffing")]
+    mime_sniffing: Option<MimeMismatchPolicy>,
+    authorizer: Option<Authorizer>,
+    batch_creation: Option<usize>,
+    #[cfg(feature = "remote-fetch")]
+    remote_fetch: Option<Arc<RemoteFetch>>,
+    completion_ack: bool,
+    token_rotation: bool,
+    pending_acks: Arc<PendingAcks>,
+    launch_check: Arc<OnceLock<()>>,
+    disclosure_policy: DisclosurePolicy,
+    ownership_check: Option<OwnershipCheck>,
+    cache_control: &'static str,
+    captured_headers: Vec<String>,
+    storage_classes: Option<Vec<String>>,
+    range_repair: bool,
+    metadata_validators: Arc<MetadataValidators>,
+    length_increase: bool,
+    parallel_chunks: bool,
+    compatibility_check: bool,
+    policy_validator: Option<Arc<dyn PolicyValidator>>,
+    location_resolver: Option<Arc<dyn LocationResolver>>,
+    concat_groups: Option<Arc<ConcatGroups>>,
+    on_checkpoint: Option<(ByteUnit, EventCallback<Created>)>,
+    on_upload_started: Option<EventCallback<Created>>,
+    on_slow_storage: Option<(Duration, SlowStorageCallback)>,
+    completed_tombstones: Option<Duration>,
+    terminated_tombstones: Option<Duration>,
+    tombstones: Arc<Tombstones>,
+    timelines: Arc<Timelines>,
+    telemetry: Option<Arc<dyn TelemetrySink>>,
+    #[cfg(feature = "checksum")]
+    checksum_algorithms: Arc<ChecksumRegistry>,
+    on_group_completed: Option<GroupCompletedCallback>,
+    upload_groups: Arc<UploadGroups>,
+    swept_uploads: Arc<SweptUploads>,
     state: std::marker::PhantomData<P>,
 }
 
@@ -171,7 +277,26 @@ impl<P: Phase> Meteoritus<P> {
     }
 
     pub fn get_protocol_extensions(&self) -> MeteoritusHeaders {
-        MeteoritusHeaders::Extensions(&["creation", "termination"])
+        MeteoritusHeaders::Extensions(self.protocol_extensions())
+    }
+
+    /// Returns the tus extensions supported by the server.
+    pub(crate) fn protocol_extensions(&self) -> &'static [&'static str] {
+        &[
+            "creation",
+            "creation-defer-length",
+            "creation-with-upload",
+            "termination",
+            #[cfg(feature = "checksum")]
+            "checksum",
+            #[cfg(feature = "expiration")]
+            "expiration",
+        ]
+    }
+
+    #[cfg(feature = "checksum")]
+    pub fn get_protocol_checksum_algorithms(&self) -> MeteoritusHeaders {
+        MeteoritusHeaders::ChecksumAlgorithms(self.checksum_algorithms.names())
     }
 
     pub fn get_protocol_max_size(&self) -> MeteoritusHeaders {
@@ -179,34 +304,193 @@ impl<P: Phase> Meteoritus<P> {
     }
 }
 
+impl Default for Meteoritus<Build> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Meteoritus<Build> {
     /// Returns a instance of [`Meteoritus`] into the _[`Build`]_ phase.
     pub fn new() -> Meteoritus<Build> {
         Meteoritus::<Build> {
             auto_terminate: true,
             base_route: "/meteoritus",
+            route_rank_offset: None,
+            public_url: None,
             max_size: ByteUnit::Megabyte(5),
             vault: Arc::new(LocalVault::new("./tmp/files")),
             on_creation: Default::default(),
             on_created: Default::default(),
             on_completed: Default::default(),
             on_termination: Default::default(),
+            plain_text_errors: false,
+            use_catchers: false,
+            termination_policy: TerminationPolicy::Immediate,
+            sweep_interval: Duration::from_secs(60),
+            sweeper_queue: Default::default(),
+            response_decorator: None,
+            fingerprint_key: None,
+            fingerprint_index: Arc::new(FingerprintIndex::default()),
+            max_uploads_per_client: None,
+            max_retained_uploads: None,
+            client_uploads: Arc::new(ClientUploads::default()),
+            stall_timeout: None,
+            #[cfg(feature = "expiration")]
+            expiration: None,
+            read_timeout: None,
+            min_transfer_rate: None,
+            activity: Arc::new(ActivityTracker::default()),
+            deadlines: Default::default(),
+            on_stalled: None,
+            on_expired: None,
+            #[cfg(feature = "grpc-hooks")]
+            grpc_hooks: None,
+            event_publishers: Vec::new(),
+            progress_headers: false,
+            server_timing: false,
+            max_chunk_size: None,
+            uuid_format: None,
+            time_ordered_ids: false,
+            clock: Arc::new(SystemClock),
+            resume_verification: false,
+            quarantine_status: Status::Forbidden,
+            paused_uploads: Arc::new(PausedUploads::default()),
+            pause_retry_after: Duration::from_secs(60),
+            processors: Vec::new(),
+            on_processed: None,
+            #[cfg(feature = "mime-sniffing")]
+            mime_sniffing: None,
+            authorizer: None,
+            batch_creation: None,
+            #[cfg(feature = "remote-fetch")]
+            remote_fetch: None,
+            completion_ack: false,
+            token_rotation: false,
+            pending_acks: Default::default(),
+            launch_check: Default::default(),
+            disclosure_policy: DisclosurePolicy::Precise,
+            ownership_check: None,
+            cache_control: "no-store",
+            captured_headers: Vec::new(),
+            storage_classes: None,
+            range_repair: false,
+            metadata_validators: Default::default(),
+            length_increase: false,
+            parallel_chunks: false,
+            compatibility_check: false,
+            policy_validator: None,
+            location_resolver: None,
+            concat_groups: None,
+            on_checkpoint: None,
+            on_upload_started: None,
+            on_slow_storage: None,
+            completed_tombstones: None,
+            terminated_tombstones: None,
+            tombstones: Arc::new(Tombstones::default()),
+            timelines: Arc::new(Timelines::default()),
+            telemetry: None,
+            #[cfg(feature = "checksum")]
+            checksum_algorithms: Default::default(),
+            on_group_completed: None,
+            upload_groups: Arc::new(UploadGroups::default()),
+            swept_uploads: Arc::new(SweptUploads::default()),
             state: PhantomData::<Build>,
         }
     }
 
     /// Returns a instance of [`Meteoritus`] into the _[`Ignite`]_ phase.
     pub fn build(self) -> Meteoritus<Ignite> {
+        let vault: Arc<dyn Vault> = match &self.telemetry {
+            Some(sink) => {
+                Arc::new(InstrumentedVault::new(self.vault, sink.to_owned()))
+            }
+            None => self.vault,
+        };
+
         Meteoritus::<Ignite> {
             state: std::marker::PhantomData,
             auto_terminate: self.auto_terminate,
             base_route: self.base_route,
+            route_rank_offset: self.route_rank_offset,
+            public_url: self.public_url,
             max_size: self.max_size,
-            vault: self.vault,
+            vault,
             on_creation: self.on_creation,
             on_created: self.on_created,
             on_completed: self.on_completed,
             on_termination: self.on_termination,
+            plain_text_errors: self.plain_text_errors,
+            use_catchers: self.use_catchers,
+            termination_policy: self.termination_policy,
+            sweep_interval: self.sweep_interval,
+            sweeper_queue: self.sweeper_queue,
+            response_decorator: self.response_decorator,
+            fingerprint_key: self.fingerprint_key,
+            fingerprint_index: self.fingerprint_index,
+            max_uploads_per_client: self.max_uploads_per_client,
+            max_retained_uploads: self.max_retained_uploads,
+            client_uploads: self.client_uploads,
+            stall_timeout: self.stall_timeout,
+            #[cfg(feature = "expiration")]
+            expiration: self.expiration,
+            read_timeout: self.read_timeout,
+            min_transfer_rate: self.min_transfer_rate,
+            activity: self.activity,
+            deadlines: self.deadlines,
+            on_stalled: self.on_stalled,
+            on_expired: self.on_expired,
+            #[cfg(feature = "grpc-hooks")]
+            grpc_hooks: self.grpc_hooks,
+            event_publishers: self.event_publishers,
+            progress_headers: self.progress_headers,
+            server_timing: self.server_timing,
+            max_chunk_size: self.max_chunk_size,
+            uuid_format: self.uuid_format,
+            time_ordered_ids: self.time_ordered_ids,
+            clock: self.clock,
+            resume_verification: self.resume_verification,
+            quarantine_status: self.quarantine_status,
+            paused_uploads: self.paused_uploads,
+            pause_retry_after: self.pause_retry_after,
+            processors: self.processors,
+            on_processed: self.on_processed,
+            #[cfg(feature = "mime-sniffing")]
+            mime_sniffing: self.mime_sniffing,
+            authorizer: self.authorizer,
+            batch_creation: self.batch_creation,
+            #[cfg(feature = "remote-fetch")]
+            remote_fetch: self.remote_fetch,
+            completion_ack: self.completion_ack,
+            token_rotation: self.token_rotation,
+            pending_acks: self.pending_acks,
+            launch_check: self.launch_check,
+            disclosure_policy: self.disclosure_policy,
+            ownership_check: self.ownership_check,
+            cache_control: self.cache_control,
+            captured_headers: self.captured_headers,
+            storage_classes: self.storage_classes,
+            range_repair: self.range_repair,
+            metadata_validators: self.metadata_validators,
+            length_increase: self.length_increase,
+            parallel_chunks: self.parallel_chunks,
+            compatibility_check: self.compatibility_check,
+            policy_validator: self.policy_validator,
+            location_resolver: self.location_resolver,
+            concat_groups: self.concat_groups,
+            on_checkpoint: self.on_checkpoint,
+            on_upload_started: self.on_upload_started,
+            on_slow_storage: self.on_slow_storage,
+            completed_tombstones: self.completed_tombstones,
+            terminated_tombstones: self.terminated_tombstones,
+            tombstones: self.tombstones,
+            timelines: self.timelines,
+            telemetry: self.telemetry,
+            #[cfg(feature = "checksum")]
+            checksum_algorithms: self.checksum_algorithms,
+            on_group_completed: self.on_group_completed,
+            upload_groups: self.upload_groups,
+            swept_uploads: self.swept_uploads,
         }
         /*  Consider Update to: #![feature(type_changing_struct_update)]
         Meteoritus::<Ignite> {
@@ -223,25 +507,22 @@ impl Meteoritus<Build> {
         self
     }
 
-    /// Mounts all tus middleware routes in the supplied given `base` path.
-    ///
-    /// # Panics
-    ///
-    /// Panics if either:
-    ///   * the `base` mount point is not a valid static path: a valid origin
-    ///     URI without dynamic parameters.
+    /// Optional configuration that requires a one-time token on every `PATCH` request,
+    /// limiting the damage of a leaked upload URL.
     ///
-    ///   * any route's URI is not a valid origin URI.
+    /// The creation response holds the first token in its `Upload-Token` header, which
+    /// the client must send back on its next `PATCH` request. Each successful `PATCH`
+    /// responds a rotated token, invalidating the previous one. Requests with a missing
+    /// or outdated token are rejected with `403 Forbidden`.
     ///
-    ///     **Note:** _This kind of panic is guaranteed not to occur if the routes
-    ///     were generated using Rocket's code generation._
+    /// **Note:** Tokens are stored along with the upload [`FileInfo`], so creations
+    /// are rejected with `501 Not Implemented` by vaults not persisting them, like the
+    /// `SqlVault`. When a rotated token can't be stored through
+    /// [`Vault::update_upload_token()`], the previous one stays valid. Uploads created
+    /// before enabling the rotation are not checked.
     ///
     /// # Examples
     ///
-    /// Manually create a route path mounted at base
-    /// `"/api/files"`. Requests to the `/api/files` URI will be dispatched to the
-    /// [`Meteoritus`] middleware.
-    ///
     ///   ```rust,no_run
     ///   # #[macro_use] extern crate rocket;
     ///   use rocket::Ignite;
@@ -250,353 +531,1988 @@ impl Meteoritus<Build> {
     ///   #[launch]
     ///   fn rocket() -> _ {
     ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
-    ///           .mount_to("/api/files")
+    ///           .with_token_rotation()
     ///           .build();
     ///     
     ///       rocket::build().attach(meteoritus)
     /// }
     /// ```
-    /// **Note:** [`Meteoritus`] will mount many tus protocol routes based on the specified path.
-    pub fn mount_to(mut self, base_route: &'static str) -> Self {
-        self.base_route = base_route;
+    pub fn with_token_rotation(mut self) -> Self {
+        self.token_rotation = true;
         self
     }
 
-    /// Directory to store temporary files.
+    /// Optional configuration that holds the auto-termination of completed uploads until
+    /// the application acknowledges them through [`Meteoritus::ack()`], like once its
+    /// database transaction commits.
     ///
-    /*# **Note:** If a custom [`Vault`] has provided then the [`Meteoritus`] will ignore
-    ///# the supplied `temp_path`.*/
+    /// Completed uploads still fire the `on_completed` callback, but the
+    /// [`TerminationPolicy`] is only applied once acknowledged and processed, so a
+    /// failing step never loses the data. Uploads that are never acknowledged are
+    /// kept in storage.
     ///
-    /// # Examples
+    /// **Note:** Pending acknowledgments are held in memory, so they don't survive
+    /// restarts nor are shared across nodes.
     ///
-    /// Assign relative `tmp/uploads` to store uploaded files into.
+    /// # Examples
     ///
     ///   ```rust,no_run
     ///   # #[macro_use] extern crate rocket;
-    ///   use rocket::Ignite;
+    ///   use rocket::{Ignite, Orbit};
     ///   use meteoritus::Meteoritus;
     ///
     ///   #[launch]
     ///   fn rocket() -> _ {
     ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
-    ///           .with_temp_path("./tmp/uploads")
+    ///           .with_completion_ack()
+    ///           .on_completed(|ctx| {
+    ///               // Persist the upload, then acknowledge it
+    ///               let meteoritus = ctx.rocket.state::<Meteoritus<Orbit>>().unwrap();
+    ///               let _ = meteoritus.ack(ctx.file_info.id());
+    ///           })
     ///           .build();
     ///     
     ///       rocket::build().attach(meteoritus)
     /// }
     /// ```
-    pub fn with_temp_path(self, temp_path: &'static str) -> Self {
-        self.with_vault(LocalVault::new(temp_path))
+    pub fn with_completion_ack(mut self) -> Self {
+        self.completion_ack = true;
+        self
     }
 
-    #[doc(hidden)]
-    /// Overrides the default instance of [`Vault`].
+    /// Specifies when completed uploads are auto-terminated, see [`TerminationPolicy`].
     ///
-    /// If a custom vault has provided then the [`Meteoritus`] will ignore the [`Meteoritus::with_temp_path()`]
-    /// configuration. Since it assumes that all file system operations will be responsibility of
-    /// the custom vault implementation.
+    /// By default completed uploads are terminated right after the `on_completed` callback returns,
+    /// which may lose data when the callback fails to copy it. Consider delaying the termination
+    /// or only terminating after the application has released the upload with [`Meteoritus::release()`].
     ///
-    /// # Example
+    /// # Examples
     ///
-    /// ```rust,no_run
-    /// # // #[macro_use] extern crate rocket;
-    /// # // use std::io::Result;
-    /// # // use rocket::Ignite;
-    /// # // use meteoritus::{Meteoritus, Vault, FileInfo};
-    /// # //
-    /// # // pub struct MyCustomVault {}
-    /// # //
-    /// # // impl MyCustomVault {
-    /// # //     pub fn new() -> Self {
-    /// # //         Self {}
-    /// # //     }
-    /// # // }
-    /// # //
-    /// # // impl Vault for MyCustomVault {
-    /// # //     fn add(&self, file: &CometFile) -> Result<()> {
-    /// # //         // Save file information on some persistent storage
-    /// # //         todo!()
-    /// # //     }
-    /// # //
-    /// # //     fn take(&self, id: String) -> Result<CometFile> {
-    /// # //         // Get the file information from persistent storage
-    /// # //         todo!()
-    /// # //     }
-    /// # //
-    /// # //     fn remove(&self, file: &CometFile) -> Result<()> {
-    /// # //         // Remove file information and all data from persistent storage
-    /// # //         todo!()
-    /// # //     }
-    /// # //
-    /// # //     fn update(&self, file: &mut CometFile, buf: &mut [u8]) -> std::io::Result<()> {
-    /// # //         // Patch the file content based on current offset
-    /// # //         todo!()
-    /// # //     }
-    /// # // }
-    ///
-    /// # //   #[launch]
-    /// # //   fn rocket() -> _ {
-    /// # //       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
-    /// # //           .with_temp_path("./tmp/uploads") // This will be ignored by Meteoritus
-    /// # //           .with_vault(MyCustomVault::new())
-    /// # //           .build();
-    /// # //     
-    /// # //       rocket::build().attach(meteoritus)
-    /// # //   }
-    ///   ```
-    pub(crate) fn with_vault<V: Vault + 'static>(mut self, vault: V) -> Self {
-        self.vault = Arc::new(vault);
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use std::time::Duration;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::{Meteoritus, TerminationPolicy};
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_termination_policy(TerminationPolicy::Delayed(Duration::from_secs(300)))
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_termination_policy(
+        mut self,
+        policy: TerminationPolicy,
+    ) -> Self {
+        self.termination_policy = policy;
         self
     }
 
-    /// Maximum upload size in a single `PATCH` request.
+    /// Specifies how often the background sweeper runs.
+    ///
+    /// By default the sweeper runs every 60 seconds.
+    pub fn with_sweep_interval(mut self, interval: Duration) -> Self {
+        self.sweep_interval = interval;
+        self
+    }
+
+    /// Optional configuration that deduplicates uploads by a client fingerprint.
+    ///
+    /// Uploads are indexed by the value of the given `Upload-Metadata` key, like the
+    /// `fingerprint` sent by some tus clients. When a client re-creates an interrupted
+    /// upload with the same fingerprint and `Upload-Length`, it gets redirected through
+    /// the `Location` header to the existing partial upload rather than starting from zero.
+    ///
+    /// Fingerprints are scoped to the IP address of the client that created the upload,
+    /// and redirections must pass the [`Meteoritus::with_ownership_check()`] when one
+    /// is configured, otherwise a fresh upload is created. Clients without a known IP
+    /// address are only deduplicated along with an ownership check.
+    ///
+    /// **Note:** Redirected creations are still checked against the client quota
+    /// and the `on_creation` callback, but the `on_created` callback is not called.
     ///
     /// # Examples
     ///
     ///   ```rust,no_run
     ///   # #[macro_use] extern crate rocket;
-    ///   use rocket::{Ignite, data::ByteUnit};
+    ///   use rocket::Ignite;
     ///   use meteoritus::Meteoritus;
     ///
     ///   #[launch]
     ///   fn rocket() -> _ {
     ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
-    ///           .with_max_size(ByteUnit::Gibibyte(1))
+    ///           .with_fingerprint_deduplication("fingerprint")
     ///           .build();
     ///     
     ///       rocket::build().attach(meteoritus)
     /// }
     /// ```
-    pub fn with_max_size(mut self, size: ByteUnit) -> Self {
-        self.max_size = size;
+    pub fn with_fingerprint_deduplication(mut self, key: &'static str) -> Self {
+        self.fingerprint_key = Some(key);
         self
     }
 
-    /// Adds a custom validation callback to be executed during file creation.
+    /// Limits the number of uploads in progress each client can hold.
     ///
-    /// The callback function will be called during file creation and can be used to perform custom metadata validation
-    /// or other tasks related to the creation process. The function takes a [`HandlerContext`] parameter that contains
-    /// information about the file being created, including its metadata.
+    /// Clients are identified by their IP address, see [`Request::client_ip()`].
+    /// Creations beyond the `limit` are rejected with `429 Too Many Requests`,
+    /// preventing a single misbehaving uploader from filling the vault with partial files.
     ///
-    /// The callback function should return a `Result<(), Box<dyn Error>>` indicating whether the validation
-    /// succeeded or failed. If the validation fails, the function should return an Err containing an error message.
     /// # Examples
     ///
     ///   ```rust,no_run
     ///   # #[macro_use] extern crate rocket;
-    ///   use rocket::{Ignite, data::ByteUnit};
-    ///   use meteoritus::{Built, HandlerContext, Meteoritus};
-    ///   # pub struct DbService {}
-    ///   #
-    ///   # impl DbService {
-    ///   #     fn new() -> DbService {
-    ///   #         Self {}
-    ///   #     }
-    ///   #
-    ///   #     fn say_hello(&self) {
-    ///   #         println!("Hello from DbService")
-    ///   #     }
-    ///   # }
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
     ///
     ///   #[launch]
     ///   fn rocket() -> _ {
     ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
-    ///           .on_creation(|ctx: HandlerContext<Built>| {
-    ///               println!("On Creation: {:?}", ctx.file_info);
-    ///
-    ///               // Apply metadata validation here:
-    ///               let Some(metadata) = ctx.file_info.metadata() else {
-    ///                   return Err("Metadata not specified!".into());
-    ///               };
-    ///     
-    ///               if let Err(e) = metadata.get_raw("filetype") {
-    ///                   return Err(e.into());
-    ///               }
-    ///     
-    ///               // Using rocket instance to get managed services
-    ///               let db_service = ctx.rocket.state::<DbService>().unwrap();
-    ///               db_service.say_hello();
-    ///     
-    ///               Ok(())
-    ///           })
+    ///           .with_max_uploads_per_client(10)
     ///           .build();
     ///     
     ///       rocket::build().attach(meteoritus)
     /// }
     /// ```
-    /// The above example adds a custom validation callback that checks the metadata of the file being created to
-    /// ensure that it contains a `"filetype"` field. If the validation fails, an error message is returned. The
-    /// callback also demonstrates the use of the rocket instance to access managed services.
-    pub fn on_creation<F>(mut self, callback: F) -> Self
-    where
-        F: Fn(HandlerContext<Built>) -> Result<(), Box<dyn Error>>
-            + Send
-            + Sync
-            + 'static,
-    {
-        self.on_creation = Some(Arc::new(callback));
+    pub fn with_max_uploads_per_client(mut self, limit: usize) -> Self {
+        self.max_uploads_per_client = Some(limit);
         self
     }
 
-    /// Adds a callback to be executed after a file has been successfully created and saved to disk.
+    /// Bounds the number of uploads retained by the [`Vault`], for deployments where
+    /// nobody runs cleanup jobs, like kiosks or embedded devices.
     ///
-    /// The callback function will be called after a file has been successfully created and saved to disk. The function
-    /// takes a [`HandlerContext`] parameter that contains information about the file that was just created,
-    /// including its metadata.
+    /// Once more uploads than the `limit` are stored, the background sweeper evicts
+    /// the least recently active uploads in progress within a
+    /// [`Meteoritus::with_sweep_interval()`], firing the
+    /// [`Meteoritus::on_termination()`] callback. Completed and quarantined uploads
+    /// are never evicted.
+    ///
+    /// **Note:** The [`Vault`] must be able to enumerate its uploads, see
+    /// [`Vault::list_files()`].
     ///
     /// # Examples
+    ///
     ///   ```rust,no_run
     ///   # #[macro_use] extern crate rocket;
-    ///   use rocket::{Ignite, data::ByteUnit};
-    ///   use meteoritus::{Created, HandlerContext, Meteoritus};
-    ///   # pub struct DbService {}
-    ///   #
-    ///   # impl DbService {
-    ///   #     fn new() -> DbService {
-    ///   #         Self {}
-    ///   #     }
-    ///   #
-    ///   #     fn say_hello(&self) {
-    ///   #         println!("Hello from DbService")
-    ///   #     }
-    ///   # }
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
     ///
     ///   #[launch]
     ///   fn rocket() -> _ {
     ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
-    ///           .on_created(|ctx: HandlerContext<Created>| {
-    ///               println!("File saved on disk: {:?}", ctx.file_info);
-    ///
-    ///               // Using rocket instance to get managed services
-    ///               let db_service = ctx.rocket.state::<DbService>().unwrap();
-    ///               db_service.say_hello();
-    ///           })
+    ///           .with_max_retained_uploads(100)
     ///           .build();
     ///     
     ///       rocket::build().attach(meteoritus)
     /// }
     /// ```
-    /// The above example adds a callback function that simply logs the file information after it has been successfully
-    /// created and saved to disk also demonstrates the use of the rocket instance to access managed services.
-    pub fn on_created<F>(mut self, callback: F) -> Self
-    where
-        F: Fn(HandlerContext<Created>) + Send + Sync + 'static,
-    {
-        self.on_created = Some(Arc::new(callback));
+    pub fn with_max_retained_uploads(mut self, limit: usize) -> Self {
+        self.max_retained_uploads = Some(limit);
         self
     }
 
-    /// Specifies a callback to be called when a file upload is completed.
+    /// Specifies an inactivity timeout for uploads in progress.
     ///
-    /// The `on_completed` callback function takes a [`HandlerContext<Completed>`] parameter and
-    /// is called once a file upload is completed. This allows users of the library to perform
-    /// additional actions after the file has been uploaded and processed.
+    /// Uploads not receiving any chunk within the given `timeout` are considered stalled
+    /// and get terminated by the background sweeper, firing the [`Meteoritus::on_stalled()`] callback.
+    /// Stalled uploads are checked every [`Meteoritus::with_sweep_interval()`].
     ///
-    /// At this point is recommended to move uploads to a permanent/secure location, by default Meteoritus
-    /// is configured to auto-terminate after `on_completed` was invoked.
-    /// Consider add [` Meteoritus::keep_on_disk()`] in order to overwrite this.
+    /// **Note:** Only uploads created or patched since the server launched are tracked.
     ///
     /// # Examples
+    ///
     ///   ```rust,no_run
     ///   # #[macro_use] extern crate rocket;
-    ///   use rocket::{Ignite, data::ByteUnit};
-    ///   use std::{fs, path::Path, str::from_utf8};
-    ///   use meteoritus::{Completed, HandlerContext, Meteoritus};
-    ///   # pub struct DbService {}
-    ///   #
-    ///   # impl DbService {
-    ///   #     fn new() -> DbService {
-    ///   #         Self {}
-    ///   #     }
-    ///   #
-    ///   #     fn say_hello(&self) {
-    ///   #         println!("Hello from DbService")
-    ///   #     }
-    ///   # }
+    ///   use std::time::Duration;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
     ///
     ///   #[launch]
     ///   fn rocket() -> _ {
     ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
-    ///           .on_completed(|ctx: HandlerContext<Completed>| {
-    ///               println!("Upload completed: {:?}", ctx.file_info);
-    ///       
-    ///               // Retrieving mimetype from Metadata
-    ///               let mime = ctx
-    ///                   .file_info
-    ///                   .metadata()
-    ///                   .as_ref()
-    ///                   .unwrap()
-    ///                   .get_raw("filetype")
-    ///                   .unwrap();
-    ///       
-    ///               let source_path = Path::new(ctx.file_info.file_name());
-    ///       
-    ///               let destination_dir = Path::new("./tmp/files");
-    ///               fs::create_dir_all(destination_dir).unwrap();
-    ///       
-    ///               let file_ext =
-    ///                   from_utf8(&mime).unwrap().split("/").collect::<Vec<&str>>()[1];
-    ///       
-    ///               let destination_path = destination_dir
-    ///                   .join(ctx.file_info.id())
-    ///                   .with_extension(file_ext);
-    ///       
-    ///               // copying file to permanent location
-    ///               fs::copy(source_path, destination_path).unwrap();
-    ///       
-    ///               // Using rocket instance to get managed services
-    ///               let db_service = ctx.rocket.state::<DbService>().unwrap();
-    ///               db_service.say_hello();
-    ///           })
+    ///           .with_stall_timeout(Duration::from_secs(15 * 60))
     ///           .build();
     ///     
     ///       rocket::build().attach(meteoritus)
     /// }
     /// ```
-    /// The above example adds a callback function that move the uploaded file to a permanent location using
-    /// [`Metadata`] values to discover the file extension also demonstrates the use of the rocket instance to access
-    /// managed services.
-    ///
-    pub fn on_completed<F>(mut self, callback: F) -> Self
-    where
-        F: Fn(HandlerContext<Completed>) + Send + Sync + 'static,
-    {
-        self.on_completed = Some(Arc::new(callback));
+    pub fn with_stall_timeout(mut self, timeout: Duration) -> Self {
+        self.stall_timeout = Some(timeout);
         self
     }
 
-    /// Specifies a callback to be executed after a file has been successfully terminated deleted from disk.
+    /// Specifies how long uploads created without a deadline are kept, through the
+    /// tus `expiration` extension.
     ///
-    /// The callback function will be called when a client Termination request occurs. The function
-    /// takes a [`HandlerContext`] parameter that contains information about the file that was deleted from disk.
+    /// Uploads not completed within the given `expiration` are rejected and terminated
+    /// like the ones past a deadline set by [`HandlerContext::set_deadline()`], which
+    /// takes precedence. Responses of uploads in progress having a deadline carry the
+    /// `Upload-Expires` header.
     ///
     /// # Examples
+    ///
     ///   ```rust,no_run
     ///   # #[macro_use] extern crate rocket;
-    ///   use rocket::{Ignite, data::ByteUnit};
-    ///   use meteoritus::{Terminated, HandlerContext, Meteoritus};
-    ///   # pub struct DbService {}
-    ///   #
-    ///   # impl DbService {
-    ///   #     fn new() -> DbService {
-    ///   #         Self {}
-    ///   #     }
-    ///   #
-    ///   #     fn say_hello(&self) {
-    ///   #         println!("Hello from DbService")
-    ///   #     }
-    ///   # }
+    ///   use std::time::Duration;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
     ///
     ///   #[launch]
     ///   fn rocket() -> _ {
     ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
-    ///           .on_termination(|ctx: HandlerContext<Terminated>| {
-    ///               println!("File was terminated by client: {:?}", ctx.file_info);
-    ///
-    ///               // Using rocket instance to get managed services
-    ///               let db_service = ctx.rocket.state::<DbService>().unwrap();
-    ///               db_service.say_hello();
-    ///           })
+    ///           .with_expiration(Duration::from_secs(24 * 60 * 60))
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    #[cfg(feature = "expiration")]
+    pub fn with_expiration(mut self, expiration: Duration) -> Self {
+        self.expiration = Some(expiration);
+        self
+    }
+
+    /// Specifies how long a `PATCH` request can wait for the next bytes of its body.
+    ///
+    /// Requests whose body stalls for longer, like from dead TCP connections, are cut
+    /// short: the bytes received so far are stored and the upload offset advanced,
+    /// releasing the worker task held by the request.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use std::time::Duration;
+    ///   use rocket::{data::ByteUnit, Ignite};
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_read_timeout(Duration::from_secs(30))
+    ///           .with_min_transfer_rate(ByteUnit::Kibibyte(16))
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Specifies the minimum average transfer rate, in bytes per second, of the
+    /// `PATCH` request bodies.
+    ///
+    /// The rate is enforced once a body was streamed for 10 seconds. Slower requests
+    /// are cut short like by [`Meteoritus::with_read_timeout()`], storing the bytes
+    /// received so far.
+    pub fn with_min_transfer_rate(mut self, rate: ByteUnit) -> Self {
+        self.min_transfer_rate = Some(rate);
+        self
+    }
+
+    /// Optional configuration that remembers completed uploads for the given `window`,
+    /// once removed from the [`Vault`] by the termination policy.
+    ///
+    /// Clients retrying the final `HEAD` request of a removed upload get `204 No Content`
+    /// with its `Upload-Offset` at the `Upload-Length`, instead of `404 Not Found`, so
+    /// they don't restart it from scratch. Retried `PATCH` requests at the final offset
+    /// get the same answer, while other offsets get `409 Conflict`.
+    ///
+    /// **Note:** Tombstones are kept in memory, only uploads completed since the server
+    /// launched are remembered.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use std::time::Duration;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_completed_tombstones(Duration::from_secs(60 * 60))
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_completed_tombstones(mut self, window: Duration) -> Self {
+        self.completed_tombstones = Some(window);
+        self
+    }
+
+    /// Optional configuration that remembers uploads terminated by a `DELETE` request,
+    /// or by the background sweeper like stalled and expired ones, for the given `window`.
+    ///
+    /// Later `HEAD` and `PATCH` requests of a terminated upload get `410 Gone` instead
+    /// of `404 Not Found`, telling clients not to retry it. The status follows the
+    /// [`DisclosurePolicy`], so [`DisclosurePolicy::Private`] still answers `404 Not Found`.
+    ///
+    /// **Note:** Tombstones are kept in memory, only uploads terminated since the server
+    /// launched are remembered.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use std::time::Duration;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_terminated_tombstones(Duration::from_secs(24 * 60 * 60))
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_terminated_tombstones(mut self, window: Duration) -> Self {
+        self.terminated_tombstones = Some(window);
+        self
+    }
+
+    /// Optional configuration that verifies the content of completed uploads against
+    /// their declared `filetype` metadata, detecting its type from the magic bytes.
+    ///
+    /// The detected type is exposed to the `on_completed` callback through
+    /// [`HandlerContext::sniffed_type()`], while mismatches are handled by the given
+    /// [`MimeMismatchPolicy`]. Uploads without a declared `filetype`, or whose type
+    /// can't be detected, are never considered mismatched.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::{Meteoritus, MimeMismatchPolicy};
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_mime_sniffing(MimeMismatchPolicy::Reject)
+    ///           .on_completed(|ctx| {
+    ///               println!("Detected type: {:?}", ctx.sniffed_type());
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    #[cfg(feature = "mime-sniffing")]
+    pub fn with_mime_sniffing(mut self, policy: MimeMismatchPolicy) -> Self {
+        self.mime_sniffing = Some(policy);
+        self
+    }
+
+    /// Optional configuration that lets clients create uploads from a remote URL,
+    /// similar to [Uppy Companion](https://uppy.io/docs/companion).
+    ///
+    /// Uploads created with a `sourceUrl` metadata are downloaded by the server itself
+    /// in background, emitting the usual progress events, while their `PATCH` requests
+    /// are rejected. Only URLs whose host is in the given allow-list are fetched,
+    /// including redirects. Uploads with a deferred length take the length of the
+    /// remote content, and are terminated when the download fails.
+    ///
+    /// Since no request is involved on completion, fetched uploads don't invoke the
+    /// `on_completed` callback, use [`Meteoritus::on_processed()`] or an
+    /// [`EventPublisher`] instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the HTTP client can't be initialized.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_remote_fetch(["cdn.example.com", "images.example.com"])
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    #[cfg(feature = "remote-fetch")]
+    pub fn with_remote_fetch<I, S>(mut self, hosts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let hosts = hosts.into_iter().map(Into::into).collect();
+
+        self.remote_fetch = Some(Arc::new(RemoteFetch::new(hosts)));
+        self
+    }
+
+    /// Registers a [`Processor`] executed after an upload is completed, producing
+    /// derived artifacts like thumbnails or transcodes.
+    ///
+    /// Processors run in background, in registration order, after the `on_completed`
+    /// callback. The produced artifact references are written back into the upload
+    /// metadata through [`Vault::update_metadata()`], then the [`TerminationPolicy`]
+    /// is applied. Consider [`Meteoritus::keep_on_disk()`] or [`Meteoritus::on_processed()`]
+    /// to read the updated metadata.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use std::{error::Error, io::Read};
+    ///   use rocket::Ignite;
+    ///   use meteoritus::{Artifact, Completed, FileInfo, Meteoritus, Processor};
+    ///
+    ///   struct ThumbnailProcessor;
+    ///
+    ///   impl Processor for ThumbnailProcessor {
+    ///       fn process(
+    ///           &self,
+    ///           file: &FileInfo<Completed>,
+    ///           content: &mut dyn Read,
+    ///       ) -> Result<Vec<Artifact>, Box<dyn Error + Send + Sync>> {
+    ///           // Generate the thumbnail from the content
+    ///           Ok(vec![Artifact::new("thumbnail", format!("thumbs/{}.png", file.id()))])
+    ///       }
+    ///   }
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_processor(ThumbnailProcessor)
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_processor<T>(mut self, processor: T) -> Self
+    where
+        T: Processor + 'static,
+    {
+        self.processors.push(Arc::new(processor));
+        self
+    }
+
+    /// Requires creation requests to supply a signed policy document, validated by
+    /// the given [`PolicyValidator`] before the upload is created.
+    ///
+    /// This allows untrusted clients, like browsers, to upload with a server-issued
+    /// policy restricting the upload length and metadata. Requests without a valid
+    /// policy, or violating it, are rejected with `403 Forbidden`. The policy applies
+    /// to every upload of a batch creation.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use std::error::Error;
+    ///   use rocket::{http::HeaderMap, Ignite};
+    ///   use meteoritus::{Meteoritus, PolicyValidator, UploadPolicy};
+    ///
+    ///   struct StaticValidator;
+    ///
+    ///   impl PolicyValidator for StaticValidator {
+    ///       fn validate(
+    ///           &self,
+    ///           document: &str,
+    ///           _headers: &HeaderMap<'_>,
+    ///       ) -> Result<UploadPolicy, Box<dyn Error + Send + Sync>> {
+    ///           // Verify the document signature
+    ///           match document == "trusted" {
+    ///               true => Ok(UploadPolicy::new().with_length_range(1..=1024)),
+    ///               false => Err("invalid policy signature".into()),
+    ///           }
+    ///       }
+    ///   }
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_policy_validator(StaticValidator)
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_policy_validator<T>(mut self, validator: T) -> Self
+    where
+        T: PolicyValidator + 'static,
+    {
+        self.policy_validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// Resolves the `Location` of created uploads through the given [`LocationResolver`],
+    /// instead of the `base_route` followed by the upload id.
+    ///
+    /// This allows to emit CDN fronted URLs, per region hosts or obfuscated paths.
+    /// The resolved location is responded on creation and exposed to callbacks
+    /// through [`HandlerContext::location()`].
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::{LocationResolver, Metadata, Meteoritus};
+    ///
+    ///   struct CdnResolver;
+    ///
+    ///   impl LocationResolver for CdnResolver {
+    ///       fn resolve(
+    ///           &self,
+    ///           base_route: &str,
+    ///           file_id: &str,
+    ///           _metadata: Option<&Metadata>,
+    ///       ) -> String {
+    ///           format!("https://uploads.example.com{}/{}", base_route, file_id)
+    ///       }
+    ///   }
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_location_resolver(CdnResolver)
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_location_resolver<T>(mut self, resolver: T) -> Self
+    where
+        T: LocationResolver + 'static,
+    {
+        self.location_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Optional configuration that sets the `Retry-After` responded to `PATCH` requests
+    /// of paused uploads. Defaults to 60 seconds.
+    ///
+    /// Uploads are paused through [`Meteoritus::pause()`].
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use std::time::Duration;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_pause_retry_after(Duration::from_secs(5 * 60))
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_pause_retry_after(mut self, retry_after: Duration) -> Self {
+        self.pause_retry_after = retry_after;
+        self
+    }
+
+    /// Sends the upload events to a [tusd](https://github.com/tus/tusd) compatible gRPC hook service.
+    ///
+    /// Organizations already running tusd hook services can point Meteoritus at the same
+    /// `endpoint`. The following hooks are sent:
+    ///
+    /// * `pre-create`: before the upload gets created, rejecting it when the hook
+    ///   responds with `rejectUpload`, using the hook `httpResponse` status and body.
+    /// * `post-create`, `post-receive`, `post-finish` and `post-terminate`: sent
+    ///   in background, without waiting for the hook response.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `endpoint` is not a valid URI.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_grpc_hooks("http://localhost:8081")
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    #[cfg(feature = "grpc-hooks")]
+    pub fn with_grpc_hooks(mut self, endpoint: &str) -> Self {
+        self.grpc_hooks = Some(Arc::new(GrpcHooks::new(endpoint)));
+        self
+    }
+
+    /// Registers an [`EventPublisher`] to receive the upload lifecycle events.
+    ///
+    /// Events are sent when uploads get created, receive chunks, get completed and
+    /// get terminated by clients.
+    /// It can be called multiple times to register many publishers.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::{EventPublisher, Meteoritus, UploadEvent};
+    ///
+    ///   struct StdoutPublisher;
+    ///
+    ///   impl EventPublisher for StdoutPublisher {
+    ///       fn publish(&self, event: &UploadEvent) {
+    ///           println!("{}", event.to_cloud_event("my-app"));
+    ///       }
+    ///   }
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_event_publisher(StdoutPublisher)
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_event_publisher<E>(mut self, publisher: E) -> Self
+    where
+        E: EventPublisher + 'static,
+    {
+        self.event_publishers.push(Arc::new(publisher));
+        self
+    }
+
+    /// Records metrics of the tus handlers and of the vault operations into the
+    /// given [`TelemetrySink`], like the [`PrometheusSink`](crate::PrometheusSink).
+    ///
+    /// See [`TelemetrySink`] for the recorded metrics.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::{Meteoritus, PrometheusSink};
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_telemetry(PrometheusSink::new())
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_telemetry<T>(mut self, sink: T) -> Self
+    where
+        T: TelemetrySink + 'static,
+    {
+        self.telemetry = Some(Arc::new(sink));
+        self
+    }
+
+    /// Registers an additional [`ChecksumAlgorithm`] of the `checksum` extension,
+    /// replacing any algorithm of the same name, including the built-in `sha1`,
+    /// `sha256` and `md5`.
+    ///
+    /// Registered algorithms are advertised by the `Tus-Checksum-Algorithm` header.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::{ChecksumAlgorithm, Meteoritus};
+    ///
+    ///   struct Crc32;
+    ///
+    ///   impl ChecksumAlgorithm for Crc32 {
+    ///       fn name(&self) -> &str {
+    ///           "crc32"
+    ///       }
+    ///
+    ///       fn digest(&self, chunk: &[u8]) -> Vec<u8> {
+    ///           crc32fast::hash(chunk).to_be_bytes().to_vec()
+    ///       }
+    ///   }
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_checksum_algorithm(Crc32)
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    #[cfg(feature = "checksum")]
+    pub fn with_checksum_algorithm<A>(mut self, algorithm: A) -> Self
+    where
+        A: ChecksumAlgorithm + 'static,
+    {
+        Arc::make_mut(&mut self.checksum_algorithms)
+            .register(Arc::new(algorithm));
+        self
+    }
+
+    /// Overrides the textual format of the generated upload ids, which are [`UuidFormat::Simple`]
+    /// by default.
+    ///
+    /// When configured, ids are generated by Meteoritus on creation for any [`Vault`].
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::{Meteoritus, UuidFormat};
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_uuid_format(UuidFormat::Hyphenated)
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_uuid_format(mut self, format: UuidFormat) -> Self {
+        self.uuid_format = Some(format);
+        self
+    }
+
+    /// Optional configuration that generates time-ordered UUIDv7 upload ids, instead
+    /// of random UUIDv4 ones.
+    ///
+    /// Time-ordered ids keep vault directory listings and database indexes naturally
+    /// sorted by creation time, which benefits high-volume deployments.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_time_ordered_ids()
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_time_ordered_ids(mut self) -> Self {
+        self.time_ordered_ids = true;
+        self
+    }
+
+    /// Overrides the [`Clock`] consulted for time based behaviors, like the stall timeout,
+    /// which reads the system time by default.
+    ///
+    /// It allows tests to fast-forward time deterministically, using a clock like
+    /// [`ManualClock`](crate::testing::ManualClock).
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::{Meteoritus, SystemClock};
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_clock(SystemClock)
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Optional configuration that verifies the stored content on `HEAD` requests, before
+    /// telling clients where to resume.
+    ///
+    /// Uploads are checked through [`Vault::repair_offset()`], so corrupted or truncated
+    /// content rewinds the `Upload-Offset` to the last intact byte, instead of letting
+    /// clients resume after a hole. Consider [`LocalVault::with_rolling_checksums()`] to
+    /// also detect corrupted bytes.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::{LocalVault, Meteoritus};
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_vault(LocalVault::new("./tmp/uploads").with_rolling_checksums())
+    ///           .with_resume_verification()
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_resume_verification(mut self) -> Self {
+        self.resume_verification = true;
+        self
+    }
+
+    /// Optional configuration that sets the status responded to `HEAD` and `PATCH`
+    /// requests of quarantined uploads. Defaults to `403 Forbidden`.
+    ///
+    /// Uploads are quarantined through [`Meteoritus::quarantine()`], like when a scanner
+    /// flags their content, and are kept in storage for inspection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `status` is not a client error (`4xx`) status.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::{http::Status, Ignite};
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_quarantine_status(Status::UnavailableForLegalReasons)
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_quarantine_status(mut self, status: Status) -> Self {
+        assert!(
+            status.class() == StatusClass::ClientError,
+            "quarantine status must be a 4xx status"
+        );
+
+        self.quarantine_status = status;
+        self
+    }
+
+    /// Optional configuration that adds progress headers to the `HEAD` responses.
+    ///
+    /// When enabled, upload information responses also carry:
+    ///
+    /// * `Upload-Progress`: the uploaded percentage, from `0` to `100`.
+    /// * `Upload-Last-Modified`: the HTTP date of the last stored chunk, when the
+    ///   upload content is stored on disk.
+    ///
+    /// So lightweight clients can show progress without computing it from the
+    /// `Upload-Length` and `Upload-Offset` headers.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_progress_headers()
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_progress_headers(mut self) -> Self {
+        self.progress_headers = true;
+        self
+    }
+
+    /// Optional configuration that makes `PATCH` responses carry a `Server-Timing`
+    /// header, measuring how long the server spent reading the body, verifying its
+    /// checksum and writing it to the vault, in milliseconds:
+    ///
+    /// ```text
+    /// Server-Timing: read;dur=812.410, checksum;dur=3.205, vault;dur=41.988
+    /// ```
+    ///
+    /// So frontend teams can attribute slow uploads to the network or to the server
+    /// storage, like from the browser devtools. Browsers only expose the header of
+    /// cross-origin responses allowed by `Timing-Allow-Origin`.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_server_timing()
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_server_timing(mut self) -> Self {
+        self.server_timing = true;
+        self
+    }
+
+    /// Optional configuration that makes failure responses carry a `text/plain` body.
+    ///
+    /// By default Meteoritus responds failures with a [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807)
+    /// `application/problem+json` body holding a machine-readable error `code`, see [`TusError`].
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_plain_text_errors()
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_plain_text_errors(mut self) -> Self {
+        self.plain_text_errors = true;
+        self
+    }
+
+    /// Optional configuration that forwards failures to the application registered catchers.
+    ///
+    /// When enabled, failures are not responded by Meteoritus, instead Rocket invokes the
+    /// [`catcher`](https://api.rocket.rs/v0.5/rocket/struct.Catcher.html) registered for the
+    /// failure status, so applications can keep a uniform error format. The original failure
+    /// is available to catchers through [`TusError::cached()`].
+    ///
+    /// The `Tus-Resumable` header is still attached to the catcher responses.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .use_catchers(true)
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn use_catchers(mut self, enabled: bool) -> Self {
+        self.use_catchers = enabled;
+        self
+    }
+
+    /// Optional configuration that sets the `Cache-Control` directives of the `HEAD`,
+    /// `POST` and `PATCH` responses. Defaults to `no-store`.
+    ///
+    /// Upload offsets change with every chunk, so intermediaries must not serve them
+    /// from a cache, or clients would resume from stale positions. Responses already
+    /// carrying a `Cache-Control` header, like from a
+    /// [`Meteoritus::with_response_decorator()`], are kept as is.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `directives` is empty.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_cache_control("no-store, no-cache, private")
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_cache_control(mut self, directives: &'static str) -> Self {
+        assert!(
+            !directives.trim().is_empty(),
+            "cache control directives must not be empty"
+        );
+
+        self.cache_control = directives;
+        self
+    }
+
+    /// Captures the given request headers on upload creation, storing them along
+    /// with the upload for traceability.
+    ///
+    /// Captured headers are available to later callbacks through
+    /// [`FileInfo::captured_headers()`] and listed by the upload manifest. Names are
+    /// matched case-insensitively, missing headers are skipped and repeated ones are
+    /// joined by `", "`.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_captured_headers(["X-Request-Id", "X-Client-Version"])
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_captured_headers<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.captured_headers = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Restricts the storage class hints clients can declare through the
+    /// `meteoritus-storage-class` metadata to the given classes, like the S3
+    /// `STANDARD_IA` and `GLACIER` ones.
+    ///
+    /// Creations declaring any other class are rejected with `400 Bad Request`,
+    /// before the hint reaches the vault. Hints are exposed by
+    /// [`FileInfo::storage_class()`], so cloud vaults can store archival uploads
+    /// directly in cheaper tiers.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_storage_classes(["STANDARD", "STANDARD_IA", "GLACIER"])
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_storage_classes<I, S>(mut self, classes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.storage_classes =
+            Some(classes.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Optional configuration that enables the admin-only `PATCH <base>/<id>/repair`
+    /// route, re-sending an earlier range of an upload to fix a detected corruption
+    /// without restarting the whole transfer.
+    ///
+    /// The repair request carries the `Upload-Offset` where the range starts, the
+    /// range bytes as body and their mandatory `Upload-Checksum`. The range must be
+    /// within the stored bytes and the upload offset is left unchanged. Corrupted
+    /// ranges can be located through the `GET <base>/<id>/checksum` route.
+    ///
+    /// Since it overwrites stored bytes, the route requires an authorizer, see
+    /// [`Meteoritus::with_authorizer()`], rejecting all requests with
+    /// `403 Forbidden` when none is configured. It requires a [`Vault`] supporting
+    /// [`Vault::repair_range()`].
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_authorizer(|req| {
+    ///               req.headers().get_one("Authorization") == Some("Bearer admin-token")
+    ///           })
+    ///           .with_range_repair()
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    #[cfg(feature = "checksum")]
+    pub fn with_range_repair(mut self) -> Self {
+        self.range_repair = true;
+        self
+    }
+
+    /// Registers a validator of the given metadata key, enforced on upload creation
+    /// before the `on_creation` callback.
+    ///
+    /// The validator receives the UTF-8 value of the key, or `None` when it's
+    /// missing, so it can also require keys. Creations failing any validator are
+    /// rejected with `400 Bad Request`, listing every invalid key along with the
+    /// reason returned by its validator. Keys holding non UTF-8 values are invalid.
+    ///
+    /// Several validators can be registered for the same key, the first failure
+    /// being reported.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_metadata_validator("filename", |value| match value {
+    ///               Some(name) if name.ends_with(".pdf") => Ok(()),
+    ///               Some(_) => Err("must be a PDF document".to_string()),
+    ///               None => Err("is required".to_string()),
+    ///           })
+    ///           .with_metadata_validator("pages", |value| match value {
+    ///               Some(pages) if pages.parse::<u32>().is_err() => {
+    ///                   Err("must be a number".to_string())
+    ///               }
+    ///               _ => Ok(()),
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_metadata_validator<K, F>(mut self, key: K, validator: F) -> Self
+    where
+        K: Into<String>,
+        F: Fn(Option<&str>) -> Result<(), String> + Send + Sync + 'static,
+    {
+        Arc::make_mut(&mut self.metadata_validators)
+            .register(key.into(), Arc::new(validator));
+        self
+    }
+
+    /// Allows clients to enlarge the declared length of uploads which aren't
+    /// completed yet, for streaming producers who underestimated their size.
+    ///
+    /// Clients send a `PATCH` request with an `Upload-Length` greater than the
+    /// current one, which is bound by the [`Meteoritus::with_max_size()`] and
+    /// the storage quota, and the content is grown according to the vault
+    /// preallocation. The extension is advertised by the
+    /// `Meteoritus-Length-Increase` header of `OPTIONS` responses, since it isn't
+    /// part of the tus protocol. It requires a [`Vault`] supporting
+    /// [`Vault::increase_length()`].
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_length_increase()
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_length_increase(mut self) -> Self {
+        self.length_increase = true;
+        self
+    }
+
+    /// Allows clients to upload distinct ranges of an upload concurrently, like
+    /// high-bandwidth clients behind long-latency links.
+    ///
+    /// Clients send `PATCH` requests with the `Meteoritus-Parallel-Chunks: 1`
+    /// header, whose `Upload-Offset` may be past the upload offset. The ranges
+    /// received past the offset are tracked in the [`FileInfo`], and the offset
+    /// moves over them once the bytes before are stored, completing the upload as
+    /// soon as every byte was received. The received ranges are listed by the
+    /// `Meteoritus-Received-Ranges` header of `HEAD` responses, like
+    /// `100-199,300-349`, so interrupted clients resume the missing ones.
+    ///
+    /// The extension is advertised by the `Meteoritus-Parallel-Chunks` header of
+    /// `OPTIONS` responses, since it isn't part of the tus protocol, and only
+    /// applies to uploads with a known length. It requires a [`Vault`] supporting
+    /// [`Vault::write_range()`].
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_parallel_chunks()
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_parallel_chunks(mut self) -> Self {
+        self.parallel_chunks = true;
+        self
+    }
+
+    /// Enables the `GET <base>/compatibility?client=<client>` route, reporting how
+    /// the configuration fits a front-end tus client, to ease integration
+    /// debugging.
+    ///
+    /// The `client` hint is one of `uppy`, `tus-js-client` or `tus-java-client`.
+    /// The JSON report lists the advertised extensions along with the client
+    /// support of each, the headers enforced by the server and whether the
+    /// checking request carried them, so it can be sent with the same headers as
+    /// the client, and the client options required by the configuration, like a
+    /// chunk size within the [`Meteoritus::with_max_chunk_size()`].
+    ///
+    /// Since the report discloses the configuration, the route is subject to the
+    /// [`Meteoritus::with_authorizer()`].
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_compatibility_check()
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_compatibility_check(mut self) -> Self {
+        self.compatibility_check = true;
+        self
+    }
+
+    /// Specifies a decorator to be invoked by all tus responses before they are sent.
+    ///
+    /// The decorator receives the upload related to the response, when there is one,
+    /// and the [`response::Builder`] so applications can attach custom headers like
+    /// cache directives, request ids or cost headers, without forking the handlers.
+    ///
+    /// The upload is `None` for responses not bound to a stored upload, like
+    /// `OPTIONS`, terminations and failures.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_response_decorator(|file, res| {
+    ///               res.raw_header("Cache-Control", "no-store");
+    ///
+    ///               if let Some(file) = file {
+    ///                   res.raw_header("X-Upload-Id", file.id().to_string());
+    ///               }
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_response_decorator<F>(mut self, decorator: F) -> Self
+    where
+        F: Fn(Option<&FileInfo<Created>>, &mut response::Builder<'static>)
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.response_decorator = Some(Arc::new(decorator));
+        self
+    }
+
+    /// Optional configuration that enables creating up to `max_uploads` uploads in a single
+    /// `POST` request to the `<base>/batch` route, cutting round trips for folder uploads of
+    /// many small files.
+    ///
+    /// It is a vendor extension, advertised to `OPTIONS` requests by the
+    /// `Meteoritus-Creation-Batch` header holding `max_uploads`. The request carries the
+    /// `Tus-Resumable` header along with a JSON body listing the uploads, whose metadata
+    /// values are plain strings:
+    ///
+    /// ```json
+    /// { "uploads": [{ "length": 1024, "metadata": { "filename": "a.txt" } }] }
+    /// ```
+    ///
+    /// It responds `201 Created` listing the `id` and `location` of each upload, in order.
+    /// Each upload goes through the same callbacks as single creations, and uploads created
+    /// before a failing one are terminated.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_batch_creation(500)
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_batch_creation(mut self, max_uploads: usize) -> Self {
+        self.batch_creation = Some(max_uploads);
+        self
+    }
+
+    /// Optional configuration that enables concatenation groups of up to `max_parts`
+    /// partial uploads, allowing clients to upload a single file through parallel
+    /// requests.
+    ///
+    /// Each part is created with the `concat-group`, `concat-parts` and `concat-index`
+    /// metadata, holding the group id, its number of parts and the position of the part.
+    /// Once every part is completed, they are concatenated in order into a new upload,
+    /// carrying the metadata of the first part but the `concat-parts` and `concat-index`
+    /// entries, then the parts are terminated. Only the concatenated upload goes through
+    /// the completion callbacks, like `on_completed`.
+    ///
+    /// The progress of a group is reported by the `GET <base>/concat/<group>` route,
+    /// protected by the authorizer like the other inspection routes. It requires a
+    /// [`Vault`] able to list its uploads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_parts` is zero.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_concat_groups(16)
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_concat_groups(mut self, max_parts: usize) -> Self {
+        assert!(max_parts > 0, "concatenation groups require parts");
+
+        self.concat_groups = Some(Arc::new(ConcatGroups::new(max_parts)));
+        self
+    }
+
+    /// Specifies an authorizer protecting the inspection routes, like `GET /<id>/info`
+    /// or `GET /<id>/checksum`, which respond `403 Forbidden` to the requests it rejects.
+    ///
+    /// The inspection routes are open when no authorizer is configured, like the tus routes.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_authorizer(|req| {
+    ///               req.headers().get_one("Authorization") == Some("Bearer support-token")
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_authorizer<F>(mut self, authorizer: F) -> Self
+    where
+        F: Fn(&Request<'_>) -> bool + Send + Sync + 'static,
+    {
+        self.authorizer = Some(Arc::new(authorizer));
+        self
+    }
+
+    /// Specifies a check restricting the `HEAD`, `PATCH` and `DELETE` requests of an
+    /// upload to its owner, like the user who created it.
+    ///
+    /// Requests rejected by the check are responded as defined by the
+    /// [`DisclosurePolicy`], see [`Meteoritus::with_disclosure_policy()`].
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_ownership_check(|req, file| {
+    ///               let owner = file
+    ///                   .metadata()
+    ///                   .as_ref()
+    ///                   .and_then(|metadata| metadata.get_raw("userId").ok());
+    ///
+    ///               owner.as_deref() == req.headers().get_one("X-User-Id").map(str::as_bytes)
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_ownership_check<F>(mut self, check: F) -> Self
+    where
+        F: Fn(&Request<'_>, &FileInfo<Created>) -> bool + Send + Sync + 'static,
+    {
+        self.ownership_check = Some(Arc::new(check));
+        self
+    }
+
+    /// Optional configuration that sets how requests of unknown or foreign uploads
+    /// are responded. Defaults to [`DisclosurePolicy::Precise`].
+    ///
+    /// Consider [`DisclosurePolicy::Private`] to respond `404 Not Found` to all of them,
+    /// so clients can't probe which uploads exist.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::{DisclosurePolicy, Meteoritus};
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_disclosure_policy(DisclosurePolicy::Private)
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_disclosure_policy(mut self, policy: DisclosurePolicy) -> Self {
+        self.disclosure_policy = policy;
+        self
+    }
+
+    /// Mounts all tus middleware routes in the supplied given `base` path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either:
+    ///   * the `base` mount point is not a valid static path: a valid origin
+    ///     URI without dynamic parameters.
+    ///
+    ///   * any route's URI is not a valid origin URI.
+    ///
+    ///     **Note:** _This kind of panic is guaranteed not to occur if the routes
+    ///     were generated using Rocket's code generation._
+    ///
+    /// # Examples
+    ///
+    /// Manually create a route path mounted at base
+    /// `"/api/files"`. Requests to the `/api/files` URI will be dispatched to the
+    /// [`Meteoritus`] middleware.
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .mount_to("/api/files")
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    /// **Note:** [`Meteoritus`] will mount many tus protocol routes based on the specified path.
+    pub fn mount_to(mut self, base_route: &'static str) -> Self {
+        self.base_route = base_route;
+        self
+    }
+
+    /// Shifts the Rocket ranks of all tus routes by `offset`, keeping their
+    /// relative order, so they coexist with application routes mounted at
+    /// overlapping paths.
+    ///
+    /// A positive `offset` lets application routes with default ranks take
+    /// precedence over the tus routes, like a `GET <base>/<id>/info` download
+    /// route, while a negative one makes the tus routes win. Since the ranks are
+    /// chosen deliberately, routes taking precedence over the tus routes are
+    /// only logged instead of being reported as warnings.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[get("/<id>/info")]
+    ///   fn download(id: &str) -> String {
+    ///       format!("Downloading {id}")
+    ///   }
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .mount_to("/api/files")
+    ///           .with_route_rank_offset(20)
+    ///           .build();
+    ///     
+    ///       rocket::build()
+    ///           .attach(meteoritus)
+    ///           .mount("/api/files", routes![download])
+    /// }
+    /// ```
+    pub fn with_route_rank_offset(mut self, offset: isize) -> Self {
+        self.route_rank_offset = Some(offset);
+        self
+    }
+
+    /// Sets the public URL the server is reached at, like
+    /// `https://uploads.example.com`, to build absolute upload URLs through
+    /// [`Meteoritus::upload_url()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `url` is not an absolute URL.
+    pub fn with_public_url(mut self, url: &'static str) -> Self {
+        assert!(
+            Absolute::parse(url).is_ok(),
+            "public url must be an absolute URL: {url}"
+        );
+
+        self.public_url = Some(url);
+        self
+    }
+
+    /// Directory to store temporary files.
+    ///
+    /// **Note:** This is a shortcut to [`Meteoritus::with_vault()`] using a [`LocalVault`],
+    /// so it overrides any custom [`Vault`] previously provided.
+    ///
+    /// # Examples
+    ///
+    /// Assign relative `tmp/uploads` to store uploaded files into.
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_temp_path("./tmp/uploads")
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_temp_path(self, temp_path: &'static str) -> Self {
+        self.with_vault(LocalVault::new(temp_path))
+    }
+
+    /// Overrides the default instance of [`Vault`].
+    ///
+    /// If a custom vault has provided then the [`Meteoritus`] will ignore any previous [`Meteoritus::with_temp_path()`]
+    /// configuration. Since it assumes that all file system operations will be responsibility of
+    /// the custom vault implementation.
+    ///
+    /// # Example
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::{LocalVault, Meteoritus};
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_vault(LocalVault::new("./tmp/uploads"))
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_vault<V: Vault + 'static>(mut self, vault: V) -> Self {
+        self.vault = Arc::new(vault);
+        self
+    }
+
+    /// Maximum upload size, capping the `Upload-Length` of new uploads.
+    ///
+    /// It also limits each `PATCH` body, unless a smaller [`Meteoritus::with_max_chunk_size()`]
+    /// is configured.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::{Ignite, data::ByteUnit};
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_max_size(ByteUnit::Gibibyte(1))
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_max_size(mut self, size: ByteUnit) -> Self {
+        self.max_size = size;
+        self
+    }
+
+    /// Maximum body size of a single `PATCH` request, distinct from the maximum upload size.
+    ///
+    /// Since chunks are buffered in memory before reaching the [`Vault`], this keeps large
+    /// uploads from being sent in a single huge request. Requests exceeding it are rejected
+    /// with `413 Payload Too Large`, so clients should split uploads into smaller chunks.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::{Ignite, data::ByteUnit};
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_max_size(ByteUnit::Gibibyte(1))
+    ///           .with_max_chunk_size(ByteUnit::Mebibyte(8))
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn with_max_chunk_size(mut self, size: ByteUnit) -> Self {
+        self.max_chunk_size = Some(size);
+        self
+    }
+
+    /// Adds a custom validation callback to be executed during file creation.
+    ///
+    /// The callback function will be called during file creation and can be used to perform custom metadata validation
+    /// or other tasks related to the creation process. The function takes a [`HandlerContext`] parameter that contains
+    /// information about the file being created, including its metadata.
+    ///
+    /// The callback function should return a `Result<(), Box<dyn Error>>` indicating whether the validation
+    /// succeeded or failed. If the validation fails, the function should return an Err containing an error message.
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::{Ignite, data::ByteUnit};
+    ///   use meteoritus::{Built, HandlerContext, Meteoritus};
+    ///   # pub struct DbService {}
+    ///   #
+    ///   # impl DbService {
+    ///   #     fn new() -> DbService {
+    ///   #         Self {}
+    ///   #     }
+    ///   #
+    ///   #     fn say_hello(&self) {
+    ///   #         println!("Hello from DbService")
+    ///   #     }
+    ///   # }
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .on_creation(|ctx: HandlerContext<Built>| {
+    ///               println!("On Creation: {:?}", ctx.file_info);
+    ///
+    ///               // Apply metadata validation here:
+    ///               let Some(metadata) = ctx.file_info.metadata() else {
+    ///                   return Err("Metadata not specified!".into());
+    ///               };
+    ///     
+    ///               if let Err(e) = metadata.get_raw("filetype") {
+    ///                   return Err(e.into());
+    ///               }
+    ///     
+    ///               // Using rocket instance to get managed services
+    ///               let db_service = ctx.rocket.state::<DbService>().unwrap();
+    ///               db_service.say_hello();
+    ///     
+    ///               Ok(())
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    /// The above example adds a custom validation callback that checks the metadata of the file being created to
+    /// ensure that it contains a `"filetype"` field. If the validation fails, an error message is returned. The
+    /// callback also demonstrates the use of the rocket instance to access managed services.
+    ///
+    /// **Note:** The callback also runs for dry-run creations, sent with the
+    /// `Upload-Validate-Only: 1` header, which respond `204 No Content` without
+    /// creating the upload. Side effects should be deferred to `on_created`.
+    pub fn on_creation<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(HandlerContext<Built>) -> Result<(), Box<dyn Error>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.on_creation = Some(Arc::new(callback));
+        self
+    }
+
+    /// Adds a callback to be executed after a file has been successfully created and saved to disk.
+    ///
+    /// The callback function will be called after a file has been successfully created and saved to disk. The function
+    /// takes a [`HandlerContext`] parameter that contains information about the file that was just created,
+    /// including its metadata.
+    ///
+    /// # Examples
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::{Ignite, data::ByteUnit};
+    ///   use meteoritus::{Created, HandlerContext, Meteoritus};
+    ///   # pub struct DbService {}
+    ///   #
+    ///   # impl DbService {
+    ///   #     fn new() -> DbService {
+    ///   #         Self {}
+    ///   #     }
+    ///   #
+    ///   #     fn say_hello(&self) {
+    ///   #         println!("Hello from DbService")
+    ///   #     }
+    ///   # }
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .on_created(|ctx: HandlerContext<Created>| {
+    ///               println!("File saved on disk: {:?}", ctx.file_info);
+    ///
+    ///               // Using rocket instance to get managed services
+    ///               let db_service = ctx.rocket.state::<DbService>().unwrap();
+    ///               db_service.say_hello();
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    /// The above example adds a callback function that simply logs the file information after it has been successfully
+    /// created and saved to disk also demonstrates the use of the rocket instance to access managed services.
+    ///
+    /// The callback may also return [`ResponseHeaders`], merged into the `201 Created`
+    /// response, to hand the client supplementary data without a second request:
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::{Created, HandlerContext, Meteoritus, ResponseHeaders};
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .on_created(|ctx: HandlerContext<Created>| {
+    ///               ResponseHeaders::new()
+    ///                   .with("X-Upload-Token", format!("token-{}", ctx.file_info.id()))
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    ///
+    /// **Note:** Extra headers are not responded to batch creations.
+    pub fn on_created<F, R>(mut self, callback: F) -> Self
+    where
+        F: Fn(HandlerContext<Created>) -> R + Send + Sync + 'static,
+        R: Into<ResponseHeaders>,
+    {
+        self.on_created = Some(Arc::new(move |ctx| callback(ctx).into()));
+        self
+    }
+
+    /// Specifies a callback to be called when a file upload is completed.
+    ///
+    /// The `on_completed` callback function takes a [`HandlerContext<Completed>`] parameter and
+    /// is called once a file upload is completed. This allows users of the library to perform
+    /// additional actions after the file has been uploaded and processed.
+    ///
+    /// At this point is recommended to move uploads to a permanent/secure location, by default Meteoritus
+    /// is configured to auto-terminate after `on_completed` was invoked.
+    /// Consider add [` Meteoritus::keep_on_disk()`] in order to overwrite this.
+    ///
+    /// # Examples
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::{Ignite, data::ByteUnit};
+    ///   use std::{fs, path::Path, str::from_utf8};
+    ///   use meteoritus::{Completed, HandlerContext, Meteoritus};
+    ///   # pub struct DbService {}
+    ///   #
+    ///   # impl DbService {
+    ///   #     fn new() -> DbService {
+    ///   #         Self {}
+    ///   #     }
+    ///   #
+    ///   #     fn say_hello(&self) {
+    ///   #         println!("Hello from DbService")
+    ///   #     }
+    ///   # }
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .on_completed(|ctx: HandlerContext<Completed>| {
+    ///               println!("Upload completed: {:?}", ctx.file_info);
+    ///       
+    ///               // Retrieving mimetype from Metadata
+    ///               let mime = ctx
+    ///                   .file_info
+    ///                   .metadata()
+    ///                   .as_ref()
+    ///                   .unwrap()
+    ///                   .get_raw("filetype")
+    ///                   .unwrap();
+    ///       
+    ///               let source_ref = ctx.file_info.storage_ref();
+    ///               let source_path = source_ref.as_path().unwrap();
+    ///       
+    ///               let destination_dir = Path::new("./tmp/files");
+    ///               fs::create_dir_all(destination_dir).unwrap();
+    ///       
+    ///               let file_ext =
+    ///                   from_utf8(&mime).unwrap().split("/").collect::<Vec<&str>>()[1];
+    ///       
+    ///               let destination_path = destination_dir
+    ///                   .join(ctx.file_info.id())
+    ///                   .with_extension(file_ext);
+    ///       
+    ///               // copying file to permanent location
+    ///               fs::copy(source_path, destination_path).unwrap();
+    ///       
+    ///               // Using rocket instance to get managed services
+    ///               let db_service = ctx.rocket.state::<DbService>().unwrap();
+    ///               db_service.say_hello();
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    /// The above example adds a callback function that move the uploaded file to a permanent location using
+    /// [`Metadata`] values to discover the file extension also demonstrates the use of the rocket instance to access
+    /// managed services.
+    ///
+    pub fn on_completed<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(HandlerContext<Completed>) + Send + Sync + 'static,
+    {
+        self.on_completed = Some(Arc::new(callback));
+        self
+    }
+
+    /// Specifies a callback to be executed after a file has been successfully terminated deleted from disk.
+    ///
+    /// The callback function will be called when a client Termination request occurs. The function
+    /// takes a [`HandlerContext`] parameter that contains information about the file that was deleted from disk.
+    ///
+    /// Uploads terminated by the background sweeper, like stalled or expired ones, are reported
+    /// too, along with the next request handled, since the callback requires the Rocket instance.
+    ///
+    /// # Examples
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::{Ignite, data::ByteUnit};
+    ///   use meteoritus::{Terminated, HandlerContext, Meteoritus};
+    ///   # pub struct DbService {}
+    ///   #
+    ///   # impl DbService {
+    ///   #     fn new() -> DbService {
+    ///   #         Self {}
+    ///   #     }
+    ///   #
+    ///   #     fn say_hello(&self) {
+    ///   #         println!("Hello from DbService")
+    ///   #     }
+    ///   # }
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .on_termination(|ctx: HandlerContext<Terminated>| {
+    ///               println!("File was terminated by client: {:?}", ctx.file_info);
+    ///
+    ///               // Using rocket instance to get managed services
+    ///               let db_service = ctx.rocket.state::<DbService>().unwrap();
+    ///               db_service.say_hello();
+    ///           })
     ///           .build();
     ///     
     ///       rocket::build().attach(meteoritus)
@@ -605,85 +2521,1393 @@ impl Meteoritus<Build> {
     /// The above example adds a callback function that simply logs the file information after it has been terminated by a client request. Also demonstrates the use of the rocket instance to access managed services.
     pub fn on_termination<F>(mut self, callback: F) -> Self
     where
-        F: Fn(HandlerContext<Terminated>) + Send + Sync + 'static,
+        F: Fn(HandlerContext<Terminated>) + Send + Sync + 'static,
+    {
+        self.on_termination = Some(Arc::new(callback));
+        self
+    }
+
+    /// Specifies a callback to be executed after a stalled upload has been terminated.
+    ///
+    /// Since stalled uploads are terminated by the background sweeper, out of any request,
+    /// the callback takes the terminated [`FileInfo`] instead of a [`HandlerContext`].
+    /// See [`Meteoritus::with_stall_timeout()`].
+    ///
+    /// # Examples
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use std::time::Duration;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .with_stall_timeout(Duration::from_secs(15 * 60))
+    ///           .on_stalled(|file_info| {
+    ///               println!("Upload stalled: {}", file_info.id());
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn on_stalled<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&FileInfo<Terminated>) + Send + Sync + 'static,
+    {
+        self.on_stalled = Some(Arc::new(callback));
+        self
+    }
+
+    /// Specifies a callback to be executed after an upload past its deadline has
+    /// been terminated.
+    ///
+    /// Expired uploads are terminated by the background sweeper, whether it finds
+    /// them past their deadline or a `PATCH` request did, so like `on_stalled` the
+    /// callback takes the terminated [`FileInfo`]. It lets applications mark their
+    /// records as abandoned rather than discovering it later through missing files.
+    /// See [`HandlerContext::set_deadline()`].
+    ///
+    /// # Examples
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use std::time::{Duration, SystemTime};
+    ///   use rocket::Ignite;
+    ///   use meteoritus::{Built, HandlerContext, Meteoritus};
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .on_creation(|ctx: HandlerContext<Built>| {
+    ///               ctx.set_deadline(SystemTime::now() + Duration::from_secs(3600));
+    ///               Ok(())
+    ///           })
+    ///           .on_expired(|file_info| {
+    ///               println!("Upload expired: {}", file_info.id());
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn on_expired<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&FileInfo<Terminated>) + Send + Sync + 'static,
+    {
+        self.on_expired = Some(Arc::new(callback));
+        self
+    }
+
+    /// Specifies a callback to be executed after the first bytes of an upload have
+    /// been stored, by its first successful `PATCH` request.
+    ///
+    /// Unlike `on_created`, the callback signals that the client actually started
+    /// transferring the upload, like to flip its record from "registered" to
+    /// "transferring" and notify the consumers waiting for it. Uploads completed by a
+    /// single request call it before `on_completed`.
+    ///
+    /// # Examples
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::{Created, HandlerContext, Meteoritus};
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .on_upload_started(|ctx: HandlerContext<Created>| {
+    ///               println!("Upload started: {}", ctx.file_info.id());
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn on_upload_started<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(HandlerContext<Created>) + Send + Sync + 'static,
+    {
+        self.on_upload_started = Some(Arc::new(callback));
+        self
+    }
+
+    /// Specifies a callback to be called each time an upload crosses a checkpoint,
+    /// every `every` bytes of its content.
+    ///
+    /// Unlike the per-chunk progress events, the callback is called once per crossed
+    /// checkpoint interval, whatever the chunk size is, making it suitable to persist
+    /// the upload progress without writing on every `PATCH` request. A chunk crossing
+    /// several checkpoints calls it a single time, with the upload at its new offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is zero.
+    ///
+    /// # Examples
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::{data::ByteUnit, Ignite};
+    ///   use meteoritus::{Created, HandlerContext, Meteoritus};
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .on_checkpoint(ByteUnit::Mebibyte(50), |ctx: HandlerContext<Created>| {
+    ///               println!(
+    ///                   "Upload {} reached {} bytes",
+    ///                   ctx.file_info.id(),
+    ///                   ctx.file_info.offset()
+    ///               );
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn on_checkpoint<F>(mut self, every: ByteUnit, callback: F) -> Self
+    where
+        F: Fn(HandlerContext<Created>) + Send + Sync + 'static,
     {
-        self.on_termination = Some(Arc::new(callback));
+        assert!(every.as_u64() > 0, "checkpoint interval must not be zero");
+
+        self.on_checkpoint = Some((every, Arc::new(callback)));
+        self
+    }
+
+    /// Specifies a callback to be called when a vault write, creating an upload or
+    /// storing one of its chunks, takes longer than the given `threshold`.
+    ///
+    /// Slow writes are also logged as warnings, giving operators an early signal of
+    /// a degraded storage before uploads start timing out.
+    ///
+    /// # Examples
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use std::time::Duration;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .on_slow_storage(Duration::from_millis(500), |warning| {
+    ///               println!(
+    ///                   "Slow {} of upload {}: {:?}",
+    ///                   warning.operation(),
+    ///                   warning.file_id(),
+    ///                   warning.duration()
+    ///               );
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn on_slow_storage<F>(
+        mut self,
+        threshold: Duration,
+        callback: F,
+    ) -> Self
+    where
+        F: Fn(&SlowStorageWarning) + Send + Sync + 'static,
+    {
+        self.on_slow_storage = Some((threshold, Arc::new(callback)));
+        self
+    }
+
+    /// Specifies a callback to be executed after the processors of a completed upload
+    /// have run, before its termination.
+    ///
+    /// Since processors run in background, out of any request, the callback takes the
+    /// completed [`FileInfo`], holding the artifact references in its metadata, instead
+    /// of a [`HandlerContext`]. See [`Meteoritus::with_processor()`].
+    ///
+    /// # Examples
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .on_processed(|file_info| {
+    ///               println!("Upload processed: {:?}", file_info.metadata());
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn on_processed<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&FileInfo<Completed>) + Send + Sync + 'static,
+    {
+        self.on_processed = Some(Arc::new(callback));
+        self
+    }
+
+    /// Specifies a callback to be executed once every upload of a group is completed.
+    ///
+    /// Related uploads, like the files of a single submission, are grouped by creating
+    /// them with the `group` metadata holding the group id, and the `group-size`
+    /// metadata holding the number of uploads in the group. Creations with a malformed
+    /// membership are rejected with `400 Bad Request`.
+    ///
+    /// The callback is called a single time per group, after the `on_completed` callback
+    /// of its last completed upload, with the ids of every member. The members can be
+    /// packaged into a single archive, see [`CompletedGroup::archive()`].
+    ///
+    /// **Note:** Group progress is kept in memory, so members completed before a
+    /// restart are not counted.
+    ///
+    /// # Examples
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::Ignite;
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .on_group_completed(|group| {
+    ///               println!("Group {} completed: {:?}", group.id(), group.members());
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn on_group_completed<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&CompletedGroup) + Send + Sync + 'static,
+    {
+        self.on_group_completed = Some(Arc::new(callback));
         self
     }
-}
+}
+
+impl<P: Phase> Meteoritus<P> {
+    /// Returns a instance of [`Meteoritus`] into the _[`Orbit`]_ phase, sharing the
+    /// state of the current one.
+    pub(crate) fn launch(&self) -> Meteoritus<Orbit> {
+        Meteoritus::<Orbit> {
+            state: std::marker::PhantomData,
+            auto_terminate: self.auto_terminate,
+            base_route: self.base_route,
+            route_rank_offset: self.route_rank_offset,
+            public_url: self.public_url,
+            max_size: self.max_size,
+            vault: self.vault.to_owned(),
+            on_creation: self.on_creation.to_owned(),
+            on_created: self.on_created.to_owned(),
+            on_completed: self.on_completed.to_owned(),
+            on_termination: self.on_termination.to_owned(),
+            plain_text_errors: self.plain_text_errors,
+            use_catchers: self.use_catchers,
+            termination_policy: self.termination_policy,
+            sweep_interval: self.sweep_interval,
+            sweeper_queue: self.sweeper_queue.to_owned(),
+            response_decorator: self.response_decorator.to_owned(),
+            fingerprint_key: self.fingerprint_key,
+            fingerprint_index: self.fingerprint_index.to_owned(),
+            max_uploads_per_client: self.max_uploads_per_client,
+            max_retained_uploads: self.max_retained_uploads,
+            client_uploads: self.client_uploads.to_owned(),
+            stall_timeout: self.stall_timeout,
+            #[cfg(feature = "expiration")]
+            expiration: self.expiration,
+            read_timeout: self.read_timeout,
+            min_transfer_rate: self.min_transfer_rate,
+            activity: self.activity.to_owned(),
+            deadlines: self.deadlines.to_owned(),
+            on_stalled: self.on_stalled.to_owned(),
+            on_expired: self.on_expired.to_owned(),
+            #[cfg(feature = "grpc-hooks")]
+            grpc_hooks: self.grpc_hooks.to_owned(),
+            event_publishers: self.event_publishers.to_owned(),
+            progress_headers: self.progress_headers,
+            server_timing: self.server_timing,
+            max_chunk_size: self.max_chunk_size,
+            uuid_format: self.uuid_format,
+            time_ordered_ids: self.time_ordered_ids,
+            clock: self.clock.to_owned(),
+            resume_verification: self.resume_verification,
+            quarantine_status: self.quarantine_status,
+            paused_uploads: self.paused_uploads.to_owned(),
+            pause_retry_after: self.pause_retry_after,
+            processors: self.processors.to_owned(),
+            on_processed: self.on_processed.to_owned(),
+            #[cfg(feature = "mime-sniffing")]
+            mime_sniffing: self.mime_sniffing,
+            authorizer: self.authorizer.to_owned(),
+            batch_creation: self.batch_creation,
+            #[cfg(feature = "remote-fetch")]
+            remote_fetch: self.remote_fetch.to_owned(),
+            completion_ack: self.completion_ack,
+            token_rotation: self.token_rotation,
+            pending_acks: self.pending_acks.to_owned(),
+            launch_check: self.launch_check.to_owned(),
+            disclosure_policy: self.disclosure_policy,
+            ownership_check: self.ownership_check.to_owned(),
+            cache_control: self.cache_control,
+            captured_headers: self.captured_headers.to_owned(),
+            storage_classes: self.storage_classes.to_owned(),
+            range_repair: self.range_repair,
+            metadata_validators: self.metadata_validators.to_owned(),
+            length_increase: self.length_increase,
+            parallel_chunks: self.parallel_chunks,
+            compatibility_check: self.compatibility_check,
+            policy_validator: self.policy_validator.to_owned(),
+            location_resolver: self.location_resolver.to_owned(),
+            concat_groups: self.concat_groups.to_owned(),
+            on_checkpoint: self.on_checkpoint.to_owned(),
+            on_upload_started: self.on_upload_started.to_owned(),
+            on_slow_storage: self.on_slow_storage.to_owned(),
+            completed_tombstones: self.completed_tombstones,
+            terminated_tombstones: self.terminated_tombstones,
+            tombstones: self.tombstones.to_owned(),
+            timelines: self.timelines.to_owned(),
+            telemetry: self.telemetry.to_owned(),
+            #[cfg(feature = "checksum")]
+            checksum_algorithms: self.checksum_algorithms.to_owned(),
+            on_group_completed: self.on_group_completed.to_owned(),
+            upload_groups: self.upload_groups.to_owned(),
+            swept_uploads: self.swept_uploads.to_owned(),
+        }
+        /*  Consider Update to: #![feature(type_changing_struct_update)]
+        Meteoritus::<Orbit> {
+            state: std::marker::PhantomData,
+            vault: self.vault.to_owned(),
+            on_creation: self.on_creation.to_owned(),
+            on_created: self.on_created.to_owned(),
+            on_completed: self.on_completed.to_owned(),
+            on_termination: self.on_termination.to_owned(),
+            ..*self
+        } */
+    }
+
+    /// Returns `true` when the route is one of the tus routes mounted by the fairing,
+    /// rather than an application route sharing its base.
+    fn is_tus_route(&self, route: &Route) -> bool {
+        static NAMES: OnceLock<Vec<Option<Cow<'static, str>>>> =
+            OnceLock::new();
+
+        let names = NAMES.get_or_init(|| {
+            tus_routes(0).into_iter().map(|route| route.name).collect()
+        });
+
+        route.uri.base() == self.base_route && names.contains(&route.name)
+    }
+
+    /// Returns `false` for the optional routes whose extension isn't enabled, so
+    /// they are left unmounted rather than answering `404 Not Found`.
+    fn is_route_enabled(&self, route: &Route) -> bool {
+        match route.name.as_deref() {
+            Some("batch_creation_handler") => self.batch_creation.is_some(),
+            Some("concat_progress_handler") => self.concat_groups.is_some(),
+            Some("compatibility_handler") => self.compatibility_check,
+            Some("repair_handler") => self.range_repair,
+            _ => true,
+        }
+    }
+}
+
+impl Meteoritus<Orbit> {
+    /// Returns the `base` route where all tus middleware routes are mounted.
+    pub fn base_route(&self) -> &str {
+        self.base_route
+    }
+
+    /// Returns the offset applied to the ranks of the tus routes, if any.
+    pub fn route_rank_offset(&self) -> Option<isize> {
+        self.route_rank_offset
+    }
+
+    /// Returns the URI of the given upload, as routed to the `PATCH`, `HEAD` and
+    /// `DELETE` handlers, so applications build resumable URLs like the creation
+    /// handler does by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::{Orbit, State};
+    /// use meteoritus::Meteoritus;
+    ///
+    /// #[get("/resume/<id>")]
+    /// fn resume(id: &str, meteoritus: &State<Meteoritus<Orbit>>) -> String {
+    ///     meteoritus.upload_uri(id).to_string()
+    /// }
+    /// ```
+    pub fn upload_uri(&self, file_id: &str) -> Origin<'static> {
+        /* The base route was already validated by mounting the tus routes */
+        upload_origin(self.base_route, file_id).unwrap_or(Origin::ROOT)
+    }
+
+    /// Returns the absolute URL of the given upload, below the public URL set by
+    /// [`Meteoritus::with_public_url()`], if any.
+    pub fn upload_url(&self, file_id: &str) -> Option<Absolute<'static>> {
+        let public_url = self.public_url?.trim_end_matches('/');
+
+        Absolute::parse_owned(format!(
+            "{}{}",
+            public_url,
+            self.upload_uri(file_id)
+        ))
+        .ok()
+    }
+
+    /// Indicates if completed uploads should be auto deleted from disk.
+    pub fn auto_terminate(&self) -> bool {
+        self.auto_terminate
+    }
+
+    /// Returns the maximum allowed upload size.
+    pub fn max_size(&self) -> ByteUnit {
+        self.max_size
+    }
+
+    /// Returns the maximum allowed body size of a single `PATCH` request.
+    pub fn max_chunk_size(&self) -> ByteUnit {
+        match self.max_chunk_size {
+            Some(size) => size.min(self.max_size),
+            None => self.max_size,
+        }
+    }
+
+    /// Returns when completed uploads are auto-terminated.
+    pub fn termination_policy(&self) -> TerminationPolicy {
+        self.termination_policy
+    }
+
+    /// Returns how often the background sweeper runs.
+    pub fn sweep_interval(&self) -> Duration {
+        self.sweep_interval
+    }
+
+    /// Returns how long a `PATCH` request can wait for the next bytes of its body.
+    pub fn read_timeout(&self) -> Option<Duration> {
+        self.read_timeout
+    }
+
+    /// Returns the minimum average transfer rate of the `PATCH` request bodies, per
+    /// second.
+    pub fn min_transfer_rate(&self) -> Option<ByteUnit> {
+        self.min_transfer_rate
+    }
+
+    /// Terminates a completed upload, releasing its resources.
+    ///
+    /// This is meant to be used along with [`TerminationPolicy::OnRelease`], once the
+    /// application has safely moved the upload to a permanent location. Fails with a
+    /// [`VaultError::Conflict`] for uploads which aren't completed yet.
+    ///
+    /// The termination is reported like the ones requested by clients, through the
+    /// `Terminated` event and the [`Meteoritus::on_termination()`] callback, called
+    /// along with the next request.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::{Orbit, State};
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[post("/uploads/<id>/release")]
+    ///   fn release(id: &str, meteoritus: &State<Meteoritus<Orbit>>) -> Option<()> {
+    ///       meteoritus.release(id).ok().map(|_| ())
+    ///   }
+    ///   ```
+    pub fn release(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Terminated>, VaultError> {
+        let file = self.vault.get_file(file_id)?;
+
+        if file.is_length_deferred() || file.offset() < file.length() {
+            return Err(VaultError::Conflict(format!(
+                "upload is at offset {} of {}",
+                file.offset(),
+                file.length()
+            )));
+        }
+
+        self.sweeper_queue.remove(file_id);
+
+        let file = self.vault.terminate_file(file_id)?;
+        self.report_detached(&file);
+
+        Ok(file)
+    }
+
+    /// Acknowledges a completed upload, making it eligible for auto-termination once its
+    /// processors have run, see [`Meteoritus::with_completion_ack()`].
+    ///
+    /// Fails if the upload isn't awaiting an acknowledgment.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::{Orbit, State};
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[post("/uploads/<id>/ack")]
+    ///   fn ack(id: &str, meteoritus: &State<Meteoritus<Orbit>>) -> Option<()> {
+    ///       meteoritus.ack(id).ok()
+    ///   }
+    ///   ```
+    pub fn ack(&self, file_id: &str) -> Result<(), VaultError> {
+        match self.pending_acks.ack(file_id) {
+            None => Err(VaultError::NotFound),
+            Some(false) => Ok(()),
+            Some(true) => apply_termination_policy(
+                &self.vault,
+                &self.sweeper_queue,
+                self.clock.as_ref(),
+                self.termination_policy,
+                file_id,
+            ),
+        }
+    }
+
+    /// Returns `true` while the given completed upload awaits an acknowledgment.
+    pub fn is_awaiting_ack(&self, file_id: &str) -> bool {
+        self.pending_acks.contains(file_id)
+    }
+
+    /// Pauses an upload, rejecting its `PATCH` requests with `423 Locked` and a
+    /// `Retry-After` header until [`Meteoritus::resume()`] is called.
+    ///
+    /// Useful during maintenance windows, like while migrating the storage backend.
+    /// Paused uploads are not considered stalled. Returns `false` if the upload was
+    /// already paused.
+    ///
+    /// **Note:** Pauses are held in memory, so they don't survive restarts nor are
+    /// shared across nodes.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::{Orbit, State};
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[post("/uploads/<id>/pause")]
+    ///   fn pause(id: &str, meteoritus: &State<Meteoritus<Orbit>>) {
+    ///       meteoritus.pause(id);
+    ///   }
+    ///
+    ///   #[post("/uploads/<id>/resume")]
+    ///   fn resume(id: &str, meteoritus: &State<Meteoritus<Orbit>>) {
+    ///       meteoritus.resume(id);
+    ///   }
+    ///   ```
+    pub fn pause(&self, file_id: &str) -> bool {
+        self.paused_uploads.insert(file_id)
+    }
+
+    /// Resumes an upload paused by [`Meteoritus::pause()`], accepting its `PATCH`
+    /// requests again. Returns `false` if the upload was not paused.
+    pub fn resume(&self, file_id: &str) -> bool {
+        let resumed = self.paused_uploads.remove(file_id);
+
+        /* Restarting the stall timeout, since clients were kept waiting */
+        if resumed {
+            self.touch_upload(file_id);
+        }
+
+        resumed
+    }
+
+    /// Returns `true` while the given upload is paused.
+    pub fn is_paused(&self, file_id: &str) -> bool {
+        self.paused_uploads.contains(file_id)
+    }
+
+    /// Returns the `Retry-After` responded to `PATCH` requests of paused uploads.
+    pub fn pause_retry_after(&self) -> Duration {
+        self.pause_retry_after
+    }
+
+    /// Quarantines an upload, like when a scanner flags its content.
+    ///
+    /// Quarantined uploads are kept in storage, but are no longer terminated
+    /// automatically and respond [`Meteoritus::with_quarantine_status()`] to `HEAD` and
+    /// `PATCH` requests. It may be called from the event callbacks, through the
+    /// [`HandlerContext`] rocket instance.
+    ///
+    /// # Examples
+    ///
+    ///   ```rust,no_run
+    ///   # #[macro_use] extern crate rocket;
+    ///   use rocket::{Ignite, Orbit};
+    ///   use meteoritus::Meteoritus;
+    ///
+    ///   #[launch]
+    ///   fn rocket() -> _ {
+    ///       let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+    ///           .keep_on_disk()
+    ///           .on_completed(|ctx| {
+    ///               let infected = false; // Scan the upload content
+    ///
+    ///               if infected {
+    ///                   let meteoritus = ctx.rocket.state::<Meteoritus<Orbit>>().unwrap();
+    ///                   meteoritus.quarantine(ctx.file_info.id()).ok();
+    ///               }
+    ///           })
+    ///           .build();
+    ///     
+    ///       rocket::build().attach(meteoritus)
+    /// }
+    /// ```
+    pub fn quarantine(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Quarantined>, VaultError> {
+        self.sweeper_queue.remove(file_id);
+        self.activity.remove(file_id);
+        self.deadlines.remove(file_id);
+        self.vault.quarantine_file(file_id)
+    }
+
+    /// Returns every quarantined upload, so they can be reviewed or released.
+    pub fn quarantined_uploads(
+        &self,
+    ) -> Result<Vec<FileInfo<Quarantined>>, VaultError> {
+        self.vault.list_quarantined()
+    }
+
+    /// Indicates if `PATCH` requests require a rotated one-time token, see
+    /// [`Meteoritus::with_token_rotation()`].
+    pub fn token_rotation(&self) -> bool {
+        self.token_rotation
+    }
+
+    /// Returns the `Cache-Control` directives of the `HEAD`, `POST` and `PATCH` responses.
+    pub fn cache_control(&self) -> &'static str {
+        self.cache_control
+    }
+
+    pub(crate) fn policy_validator(&self) -> Option<&dyn PolicyValidator> {
+        self.policy_validator.as_deref()
+    }
+
+    /// Resolves the location of the given upload, through the configured
+    /// [`LocationResolver`] or below the `base_route` by default.
+    pub(crate) fn upload_location(
+        &self,
+        file_id: &str,
+        metadata: &Option<Metadata>,
+    ) -> Result<String, TusError> {
+        match &self.location_resolver {
+            Some(resolver) => Ok(resolver.resolve(
+                self.base_route,
+                file_id,
+                metadata.as_ref(),
+            )),
+            None => default_location(self.base_route, file_id),
+        }
+    }
+
+    /// Returns the names of the request headers captured on upload creation.
+    pub fn captured_headers(&self) -> &[String] {
+        &self.captured_headers
+    }
 
-impl Meteoritus<Ignite> {
-    /// Returns a instance of [`Meteoritus`] into the _[`Orbit`]_ phase.
-    pub(crate) fn launch(&self) -> Meteoritus<Orbit> {
-        Meteoritus::<Orbit> {
-            state: std::marker::PhantomData,
-            auto_terminate: self.auto_terminate,
-            base_route: self.base_route,
-            max_size: self.max_size,
-            vault: self.vault.to_owned(),
-            on_creation: self.on_creation.to_owned(),
-            on_created: self.on_created.to_owned(),
-            on_completed: self.on_completed.to_owned(),
-            on_termination: self.on_termination.to_owned(),
+    /// Returns the storage class hints clients are allowed to declare, if
+    /// restricted.
+    pub fn storage_classes(&self) -> Option<&[String]> {
+        self.storage_classes.as_deref()
+    }
+
+    /// Indicates if the range repair route is enabled.
+    pub fn range_repair(&self) -> bool {
+        self.range_repair
+    }
+
+    pub(crate) fn metadata_validators(&self) -> &MetadataValidators {
+        &self.metadata_validators
+    }
+
+    /// Indicates if clients are allowed to enlarge the length of their uploads.
+    pub fn length_increase(&self) -> bool {
+        self.length_increase
+    }
+
+    /// Indicates if clients are allowed to upload ranges of an upload concurrently.
+    pub fn parallel_chunks(&self) -> bool {
+        self.parallel_chunks
+    }
+
+    /// Indicates if the compatibility report route is enabled.
+    pub fn compatibility_check(&self) -> bool {
+        self.compatibility_check
+    }
+
+    /// Collects the configured headers present on the given request.
+    pub(crate) fn capture_headers(
+        &self,
+        req: &Request<'_>,
+    ) -> BTreeMap<String, String> {
+        self.captured_headers
+            .iter()
+            .filter_map(|name| {
+                let values: Vec<&str> = req.headers().get(name).collect();
+
+                (!values.is_empty()).then(|| (name.clone(), values.join(", ")))
+            })
+            .collect()
+    }
+
+    /// Returns the status responded to requests of quarantined uploads.
+    pub fn quarantine_status(&self) -> Status {
+        self.quarantine_status
+    }
+
+    /// Generates a new upload id, when a custom id format is configured.
+    pub(crate) fn generate_id(&self) -> Option<String> {
+        if self.uuid_format.is_none() && !self.time_ordered_ids {
+            return None;
         }
-        /*  Consider Update to: #![feature(type_changing_struct_update)]
-        Meteoritus::<Orbit> {
-            state: std::marker::PhantomData,
-            vault: self.vault.to_owned(),
-            on_creation: self.on_creation.to_owned(),
-            on_created: self.on_created.to_owned(),
-            on_completed: self.on_completed.to_owned(),
-            on_termination: self.on_termination.to_owned(),
-            ..*self
-        } */
+
+        let uuid = match self.time_ordered_ids {
+            true => Uuid::now_v7(),
+            false => Uuid::new_v4(),
+        };
+
+        Some(self.uuid_format.unwrap_or_default().format(uuid))
     }
-}
 
-impl Meteoritus<Orbit> {
-    /// Returns the `base` route where all tus middleware routes are mounted.
-    pub fn base_route(&self) -> &str {
-        self.base_route
+    /// Records activity on an upload in progress, when a stall timeout is configured.
+    pub(crate) fn touch_upload(&self, file_id: &str) {
+        if self.stall_timeout.is_some() || self.max_retained_uploads.is_some() {
+            self.activity.touch(file_id, self.clock.now());
+        }
     }
 
-    /// Indicates if completed uploads should be auto deleted from disk.
-    pub fn auto_terminate(&self) -> bool {
-        self.auto_terminate
+    /// Tracks the deadline of an upload in progress, set by the `on_creation` callback
+    /// through [`HandlerContext::set_deadline()`].
+    pub(crate) fn track_deadline<S>(&self, file: &FileInfo<S>) {
+        if let Some(deadline) = file.deadline() {
+            self.deadlines.insert(file.id(), deadline);
+        }
     }
 
-    /// Returns the maximum allowed upload size.
-    pub fn max_size(&self) -> ByteUnit {
-        self.max_size
+    /// Sets the deadline of an upload created without one to the configured
+    /// expiration.
+    #[cfg(feature = "expiration")]
+    pub(crate) fn apply_expiration<S>(&self, file: &FileInfo<S>) {
+        if let (Some(expiration), None) = (self.expiration, file.deadline()) {
+            let deadline = UploadDeadline::new(self.clock.now() + expiration);
+
+            /* Serializing the UNIX seconds can't fail */
+            let _ = file.extensions().insert(deadline);
+        }
+    }
+
+    /// Returns `true` when the deadline of the upload is past.
+    pub(crate) fn is_past_deadline<S>(&self, file: &FileInfo<S>) -> bool {
+        file.deadline()
+            .is_some_and(|deadline| deadline <= self.clock.now())
+    }
+
+    /// Schedules the termination of an upload past its deadline by the background
+    /// sweeper.
+    pub(crate) fn expire_upload(&self, file_id: &str) {
+        self.untrack_upload(file_id);
+
+        /* Handing the upload as already expired, so the sweeper reports it */
+        self.deadlines.insert(file_id, self.clock.now());
+    }
+
+    /// Stops tracking the activity, deadline, pause and acknowledgment of an upload
+    /// no longer in progress.
+    pub(crate) fn untrack_upload(&self, file_id: &str) {
+        self.activity.remove(file_id);
+        self.deadlines.remove(file_id);
+        self.paused_uploads.remove(file_id);
+        self.pending_acks.remove(file_id);
     }
 
-    pub(crate) fn on_creation(
+    /// Reports an upload terminated by its client or by the server, forgetting its
+    /// tracking state, publishing the `Terminated` event and notifying the gRPC
+    /// `post-terminate` hook.
+    ///
+    /// The `on_termination` callback is left to the caller, holding the Rocket
+    /// instance it requires.
+    pub(crate) fn report_termination(
         &self,
-    ) -> &Option<
-        Arc<
-            dyn Fn(HandlerContext<Built>) -> Result<(), Box<dyn Error>>
-                + Send
-                + Sync,
-        >,
-    > {
-        &self.on_creation
+        file: &FileInfo<Terminated>,
+        #[cfg(feature = "grpc-hooks")] http: HookHttpRequest,
+    ) {
+        self.untrack_upload(file.id());
+        self.publish_event(UploadEventKind::Terminated, file, *file.offset());
+
+        #[cfg(feature = "grpc-hooks")]
+        if let Some(hooks) = self.grpc_hooks() {
+            hooks.notify(HookType::PostTerminate, file, *file.offset(), http);
+        }
+    }
+
+    /// Reports an upload terminated out of the tus routes, by the background
+    /// sweeper or [`Meteoritus::release()`], holding it until the next request
+    /// calls the `on_termination` callback.
+    fn report_detached(&self, file: &FileInfo<Terminated>) {
+        /* Completed uploads keep the tombstone recorded on completion */
+        if file.is_length_deferred() || file.offset() < file.length() {
+            self.bury_terminated(file.id());
+        }
+
+        self.report_termination(
+            file,
+            #[cfg(feature = "grpc-hooks")]
+            HookHttpRequest::default(),
+        );
+
+        if self.on_termination.is_some() {
+            self.swept_uploads.push(file.clone());
+        }
+    }
+
+    /// Stops tracking an upload removed without being completed or terminated, like
+    /// rolled back or rejected ones, including its timeline.
+    pub(crate) fn forget_upload(&self, file_id: &str) {
+        self.untrack_upload(file_id);
+        self.timelines.remove(file_id);
+    }
+
+    /// Records the tombstone of a completed upload, when enabled.
+    pub(crate) fn bury_completed(&self, file_id: &str, length: u64) {
+        if let Some(window) = self.completed_tombstones {
+            self.tombstones.insert(
+                file_id,
+                Tombstone::Completed(length),
+                self.clock.now(),
+                window,
+            );
+        }
+    }
+
+    /// Records the tombstone of an upload terminated by its client or by the
+    /// background sweeper, when enabled.
+    pub(crate) fn bury_terminated(&self, file_id: &str) {
+        if let Some(window) = self.terminated_tombstones {
+            self.tombstones.insert(
+                file_id,
+                Tombstone::Terminated,
+                self.clock.now(),
+                window,
+            );
+        }
+    }
+
+    /// Returns the last known state of a recently removed upload.
+    pub(crate) fn tombstone(&self, file_id: &str) -> Option<Tombstone> {
+        self.tombstones.find(file_id, self.clock.now())
+    }
+
+    /// Applies the [`TerminationPolicy`] to a completed upload.
+    pub(crate) fn terminate_completed(
+        &self,
+        file_id: &str,
+    ) -> Result<(), VaultError> {
+        if !self.auto_terminate {
+            return Ok(());
+        }
+
+        /* Terminated once acknowledged instead */
+        if self.completion_ack {
+            return Ok(());
+        }
+
+        apply_termination_policy(
+            &self.vault,
+            &self.sweeper_queue,
+            self.clock.as_ref(),
+            self.termination_policy,
+            file_id,
+        )
+    }
+
+    /// Verifies the configuration of the ignited Rocket instance once, warning
+    /// about every issue found.
+    pub(crate) fn check_launch(&self, rocket: &Rocket<Ignite>) {
+        self.launch_check.get_or_init(|| {
+            self.claim_vault_path();
+            self.warn_shadowed_routes(rocket);
+        });
+    }
+
+    /// Warns about the mounted routes answering every request of a tus route in
+    /// its place.
+    fn warn_shadowed_routes(&self, rocket: &Rocket<Ignite>) {
+        for tus_route in rocket.routes().filter(|r| self.is_tus_route(r)) {
+            for route in rocket.routes().filter(|r| !self.is_tus_route(r)) {
+                if route.method != tus_route.method
+                    || route.rank > tus_route.rank
+                    || !path_shadows(route.uri.path(), tus_route.uri.path())
+                {
+                    continue;
+                }
+
+                /* Deliberately ranked routes are expected to be shadowed */
+                if self.route_rank_offset.is_some() {
+                    info_!(
+                        "Route {} takes precedence over the Meteoritus route {}",
+                        route,
+                        tus_route
+                    );
+                } else {
+                    warn_!(
+                        "Route {} shadows the Meteoritus route {}",
+                        route,
+                        tus_route
+                    );
+                }
+            }
+        }
+    }
+
+    /// Claims the storage path of the vault, warning when another running instance
+    /// of the process already uses it.
+    ///
+    /// Sharing a path is legit for parallel tests or Rocket instances restarted in
+    /// process, but two live instances sweeping the same uploads is usually a mistake.
+    fn claim_vault_path(&self) {
+        let Some(path) = self.vault.storage_path() else {
+            return;
+        };
+
+        let path = fs::canonicalize(path)
+            .or_else(|_| path::absolute(path))
+            .unwrap_or_else(|_| path.to_path_buf());
+
+        let mut claimed =
+            CLAIMED_PATHS.lock().unwrap_or_else(|e| e.into_inner());
+
+        claimed.retain(|(_, instance)| instance.strong_count() > 0);
+
+        let instance = Arc::downgrade(&self.launch_check);
+
+        if claimed.iter().any(|(claimed_path, claimant)| {
+            *claimed_path == path && !claimant.ptr_eq(&instance)
+        }) {
+            warn_!(
+                "Vault path {} is already used by another Meteoritus instance",
+                path.display()
+            );
+        }
+
+        claimed.push((path, instance));
+    }
+
+    /// Starts awaiting the acknowledgment of a completed upload, when required.
+    pub(crate) fn await_ack(&self, file_id: &str) {
+        if self.auto_terminate && self.completion_ack {
+            self.pending_acks.expect(file_id, !self.has_processors());
+        }
+    }
+
+    /// Indicates if completed uploads go through the post-processing pipeline.
+    pub(crate) fn has_processors(&self) -> bool {
+        !self.processors.is_empty()
+    }
+
+    /// Runs the processors over a completed upload in background, then applies the
+    /// [`TerminationPolicy`].
+    pub(crate) fn process_completed(&self, mut file: FileInfo<Completed>) {
+        let vault = self.vault.to_owned();
+        let processors = self.processors.to_owned();
+        let on_processed = self.on_processed.to_owned();
+        let sweeper_queue = self.sweeper_queue.to_owned();
+        let termination_policy = self.termination_policy;
+        let auto_terminate = self.auto_terminate;
+        let clock = self.clock.to_owned();
+        let pending_acks = self.pending_acks.to_owned();
+        let completion_ack = auto_terminate && self.completion_ack;
+
+        tokio::task::spawn_blocking(move || {
+            if !is_quarantined(vault.as_ref(), file.id()) {
+                run_processors(vault.as_ref(), &processors, &mut file);
+
+                if let Some(callback) = &on_processed {
+                    callback(&file);
+                }
+            }
+
+            /* Waiting for the acknowledgment, when not received yet */
+            if completion_ack && !pending_acks.processed(file.id()) {
+                return;
+            }
+
+            if auto_terminate
+                && apply_termination_policy(
+                    &vault,
+                    &sweeper_queue,
+                    clock.as_ref(),
+                    termination_policy,
+                    file.id(),
+                )
+                .is_err()
+            {
+                warn_!("Unable to terminate processed upload: {}", file.id());
+            }
+        });
+    }
+
+    /// Returns how MIME mismatches are handled, when MIME sniffing is enabled.
+    #[cfg(feature = "mime-sniffing")]
+    pub fn mime_sniffing(&self) -> Option<MimeMismatchPolicy> {
+        self.mime_sniffing
+    }
+
+    #[cfg(feature = "remote-fetch")]
+    pub(crate) fn remote_fetch(&self) -> Option<&Arc<RemoteFetch>> {
+        self.remote_fetch.as_ref()
+    }
+
+    /// Returns the maximum number of uploads of a batch creation, when enabled.
+    pub fn batch_creation(&self) -> Option<usize> {
+        self.batch_creation
+    }
+
+    pub(crate) fn concat_groups(&self) -> Option<&ConcatGroups> {
+        self.concat_groups.as_deref()
+    }
+
+    /// Returns `true` when the request is allowed to access the given upload by the
+    /// ownership check, if any.
+    pub(crate) fn is_owner(
+        &self,
+        req: &Request<'_>,
+        file: &FileInfo<Created>,
+    ) -> bool {
+        self.ownership_check
+            .as_ref()
+            .is_none_or(|check| check(req, file))
+    }
+
+    /// Indicates if an ownership check is configured.
+    pub(crate) fn has_ownership_check(&self) -> bool {
+        self.ownership_check.is_some()
+    }
+
+    /// Returns how requests of unknown or foreign uploads are responded.
+    pub fn disclosure_policy(&self) -> DisclosurePolicy {
+        self.disclosure_policy
+    }
+
+    /// Returns `true` when the request is allowed by the authorizer, if any.
+    pub(crate) fn is_authorized(&self, req: &Request<'_>) -> bool {
+        self.authorizer
+            .as_ref()
+            .is_none_or(|authorizer| authorizer(req))
+    }
+
+    /// Indicates if an authorizer is configured.
+    pub(crate) fn has_authorizer(&self) -> bool {
+        self.authorizer.is_some()
+    }
+
+    /// Indicates if the stored content is verified before responding upload information.
+    pub fn resume_verification(&self) -> bool {
+        self.resume_verification
+    }
+
+    /// Indicates if upload information responses should carry progress headers.
+    pub fn progress_headers(&self) -> bool {
+        self.progress_headers
+    }
+
+    /// Indicates if `PATCH` responses should carry a `Server-Timing` header.
+    pub fn server_timing(&self) -> bool {
+        self.server_timing
+    }
+
+    /// Indicates if failures should respond with a `text/plain` body.
+    pub fn plain_text_errors(&self) -> bool {
+        self.plain_text_errors
+    }
+
+    /// Indicates if failures should be forwarded to the application catchers.
+    pub fn use_catchers(&self) -> bool {
+        self.use_catchers
+    }
+
+    /// Returns the fingerprint of the given upload, when deduplication is enabled.
+    pub(crate) fn fingerprint_of<S>(
+        &self,
+        file: &FileInfo<S>,
+    ) -> Option<String> {
+        let key = self.fingerprint_key?;
+        let value = file.metadata().as_ref()?.get_raw(key).ok()?;
+
+        String::from_utf8(value).ok()
+    }
+
+    pub(crate) fn max_uploads_per_client(&self) -> Option<usize> {
+        self.max_uploads_per_client
+    }
+
+    pub(crate) fn client_uploads(&self) -> &ClientUploads {
+        &self.client_uploads
+    }
+
+    pub(crate) fn fingerprint_index(&self) -> &FingerprintIndex {
+        &self.fingerprint_index
+    }
+
+    /// Returns the registry of checksum algorithms verifying `Upload-Checksum`.
+    #[cfg(feature = "checksum")]
+    pub(crate) fn checksum_algorithms(&self) -> &ChecksumRegistry {
+        &self.checksum_algorithms
     }
 
-    pub(crate) fn on_created(
+    /// Returns the configured [`TelemetrySink`], or a [`NoopSink`] when none is.
+    pub(crate) fn telemetry(&self) -> &dyn TelemetrySink {
+        self.telemetry.as_deref().unwrap_or(&NoopSink)
+    }
+
+    #[cfg(feature = "grpc-hooks")]
+    pub(crate) fn grpc_hooks(&self) -> Option<&GrpcHooks> {
+        self.grpc_hooks.as_deref()
+    }
+
+    /// Records the event into the upload timeline and telemetry, then publishes it
+    /// to every registered [`EventPublisher`].
+    pub(crate) fn publish_event<S>(
+        &self,
+        kind: UploadEventKind,
+        file: &FileInfo<S>,
+        offset: u64,
+    ) {
+        let timestamp = Timestamp::now(self.clock.as_ref());
+        let timeline = self.timelines.record(kind, file.id(), timestamp);
+
+        /* Exposing the timeline to the callbacks through the upload extensions */
+        file.extensions().insert_local(timeline.clone());
+
+        if let Some(sink) = &self.telemetry {
+            sink.counter(UPLOAD_EVENTS_TOTAL, 1, &[("kind", kind.as_str())]);
+            sink.gauge(UPLOADS_IN_PROGRESS, self.timelines.len() as f64, &[]);
+
+            if let Some(duration) = timeline.duration() {
+                sink.histogram(
+                    UPLOAD_DURATION_SECONDS,
+                    duration.as_secs_f64(),
+                    &[],
+                );
+            }
+        }
+
+        if self.event_publishers.is_empty() {
+            return;
+        }
+
+        let event = UploadEvent::new(kind, file, offset, timestamp, &timeline);
+
+        for publisher in &self.event_publishers {
+            publisher.publish(&event);
+        }
+    }
+
+    pub(crate) fn decorate_response(
         &self,
-    ) -> &Option<Arc<dyn Fn(HandlerContext<Created>) + Send + Sync>> {
+        file: Option<&FileInfo<Created>>,
+        res: &mut response::Builder<'static>,
+    ) {
+        if let Some(decorator) = &self.response_decorator {
+            decorator(file, res);
+        }
+    }
+
+    pub(crate) fn on_creation(&self) -> &Option<CreationCallback> {
+        &self.on_creation
+    }
+
+    pub(crate) fn on_created(&self) -> &Option<CreatedCallback> {
         &self.on_created
     }
 
-    pub(crate) fn on_completed(
+    /// Returns the checkpoint callback, when an upload moving from offset `from` to
+    /// `to` crosses a checkpoint.
+    pub(crate) fn on_checkpoint(
         &self,
-    ) -> &Option<Arc<dyn Fn(HandlerContext<Completed>) + Send + Sync>> {
-        &self.on_completed
+        from: u64,
+        to: u64,
+    ) -> Option<&EventCallback<Created>> {
+        let (every, callback) = self.on_checkpoint.as_ref()?;
+
+        (from / every.as_u64() < to / every.as_u64()).then_some(callback)
     }
 
-    pub(crate) fn on_termination(
+    /// Returns the started callback, when an upload moving from offset `from` to `to`
+    /// stored its first bytes.
+    pub(crate) fn on_upload_started(
         &self,
-    ) -> &Option<Arc<dyn Fn(HandlerContext<Terminated>) + Send + Sync>> {
+        from: u64,
+        to: u64,
+    ) -> Option<&EventCallback<Created>> {
+        (from == 0 && to > 0)
+            .then_some(self.on_upload_started.as_ref())
+            .flatten()
+    }
+
+    /// Warns about a vault `operation` of an upload, writing `bytes` in `duration`,
+    /// when it exceeds the slow storage threshold.
+    pub(crate) fn check_storage_latency(
+        &self,
+        operation: &'static str,
+        file_id: &str,
+        bytes: u64,
+        duration: Duration,
+    ) {
+        let Some((threshold, callback)) = &self.on_slow_storage else {
+            return;
+        };
+
+        if duration <= *threshold {
+            return;
+        }
+
+        warn_!(
+            "Slow storage: {} of upload {} took {:?}",
+            operation,
+            file_id,
+            duration
+        );
+
+        callback(&SlowStorageWarning::new(
+            file_id, operation, bytes, duration,
+        ));
+    }
+
+    pub(crate) fn on_completed(&self) -> &Option<EventCallback<Completed>> {
+        &self.on_completed
+    }
+
+    pub(crate) fn on_termination(&self) -> &Option<EventCallback<Terminated>> {
         &self.on_termination
     }
+
+    /// Returns `true` when uploads are grouped by their `group` metadata.
+    pub(crate) fn has_upload_groups(&self) -> bool {
+        self.on_group_completed.is_some()
+    }
+
+    /// Records a completed upload, calling the `on_group_completed` callback once
+    /// its group is completed.
+    pub(crate) fn complete_group_member(&self, file: &FileInfo<Completed>) {
+        let Some(callback) = &self.on_group_completed else {
+            return;
+        };
+
+        if let Some(group) = self.upload_groups.complete(file) {
+            callback(&group);
+        }
+    }
+}
+
+/// The storage paths claimed by the running Meteoritus instances, identified by
+/// their launch check.
+static CLAIMED_PATHS: Mutex<Vec<(PathBuf, Weak<OnceLock<()>>)>> =
+    Mutex::new(Vec::new());
+
+/// Returns the tus routes, with their ranks shifted by `rank_offset`.
+fn tus_routes(rank_offset: isize) -> Vec<Route> {
+    #[allow(unused_mut)]
+    let mut routes = routes![
+        creation_handler,
+        info_handler,
+        upload_info_handler,
+        file_info_handler,
+        termination_handler,
+        upload_handler,
+        manifest_handler,
+        batch_creation_handler,
+        concat_progress_handler,
+        compatibility_handler,
+    ];
+
+    #[cfg(feature = "checksum")]
+    routes.extend(routes![
+        crate::handlers::checksum_handler,
+        crate::handlers::repair_handler
+    ]);
+
+    for route in &mut routes {
+        route.rank = route.rank.saturating_add(rank_offset);
+    }
+
+    routes
+}
+
+/// Returns `true` when `path` matches every request of the `shadowed` path, that
+/// is when both have the same number of segments and each segment of `path` is
+/// either dynamic or equal to the static segment of `shadowed`.
+///
+/// Paths merely colliding, like a static segment against a dynamic one, only
+/// take over a few requests and aren't considered shadowing.
+fn path_shadows(path: &str, shadowed: &str) -> bool {
+    let is_dynamic = |segment: &str| segment.starts_with('<');
+
+    let path: Vec<_> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let shadowed: Vec<_> =
+        shadowed.split('/').filter(|s| !s.is_empty()).collect();
+
+    path.len() == shadowed.len()
+        && path.iter().zip(&shadowed).all(|(segment, shadowed)| {
+            is_dynamic(segment) || segment == shadowed
+        })
+}
+
+/// Applies the [`TerminationPolicy`] to a completed upload.
+fn apply_termination_policy(
+    vault: &Arc<dyn Vault>,
+    sweeper_queue: &SweeperQueue,
+    clock: &dyn Clock,
+    termination_policy: TerminationPolicy,
+    file_id: &str,
+) -> Result<(), VaultError> {
+    /* Quarantined uploads are kept for inspection */
+    if is_quarantined(vault.as_ref(), file_id) {
+        return Ok(());
+    }
+
+    match termination_policy {
+        TerminationPolicy::Immediate => {
+            vault.terminate_file(file_id)?;
+        }
+        TerminationPolicy::Delayed(delay) => {
+            let time = clock.now() + delay;
+
+            /* Persisting the schedule, so the sweeper still acts on it after a restart */
+            if let Err(e) = vault.schedule_termination(file_id, time) {
+                if e.io_error_kind() != Some(io::ErrorKind::Unsupported) {
+                    warn_!(
+                        "Unable to persist the termination of upload {}: {}",
+                        file_id,
+                        e
+                    );
+                }
+            }
+
+            sweeper_queue.schedule_at(file_id, time);
+        }
+        TerminationPolicy::Sweeper => sweeper_queue.schedule(file_id),
+        TerminationPolicy::OnRelease => {}
+    }
+
+    Ok(())
+}
+
+fn is_quarantined(vault: &dyn Vault, file_id: &str) -> bool {
+    vault
+        .get_file(file_id)
+        .is_ok_and(|file| file.is_quarantined())
 }
 
 #[rocket::async_trait]
@@ -691,22 +3915,113 @@ impl Fairing for Meteoritus<Ignite> {
     fn info(&self) -> Info {
         Info {
             name: "Meteoritus",
-            kind: Kind::Ignite,
+            kind: Kind::Ignite | Kind::Liftoff | Kind::Request | Kind::Response,
         }
     }
 
     async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
-        let routes = routes![
-            creation_handler,
-            info_handler,
-            file_info_handler,
-            termination_handler,
-            upload_handler,
-        ];
-
-        Ok(rocket
+        if rocket.state::<Meteoritus<Orbit>>().is_some() {
+            error_!("Only one Meteoritus instance can be attached to Rocket");
+            return Err(rocket);
+        }
+
+        let rocket = rocket
             .manage(self.launch())
             .manage(self.vault.to_owned())
-            .mount(self.base_route, routes))
+            .mount(
+                self.base_route,
+                tus_routes(self.route_rank_offset.unwrap_or_default())
+                    .into_iter()
+                    .filter(|route| self.is_route_enabled(route))
+                    .collect::<Vec<_>>(),
+            );
+
+        /* Application catchers take care of failures when enabled */
+        if self.use_catchers {
+            return Ok(rocket);
+        }
+
+        Ok(rocket.register(self.base_route, catchers![tus_catcher]))
+    }
+
+    async fn on_liftoff(&self, _: &Rocket<Orbit>) {
+        let meteoritus = self.launch();
+
+        Sweeper {
+            interval: self.sweep_interval,
+            queue: self.sweeper_queue.to_owned(),
+            vault: self.vault.to_owned(),
+            stall_timeout: self.stall_timeout,
+            max_retained_uploads: self.max_retained_uploads,
+            activity: self.activity.to_owned(),
+            deadlines: self.deadlines.to_owned(),
+            paused_uploads: self.paused_uploads.to_owned(),
+            clock: self.clock.to_owned(),
+            on_stalled: self.on_stalled.to_owned(),
+            on_expired: self.on_expired.to_owned(),
+            on_terminated: Arc::new(move |file| {
+                meteoritus.report_detached(file)
+            }),
+        }
+        .spawn();
+    }
+
+    /// Calls the `on_termination` callback of the uploads terminated by the
+    /// background sweeper since the previous request.
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data<'_>) {
+        let Some(callback) = &self.on_termination else {
+            return;
+        };
+
+        for file in self.swept_uploads.drain() {
+            callback(HandlerContext {
+                rocket: req.rocket(),
+                file_info: &file,
+            });
+        }
+    }
+
+    /// Attaches the `Tus-Resumable` header to every response produced by the tus routes,
+    /// including the ones produced by catchers, except for `OPTIONS` requests.
+    ///
+    /// The `HEAD`, `POST` and `PATCH` responses also get the `Cache-Control`
+    /// directives, unless already set.
+    async fn on_response<'r>(
+        &self,
+        req: &'r Request<'_>,
+        res: &mut Response<'r>,
+    ) {
+        let is_tus_route = req.route().is_some_and(|r| self.is_tus_route(r));
+
+        if !is_tus_route && TusError::cached(req).is_none() {
+            return;
+        }
+
+        if matches!(req.method(), Method::Head | Method::Post | Method::Patch)
+            && !res.headers().contains("Cache-Control")
+        {
+            res.set_raw_header("Cache-Control", self.cache_control);
+        }
+
+        if req.method() != Method::Options
+            && !res.headers().contains("Tus-Resumable")
+        {
+            res.set_header(self.get_protocol_resumable_version());
+        }
+
+        /* Restoring the headers of failures responded by the application catchers */
+        if let Some(error) = TusError::cached(req) {
+            error.apply_headers(res);
+        }
+
+        if let Some(sink) = &self.telemetry {
+            let status = res.status().code.to_string();
+
+            sink.counter(
+                REQUESTS_TOTAL,
+                1,
+                &[("method", req.method().as_str()), ("status", &status)],
+            );
+        }
     }
 }
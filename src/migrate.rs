@@ -0,0 +1,69 @@
+use crate::{Vault, VaultError};
+
+/// The outcome of a [`migrate()`] run.
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    migrated: Vec<String>,
+    failed: Vec<(String, VaultError)>,
+}
+
+impl MigrationReport {
+    /// Returns the id of every upload moved to the target vault.
+    pub fn migrated(&self) -> &[String] {
+        &self.migrated
+    }
+
+    /// Returns the id of every upload that couldn't be moved, along with the error.
+    pub fn failed(&self) -> &[(String, VaultError)] {
+        &self.failed
+    }
+
+    /// Returns `true` when every upload was moved.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Moves every upload of the `source` vault into the `target` one, like when
+/// switching from a local disk to an object storage.
+///
+/// Uploads are copied through [`Vault::export_to()`], preserving their ids, metadata
+/// and offsets, so clients can keep resuming them from the new backend. Each upload
+/// is only removed from `source` once fully copied; failed uploads are left in
+/// place and reported. Migrations should run while no server is using the vaults.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use meteoritus::{migrate, LocalVault};
+///
+/// let report = migrate(
+///     &LocalVault::new("./tmp/uploads"),
+///     &LocalVault::new("/mnt/storage/uploads"),
+/// )
+/// .expect("Unable to list the uploads");
+///
+/// for (id, error) in report.failed() {
+///     eprintln!("Unable to migrate {id}: {error:?}");
+/// }
+/// ```
+pub fn migrate(
+    source: &dyn Vault,
+    target: &dyn Vault,
+) -> Result<MigrationReport, VaultError> {
+    let mut report = MigrationReport::default();
+
+    for file_id in source.list_files()? {
+        if let Err(e) = source.export_to(target, &file_id) {
+            report.failed.push((file_id, e));
+            continue;
+        }
+
+        match source.terminate_file(&file_id) {
+            Ok(_) => report.migrated.push(file_id),
+            Err(e) => report.failed.push((file_id, e)),
+        }
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,30 @@
+use std::{collections::HashSet, sync::Mutex};
+
+/// Holds the uploads paused by the application, rejecting their chunks until resumed.
+#[derive(Default)]
+pub(crate) struct PausedUploads {
+    entries: Mutex<HashSet<String>>,
+}
+
+impl PausedUploads {
+    pub(crate) fn insert(&self, file_id: &str) -> bool {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(file_id.to_string())
+    }
+
+    pub(crate) fn remove(&self, file_id: &str) -> bool {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(file_id)
+    }
+
+    pub(crate) fn contains(&self, file_id: &str) -> bool {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(file_id)
+    }
+}
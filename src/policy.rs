@@ -0,0 +1,201 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    ops::RangeInclusive,
+};
+
+use rocket::http::{HeaderMap, Status};
+
+use crate::{Built, ErrorCode, FileInfo, TusError};
+
+/// The upload constraints granted by a signed policy document, see [`PolicyValidator`].
+///
+/// A policy without constraints allows any upload.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UploadPolicy {
+    length: Option<RangeInclusive<u64>>,
+    allowed_metadata: Option<BTreeSet<String>>,
+    pinned_metadata: BTreeMap<String, String>,
+}
+
+impl UploadPolicy {
+    /// Creates an [`UploadPolicy`] without constraints.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Restricts the `Upload-Length` to the given range of bytes.
+    ///
+    /// Uploads with a deferred length are rejected, since their length is unknown
+    /// on creation.
+    pub fn with_length_range(mut self, range: RangeInclusive<u64>) -> Self {
+        self.length = Some(range);
+        self
+    }
+
+    /// Restricts the `Upload-Metadata` to the given keys.
+    pub fn with_allowed_metadata<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_metadata =
+            Some(keys.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Requires the `Upload-Metadata` to hold `key` with exactly the given `value`.
+    pub fn with_metadata_value(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.pinned_metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns the allowed range of the `Upload-Length`, if any.
+    pub fn length_range(&self) -> Option<&RangeInclusive<u64>> {
+        self.length.as_ref()
+    }
+
+    /// Checks the given upload against the policy constraints.
+    pub(crate) fn check(&self, file: &FileInfo<Built>) -> Result<(), TusError> {
+        if let Some(range) = &self.length {
+            if file.is_length_deferred() {
+                return Err(violation(
+                    "Upload-Length is required by the upload policy",
+                ));
+            }
+
+            if !range.contains(file.length()) {
+                return Err(violation(
+                    "Upload-Length is out of the upload policy range",
+                ));
+            }
+        }
+
+        let metadata = file
+            .metadata()
+            .as_ref()
+            .map(|metadata| metadata.decoded())
+            .unwrap_or_default();
+
+        if let Some(allowed) = &self.allowed_metadata {
+            if let Some(key) =
+                metadata.keys().find(|key| !allowed.contains(key.as_str()))
+            {
+                return Err(violation(format!(
+                    "Upload-Metadata key '{}' is not allowed by the upload policy",
+                    key
+                )));
+            }
+        }
+
+        for (key, value) in &self.pinned_metadata {
+            if metadata.get(key) != Some(value) {
+                return Err(violation(format!(
+                    "Upload-Metadata key '{}' doesn't match the upload policy",
+                    key
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn violation(message: impl Into<String>) -> TusError {
+    TusError::new(Status::Forbidden, ErrorCode::PolicyViolation, message)
+}
+
+/// A validator of signed policy documents supplied on upload creation, allowing
+/// untrusted clients, like browsers, to upload with a server-issued policy.
+///
+/// The policy document is read from the [`PolicyValidator::header_name()`] header
+/// of the creation request. The validator checks its signature, then returns the
+/// [`UploadPolicy`] it grants, which is enforced before the upload is created.
+/// Creation requests without a valid policy are rejected with `403 Forbidden`.
+///
+/// Validators are registered through [`Meteoritus::with_policy_validator()`](crate::Meteoritus::with_policy_validator).
+///
+/// # Example
+///
+/// ```rust
+/// use std::error::Error;
+/// use rocket::http::HeaderMap;
+/// use meteoritus::{PolicyValidator, UploadPolicy};
+///
+/// struct SharedSecretValidator {
+///     secret: String,
+/// }
+/// # impl SharedSecretValidator {
+/// #     fn is_signed(&self, _policy: &str, _signature: &str) -> bool { true }
+/// # }
+///
+/// impl PolicyValidator for SharedSecretValidator {
+///     fn validate(
+///         &self,
+///         document: &str,
+///         _headers: &HeaderMap<'_>,
+///     ) -> Result<UploadPolicy, Box<dyn Error + Send + Sync>> {
+///         let (policy, signature) = document
+///             .split_once('.')
+///             .ok_or("malformed policy document")?;
+///
+///         // Verify the signature, like an HMAC of the policy with `self.secret`
+///         if !self.is_signed(policy, signature) {
+///             return Err("invalid policy signature".into());
+///         }
+///
+///         Ok(UploadPolicy::new()
+///             .with_length_range(1..=10 * 1024 * 1024)
+///             .with_allowed_metadata(["filename", "filetype"]))
+///     }
+/// }
+/// ```
+pub trait PolicyValidator: Send + Sync {
+    /// Returns the name of the request header holding the policy document.
+    ///
+    /// Defaults to `Upload-Policy`.
+    fn header_name(&self) -> &str {
+        "Upload-Policy"
+    }
+
+    /// Validates the signed policy `document`, returning the granted [`UploadPolicy`].
+    ///
+    /// All creation request `headers` are available, allowing to check signatures
+    /// spread across several headers or bound to the requesting client.
+    fn validate(
+        &self,
+        document: &str,
+        headers: &HeaderMap<'_>,
+    ) -> Result<UploadPolicy, Box<dyn Error + Send + Sync>>;
+}
+
+/// Reads and validates the policy document of a creation request.
+pub(crate) fn validate_policy(
+    validator: &dyn PolicyValidator,
+    headers: &HeaderMap<'_>,
+) -> Result<UploadPolicy, TusError> {
+    let document = match headers.get_one(validator.header_name()) {
+        Some(document) if !document.is_empty() => document,
+        _ => {
+            return Err(TusError::new(
+                Status::Forbidden,
+                ErrorCode::MissingPolicy,
+                format!("Missing {} header", validator.header_name()),
+            ))
+        }
+    };
+
+    validator.validate(document, headers).map_err(|e| {
+        warn_!("Rejected upload policy: {}", e);
+
+        TusError::new(
+            Status::Forbidden,
+            ErrorCode::InvalidPolicy,
+            "Invalid upload policy",
+        )
+    })
+}
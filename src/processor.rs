@@ -0,0 +1,120 @@
+use std::{error::Error, io::Read, sync::Arc};
+
+use crate::{Completed, FileInfo, Vault};
+
+pub(crate) type ProcessedCallback =
+    Arc<dyn Fn(&FileInfo<Completed>) + Send + Sync>;
+
+/// A reference to an artifact derived from a completed upload by a [`Processor`],
+/// like a thumbnail or a transcoded video.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artifact {
+    key: String,
+    reference: String,
+}
+
+impl Artifact {
+    /// Creates an [`Artifact`] written back into the upload metadata as `key`, holding
+    /// the artifact `reference`, like its path or bucket key.
+    pub fn new(key: impl Into<String>, reference: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            reference: reference.into(),
+        }
+    }
+
+    /// Returns the metadata key of the artifact.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns where the artifact is stored.
+    pub fn reference(&self) -> &str {
+        &self.reference
+    }
+}
+
+/// A post-processing step executed after an upload is completed, producing derived
+/// [`Artifact`]s like thumbnails or transcodes.
+///
+/// Processors are registered through [`Meteoritus::with_processor()`](crate::Meteoritus::with_processor)
+/// and run in background, in registration order, before the termination policy is
+/// applied. The references of the produced artifacts are written back into the
+/// upload metadata.
+///
+/// # Example
+///
+/// ```rust
+/// use std::{error::Error, io::Read};
+/// use meteoritus::{Artifact, Completed, FileInfo, Processor};
+///
+/// struct SizeProcessor;
+///
+/// impl Processor for SizeProcessor {
+///     fn process(
+///         &self,
+///         file: &FileInfo<Completed>,
+///         content: &mut dyn Read,
+///     ) -> Result<Vec<Artifact>, Box<dyn Error + Send + Sync>> {
+///         let mut buf = Vec::new();
+///         content.read_to_end(&mut buf)?;
+///
+///         // Store a derived artifact, like a thumbnail, then reference it
+///         let reference = format!("thumbnails/{}.png", file.id());
+///
+///         Ok(vec![Artifact::new("thumbnail", reference)])
+///     }
+/// }
+/// ```
+pub trait Processor: Send + Sync {
+    /// Processes the `content` of the completed upload, returning the derived artifacts.
+    fn process(
+        &self,
+        file: &FileInfo<Completed>,
+        content: &mut dyn Read,
+    ) -> Result<Vec<Artifact>, Box<dyn Error + Send + Sync>>;
+}
+
+/// Runs the processors over the given upload, writing the produced artifacts back
+/// into its metadata.
+pub(crate) fn run_processors(
+    vault: &dyn Vault,
+    processors: &[Arc<dyn Processor>],
+    file: &mut FileInfo<Completed>,
+) {
+    let mut artifacts = Vec::new();
+
+    for processor in processors {
+        let mut content = match vault.open_content(file.id()) {
+            Ok(content) => content,
+            Err(_) => {
+                warn_!("Unable to read upload for processing: {}", file.id());
+                return;
+            }
+        };
+
+        match processor.process(file, &mut content) {
+            Ok(produced) => artifacts.extend(produced),
+            Err(e) => warn_!("Unable to process upload {}: {}", file.id(), e),
+        }
+    }
+
+    if artifacts.is_empty() {
+        return;
+    }
+
+    let mut metadata = file.metadata().to_owned().unwrap_or_default();
+
+    for artifact in &artifacts {
+        metadata.insert(artifact.key(), artifact.reference().as_bytes());
+    }
+
+    if vault
+        .update_metadata(file.id(), metadata.to_owned())
+        .is_err()
+    {
+        warn_!("Unable to store the artifacts of upload: {}", file.id());
+    }
+
+    file.set_metadata(metadata);
+}
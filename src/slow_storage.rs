@@ -0,0 +1,53 @@
+use std::{sync::Arc, time::Duration};
+
+pub(crate) type SlowStorageCallback =
+    Arc<dyn Fn(&SlowStorageWarning) + Send + Sync>;
+
+/// A vault write exceeding the latency threshold of
+/// [`Meteoritus::on_slow_storage()`](crate::Meteoritus::on_slow_storage), an early
+/// signal of a degraded storage before uploads start timing out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlowStorageWarning {
+    file_id: String,
+    operation: &'static str,
+    bytes: u64,
+    duration: Duration,
+}
+
+impl SlowStorageWarning {
+    pub(crate) fn new(
+        file_id: &str,
+        operation: &'static str,
+        bytes: u64,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            file_id: file_id.to_string(),
+            operation,
+            bytes,
+            duration,
+        }
+    }
+
+    /// Returns the id of the upload being written.
+    pub fn file_id(&self) -> &str {
+        &self.file_id
+    }
+
+    /// Returns the slow [`Vault`](crate::Vault) operation, either `create_file` or
+    /// `patch_file`.
+    pub fn operation(&self) -> &str {
+        self.operation
+    }
+
+    /// Returns the number of bytes written, the upload length for `create_file`
+    /// and the chunk size for `patch_file`.
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Returns how long the write took.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
@@ -0,0 +1,57 @@
+use std::io::Read;
+
+use crate::{Completed, FileInfo, Vault};
+
+/// The number of leading content bytes inspected by the detector.
+const SNIFF_LENGTH: u64 = 8192;
+
+/// Defines how completed uploads whose content doesn't match their declared `filetype`
+/// metadata are handled, see [`Meteoritus::with_mime_sniffing()`](crate::Meteoritus::with_mime_sniffing).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MimeMismatchPolicy {
+    /// Accepts the upload, only exposing the sniffed type to the `on_completed` callback.
+    #[default]
+    Allow,
+    /// Quarantines the upload, keeping it for inspection.
+    Quarantine,
+    /// Terminates the upload, responding `415 Unsupported Media Type` to the last `PATCH`.
+    Reject,
+}
+
+/// Detects the content type of the given upload from its magic bytes.
+pub(crate) fn sniff(vault: &dyn Vault, file_id: &str) -> Option<String> {
+    let mut buf = Vec::new();
+
+    vault
+        .open_content(file_id)
+        .ok()?
+        .take(SNIFF_LENGTH)
+        .read_to_end(&mut buf)
+        .ok()?;
+
+    infer::get(&buf).map(|kind| kind.mime_type().to_string())
+}
+
+/// Returns `true` when the upload declares a `filetype` differing from its sniffed type.
+///
+/// Uploads without a declared or a detectable type are never considered mismatched.
+pub(crate) fn is_mismatch(file: &FileInfo<Completed>) -> bool {
+    let Some(sniffed) = file.sniffed_type() else {
+        return false;
+    };
+
+    let declared = file
+        .metadata()
+        .as_ref()
+        .and_then(|metadata| metadata.get_raw("filetype").ok())
+        .and_then(|value| String::from_utf8(value).ok());
+
+    let Some(declared) = declared else {
+        return false;
+    };
+
+    /* Comparing the essence only, ignoring parameters like charset */
+    let essence = declared.split(';').next().unwrap_or_default().trim();
+
+    !essence.eq_ignore_ascii_case(sniffed)
+}
@@ -0,0 +1,38 @@
+use rocket::http::Status;
+
+use crate::{ErrorCode, FileInfo, Metadata, TusError};
+
+/// The metadata key holding the storage class hint of an upload.
+pub(crate) const STORAGE_CLASS_KEY: &str = "meteoritus-storage-class";
+
+/// Returns the storage class hint declared by the given metadata, if any.
+pub(crate) fn storage_class(metadata: Option<&Metadata>) -> Option<String> {
+    let class = metadata?.get_raw(STORAGE_CLASS_KEY).ok()?;
+
+    String::from_utf8(class).ok()
+}
+
+/// Checks that the storage class hint of an upload, if any, is one of the
+/// `allowed` classes.
+pub(crate) fn validate_storage_class<S>(
+    file: &FileInfo<S>,
+    allowed: &[String],
+) -> Result<(), TusError> {
+    let declared = file
+        .metadata()
+        .as_ref()
+        .is_some_and(|metadata| metadata.get_raw(STORAGE_CLASS_KEY).is_ok());
+
+    if !declared {
+        return Ok(());
+    }
+
+    match file.storage_class() {
+        Some(class) if allowed.contains(&class) => Ok(()),
+        _ => Err(TusError::new(
+            Status::BadRequest,
+            ErrorCode::InvalidStorageClass,
+            format!("Invalid {} metadata", STORAGE_CLASS_KEY),
+        )),
+    }
+}
@@ -0,0 +1,432 @@
+use std::{
+    collections::HashMap,
+    fs,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use rocket::{
+    serde::{Deserialize, Serialize},
+    tokio,
+};
+
+use crate::{pause::PausedUploads, Clock, FileInfo, Terminated, Vault};
+
+pub(crate) type StalledCallback =
+    Arc<dyn Fn(&FileInfo<Terminated>) + Send + Sync>;
+
+pub(crate) type ExpiredCallback =
+    Arc<dyn Fn(&FileInfo<Terminated>) + Send + Sync>;
+
+/// Reports an upload terminated by the sweeper through the same path as the ones
+/// terminated by their client.
+pub(crate) type TerminatedCallback =
+    Arc<dyn Fn(&FileInfo<Terminated>) + Send + Sync>;
+
+/// Specifies when completed uploads are auto-terminated.
+///
+/// Auto-termination only happens when [`Meteoritus::keep_on_disk()`](crate::Meteoritus::keep_on_disk) was not configured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TerminationPolicy {
+    /// Terminates the upload right after the `on_completed` callback returns.
+    #[default]
+    Immediate,
+    /// Terminates the upload once the given grace period has elapsed after completion.
+    ///
+    /// The termination is carried out by the background sweeper, within a
+    /// [`Meteoritus::with_sweep_interval()`](crate::Meteoritus::with_sweep_interval)
+    /// after the grace period. Its time is persisted along with the upload by vaults
+    /// supporting [`Vault::schedule_termination()`], so it survives restarts.
+    Delayed(Duration),
+    /// Delegates the termination to the background sweeper, which runs every
+    /// [`Meteoritus::with_sweep_interval()`](crate::Meteoritus::with_sweep_interval).
+    Sweeper,
+    /// Only terminates the upload when the application calls [`Meteoritus::release()`](crate::Meteoritus::release).
+    OnRelease,
+}
+
+/// Holds the uploads awaiting termination by the background sweeper, along with
+/// when they are due.
+#[derive(Default)]
+pub(crate) struct SweeperQueue {
+    pending: Mutex<HashMap<String, SystemTime>>,
+}
+
+impl SweeperQueue {
+    /// Schedules the termination of an upload by the next sweep.
+    pub(crate) fn schedule(&self, file_id: &str) {
+        self.schedule_at(file_id, UNIX_EPOCH);
+    }
+
+    /// Schedules the termination of an upload by the first sweep past `time`.
+    pub(crate) fn schedule_at(&self, file_id: &str, time: SystemTime) {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(file_id.to_string(), time);
+    }
+
+    pub(crate) fn remove(&self, file_id: &str) {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(file_id);
+    }
+
+    /// Removes and returns the uploads due at `now`.
+    fn drain_due(&self, now: SystemTime) -> Vec<String> {
+        let mut pending =
+            self.pending.lock().unwrap_or_else(|e| e.into_inner());
+
+        let due: Vec<String> = pending
+            .iter()
+            .filter(|(_, time)| **time <= now)
+            .map(|(file_id, _)| file_id.to_owned())
+            .collect();
+
+        for file_id in &due {
+            pending.remove(file_id);
+        }
+
+        due
+    }
+}
+
+/// Holds the uploads terminated by the background sweeper until the next request,
+/// which hands the Rocket instance required by the `on_termination` callback.
+#[derive(Default)]
+pub(crate) struct SweptUploads {
+    terminated: Mutex<Vec<FileInfo<Terminated>>>,
+}
+
+impl SweptUploads {
+    pub(crate) fn push(&self, file: FileInfo<Terminated>) {
+        self.terminated
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(file);
+    }
+
+    pub(crate) fn drain(&self) -> Vec<FileInfo<Terminated>> {
+        std::mem::take(
+            &mut *self.terminated.lock().unwrap_or_else(|e| e.into_inner()),
+        )
+    }
+}
+
+/// Holds when each upload in progress last received a chunk.
+#[derive(Default)]
+pub(crate) struct ActivityTracker {
+    last_activity: Mutex<HashMap<String, SystemTime>>,
+}
+
+impl ActivityTracker {
+    pub(crate) fn touch(&self, file_id: &str, now: SystemTime) {
+        self.last_activity
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(file_id.to_string(), now);
+    }
+
+    /// Returns when the given upload last received a chunk, if tracked.
+    pub(crate) fn last_activity(&self, file_id: &str) -> Option<SystemTime> {
+        self.last_activity
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(file_id)
+            .copied()
+    }
+
+    pub(crate) fn remove(&self, file_id: &str) {
+        self.last_activity
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(file_id);
+    }
+
+    /// Removes and returns the uploads without activity within the given `timeout`.
+    fn drain_stalled(&self, timeout: Duration, now: SystemTime) -> Vec<String> {
+        let mut last_activity =
+            self.last_activity.lock().unwrap_or_else(|e| e.into_inner());
+
+        let stalled: Vec<String> = last_activity
+            .iter()
+            .filter(|(_, last)| {
+                now.duration_since(**last).unwrap_or_default() >= timeout
+            })
+            .map(|(file_id, _)| file_id.to_owned())
+            .collect();
+
+        for file_id in &stalled {
+            last_activity.remove(file_id);
+        }
+
+        stalled
+    }
+}
+
+/// The deadline of an upload, persisted as the UNIX seconds through its
+/// [`Extensions`](crate::Extensions).
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct UploadDeadline(u64);
+
+impl UploadDeadline {
+    pub(crate) fn new(deadline: SystemTime) -> Self {
+        /* Times before the epoch are already past, so saturating is harmless */
+        let secs = deadline
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+
+        Self(secs)
+    }
+
+    pub(crate) fn time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.0)
+    }
+}
+
+/// The time a completed upload is due to be terminated, persisted like an
+/// [`UploadDeadline`] through its [`Extensions`](crate::Extensions).
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct ScheduledTermination(UploadDeadline);
+
+impl ScheduledTermination {
+    pub(crate) fn new(time: SystemTime) -> Self {
+        Self(UploadDeadline::new(time))
+    }
+
+    pub(crate) fn time(&self) -> SystemTime {
+        self.0.time()
+    }
+}
+
+/// Holds the deadline of each upload in progress having one.
+#[derive(Default)]
+pub(crate) struct Deadlines {
+    deadlines: Mutex<HashMap<String, SystemTime>>,
+}
+
+impl Deadlines {
+    pub(crate) fn insert(&self, file_id: &str, deadline: SystemTime) {
+        self.deadlines
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(file_id.to_string(), deadline);
+    }
+
+    pub(crate) fn remove(&self, file_id: &str) {
+        self.deadlines
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(file_id);
+    }
+
+    /// Removes and returns the uploads whose deadline is past at `now`.
+    fn drain_expired(&self, now: SystemTime) -> Vec<String> {
+        let mut deadlines =
+            self.deadlines.lock().unwrap_or_else(|e| e.into_inner());
+
+        let expired: Vec<String> = deadlines
+            .iter()
+            .filter(|(_, deadline)| **deadline <= now)
+            .map(|(file_id, _)| file_id.to_owned())
+            .collect();
+
+        for file_id in &expired {
+            deadlines.remove(file_id);
+        }
+
+        expired
+    }
+}
+
+/// The background task terminating the uploads scheduled into its queue,
+/// along with the stalled uploads, the ones past their deadline and the least
+/// recently active ones beyond the maximum retained.
+pub(crate) struct Sweeper {
+    pub(crate) interval: Duration,
+    pub(crate) queue: Arc<SweeperQueue>,
+    pub(crate) vault: Arc<dyn Vault>,
+    pub(crate) stall_timeout: Option<Duration>,
+    pub(crate) max_retained_uploads: Option<usize>,
+    pub(crate) activity: Arc<ActivityTracker>,
+    pub(crate) deadlines: Arc<Deadlines>,
+    pub(crate) on_stalled: Option<StalledCallback>,
+    pub(crate) on_expired: Option<ExpiredCallback>,
+    pub(crate) on_terminated: TerminatedCallback,
+    pub(crate) paused_uploads: Arc<PausedUploads>,
+    pub(crate) clock: Arc<dyn Clock>,
+}
+
+impl Sweeper {
+    pub(crate) fn spawn(self) {
+        tokio::spawn(async move {
+            self.restore_scheduled();
+
+            let mut interval = tokio::time::interval(self.interval);
+
+            loop {
+                interval.tick().await;
+                self.sweep();
+            }
+        });
+    }
+
+    /// Schedules again the terminations persisted along with the uploads, which
+    /// the queue lost on restart.
+    fn restore_scheduled(&self) {
+        let Ok(file_ids) = self.vault.list_files() else {
+            return;
+        };
+
+        for file_id in file_ids {
+            let scheduled = self
+                .vault
+                .get_file(&file_id)
+                .ok()
+                .and_then(|file| file.scheduled_termination());
+
+            if let Some(time) = scheduled {
+                self.queue.schedule_at(&file_id, time);
+            }
+        }
+    }
+
+    fn sweep(&self) {
+        for file_id in self.queue.drain_due(self.clock.now()) {
+            /* Quarantined uploads are kept for inspection */
+            if self
+                .vault
+                .get_file(&file_id)
+                .is_ok_and(|file| file.is_quarantined())
+            {
+                continue;
+            }
+
+            match self.vault.terminate_file(&file_id) {
+                Ok(file) => (self.on_terminated)(&file),
+                Err(_) => {
+                    warn_!(
+                        "Sweeper was unable to terminate upload: {}",
+                        file_id
+                    )
+                }
+            }
+        }
+
+        for file_id in self.deadlines.drain_expired(self.clock.now()) {
+            /* Uploads past their deadline are terminated even while paused */
+            self.activity.remove(&file_id);
+            self.paused_uploads.remove(&file_id);
+
+            match self.vault.terminate_file(&file_id) {
+                Ok(file) => {
+                    (self.on_terminated)(&file);
+
+                    if let Some(callback) = &self.on_expired {
+                        callback(&file.mark_as_expired());
+                    }
+                }
+                Err(_) => {
+                    warn_!(
+                        "Sweeper was unable to terminate expired upload: {}",
+                        file_id
+                    )
+                }
+            }
+        }
+
+        if let Some(limit) = self.max_retained_uploads {
+            self.evict(limit);
+        }
+
+        let Some(timeout) = self.stall_timeout else {
+            return;
+        };
+
+        for file_id in self.activity.drain_stalled(timeout, self.clock.now()) {
+            /* Paused uploads are kept waiting on purpose */
+            if self.paused_uploads.contains(&file_id) {
+                self.activity.touch(&file_id, self.clock.now());
+                continue;
+            }
+
+            match self.vault.terminate_file(&file_id) {
+                Ok(file) => {
+                    (self.on_terminated)(&file);
+
+                    if let Some(callback) = &self.on_stalled {
+                        callback(&file.mark_as_expired());
+                    }
+                }
+                Err(_) => {
+                    warn_!(
+                        "Sweeper was unable to terminate stalled upload: {}",
+                        file_id
+                    )
+                }
+            }
+        }
+    }
+
+    /// Evicts the least recently active uploads in progress while more uploads
+    /// than the `limit` are stored.
+    fn evict(&self, limit: usize) {
+        let file_ids = match self.vault.list_files() {
+            Ok(file_ids) => file_ids,
+            Err(e) => {
+                warn_!("Unable to list the uploads to evict: {}", e);
+                return;
+            }
+        };
+
+        let excess = file_ids.len().saturating_sub(limit);
+
+        if excess == 0 {
+            return;
+        }
+
+        let mut candidates: Vec<(SystemTime, String)> = file_ids
+            .into_iter()
+            .filter_map(|file_id| {
+                let file = self.vault.get_file(&file_id).ok()?;
+
+                let completed = !file.is_length_deferred()
+                    && file.offset() == file.length();
+
+                if completed || file.is_quarantined() {
+                    return None;
+                }
+
+                /* Uploads untouched since the launch are dated by their content */
+                let last_activity = self
+                    .activity
+                    .last_activity(&file_id)
+                    .or_else(|| {
+                        let path = file.storage_ref().as_path()?.to_owned();
+                        fs::metadata(path).and_then(|m| m.modified()).ok()
+                    })
+                    .unwrap_or(UNIX_EPOCH);
+
+                Some((last_activity, file_id))
+            })
+            .collect();
+
+        candidates.sort();
+
+        for (_, file_id) in candidates.into_iter().take(excess) {
+            match self.vault.terminate_file(&file_id) {
+                Ok(file) => {
+                    info_!("Evicted upload: {}", file_id);
+                    (self.on_terminated)(&file);
+                }
+                Err(e) => {
+                    warn_!("Unable to evict upload {}: {}", file_id, e);
+                }
+            }
+        }
+    }
+}
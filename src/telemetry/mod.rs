@@ -0,0 +1,86 @@
+mod prometheus;
+
+pub use prometheus::PrometheusSink;
+
+/// Labels attached to a metric sample, as `(name, value)` pairs.
+pub type Labels<'a> = &'a [(&'a str, &'a str)];
+
+/// Total of responses of the tus routes, labeled by `method` and `status`.
+pub(crate) const REQUESTS_TOTAL: &str = "meteoritus_requests_total";
+/// Total of upload lifecycle events, labeled by `kind`, like `created`.
+pub(crate) const UPLOAD_EVENTS_TOTAL: &str = "meteoritus_upload_events_total";
+/// Number of uploads in progress tracked by the server.
+pub(crate) const UPLOADS_IN_PROGRESS: &str = "meteoritus_uploads_in_progress";
+/// Time taken by clients to upload, from creation to completion.
+pub(crate) const UPLOAD_DURATION_SECONDS: &str =
+    "meteoritus_upload_duration_seconds";
+/// Total of upload bytes received through `PATCH` requests.
+pub(crate) const RECEIVED_BYTES_TOTAL: &str = "meteoritus_received_bytes_total";
+/// Time taken by vault operations, labeled by `operation`.
+pub(crate) const VAULT_OPERATION_SECONDS: &str =
+    "meteoritus_vault_operation_seconds";
+/// Total of failed vault operations, labeled by `operation`.
+pub(crate) const VAULT_ERRORS_TOTAL: &str = "meteoritus_vault_errors_total";
+
+/// An exporter of the metrics recorded by the tus handlers and the vault, so they
+/// can be shipped to any monitoring stack, like StatsD or Datadog.
+///
+/// The following metrics are recorded:
+///
+/// | Metric                               | Kind      | Labels             |
+/// |--------------------------------------|-----------|--------------------|
+/// | `meteoritus_requests_total`          | Counter   | `method`, `status` |
+/// | `meteoritus_upload_events_total`     | Counter   | `kind`             |
+/// | `meteoritus_uploads_in_progress`     | Gauge     |                    |
+/// | `meteoritus_upload_duration_seconds` | Histogram |                    |
+/// | `meteoritus_received_bytes_total`    | Counter   |                    |
+/// | `meteoritus_vault_operation_seconds` | Histogram | `operation`        |
+/// | `meteoritus_vault_errors_total`      | Counter   | `operation`        |
+///
+/// Since it is called from the request handlers, implementations should not block.
+///
+/// Sinks are registered through [`Meteoritus::with_telemetry()`](crate::Meteoritus::with_telemetry).
+///
+/// # Example
+///
+/// ```rust
+/// use meteoritus::{Labels, TelemetrySink};
+///
+/// struct StdoutSink;
+///
+/// impl TelemetrySink for StdoutSink {
+///     fn counter(&self, name: &str, value: u64, labels: Labels<'_>) {
+///         println!("{name}:{value}|c {labels:?}");
+///     }
+///
+///     fn gauge(&self, name: &str, value: f64, labels: Labels<'_>) {
+///         println!("{name}:{value}|g {labels:?}");
+///     }
+///
+///     fn histogram(&self, name: &str, value: f64, labels: Labels<'_>) {
+///         println!("{name}:{value}|h {labels:?}");
+///     }
+/// }
+/// ```
+pub trait TelemetrySink: Send + Sync {
+    /// Increments the counter `name` by `value`.
+    fn counter(&self, name: &str, value: u64, labels: Labels<'_>);
+
+    /// Sets the gauge `name` to `value`.
+    fn gauge(&self, name: &str, value: f64, labels: Labels<'_>);
+
+    /// Records an observation of `value` into the histogram `name`.
+    fn histogram(&self, name: &str, value: f64, labels: Labels<'_>);
+}
+
+/// A [`TelemetrySink`] discarding every metric, used when no sink is configured.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopSink;
+
+impl TelemetrySink for NoopSink {
+    fn counter(&self, _name: &str, _value: u64, _labels: Labels<'_>) {}
+
+    fn gauge(&self, _name: &str, _value: f64, _labels: Labels<'_>) {}
+
+    fn histogram(&self, _name: &str, _value: f64, _labels: Labels<'_>) {}
+}
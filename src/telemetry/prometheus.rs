@@ -0,0 +1,258 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use super::{Labels, TelemetrySink};
+
+/// The default histogram buckets, matching the Prometheus client libraries.
+const DEFAULT_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Identifies a metric series by its sorted labels.
+type Series = Vec<(String, String)>;
+
+#[derive(Default)]
+struct Histogram {
+    /// Number of observations per bucket, not cumulative.
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+enum Family {
+    Counter(BTreeMap<Series, u64>),
+    Gauge(BTreeMap<Series, f64>),
+    Histogram(Vec<f64>, BTreeMap<Series, Histogram>),
+}
+
+#[derive(Default)]
+struct Registry {
+    families: BTreeMap<String, Family>,
+    buckets: HashMap<String, Vec<f64>>,
+}
+
+/// A [`TelemetrySink`] aggregating metrics in memory, rendered in the Prometheus
+/// [text exposition format](https://prometheus.io/docs/instrumenting/exposition_formats/)
+/// by [`PrometheusSink::render()`].
+///
+/// Clones share the same metrics, so a clone can be managed by Rocket and rendered
+/// by a `/metrics` route.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::{Ignite, State};
+/// use meteoritus::{Meteoritus, PrometheusSink};
+///
+/// #[get("/metrics")]
+/// fn metrics(sink: &State<PrometheusSink>) -> String {
+///     sink.render()
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let sink = PrometheusSink::new()
+///         .with_buckets("meteoritus_upload_duration_seconds", [1.0, 10.0, 60.0, 600.0]);
+///
+///     let meteoritus: Meteoritus<Ignite> = Meteoritus::new()
+///         .with_telemetry(sink.clone())
+///         .build();
+///
+///     rocket::build()
+///         .attach(meteoritus)
+///         .manage(sink)
+///         .mount("/", routes![metrics])
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct PrometheusSink {
+    registry: Arc<Mutex<Registry>>,
+}
+
+impl PrometheusSink {
+    /// Creates an empty [`PrometheusSink`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the upper bounds of the buckets of the histogram `name`, defaulting to
+    /// the buckets of the Prometheus client libraries, from 5ms to 10s.
+    pub fn with_buckets(
+        self,
+        name: impl Into<String>,
+        buckets: impl IntoIterator<Item = f64>,
+    ) -> Self {
+        let mut buckets: Vec<f64> = buckets.into_iter().collect();
+        buckets.sort_by(f64::total_cmp);
+
+        self.registry().buckets.insert(name.into(), buckets);
+        self
+    }
+
+    /// Renders every recorded metric in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let registry = self.registry();
+        let mut out = String::new();
+
+        for (name, family) in &registry.families {
+            match family {
+                Family::Counter(series) => {
+                    let _ = writeln!(out, "# TYPE {name} counter");
+
+                    for (labels, value) in series {
+                        let labels = format_labels(labels, None);
+                        let _ = writeln!(out, "{name}{labels} {value}");
+                    }
+                }
+                Family::Gauge(series) => {
+                    let _ = writeln!(out, "# TYPE {name} gauge");
+
+                    for (labels, value) in series {
+                        let labels = format_labels(labels, None);
+                        let _ = writeln!(out, "{name}{labels} {value}");
+                    }
+                }
+                Family::Histogram(bounds, series) => {
+                    let _ = writeln!(out, "# TYPE {name} histogram");
+
+                    for (labels, histogram) in series {
+                        let mut cumulative = 0;
+
+                        for (bound, count) in
+                            bounds.iter().zip(&histogram.buckets)
+                        {
+                            cumulative += count;
+
+                            let le = format_labels(labels, Some(*bound));
+                            let _ =
+                                writeln!(out, "{name}_bucket{le} {cumulative}");
+                        }
+
+                        let le = format_labels(labels, Some(f64::INFINITY));
+                        let _ = writeln!(
+                            out,
+                            "{name}_bucket{le} {}",
+                            histogram.count
+                        );
+
+                        let labels = format_labels(labels, None);
+                        let _ = writeln!(
+                            out,
+                            "{name}_sum{labels} {}",
+                            histogram.sum
+                        );
+                        let _ = writeln!(
+                            out,
+                            "{name}_count{labels} {}",
+                            histogram.count
+                        );
+                    }
+                }
+            }
+        }
+
+        out
+    }
+
+    fn registry(&self) -> MutexGuard<'_, Registry> {
+        self.registry.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl TelemetrySink for PrometheusSink {
+    fn counter(&self, name: &str, value: u64, labels: Labels<'_>) {
+        let mut registry = self.registry();
+
+        let family = registry
+            .families
+            .entry(name.to_string())
+            .or_insert_with(|| Family::Counter(BTreeMap::new()));
+
+        /* Ignoring samples of a metric already recorded with another kind */
+        if let Family::Counter(series) = family {
+            *series.entry(series_of(labels)).or_default() += value;
+        }
+    }
+
+    fn gauge(&self, name: &str, value: f64, labels: Labels<'_>) {
+        let mut registry = self.registry();
+
+        let family = registry
+            .families
+            .entry(name.to_string())
+            .or_insert_with(|| Family::Gauge(BTreeMap::new()));
+
+        if let Family::Gauge(series) = family {
+            series.insert(series_of(labels), value);
+        }
+    }
+
+    fn histogram(&self, name: &str, value: f64, labels: Labels<'_>) {
+        let mut registry = self.registry();
+
+        let bounds = registry
+            .buckets
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| DEFAULT_BUCKETS.to_vec());
+
+        let family = registry
+            .families
+            .entry(name.to_string())
+            .or_insert_with(|| Family::Histogram(bounds, BTreeMap::new()));
+
+        if let Family::Histogram(bounds, series) = family {
+            let histogram = series.entry(series_of(labels)).or_default();
+
+            histogram.buckets.resize(bounds.len(), 0);
+
+            if let Some(index) = bounds.iter().position(|bound| value <= *bound)
+            {
+                histogram.buckets[index] += 1;
+            }
+
+            histogram.sum += value;
+            histogram.count += 1;
+        }
+    }
+}
+
+fn series_of(labels: Labels<'_>) -> Series {
+    let mut series: Series = labels
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+    series.sort();
+    series
+}
+
+/// Formats the `labels` of a sample, along with the `le` label of histogram buckets.
+fn format_labels(labels: &Series, le: Option<f64>) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+        .collect();
+
+    match le {
+        Some(bound) if bound.is_infinite() => pairs.push("le=\"+Inf\"".into()),
+        Some(bound) => pairs.push(format!("le=\"{}\"", bound)),
+        None => (),
+    }
+
+    match pairs.is_empty() {
+        true => String::new(),
+        false => format!("{{{}}}", pairs.join(",")),
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
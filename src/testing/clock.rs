@@ -0,0 +1,64 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use crate::clock::Clock;
+
+/// A [`Clock`] that only moves when told to, so time based behaviors can be
+/// verified without sleeping in tests.
+///
+/// Clones share the same time, so a clone can be handed to [`Meteoritus`](crate::Meteoritus)
+/// while the test keeps advancing the original.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::{Duration, SystemTime};
+/// use meteoritus::{testing::ManualClock, Clock, Meteoritus};
+///
+/// let clock = ManualClock::new(SystemTime::UNIX_EPOCH);
+///
+/// let meteoritus = Meteoritus::new()
+///     .with_clock(clock.clone())
+///     .with_stall_timeout(Duration::from_secs(60));
+///
+/// clock.advance(Duration::from_secs(90));
+///
+/// assert_eq!(clock.now(), SystemTime::UNIX_EPOCH + Duration::from_secs(90));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    now: Arc<Mutex<SystemTime>>,
+}
+
+impl ManualClock {
+    /// Creates a [`ManualClock`] frozen at the given time.
+    pub fn new(now: SystemTime) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    /// Moves the clock forward by the given `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) += duration;
+    }
+
+    /// Sets the clock to the given time.
+    pub fn set(&self, now: SystemTime) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = now;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new(SystemTime::now())
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> SystemTime {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
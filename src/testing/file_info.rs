@@ -0,0 +1,133 @@
+use uuid::Uuid;
+
+use crate::fs::{
+    Built, Completed, Created, FileInfo, Metadata, Quarantined, Terminated,
+};
+
+/// A builder for [`FileInfo`] instances in arbitrary states.
+///
+/// Useful to unit test callbacks without going through a real upload.
+///
+/// # Example
+///
+/// ```rust
+/// use meteoritus::{testing::FileInfoBuilder, Metadata};
+///
+/// let file = FileInfoBuilder::new(1024)
+///     .id("my-upload")
+///     .offset(512)
+///     .metadata(Metadata::try_from("filename bXlfdmlkZW8ubXA0").unwrap())
+///     .created();
+///
+/// assert_eq!(file.id(), "my-upload");
+/// assert_eq!(file.offset(), &512);
+///
+/// let file = FileInfoBuilder::new(1024).completed();
+/// assert_eq!(file.length(), &1024);
+///
+/// let file = FileInfoBuilder::new(1024).quarantined();
+/// assert!(file.is_quarantined());
+/// ```
+#[derive(Debug)]
+pub struct FileInfoBuilder {
+    id: String,
+    length: u64,
+    offset: u64,
+    metadata: Option<Metadata>,
+    file_name: String,
+}
+
+impl FileInfoBuilder {
+    /// Creates a builder for a file of `length` bytes with a random id.
+    pub fn new(length: u64) -> Self {
+        let id = Uuid::new_v4().simple().to_string();
+
+        Self {
+            file_name: format!("./tmp/files/{}/file", id),
+            id,
+            length,
+            offset: 0,
+            metadata: None,
+        }
+    }
+
+    /// Overrides the generated file id.
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = id.to_string();
+        self
+    }
+
+    /// Sets the current upload offset.
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Sets the file [`Metadata`].
+    pub fn metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Sets where the file is located.
+    pub fn file_name(mut self, file_name: &str) -> Self {
+        self.file_name = file_name.to_string();
+        self
+    }
+
+    /// Returns a [`FileInfo`] into the [`Built`] state.
+    pub fn built(self) -> FileInfo<Built> {
+        let file = FileInfo::new(self.length).with_raw_id(self.id);
+
+        match self.metadata {
+            Some(metadata) => file.with_metadata(metadata).build(),
+            None => file.build(),
+        }
+    }
+
+    /// Returns a [`FileInfo`] into the [`Created`] state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset is greater than the file length.
+    pub fn created(self) -> FileInfo<Created> {
+        let offset = self.offset;
+        let file_name = self.file_name.clone();
+
+        let mut file = self.built().mark_as_created(&file_name);
+
+        file.set_offset(offset)
+            .expect("offset must not be greater than the file length");
+
+        file
+    }
+
+    /// Returns a [`FileInfo`] into the [`Completed`] state.
+    ///
+    /// The offset is always set to the file length.
+    pub fn completed(mut self) -> FileInfo<Completed> {
+        self.offset = self.length;
+
+        self.created()
+            .check_completion()
+            .expect("offset should match the file length")
+    }
+
+    /// Returns a [`FileInfo`] into the [`Terminated`] state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset is greater than the file length.
+    pub fn terminated(self) -> FileInfo<Terminated> {
+        self.created().mark_as_terminated()
+    }
+
+    /// Returns a [`FileInfo`] into the [`Quarantined`] state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset is greater than the file length.
+    pub fn quarantined(self) -> FileInfo<Quarantined> {
+        self.created().mark_as_quarantined()
+    }
+}
@@ -0,0 +1,290 @@
+use std::{
+    collections::HashMap,
+    io::{ErrorKind, Read},
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::fs::{
+    Built, Created, FileInfo, Metadata, PatchOption, Quarantined, Terminated,
+    UploadStats, Vault, VaultError,
+};
+
+use super::Operation;
+
+/// A [`Vault`] decorator that injects storage faults into another vault.
+///
+/// It helps to test client retry behavior and application error handling
+/// against realistic storage failures, like:
+///
+/// * every Nth `patch_file` call failing.
+/// * slow operations, by blocking the calling thread for a given duration.
+/// * partial writes, where only the first bytes of a chunk get stored.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use meteoritus::{
+///     testing::{FileInfoBuilder, FlakyVault, MockVault, Operation},
+///     PatchOption, Vault,
+/// };
+///
+/// let mock = MockVault::new();
+/// mock.insert(FileInfoBuilder::new(10).id("flaky").created(), vec![]);
+///
+/// let vault = FlakyVault::new(mock)
+///     .fail_every_nth_patch(2)
+///     .with_partial_writes(4)
+///     .with_latency(Operation::GetFile, Duration::from_millis(10));
+///
+/// let mut chunk = b"0123456789".to_vec();
+///
+/// // Only the first 4 bytes are stored.
+/// let Ok(PatchOption::Patched(offset)) = vault.patch_file("flaky", &mut chunk, 0)
+/// else {
+///     panic!("first patch should succeed");
+/// };
+/// assert_eq!(offset, 4);
+///
+/// // The second patch always fails.
+/// assert!(vault.patch_file("flaky", &mut chunk[4..], 4).is_err());
+/// ```
+pub struct FlakyVault<V: Vault> {
+    vault: V,
+    patch_failure_interval: Option<u64>,
+    partial_write_size: Option<usize>,
+    latency: HashMap<Operation, Duration>,
+    patch_count: AtomicU64,
+}
+
+impl<V: Vault> FlakyVault<V> {
+    /// Wraps the given `vault` without any fault configured.
+    pub fn new(vault: V) -> Self {
+        Self {
+            vault,
+            patch_failure_interval: None,
+            partial_write_size: None,
+            latency: HashMap::new(),
+            patch_count: AtomicU64::new(0),
+        }
+    }
+
+    /// Makes every `n`th call of `patch_file` fail without writing any byte.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn fail_every_nth_patch(mut self, n: u64) -> Self {
+        assert!(n > 0, "patch failure interval must be greater than zero");
+
+        self.patch_failure_interval = Some(n);
+        self
+    }
+
+    /// Limits each `patch_file` call to store at most `max_bytes` of the chunk.
+    pub fn with_partial_writes(mut self, max_bytes: usize) -> Self {
+        self.partial_write_size = Some(max_bytes);
+        self
+    }
+
+    /// Delays every call of the given `operation` by `duration`.
+    ///
+    /// **Note:** Since [`Vault`] operations are synchronous, the delay blocks the calling thread.
+    pub fn with_latency(
+        mut self,
+        operation: Operation,
+        duration: Duration,
+    ) -> Self {
+        self.latency.insert(operation, duration);
+        self
+    }
+
+    /// Returns a reference to the wrapped vault.
+    pub fn inner(&self) -> &V {
+        &self.vault
+    }
+
+    fn delay(&self, operation: Operation) {
+        if let Some(duration) = self.latency.get(&operation) {
+            thread::sleep(*duration);
+        }
+    }
+}
+
+impl<V: Vault> Vault for FlakyVault<V> {
+    fn build_file(
+        &self,
+        length: u64,
+        metadata: Option<&str>,
+    ) -> Result<FileInfo<Built>, VaultError> {
+        self.delay(Operation::BuildFile);
+        self.vault.build_file(length, metadata)
+    }
+
+    fn create_file(
+        &self,
+        file: FileInfo<Built>,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.delay(Operation::CreateFile);
+        self.vault.create_file(file)
+    }
+
+    fn exists(&self, file_id: &str) -> bool {
+        self.delay(Operation::Exists);
+        self.vault.exists(file_id)
+    }
+
+    fn get_file(&self, file_id: &str) -> Result<FileInfo<Created>, VaultError> {
+        self.delay(Operation::GetFile);
+        self.vault.get_file(file_id)
+    }
+
+    fn patch_file(
+        &self,
+        file_id: &str,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        self.delay(Operation::PatchFile);
+
+        let count = self.patch_count.fetch_add(1, Ordering::SeqCst) + 1;
+
+        if let Some(n) = self.patch_failure_interval {
+            if count.is_multiple_of(n) {
+                return Err(VaultError::Io(ErrorKind::Interrupted.into()));
+            }
+        }
+
+        let len = match self.partial_write_size {
+            Some(max_bytes) => buf.len().min(max_bytes),
+            None => buf.len(),
+        };
+
+        self.vault.patch_file(file_id, &mut buf[..len], offset)
+    }
+
+    fn write_range(
+        &self,
+        file_id: &str,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        self.delay(Operation::WriteRange);
+        self.vault.write_range(file_id, buf, offset)
+    }
+
+    fn terminate_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Terminated>, VaultError> {
+        self.delay(Operation::TerminateFile);
+        self.vault.terminate_file(file_id)
+    }
+
+    fn declare_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.delay(Operation::DeclareLength);
+        self.vault.declare_length(file_id, length)
+    }
+
+    fn increase_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.delay(Operation::DeclareLength);
+        self.vault.increase_length(file_id, length)
+    }
+
+    fn repair_offset(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        self.delay(Operation::GetFile);
+        self.vault.repair_offset(file_id)
+    }
+
+    fn repair_range(
+        &self,
+        file_id: &str,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<(), VaultError> {
+        self.delay(Operation::RepairRange);
+        self.vault.repair_range(file_id, buf, offset)
+    }
+
+    fn open_content(
+        &self,
+        file_id: &str,
+    ) -> Result<Box<dyn Read + Send>, VaultError> {
+        self.delay(Operation::OpenContent);
+        self.vault.open_content(file_id)
+    }
+
+    fn update_metadata(
+        &self,
+        file_id: &str,
+        metadata: Metadata,
+    ) -> Result<(), VaultError> {
+        self.delay(Operation::UpdateMetadata);
+        self.vault.update_metadata(file_id, metadata)
+    }
+
+    fn update_upload_token(
+        &self,
+        file_id: &str,
+        token: &str,
+    ) -> Result<(), VaultError> {
+        self.delay(Operation::UpdateUploadToken);
+        self.vault.update_upload_token(file_id, token)
+    }
+
+    fn update_stats(
+        &self,
+        file_id: &str,
+        stats: &UploadStats,
+    ) -> Result<(), VaultError> {
+        self.delay(Operation::UpdateStats);
+        self.vault.update_stats(file_id, stats)
+    }
+
+    fn schedule_termination(
+        &self,
+        file_id: &str,
+        time: SystemTime,
+    ) -> Result<(), VaultError> {
+        self.delay(Operation::ScheduleTermination);
+        self.vault.schedule_termination(file_id, time)
+    }
+
+    fn quarantine_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Quarantined>, VaultError> {
+        self.delay(Operation::QuarantineFile);
+        self.vault.quarantine_file(file_id)
+    }
+
+    fn list_quarantined(
+        &self,
+    ) -> Result<Vec<FileInfo<Quarantined>>, VaultError> {
+        self.delay(Operation::ListQuarantined);
+        self.vault.list_quarantined()
+    }
+
+    fn storage_path(&self) -> Option<&Path> {
+        self.vault.storage_path()
+    }
+
+    fn list_files(&self) -> Result<Vec<String>, VaultError> {
+        self.delay(Operation::ListFiles);
+        self.vault.list_files()
+    }
+}
@@ -0,0 +1,686 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{Cursor, Read},
+    sync::{Arc, Mutex, MutexGuard},
+    time::SystemTime,
+};
+
+use crate::fs::{
+    Built, Created, FileInfo, Metadata, PatchOption, Quarantined, Terminated,
+    UploadStats, Vault, VaultError,
+};
+
+/// Identifies an operation of the [`Vault`] trait.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    BuildFile,
+    CreateFile,
+    Exists,
+    GetFile,
+    PatchFile,
+    WriteRange,
+    TerminateFile,
+    DeclareLength,
+    IncreaseLength,
+    RepairRange,
+    OpenContent,
+    UpdateMetadata,
+    UpdateUploadToken,
+    UpdateStats,
+    ScheduleTermination,
+    QuarantineFile,
+    ListQuarantined,
+    ListFiles,
+}
+
+/// A [`Vault`] call captured by [`MockVault`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Call {
+    BuildFile {
+        length: u64,
+        metadata: Option<String>,
+    },
+    CreateFile {
+        id: String,
+    },
+    Exists {
+        id: String,
+    },
+    GetFile {
+        id: String,
+    },
+    PatchFile {
+        id: String,
+        offset: u64,
+        length: usize,
+    },
+    WriteRange {
+        id: String,
+        offset: u64,
+        length: usize,
+    },
+    TerminateFile {
+        id: String,
+    },
+    DeclareLength {
+        id: String,
+        length: u64,
+    },
+    IncreaseLength {
+        id: String,
+        length: u64,
+    },
+    RepairRange {
+        id: String,
+        offset: u64,
+        length: usize,
+    },
+    OpenContent {
+        id: String,
+    },
+    UpdateMetadata {
+        id: String,
+    },
+    UpdateUploadToken {
+        id: String,
+    },
+    UpdateStats {
+        id: String,
+    },
+    ScheduleTermination {
+        id: String,
+    },
+    QuarantineFile {
+        id: String,
+    },
+    ListQuarantined,
+    ListFiles,
+}
+
+struct StoredFile {
+    info: String,
+    content: Vec<u8>,
+}
+
+#[derive(Default)]
+struct MockState {
+    files: HashMap<String, StoredFile>,
+    calls: Vec<Call>,
+    failures: HashMap<Operation, VecDeque<VaultError>>,
+}
+
+/// An in-memory [`Vault`] with programmable failures and captured calls.
+///
+/// Clones of a [`MockVault`] share the same storage, so a clone can be handed to
+/// [`Meteoritus::with_vault()`](crate::Meteoritus::with_vault) while the original
+/// is kept to inspect the calls made by the handlers.
+///
+/// # Example
+///
+/// ```rust
+/// use meteoritus::{
+///     testing::{Call, MockVault, Operation},
+///     Vault, VaultError,
+/// };
+///
+/// let vault = MockVault::new();
+/// vault.fail_next(Operation::GetFile, VaultError::NotFound);
+///
+/// assert!(vault.get_file("some-id").is_err());
+/// assert_eq!(
+///     vault.calls(),
+///     vec![Call::GetFile {
+///         id: "some-id".to_string()
+///     }]
+/// );
+/// ```
+#[derive(Clone, Default)]
+pub struct MockVault {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockVault {
+    /// Creates a new empty [`MockVault`].
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Makes the next call of the given `operation` fail with `error`.
+    ///
+    /// Failures are queued, so calling it multiple times for the same operation
+    /// fails that many consecutive calls. A failed [`Operation::Exists`] returns `false`.
+    pub fn fail_next(&self, operation: Operation, error: VaultError) {
+        self.state()
+            .failures
+            .entry(operation)
+            .or_default()
+            .push_back(error);
+    }
+
+    /// Returns all calls received so far, in order.
+    pub fn calls(&self) -> Vec<Call> {
+        self.state().calls.clone()
+    }
+
+    /// Clears the captured calls.
+    pub fn clear_calls(&self) {
+        self.state().calls.clear();
+    }
+
+    /// Returns the bytes stored for the given file, if it exists.
+    pub fn content(&self, file_id: &str) -> Option<Vec<u8>> {
+        self.state()
+            .files
+            .get(file_id)
+            .map(|file| file.content.clone())
+    }
+
+    /// Stores the given file, replacing any file with the same id.
+    ///
+    /// Useful to seed the vault with uploads in a specific state.
+    pub fn insert(&self, file: FileInfo<Created>, content: Vec<u8>) {
+        let info = serde_json::to_string(&file)
+            .expect("file info should be serializable");
+
+        self.state()
+            .files
+            .insert(file.id().to_string(), StoredFile { info, content });
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record(
+        &self,
+        operation: Operation,
+        call: Call,
+    ) -> Result<MutexGuard<'_, MockState>, VaultError> {
+        let mut state = self.state();
+
+        state.calls.push(call);
+
+        match state
+            .failures
+            .get_mut(&operation)
+            .and_then(|failures| failures.pop_front())
+        {
+            Some(error) => Err(error),
+            None => Ok(state),
+        }
+    }
+}
+
+fn read_file<State>(
+    state: &MockState,
+    file_id: &str,
+) -> Result<FileInfo<State>, VaultError> {
+    let Some(file) = state.files.get(file_id) else {
+        return Err(VaultError::NotFound);
+    };
+
+    serde_json::from_str(&file.info).map_err(VaultError::from)
+}
+
+impl Vault for MockVault {
+    fn build_file(
+        &self,
+        length: u64,
+        metadata: Option<&str>,
+    ) -> Result<FileInfo<Built>, VaultError> {
+        drop(self.record(
+            Operation::BuildFile,
+            Call::BuildFile {
+                length,
+                metadata: metadata.map(|m| m.to_string()),
+            },
+        )?);
+
+        let metadata = match metadata {
+            Some(metadata) => Metadata::try_from(metadata)
+                .map_err(|e| VaultError::Invalid(Box::new(e)))?,
+            None => Metadata::default(),
+        };
+
+        Ok(FileInfo::new(length)
+            .with_uuid()
+            .with_metadata(metadata)
+            .build())
+    }
+
+    fn create_file(
+        &self,
+        file: FileInfo<Built>,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let mut state = self.record(
+            Operation::CreateFile,
+            Call::CreateFile {
+                id: file.id().to_string(),
+            },
+        )?;
+
+        if state.files.contains_key(file.id()) {
+            return Err(VaultError::Conflict(
+                "file already exists".to_string(),
+            ));
+        }
+
+        let file_name = format!("mock://{}", file.id());
+        let file = file.mark_as_created(&file_name);
+
+        let info = serde_json::to_string(&file)?;
+
+        state.files.insert(
+            file.id().to_string(),
+            StoredFile {
+                info,
+                content: Vec::new(),
+            },
+        );
+
+        Ok(file)
+    }
+
+    fn exists(&self, file_id: &str) -> bool {
+        match self.record(
+            Operation::Exists,
+            Call::Exists {
+                id: file_id.to_string(),
+            },
+        ) {
+            Ok(state) => state.files.contains_key(file_id),
+            Err(_) => false,
+        }
+    }
+
+    fn get_file(&self, file_id: &str) -> Result<FileInfo<Created>, VaultError> {
+        let state = self.record(
+            Operation::GetFile,
+            Call::GetFile {
+                id: file_id.to_string(),
+            },
+        )?;
+
+        read_file(&state, file_id)
+    }
+
+    fn patch_file(
+        &self,
+        file_id: &str,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        let mut state = self.record(
+            Operation::PatchFile,
+            Call::PatchFile {
+                id: file_id.to_string(),
+                offset,
+                length: buf.len(),
+            },
+        )?;
+
+        let mut file: FileInfo<Created> = read_file(&state, file_id)?;
+
+        if *file.offset() != offset {
+            return Err(VaultError::Conflict(format!(
+                "upload is at offset {}",
+                file.offset()
+            )));
+        }
+
+        let new_offset = offset + buf.len() as u64;
+
+        file.set_offset(new_offset)
+            .map_err(|e| VaultError::Invalid(e.into()))?;
+
+        let info = serde_json::to_string(&file)?;
+
+        let Some(stored) = state.files.get_mut(file_id) else {
+            return Err(VaultError::NotFound);
+        };
+
+        stored.content.truncate(offset as usize);
+        stored.content.extend_from_slice(buf);
+        stored.info = info;
+
+        match file.check_completion() {
+            Some(file) => Ok(PatchOption::Completed(file)),
+            None => Ok(PatchOption::Patched(new_offset)),
+        }
+    }
+
+    fn write_range(
+        &self,
+        file_id: &str,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<PatchOption, VaultError> {
+        let mut state = self.record(
+            Operation::WriteRange,
+            Call::WriteRange {
+                id: file_id.to_string(),
+                offset,
+                length: buf.len(),
+            },
+        )?;
+
+        let mut file: FileInfo<Created> = read_file(&state, file_id)?;
+
+        let end = offset + buf.len() as u64;
+
+        if offset < *file.offset() {
+            return Err(VaultError::Conflict(format!(
+                "upload is at offset {}",
+                file.offset()
+            )));
+        }
+
+        file.receive_range(offset..end)
+            .map_err(|e| VaultError::Invalid(e.into()))?;
+
+        let info = serde_json::to_string(&file)?;
+
+        let Some(stored) = state.files.get_mut(file_id) else {
+            return Err(VaultError::NotFound);
+        };
+
+        if stored.content.len() < end as usize {
+            stored.content.resize(end as usize, 0);
+        }
+
+        stored.content[offset as usize..end as usize].copy_from_slice(buf);
+        stored.info = info;
+
+        let offset = *file.offset();
+
+        match file.check_completion() {
+            Some(file) => Ok(PatchOption::Completed(file)),
+            None => Ok(PatchOption::Patched(offset)),
+        }
+    }
+
+    fn terminate_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Terminated>, VaultError> {
+        let mut state = self.record(
+            Operation::TerminateFile,
+            Call::TerminateFile {
+                id: file_id.to_string(),
+            },
+        )?;
+
+        let file = read_file(&state, file_id)?;
+
+        state.files.remove(file_id);
+
+        Ok(file)
+    }
+
+    fn declare_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let mut state = self.record(
+            Operation::DeclareLength,
+            Call::DeclareLength {
+                id: file_id.to_string(),
+                length,
+            },
+        )?;
+
+        let mut file: FileInfo<Created> = read_file(&state, file_id)?;
+
+        file.declare_length(length)
+            .map_err(|e| VaultError::Invalid(e.into()))?;
+
+        let info = serde_json::to_string(&file)?;
+
+        let Some(stored) = state.files.get_mut(file_id) else {
+            return Err(VaultError::NotFound);
+        };
+
+        stored.info = info;
+
+        Ok(file)
+    }
+
+    fn increase_length(
+        &self,
+        file_id: &str,
+        length: u64,
+    ) -> Result<FileInfo<Created>, VaultError> {
+        let mut state = self.record(
+            Operation::IncreaseLength,
+            Call::IncreaseLength {
+                id: file_id.to_string(),
+                length,
+            },
+        )?;
+
+        let mut file: FileInfo<Created> = read_file(&state, file_id)?;
+
+        file.increase_length(length)
+            .map_err(|e| VaultError::Invalid(e.into()))?;
+
+        let info = serde_json::to_string(&file)?;
+
+        let Some(stored) = state.files.get_mut(file_id) else {
+            return Err(VaultError::NotFound);
+        };
+
+        stored.info = info;
+
+        Ok(file)
+    }
+
+    fn repair_range(
+        &self,
+        file_id: &str,
+        buf: &[u8],
+        offset: u64,
+    ) -> Result<(), VaultError> {
+        let mut state = self.record(
+            Operation::RepairRange,
+            Call::RepairRange {
+                id: file_id.to_string(),
+                offset,
+                length: buf.len(),
+            },
+        )?;
+
+        let file: FileInfo<Created> = read_file(&state, file_id)?;
+
+        let end = offset as usize + buf.len();
+
+        if end as u64 > *file.offset() {
+            return Err(VaultError::Conflict(format!(
+                "upload is at offset {}",
+                file.offset()
+            )));
+        }
+
+        let Some(stored) = state.files.get_mut(file_id) else {
+            return Err(VaultError::NotFound);
+        };
+
+        stored.content[offset as usize..end].copy_from_slice(buf);
+
+        Ok(())
+    }
+
+    fn open_content(
+        &self,
+        file_id: &str,
+    ) -> Result<Box<dyn Read + Send>, VaultError> {
+        let state = self.record(
+            Operation::OpenContent,
+            Call::OpenContent {
+                id: file_id.to_string(),
+            },
+        )?;
+
+        let Some(stored) = state.files.get(file_id) else {
+            return Err(VaultError::NotFound);
+        };
+
+        Ok(Box::new(Cursor::new(stored.content.clone())))
+    }
+
+    fn update_metadata(
+        &self,
+        file_id: &str,
+        metadata: Metadata,
+    ) -> Result<(), VaultError> {
+        let mut state = self.record(
+            Operation::UpdateMetadata,
+            Call::UpdateMetadata {
+                id: file_id.to_string(),
+            },
+        )?;
+
+        let mut file: FileInfo<Created> = read_file(&state, file_id)?;
+
+        file.set_metadata(metadata);
+
+        let info = serde_json::to_string(&file)?;
+
+        let Some(stored) = state.files.get_mut(file_id) else {
+            return Err(VaultError::NotFound);
+        };
+
+        stored.info = info;
+
+        Ok(())
+    }
+
+    fn update_upload_token(
+        &self,
+        file_id: &str,
+        token: &str,
+    ) -> Result<(), VaultError> {
+        let mut state = self.record(
+            Operation::UpdateUploadToken,
+            Call::UpdateUploadToken {
+                id: file_id.to_string(),
+            },
+        )?;
+
+        let mut file: FileInfo<Created> = read_file(&state, file_id)?;
+
+        file.set_upload_token(token.to_string());
+
+        let info = serde_json::to_string(&file)?;
+
+        let Some(stored) = state.files.get_mut(file_id) else {
+            return Err(VaultError::NotFound);
+        };
+
+        stored.info = info;
+
+        Ok(())
+    }
+
+    fn update_stats(
+        &self,
+        file_id: &str,
+        stats: &UploadStats,
+    ) -> Result<(), VaultError> {
+        let mut state = self.record(
+            Operation::UpdateStats,
+            Call::UpdateStats {
+                id: file_id.to_string(),
+            },
+        )?;
+
+        let mut file: FileInfo<Created> = read_file(&state, file_id)?;
+
+        file.set_stats(stats.clone());
+
+        let info = serde_json::to_string(&file)?;
+
+        let Some(stored) = state.files.get_mut(file_id) else {
+            return Err(VaultError::NotFound);
+        };
+
+        stored.info = info;
+
+        Ok(())
+    }
+
+    fn schedule_termination(
+        &self,
+        file_id: &str,
+        time: SystemTime,
+    ) -> Result<(), VaultError> {
+        let mut state = self.record(
+            Operation::ScheduleTermination,
+            Call::ScheduleTermination {
+                id: file_id.to_string(),
+            },
+        )?;
+
+        let mut file: FileInfo<Created> = read_file(&state, file_id)?;
+
+        file.schedule_termination(time);
+
+        let info = serde_json::to_string(&file)?;
+
+        let Some(stored) = state.files.get_mut(file_id) else {
+            return Err(VaultError::NotFound);
+        };
+
+        stored.info = info;
+
+        Ok(())
+    }
+
+    fn quarantine_file(
+        &self,
+        file_id: &str,
+    ) -> Result<FileInfo<Quarantined>, VaultError> {
+        let mut state = self.record(
+            Operation::QuarantineFile,
+            Call::QuarantineFile {
+                id: file_id.to_string(),
+            },
+        )?;
+
+        let file = read_file::<Created>(&state, file_id)?.mark_as_quarantined();
+
+        let info = serde_json::to_string(&file)?;
+
+        let Some(stored) = state.files.get_mut(file_id) else {
+            return Err(VaultError::NotFound);
+        };
+
+        stored.info = info;
+
+        Ok(file)
+    }
+
+    fn list_quarantined(
+        &self,
+    ) -> Result<Vec<FileInfo<Quarantined>>, VaultError> {
+        let state =
+            self.record(Operation::ListQuarantined, Call::ListQuarantined)?;
+
+        let quarantined = state
+            .files
+            .keys()
+            .filter_map(|file_id| {
+                read_file::<Quarantined>(&state, file_id).ok()
+            })
+            .filter(|file| file.is_quarantined())
+            .collect();
+
+        Ok(quarantined)
+    }
+
+    fn list_files(&self) -> Result<Vec<String>, VaultError> {
+        let state = self.record(Operation::ListFiles, Call::ListFiles)?;
+
+        Ok(state.files.keys().cloned().collect())
+    }
+}
@@ -0,0 +1,77 @@
+//! Utilities to unit test applications built on top of [`Meteoritus`].
+//!
+//! This module is only available when the `testing` feature is enabled:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! meteoritus = { version = "0.2.1", features = ["testing"] }
+//! ```
+//!
+//! It provides:
+//!
+//! * [`MockVault`] - an in-memory [`Vault`] with programmable failures and captured calls.
+//! * [`FlakyVault`] - a [`Vault`] decorator injecting failures, latency and partial writes.
+//! * [`FileInfoBuilder`] - a builder for [`FileInfo`] in arbitrary states.
+//! * [`ManualClock`] - a [`Clock`] that tests can fast-forward deterministically.
+//! * [`FileInfo::expired_at()`] - sets the deadline of an upload, simulating expired uploads
+//!   along with a [`ManualClock`] handed to [`Meteoritus::with_clock()`].
+//! * request helpers like [`creation_request()`] and [`upload_request()`] that build valid
+//!   tus requests against [`rocket::local::asynchronous::Client`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use meteoritus::testing::{creation_request, upload_request, Call, MockVault};
+//! use meteoritus::Meteoritus;
+//! use rocket::{http::Status, local::asynchronous::Client};
+//!
+//! # rocket::execute(async {
+//! let vault = MockVault::new();
+//!
+//! let meteoritus = Meteoritus::new()
+//!     .mount_to("/files")
+//!     .with_vault(vault.clone())
+//!     .keep_on_disk()
+//!     .build();
+//!
+//! let client = Client::tracked(rocket::build().attach(meteoritus))
+//!     .await
+//!     .unwrap();
+//!
+//! let res = creation_request(&client, "/files", 5).dispatch().await;
+//! assert_eq!(res.status(), Status::Created);
+//!
+//! let location = res.headers().get_one("Location").unwrap().to_string();
+//!
+//! let res = upload_request(&client, location, 0, "hello").dispatch().await;
+//! assert_eq!(res.status(), Status::NoContent);
+//! assert_eq!(res.headers().get_one("Upload-Offset"), Some("5"));
+//!
+//! assert!(vault
+//!     .calls()
+//!     .iter()
+//!     .any(|call| matches!(call, Call::PatchFile { offset: 0, .. })));
+//! # });
+//! ```
+//!
+//! [`Meteoritus`]: crate::Meteoritus
+//! [`Meteoritus::with_clock()`]: crate::Meteoritus::with_clock
+//! [`FileInfo::expired_at()`]: crate::FileInfo::expired_at
+//! [`Vault`]: crate::Vault
+//! [`FileInfo`]: crate::FileInfo
+//! [`Clock`]: crate::Clock
+
+mod clock;
+mod file_info;
+mod flaky_vault;
+mod mock_vault;
+mod requests;
+
+pub use clock::ManualClock;
+pub use file_info::FileInfoBuilder;
+pub use flaky_vault::FlakyVault;
+pub use mock_vault::{Call, MockVault, Operation};
+pub use requests::{
+    creation_request, info_request, termination_request, upload_info_request,
+    upload_request,
+};
@@ -0,0 +1,90 @@
+use std::fmt::Display;
+
+use rocket::{
+    http::{uri::Origin, ContentType, Header},
+    local::asynchronous::{Client, LocalRequest},
+};
+
+fn tus_resumable() -> Header<'static> {
+    Header::new("Tus-Resumable", "1.0.0")
+}
+
+/// Builds a tus `OPTIONS` request used to discover the server capabilities.
+pub fn info_request<'c, 'u: 'c, U>(
+    client: &'c Client,
+    uri: U,
+) -> LocalRequest<'c>
+where
+    U: TryInto<Origin<'u>> + Display,
+{
+    client.options(uri)
+}
+
+/// Builds a valid tus creation `POST` request for an upload of `length` bytes.
+///
+/// Additional headers, like `Upload-Metadata`, can be attached to the returned request:
+///
+/// ```rust
+/// # use meteoritus::testing::creation_request;
+/// # use rocket::{http::Header, local::asynchronous::Client};
+/// # async fn example(client: &Client) {
+/// let res = creation_request(client, "/files", 1024)
+///     .header(Header::new("Upload-Metadata", "filename aGVsbG8udHh0"))
+///     .dispatch()
+///     .await;
+/// # }
+/// ```
+pub fn creation_request<'c, 'u: 'c, U>(
+    client: &'c Client,
+    uri: U,
+    length: u64,
+) -> LocalRequest<'c>
+where
+    U: TryInto<Origin<'u>> + Display,
+{
+    client
+        .post(uri)
+        .header(tus_resumable())
+        .header(Header::new("Upload-Length", length.to_string()))
+}
+
+/// Builds a tus `HEAD` request used to retrieve the current upload offset.
+pub fn upload_info_request<'c, 'u: 'c, U>(
+    client: &'c Client,
+    uri: U,
+) -> LocalRequest<'c>
+where
+    U: TryInto<Origin<'u>> + Display,
+{
+    client.head(uri).header(tus_resumable())
+}
+
+/// Builds a valid tus `PATCH` request sending `body` at the given `offset`.
+pub fn upload_request<'c, 'u: 'c, U, B>(
+    client: &'c Client,
+    uri: U,
+    offset: u64,
+    body: B,
+) -> LocalRequest<'c>
+where
+    U: TryInto<Origin<'u>> + Display,
+    B: AsRef<[u8]>,
+{
+    client
+        .patch(uri)
+        .header(tus_resumable())
+        .header(ContentType::new("application", "offset+octet-stream"))
+        .header(Header::new("Upload-Offset", offset.to_string()))
+        .body(body)
+}
+
+/// Builds a tus termination `DELETE` request.
+pub fn termination_request<'c, 'u: 'c, U>(
+    client: &'c Client,
+    uri: U,
+) -> LocalRequest<'c>
+where
+    U: TryInto<Origin<'u>> + Display,
+{
+    client.delete(uri).header(tus_resumable())
+}
@@ -0,0 +1,176 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime},
+};
+
+use rocket::time::OffsetDateTime;
+
+use crate::{Clock, UploadEventKind};
+
+/// A point in time of an upload lifecycle, read both from the configured
+/// [`Clock`] and from a monotonic clock.
+///
+/// Wall-clock times are meaningful across processes, like for analytics, while
+/// monotonic instants measure durations immune to system clock adjustments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timestamp {
+    wall: SystemTime,
+    monotonic: Instant,
+}
+
+impl Timestamp {
+    pub(crate) fn now(clock: &dyn Clock) -> Self {
+        Self {
+            wall: clock.now(),
+            monotonic: Instant::now(),
+        }
+    }
+
+    /// Returns the wall-clock time, as read from the configured [`Clock`].
+    pub fn wall(&self) -> SystemTime {
+        self.wall
+    }
+
+    /// Returns the monotonic instant.
+    pub fn monotonic(&self) -> Instant {
+        self.monotonic
+    }
+
+    /// Returns the time elapsed since the `earlier` timestamp, measured by their
+    /// monotonic instants.
+    pub fn duration_since(&self, earlier: &Timestamp) -> Duration {
+        self.monotonic.saturating_duration_since(earlier.monotonic)
+    }
+}
+
+/// The timestamps of an upload lifecycle, as observed by the server handling it.
+///
+/// Timelines are held in memory, so uploads resumed after a restart, or on another
+/// node, miss their earlier timestamps.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UploadTimeline {
+    created: Option<Timestamp>,
+    first_chunk: Option<Timestamp>,
+    last_chunk: Option<Timestamp>,
+    chunks: u64,
+    completed: Option<Timestamp>,
+}
+
+impl UploadTimeline {
+    /// Returns when the upload was created.
+    pub fn created(&self) -> Option<Timestamp> {
+        self.created
+    }
+
+    /// Returns when the first chunk of the upload was stored.
+    pub fn first_chunk(&self) -> Option<Timestamp> {
+        self.first_chunk
+    }
+
+    /// Returns when the last chunk of the upload was stored.
+    pub fn last_chunk(&self) -> Option<Timestamp> {
+        self.last_chunk
+    }
+
+    /// Returns the number of chunks stored, including the completing one.
+    pub fn chunks(&self) -> u64 {
+        self.chunks
+    }
+
+    /// Returns when the upload was completed.
+    pub fn completed(&self) -> Option<Timestamp> {
+        self.completed
+    }
+
+    /// Returns how long the client took to upload, from the creation, or the first
+    /// chunk when unknown, to the completion.
+    pub fn duration(&self) -> Option<Duration> {
+        let start = self.created.or(self.first_chunk)?;
+
+        Some(self.completed?.duration_since(&start))
+    }
+}
+
+/// Tracks the timeline of each upload in progress.
+#[derive(Default)]
+pub(crate) struct Timelines {
+    timelines: Mutex<HashMap<String, UploadTimeline>>,
+}
+
+impl Timelines {
+    /// Returns the number of uploads in progress being tracked.
+    pub(crate) fn len(&self) -> usize {
+        self.timelines
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .len()
+    }
+
+    /// Forgets the timeline of an upload removed without being completed or
+    /// terminated, like a rolled back one.
+    pub(crate) fn remove(&self, file_id: &str) {
+        self.timelines
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(file_id);
+    }
+
+    /// Records an upload event happening at `timestamp`, returning the updated
+    /// timeline of the upload.
+    ///
+    /// Timelines are forgotten once the upload is completed or terminated.
+    pub(crate) fn record(
+        &self,
+        kind: UploadEventKind,
+        file_id: &str,
+        timestamp: Timestamp,
+    ) -> UploadTimeline {
+        let mut timelines =
+            self.timelines.lock().unwrap_or_else(|e| e.into_inner());
+
+        let timeline = timelines.remove(file_id).unwrap_or_default();
+
+        let timeline = match kind {
+            UploadEventKind::Created => UploadTimeline {
+                created: Some(timestamp),
+                ..timeline
+            },
+            UploadEventKind::Progress | UploadEventKind::Completed => {
+                UploadTimeline {
+                    first_chunk: timeline.first_chunk.or(Some(timestamp)),
+                    last_chunk: Some(timestamp),
+                    chunks: timeline.chunks + 1,
+                    completed: match kind {
+                        UploadEventKind::Completed => Some(timestamp),
+                        _ => None,
+                    },
+                    ..timeline
+                }
+            }
+            UploadEventKind::Terminated => timeline,
+        };
+
+        if matches!(kind, UploadEventKind::Created | UploadEventKind::Progress)
+        {
+            timelines.insert(file_id.to_string(), timeline.clone());
+        }
+
+        timeline
+    }
+}
+
+/// Formats the given `time` as a [RFC 3339](https://www.rfc-editor.org/rfc/rfc3339) UTC timestamp.
+pub(crate) fn rfc3339(time: SystemTime) -> String {
+    let time = OffsetDateTime::from(time);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        time.year(),
+        time.month() as u8,
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second(),
+    )
+}
@@ -0,0 +1,50 @@
+use rocket::http::Status;
+use uuid::Uuid;
+
+use crate::{ErrorCode, FileInfo, TusError};
+
+/// Header holding the one-time upload token, see
+/// [`Meteoritus::with_token_rotation()`](crate::Meteoritus::with_token_rotation).
+pub(crate) const TOKEN_HEADER: &str = "Upload-Token";
+
+/// Generates a random one-time upload token.
+pub(crate) fn generate_token() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+/// Checks the token `presented` by a `PATCH` request against the one expected by
+/// the upload, if any.
+///
+/// Uploads holding no token, like the ones created before enabling the rotation,
+/// are not checked.
+pub(crate) fn check_token<S>(
+    file: &FileInfo<S>,
+    presented: Option<&str>,
+) -> Result<(), TusError> {
+    let Some(expected) = file.upload_token() else {
+        return Ok(());
+    };
+
+    match presented {
+        Some(presented) if constant_time_eq(expected, presented) => Ok(()),
+        Some(_) => Err(TusError::new(
+            Status::Forbidden,
+            ErrorCode::InvalidUploadToken,
+            format!("Invalid {} header", TOKEN_HEADER),
+        )),
+        None => Err(TusError::new(
+            Status::Forbidden,
+            ErrorCode::MissingUploadToken,
+            format!("Missing {} header", TOKEN_HEADER),
+        )),
+    }
+}
+
+/// Compares both tokens without leaking, through timing, how many bytes match.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
@@ -0,0 +1,53 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+/// The last known state of an upload removed from the vault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Tombstone {
+    /// The upload was completed with the given length.
+    Completed(u64),
+    /// The upload was terminated by the client.
+    Terminated,
+}
+
+/// Holds the tombstones of recently removed uploads, until they expire.
+#[derive(Default)]
+pub(crate) struct Tombstones {
+    entries: Mutex<HashMap<String, (Tombstone, SystemTime)>>,
+}
+
+impl Tombstones {
+    /// Records the tombstone of an upload, kept for the given `window` from `now`.
+    pub(crate) fn insert(
+        &self,
+        file_id: &str,
+        tombstone: Tombstone,
+        now: SystemTime,
+        window: Duration,
+    ) {
+        let mut entries =
+            self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+        /* Pruning the expired entries, so the ledger stays bounded by the window */
+        entries.retain(|_, (_, expires_at)| *expires_at > now);
+
+        entries.insert(file_id.to_string(), (tombstone, now + window));
+    }
+
+    /// Returns the tombstone of an upload, unless expired at `now`.
+    pub(crate) fn find(
+        &self,
+        file_id: &str,
+        now: SystemTime,
+    ) -> Option<Tombstone> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(file_id)
+            .filter(|(_, expires_at)| *expires_at > now)
+            .map(|(tombstone, _)| *tombstone)
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use rocket::http::Status;
+
+use crate::{ErrorCode, FileInfo, TusError};
+
+pub(crate) type MetadataValidator =
+    Arc<dyn Fn(Option<&str>) -> Result<(), String> + Send + Sync>;
+
+/// The validators of the metadata keys, enforced on upload creation, see
+/// [`Meteoritus::with_metadata_validator()`](crate::Meteoritus::with_metadata_validator).
+#[derive(Clone, Default)]
+pub(crate) struct MetadataValidators {
+    validators: Vec<(String, MetadataValidator)>,
+}
+
+impl MetadataValidators {
+    /// Registers a validator of the given key, along with the previous ones.
+    pub(crate) fn register(
+        &mut self,
+        key: String,
+        validator: MetadataValidator,
+    ) {
+        self.validators.push((key, validator));
+    }
+
+    /// Returns the validated keys, in registration order.
+    pub(crate) fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = Vec::new();
+
+        for (key, _) in &self.validators {
+            if !keys.contains(&key.as_str()) {
+                keys.push(key);
+            }
+        }
+
+        keys
+    }
+
+    /// Runs every validator against the metadata of an upload, failing with all
+    /// the invalid keys and their reasons.
+    pub(crate) fn validate<S>(
+        &self,
+        file: &FileInfo<S>,
+    ) -> Result<(), TusError> {
+        let mut invalid: Vec<(&str, String)> = Vec::new();
+
+        for (key, validator) in &self.validators {
+            /* A key is reported once, by its first failing validator */
+            if invalid.iter().any(|(invalid_key, _)| invalid_key == key) {
+                continue;
+            }
+
+            let value = file
+                .metadata()
+                .as_ref()
+                .and_then(|metadata| metadata.get_raw(key).ok());
+
+            let result = match value.map(String::from_utf8) {
+                None => validator(None),
+                Some(Ok(value)) => validator(Some(&value)),
+                Some(Err(_)) => Err("not valid UTF-8".to_string()),
+            };
+
+            if let Err(reason) = result {
+                invalid.push((key, reason));
+            }
+        }
+
+        match invalid.is_empty() {
+            true => Ok(()),
+            false => Err(TusError::new(
+                Status::BadRequest,
+                ErrorCode::InvalidMetadata,
+                format!(
+                    "Invalid Upload-Metadata, {}",
+                    invalid
+                        .iter()
+                        .map(|(key, reason)| format!("{key}: {reason}"))
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                ),
+            )),
+        }
+    }
+}
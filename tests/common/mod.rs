@@ -0,0 +1,29 @@
+#![allow(dead_code)]
+
+use std::{fs, path::PathBuf};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+/// Creates an empty directory holding the uploads of the given test, leaked so
+/// it can be handed to `LocalVault::new()`.
+pub fn vault_path(test: &str) -> &'static str {
+    let path: PathBuf = std::env::temp_dir().join(format!(
+        "meteoritus-{}-{}",
+        test,
+        std::process::id()
+    ));
+
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).unwrap();
+
+    Box::leak(path.to_string_lossy().into_owned().into_boxed_str())
+}
+
+/// Encodes the given entries as an `Upload-Metadata` header value.
+pub fn upload_metadata(entries: &[(&str, &str)]) -> String {
+    entries
+        .iter()
+        .map(|(key, value)| format!("{} {}", key, STANDARD.encode(value)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use std::{
+    fs,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use meteoritus::{
+    testing::{creation_request, upload_info_request, upload_request},
+    LocalVault, Meteoritus,
+};
+use rocket::{
+    http::{Header, Status},
+    local::asynchronous::Client,
+    tokio,
+};
+
+use common::{upload_metadata, vault_path};
+
+#[rocket::async_test]
+async fn completed_parts_are_concatenated_in_order() {
+    let concatenated = Arc::new(Mutex::new(None));
+    let completed = concatenated.clone();
+
+    let meteoritus = Meteoritus::new()
+        .with_vault(LocalVault::new(vault_path("concat")))
+        .with_concat_groups(4)
+        .keep_on_disk()
+        .on_completed(move |ctx| {
+            let path =
+                ctx.file_info.storage_ref().as_path().unwrap().to_owned();
+            let name = ctx
+                .file_info
+                .metadata()
+                .as_ref()
+                .unwrap()
+                .get_raw("filename");
+
+            *completed.lock().unwrap() =
+                Some((name.unwrap(), fs::read(path).unwrap()));
+        })
+        .build();
+
+    let client = Client::tracked(rocket::build().attach(meteoritus))
+        .await
+        .unwrap();
+
+    let mut parts = Vec::new();
+
+    for (index, length) in [(0, 5), (1, 6)] {
+        let metadata = upload_metadata(&[
+            ("filename", "hello.txt"),
+            ("concat-group", "greeting"),
+            ("concat-parts", "2"),
+            ("concat-index", &index.to_string()),
+        ]);
+
+        let res = creation_request(&client, "/meteoritus", length)
+            .header(Header::new("Upload-Metadata", metadata))
+            .dispatch()
+            .await;
+        assert_eq!(res.status(), Status::Created);
+
+        parts.push(res.headers().get_one("Location").unwrap().to_string());
+    }
+
+    /* Completing the last part first, so the order comes from the indexes */
+    for (location, chunk) in parts.iter().zip(["hello", " world"]).rev() {
+        let res = upload_request(&client, location.as_str(), 0, chunk)
+            .dispatch()
+            .await;
+        assert_eq!(res.status(), Status::NoContent);
+    }
+
+    for _ in 0..50 {
+        if concatenated.lock().unwrap().is_some() {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    let (name, content) = concatenated.lock().unwrap().take().unwrap();
+    assert_eq!(name, b"hello.txt");
+    assert_eq!(content, b"hello world");
+
+    for location in parts {
+        let res = upload_info_request(&client, location).dispatch().await;
+        assert_eq!(res.status(), Status::NotFound);
+    }
+}
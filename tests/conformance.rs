@@ -0,0 +1,44 @@
+mod common;
+
+use std::time::{Duration, SystemTime};
+
+use meteoritus::{conformance, testing::ManualClock, LocalVault, Meteoritus};
+use rocket::local::asynchronous::Client;
+
+use common::vault_path;
+
+#[rocket::async_test]
+async fn local_vault_conforms() {
+    let meteoritus = Meteoritus::new()
+        .mount_to("/files")
+        .with_vault(LocalVault::new(vault_path("conformance")))
+        .keep_on_disk()
+        .build();
+
+    let client = Client::tracked(rocket::build().attach(meteoritus))
+        .await
+        .unwrap();
+
+    conformance::run(&client, "/files").await;
+}
+
+#[rocket::async_test]
+async fn local_vault_expires_uploads() {
+    let clock = ManualClock::new(SystemTime::now());
+
+    let meteoritus = Meteoritus::new()
+        .mount_to("/files")
+        .with_vault(LocalVault::new(vault_path("expiration")))
+        .with_clock(clock.clone())
+        .with_expiration(Duration::from_secs(60))
+        .build();
+
+    let client = Client::tracked(rocket::build().attach(meteoritus))
+        .await
+        .unwrap();
+
+    conformance::expiration(&client, "/files", || {
+        clock.advance(Duration::from_secs(120))
+    })
+    .await;
+}
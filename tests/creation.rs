@@ -0,0 +1,154 @@
+mod common;
+
+use meteoritus::{
+    testing::{creation_request, upload_info_request, upload_request},
+    LocalVault, Meteoritus,
+};
+use rocket::{
+    http::{ContentType, Header, Status},
+    local::asynchronous::Client,
+};
+
+use common::{upload_metadata, vault_path};
+
+/// Fingerprints are only deduplicated for clients with a known address.
+const CLIENT: &str = "192.0.2.1:49152";
+
+fn header(
+    res: &rocket::local::asynchronous::LocalResponse<'_>,
+    name: &str,
+) -> String {
+    res.headers().get_one(name).unwrap_or_default().to_string()
+}
+
+#[rocket::async_test]
+async fn creation_with_upload_stores_initial_chunk() {
+    let meteoritus = Meteoritus::new()
+        .with_vault(LocalVault::new(vault_path("creation-with-upload")))
+        .keep_on_disk()
+        .build();
+
+    let client = Client::tracked(rocket::build().attach(meteoritus))
+        .await
+        .unwrap();
+
+    let res = creation_request(&client, "/meteoritus", 11)
+        .header(ContentType::new("application", "offset+octet-stream"))
+        .body("hello")
+        .dispatch()
+        .await;
+
+    assert_eq!(res.status(), Status::Created);
+    assert_eq!(header(&res, "Upload-Offset"), "5");
+
+    let location = header(&res, "Location");
+
+    let res = upload_info_request(&client, location.as_str())
+        .dispatch()
+        .await;
+    assert_eq!(header(&res, "Upload-Offset"), "5");
+
+    let res = upload_request(&client, location.as_str(), 5, " world")
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::NoContent);
+    assert_eq!(header(&res, "Upload-Offset"), "11");
+}
+
+#[rocket::async_test]
+async fn patch_rotates_upload_token() {
+    let meteoritus = Meteoritus::new()
+        .with_vault(LocalVault::new(vault_path("token-rotation")))
+        .with_token_rotation()
+        .keep_on_disk()
+        .build();
+
+    let client = Client::tracked(rocket::build().attach(meteoritus))
+        .await
+        .unwrap();
+
+    let res = creation_request(&client, "/meteoritus", 11)
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::Created);
+
+    let location = header(&res, "Location");
+    let created_token = header(&res, "Upload-Token");
+    assert!(!created_token.is_empty());
+
+    let res = upload_request(&client, location.as_str(), 0, "hello")
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::Forbidden);
+
+    let res = upload_request(&client, location.as_str(), 0, "hello")
+        .header(Header::new("Upload-Token", created_token.clone()))
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::NoContent);
+
+    let rotated_token = header(&res, "Upload-Token");
+    assert!(!rotated_token.is_empty());
+    assert_ne!(rotated_token, created_token);
+
+    let res = upload_request(&client, location.as_str(), 5, " world")
+        .header(Header::new("Upload-Token", created_token))
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::Forbidden);
+
+    let res = upload_request(&client, location.as_str(), 5, " world")
+        .header(Header::new("Upload-Token", rotated_token))
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::NoContent);
+}
+
+#[rocket::async_test]
+async fn recreation_rotates_upload_token() {
+    let meteoritus = Meteoritus::new()
+        .with_vault(LocalVault::new(vault_path("token-recreation")))
+        .with_token_rotation()
+        .with_fingerprint_deduplication("fingerprint")
+        .keep_on_disk()
+        .build();
+
+    let client = Client::tracked(rocket::build().attach(meteoritus))
+        .await
+        .unwrap();
+
+    let metadata = upload_metadata(&[("fingerprint", "hello.txt-11")]);
+
+    let res = creation_request(&client, "/meteoritus", 11)
+        .remote(CLIENT.parse().unwrap())
+        .header(Header::new("Upload-Metadata", metadata.clone()))
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::Created);
+
+    let location = header(&res, "Location");
+    let created_token = header(&res, "Upload-Token");
+
+    let res = creation_request(&client, "/meteoritus", 11)
+        .remote(CLIENT.parse().unwrap())
+        .header(Header::new("Upload-Metadata", metadata))
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::Created);
+    assert_eq!(header(&res, "Location"), location);
+
+    let rotated_token = header(&res, "Upload-Token");
+    assert_ne!(rotated_token, created_token);
+
+    let res = upload_request(&client, location.as_str(), 0, "hello")
+        .header(Header::new("Upload-Token", created_token))
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::Forbidden);
+
+    let res = upload_request(&client, location.as_str(), 0, "hello")
+        .header(Header::new("Upload-Token", rotated_token))
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::NoContent);
+}
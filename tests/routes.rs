@@ -0,0 +1,94 @@
+#[macro_use]
+extern crate rocket;
+
+mod common;
+
+use meteoritus::{testing::upload_info_request, LocalVault, Meteoritus};
+use rocket::{http::Status, local::asynchronous::Client, Route};
+
+use common::vault_path;
+
+#[head("/<id>")]
+fn probe(id: &str) -> String {
+    format!("Probing {id}")
+}
+
+#[get("/health")]
+fn health() -> &'static str {
+    "OK"
+}
+
+/// Ranks the given routes before every tus route.
+fn outranking(routes: Vec<Route>) -> Vec<Route> {
+    routes
+        .into_iter()
+        .map(|mut route| {
+            route.rank = -100;
+            route
+        })
+        .collect()
+}
+
+#[rocket::async_test]
+async fn shadowing_routes_are_reported_without_aborting() {
+    let meteoritus = Meteoritus::new()
+        .with_vault(LocalVault::new(vault_path("routes-shadowing")))
+        .build();
+
+    let rocket = rocket::build()
+        .attach(meteoritus)
+        .mount("/meteoritus", outranking(routes![probe]));
+
+    let client = Client::tracked(rocket).await.unwrap();
+
+    let res = upload_info_request(&client, "/meteoritus/42")
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::Ok);
+    assert!(res.headers().get_one("Tus-Resumable").is_none());
+}
+
+#[rocket::async_test]
+async fn rank_offset_lets_application_routes_take_precedence() {
+    let meteoritus = Meteoritus::new()
+        .with_vault(LocalVault::new(vault_path("routes-rank-offset")))
+        .with_route_rank_offset(20)
+        .build();
+
+    let rocket = rocket::build()
+        .attach(meteoritus)
+        .mount("/meteoritus", routes![probe]);
+
+    let client = Client::tracked(rocket).await.unwrap();
+
+    let res = upload_info_request(&client, "/meteoritus/42")
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::Ok);
+    assert!(res.headers().get_one("Tus-Resumable").is_none());
+}
+
+#[rocket::async_test]
+async fn negative_rank_offset_lets_tus_routes_win() {
+    let meteoritus = Meteoritus::new()
+        .with_vault(LocalVault::new(vault_path("routes-default")))
+        .with_route_rank_offset(-20)
+        .build();
+
+    let rocket = rocket::build()
+        .attach(meteoritus)
+        .mount("/meteoritus", routes![probe, health]);
+
+    let client = Client::tracked(rocket).await.unwrap();
+
+    let res = upload_info_request(&client, "/meteoritus/42")
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::NotFound);
+    assert_eq!(res.headers().get_one("Tus-Resumable"), Some("1.0.0"));
+
+    let res = client.get("/meteoritus/health").dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    assert!(res.headers().get_one("Tus-Resumable").is_none());
+    assert!(res.headers().get_one("Cache-Control").is_none());
+}
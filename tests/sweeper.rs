@@ -0,0 +1,178 @@
+mod common;
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime},
+};
+
+use meteoritus::{
+    testing::{
+        creation_request, upload_info_request, upload_request, ManualClock,
+    },
+    LocalVault, Meteoritus, TerminationPolicy,
+};
+use rocket::{http::Status, local::asynchronous::Client, tokio};
+
+use common::vault_path;
+
+const SWEEP_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Waits long enough for the background sweeper to run a few times.
+async fn sweep() {
+    tokio::time::sleep(SWEEP_INTERVAL * 10).await;
+}
+
+async fn create(client: &Client, length: u64) -> String {
+    let res = creation_request(client, "/meteoritus", length)
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::Created);
+
+    res.headers().get_one("Location").unwrap().to_string()
+}
+
+#[rocket::async_test]
+async fn stalled_uploads_are_terminated() {
+    let clock = ManualClock::new(SystemTime::now());
+    let terminations = Arc::new(AtomicUsize::new(0));
+    let terminated = terminations.clone();
+
+    let meteoritus = Meteoritus::new()
+        .with_vault(LocalVault::new(vault_path("sweeper-stalled")))
+        .with_clock(clock.clone())
+        .with_sweep_interval(SWEEP_INTERVAL)
+        .with_stall_timeout(Duration::from_secs(60))
+        .with_terminated_tombstones(Duration::from_secs(600))
+        .on_termination(move |_| {
+            terminated.fetch_add(1, Ordering::SeqCst);
+        })
+        .build();
+
+    let client = Client::tracked(rocket::build().attach(meteoritus))
+        .await
+        .unwrap();
+
+    let location = create(&client, 11).await;
+
+    let res = upload_request(&client, location.as_str(), 0, "hello")
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::NoContent);
+
+    sweep().await;
+
+    let res = upload_info_request(&client, location.as_str())
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::NoContent);
+
+    clock.advance(Duration::from_secs(120));
+    sweep().await;
+
+    /* The callback is called along with the next request */
+    assert_eq!(terminations.load(Ordering::SeqCst), 0);
+
+    let res = upload_info_request(&client, location.as_str())
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::Gone);
+    assert_eq!(terminations.load(Ordering::SeqCst), 1);
+}
+
+#[rocket::async_test]
+async fn delayed_terminations_survive_restarts() {
+    let path = vault_path("sweeper-delayed");
+    let clock = ManualClock::new(SystemTime::now());
+
+    let rocket = |clock: &ManualClock| {
+        let meteoritus = Meteoritus::new()
+            .with_vault(LocalVault::new(path))
+            .with_clock(clock.clone())
+            .with_sweep_interval(SWEEP_INTERVAL)
+            .with_termination_policy(TerminationPolicy::Delayed(
+                Duration::from_secs(60),
+            ))
+            .build();
+
+        rocket::build().attach(meteoritus)
+    };
+
+    let location = {
+        let client = Client::tracked(rocket(&clock)).await.unwrap();
+
+        let location = create(&client, 5).await;
+
+        let res = upload_request(&client, location.clone(), 0, "hello")
+            .dispatch()
+            .await;
+        assert_eq!(res.status(), Status::NoContent);
+
+        sweep().await;
+
+        location
+    };
+
+    let client = Client::tracked(rocket(&clock)).await.unwrap();
+    sweep().await;
+
+    let res = upload_info_request(&client, location.as_str())
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::NoContent);
+
+    clock.advance(Duration::from_secs(120));
+    sweep().await;
+
+    let res = upload_info_request(&client, location.as_str())
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::NotFound);
+}
+
+#[rocket::async_test]
+async fn least_recently_active_uploads_are_evicted() {
+    let clock = ManualClock::new(SystemTime::now());
+    let terminations = Arc::new(AtomicUsize::new(0));
+    let terminated = terminations.clone();
+
+    let meteoritus = Meteoritus::new()
+        .with_vault(LocalVault::new(vault_path("sweeper-eviction")))
+        .with_clock(clock.clone())
+        .with_sweep_interval(SWEEP_INTERVAL)
+        .with_max_retained_uploads(2)
+        .on_termination(move |_| {
+            terminated.fetch_add(1, Ordering::SeqCst);
+        })
+        .build();
+
+    let client = Client::tracked(rocket::build().attach(meteoritus))
+        .await
+        .unwrap();
+
+    let mut locations = Vec::new();
+
+    for _ in 0..3 {
+        locations.push(create(&client, 11).await);
+        clock.advance(Duration::from_secs(1));
+    }
+
+    sweep().await;
+
+    let mut statuses = Vec::new();
+
+    for location in &locations {
+        let res = upload_info_request(&client, location.as_str())
+            .dispatch()
+            .await;
+        statuses.push(res.status());
+    }
+
+    assert_eq!(
+        statuses,
+        [Status::NotFound, Status::NoContent, Status::NoContent]
+    );
+    assert_eq!(terminations.load(Ordering::SeqCst), 1);
+}